@@ -0,0 +1,56 @@
+//! Guarded ticking: when a tick shouldn't run at all.
+//!
+//! `TickEngine::tick` is forgiving: it quietly does nothing to an ended or
+//! abandoned run and ticks whatever clock the state shows. A host that
+//! drives ticks from more than one place (a frame loop and a catch-up
+//! timer, say) wants to know when it's about to advance a state twice, or
+//! tick one whose clock was moved underneath its engine. `TickEngine::advance`
+//! takes the tick the caller believes the state is at and refuses, with a
+//! `TickRefused`, rather than run a tick that would leave the counters out
+//! of step. A refused tick leaves the state untouched.
+//!
+//! The engine's `Bookkeeping` notes the tick it was written at, which is
+//! how a moved clock is caught. Saves from before that have no such note
+//! and pass.
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::types::state::GameState;
+
+/// Why a state wasn't ticked
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum TickRefused {
+    #[error("the run ended at tick {tick}")]
+    Ended { tick: u64 },
+
+    #[error("the colony was abandoned at tick {tick}")]
+    Abandoned { tick: u64 },
+
+    /// The state isn't at the tick the caller expected: most often it was
+    /// already advanced from there
+    #[error("expected the state at tick {expected}, but it is at tick {actual}")]
+    Stale { expected: u64, actual: u64 },
+
+    /// The state's clock was moved since its engine last ticked it
+    #[error("the state's clock reads tick {tick}, but its engine left it at tick {last}")]
+    ClockMoved { tick: u64, last: u64 },
+}
+
+/// Whether `state` can be ticked from `expected` (any tick, if None)
+pub fn check(state: &GameState, expected: Option<u64>) -> Result<(), TickRefused> {
+    if let Some(legacy) = &state.legacy {
+        return Err(TickRefused::Abandoned { tick: legacy.abandoned_at });
+    }
+    if let Some(ending) = &state.ended {
+        return Err(TickRefused::Ended { tick: ending.tick });
+    }
+    if let Some(expected) = expected.filter(|expected| *expected != state.tick) {
+        return Err(TickRefused::Stale { expected, actual: state.tick });
+    }
+    match state.bookkeeping.and_then(|bookkeeping| bookkeeping.tick) {
+        Some(last) if last != state.tick => Err(TickRefused::ClockMoved { tick: state.tick, last }),
+        _ => Ok(()),
+    }
+}
@@ -1,6 +1,14 @@
+// pyo3 0.20's #[pymethods] expands to impls nested in a const block.
+#![allow(non_local_definitions)]
+
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
 use crate::engine::TickEngine;
+use crate::input::TickInput;
+use crate::run::RunDescriptor;
+use crate::types::command::Command;
+use crate::types::columnar::SaveOptions;
+use crate::types::config::SimConfig;
+use crate::types::mapgen::MapGenConfig;
 use crate::types::state::GameState;
 
 #[pyclass]
@@ -17,6 +25,16 @@ impl PyGameState {
         }
     }
 
+    /// A new colony in a cavern grown from `seed`; `config_json` is a
+    /// `MapGenConfig` (any field left out takes its default)
+    #[staticmethod]
+    #[pyo3(signature = (seed, config_json = "{}"))]
+    fn generated(seed: u64, config_json: &str) -> PyResult<Self> {
+        let config: MapGenConfig = serde_json::from_str(config_json)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid map config: {}", e)))?;
+        Ok(PyGameState { inner: GameState::generated(seed, &config) })
+    }
+
     #[staticmethod]
     fn from_json(json: &str) -> PyResult<Self> {
         match GameState::from_json(json) {
@@ -25,12 +43,132 @@ impl PyGameState {
         }
     }
 
-    fn to_json(&self) -> PyResult<String> {
-        match self.inner.to_json() {
+    /// Load a save along with its compatibility verdict, as JSON
+    #[staticmethod]
+    fn from_json_checked(json: &str) -> PyResult<(Self, String)> {
+        let (state, verdict) = GameState::from_json_checked(json)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid JSON: {}", e)))?;
+        match serde_json::to_string(&verdict) {
+            Ok(verdict) => Ok((PyGameState { inner: state }, verdict)),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!("Verdict serialization failed: {}", e))),
+        }
+    }
+
+    /// The save as JSON; `columnar` writes its big tables a field at a time
+    #[pyo3(signature = (columnar = false))]
+    fn to_json(&self, columnar: bool) -> PyResult<String> {
+        match self.inner.to_json_with(SaveOptions { columnar }) {
             Ok(json) => Ok(json),
             Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!("Serialization failed: {}", e))),
         }
     }
+
+    /// Standing warnings as a JSON list
+    fn warnings(&self) -> PyResult<String> {
+        match serde_json::to_string(&self.inner.warnings()) {
+            Ok(json) => Ok(json),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!("Warning serialization failed: {}", e))),
+        }
+    }
+
+    /// What changed from this state to `other`, as a JSON `StateDiff`
+    fn diff(&self, other: &PyGameState) -> PyResult<String> {
+        match serde_json::to_string(&self.inner.diff(&other.inner)) {
+            Ok(json) => Ok(json),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!("Diff serialization failed: {}", e))),
+        }
+    }
+
+    /// The colony at a glance, as a JSON `Summary`
+    fn summary(&self) -> PyResult<String> {
+        match serde_json::to_string(&self.inner.summary()) {
+            Ok(json) => Ok(json),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!("Summary serialization failed: {}", e))),
+        }
+    }
+
+    /// The tiles to walk through from `from` to `to`, as a JSON list (null
+    /// if there's no way)
+    fn path(&self, from: &str, to: &str) -> PyResult<String> {
+        match serde_json::to_string(&self.inner.map.path(from, to).as_deref()) {
+            Ok(json) => Ok(json),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!("Path serialization failed: {}", e))),
+        }
+    }
+
+    /// Ticks an ant would take to walk from `from` to `to` (None if there's
+    /// no way)
+    fn travel_ticks(&self, from: &str, to: &str) -> Option<u64> {
+        self.inner.travel_ticks(from, to)
+    }
+
+    /// The silent receiver's stage and timers as a JSON `Silence` (null
+    /// while it's listening)
+    fn receiver_silence(&self) -> PyResult<String> {
+        match serde_json::to_string(&self.inner.receiver_silence()) {
+            Ok(json) => Ok(json),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!("Silence serialization failed: {}", e))),
+        }
+    }
+
+    /// The colony's health score and its parts, as JSON `Health`
+    fn health(&self) -> PyResult<String> {
+        match serde_json::to_string(&self.inner.health()) {
+            Ok(json) => Ok(json),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!("Health serialization failed: {}", e))),
+        }
+    }
+
+    /// Where the colony is in its day, as JSON `TimeOfDay`
+    fn time_of_day(&self) -> PyResult<String> {
+        match serde_json::to_string(&self.inner.time_of_day()) {
+            Ok(json) => Ok(json),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!("TimeOfDay serialization failed: {}", e))),
+        }
+    }
+
+    /// How the run ended as a JSON `Ending` (null while it's still going)
+    fn ended(&self) -> PyResult<String> {
+        match serde_json::to_string(&self.inner.ended) {
+            Ok(json) => Ok(json),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!("Ending serialization failed: {}", e))),
+        }
+    }
+
+    /// Add a JSON event to the plugin event queue, returning its sequence number
+    fn enqueue_event(&mut self, event_json: &str) -> PyResult<u64> {
+        let event: serde_json::Value = serde_json::from_str(event_json)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid event: {}", e)))?;
+        self.inner.queues.events.enqueue(&event)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// Start tracking a plugin event consumer by name
+    fn subscribe_events(&mut self, consumer: &str) {
+        self.inner.queues.events.subscribe(consumer);
+    }
+
+    /// Up to `limit` events `consumer` hasn't acked, as a JSON list
+    fn unread_events(&self, consumer: &str, limit: usize) -> PyResult<String> {
+        let events = self.inner.queues.events.unread(consumer, limit)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        serde_json::to_string(events)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Event serialization failed: {}", e)))
+    }
+
+    /// Mark `consumer`'s events up to `seq` read, returning how many were dropped
+    fn ack_events(&mut self, consumer: &str, seq: u64) -> PyResult<usize> {
+        self.inner.queues.events.ack(consumer, seq)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// Built-in kinds and this save's content, as JSON
+    fn registry(&self) -> PyResult<String> {
+        match serde_json::to_string(&self.inner.registry()) {
+            Ok(json) => Ok(json),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!("Registry serialization failed: {}", e))),
+        }
+    }
 }
 
 #[pyclass]
@@ -47,6 +185,20 @@ impl PyTickEngine {
         }
     }
 
+    /// An engine that runs every state under the given `SimConfig` JSON
+    #[staticmethod]
+    fn with_config(seed: u64, config_json: &str) -> PyResult<Self> {
+        match serde_json::from_str::<SimConfig>(config_json) {
+            Ok(config) => Ok(PyTickEngine { inner: TickEngine::new_with_config(seed, config) }),
+            Err(e) => Err(pyo3::exceptions::PyValueError::new_err(format!("Invalid config: {}", e))),
+        }
+    }
+
+    /// Fix plugins' invalid edits before each tick instead of carrying them
+    fn set_soft_fail(&mut self, soft_fail: bool) {
+        self.inner.set_soft_fail(soft_fail);
+    }
+
     fn tick(&mut self, state: &mut PyGameState) -> PyResult<String> {
         let events = self.inner.tick(&mut state.inner);
         // Serialize events to JSON string to pass back to Python
@@ -56,6 +208,71 @@ impl PyTickEngine {
              Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!("Event serialization failed: {}", e))),
         }
     }
+
+    /// Tick only if the state is at `expected` and fit to run; a refusal
+    /// is raised as a ValueError carrying the JSON `TickRefused`
+    fn advance(&mut self, state: &mut PyGameState, expected: u64) -> PyResult<String> {
+        match self.inner.advance(&mut state.inner, expected) {
+            Ok(events) => serde_json::to_string(&events.into_events())
+                .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Event serialization failed: {}", e))),
+            Err(refused) => Err(pyo3::exceptions::PyValueError::new_err(
+                serde_json::to_string(&refused).unwrap_or_else(|_| refused.to_string()),
+            )),
+        }
+    }
+
+    /// Catch up on time away until `now`, returning a JSON `OfflineReport`
+    fn process_offline_progress(&mut self, state: &mut PyGameState, now: f64) -> PyResult<String> {
+        let report = self.inner.process_offline_progress(&mut state.inner, now);
+        serde_json::to_string(&report)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Report serialization failed: {}", e)))
+    }
+
+    /// Take one step from a JSON `TickInput`, returning a JSON `TickOutput`
+    fn step(&mut self, state: &mut PyGameState, input_json: &str) -> PyResult<String> {
+        let input: TickInput = serde_json::from_str(input_json)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid input: {}", e)))?;
+        let output = self.inner.step(&mut state.inner, &input);
+        serde_json::to_string(&output)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Output serialization failed: {}", e)))
+    }
+
+    /// Run one tick, returning a JSON `TickReport` (events, warnings,
+    /// counter changes and, if asked, metrics) in one call
+    #[pyo3(signature = (state, metrics = false))]
+    fn tick_report(&mut self, state: &mut PyGameState, metrics: bool) -> PyResult<String> {
+        let report = self.inner.tick_report(&mut state.inner, metrics);
+        serde_json::to_string(&report)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Report serialization failed: {}", e)))
+    }
+
+    /// Run `n` ticks, returning a JSON `BatchEvents`
+    fn tick_n(&mut self, state: &mut PyGameState, n: u64) -> PyResult<String> {
+        let batch = self.inner.tick_n(&mut state.inner, n);
+        serde_json::to_string(&batch)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Event serialization failed: {}", e)))
+    }
+
+    /// Apply a JSON `Command`, returning its events as JSON
+    fn apply_command(&self, state: &mut PyGameState, command_json: &str) -> PyResult<String> {
+        let command: Command = serde_json::from_str(command_json)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid command: {}", e)))?;
+        let events = self.inner.apply_command(&mut state.inner, command)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        serde_json::to_string(&events)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Event serialization failed: {}", e)))
+    }
+}
+
+/// Run a JSON `RunDescriptor` start to finish, returning a JSON `RunOutput`
+#[pyfunction]
+fn run_simulation(descriptor_json: &str) -> PyResult<String> {
+    let descriptor: RunDescriptor = serde_json::from_str(descriptor_json)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid descriptor: {}", e)))?;
+    let output = crate::run::run_simulation(descriptor)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    serde_json::to_string(&output)
+        .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Output serialization failed: {}", e)))
 }
 
 /// The python module definition
@@ -63,5 +280,6 @@ impl PyTickEngine {
 fn anthill_core(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyGameState>()?;
     m.add_class::<PyTickEngine>()?;
+    m.add_function(wrap_pyfunction!(run_simulation, m)?)?;
     Ok(())
 }
@@ -0,0 +1,94 @@
+//! RNG budgets: how many draws each tick phase takes from the main stream.
+//!
+//! Every phase draws from the same per-tick stream, so a refactor that adds
+//! or drops a single draw shifts everything after it and quietly changes the
+//! future of every existing save. An engine recording a budget
+//! (`TickEngine::record_rng_budget`) writes down each phase's draws, tick by
+//! tick; one checking against a budget (`TickEngine::check_rng_budget`)
+//! reports the first phase whose count drifted. Strict engines fail the tick
+//! with it; others emit `RngBudgetExceeded` and carry on.
+//!
+//! Phases with streams of their own (echoes, wonders) can't move anyone
+//! else's draws and aren't budgeted.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Draws per phase, for each tick of a reference run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RngBudget {
+    /// Phases that drew nothing are left out
+    pub ticks: BTreeMap<u64, BTreeMap<String, u64>>,
+}
+
+/// A phase that drew a different number of times than its budget allows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overdraw {
+    pub phase: String,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+impl RngBudget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Note what `tick`'s phases drew, in phase order
+    pub fn record(&mut self, tick: u64, draws: &[(&'static str, u64)]) {
+        let phases = draws
+            .iter()
+            .filter(|(_, n)| *n > 0)
+            .map(|(phase, n)| (phase.to_string(), *n))
+            .collect();
+        self.ticks.insert(tick, phases);
+    }
+
+    /// The first phase of `tick` whose draws differ from the budget. Ticks
+    /// the budget never saw pass.
+    pub fn check(&self, tick: u64, draws: &[(&'static str, u64)]) -> Option<Overdraw> {
+        let budget = self.ticks.get(&tick)?;
+        if let Some(&(phase, actual)) = draws
+            .iter()
+            .find(|(phase, actual)| budget.get(*phase).copied().unwrap_or(0) != *actual)
+        {
+            let expected = budget.get(phase).copied().unwrap_or(0);
+            return Some(Overdraw { phase: phase.to_string(), expected, actual });
+        }
+        // A budgeted phase that no longer runs at all
+        budget
+            .iter()
+            .find(|(phase, _)| !draws.iter().any(|(p, _)| p == phase))
+            .map(|(phase, &expected)| Overdraw { phase: phase.clone(), expected, actual: 0 })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ticks.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_finds_the_first_drifting_phase() {
+        let mut budget = RngBudget::new();
+        budget.record(5, &[("actions", 0), ("queen", 2), ("receiver", 1)]);
+        assert_eq!(budget.ticks[&5].len(), 2);
+
+        assert_eq!(budget.check(5, &[("actions", 0), ("queen", 2), ("receiver", 1)]), None);
+        assert_eq!(budget.check(6, &[("actions", 9)]), None);
+        assert_eq!(
+            budget.check(5, &[("actions", 1), ("queen", 2), ("receiver", 2)]),
+            Some(Overdraw { phase: "actions".into(), expected: 0, actual: 1 })
+        );
+        assert_eq!(
+            budget.check(5, &[("actions", 0), ("queen", 2)]),
+            Some(Overdraw { phase: "receiver".into(), expected: 1, actual: 0 })
+        );
+
+        let json = serde_json::to_string(&budget).unwrap();
+        assert_eq!(serde_json::from_str::<RngBudget>(&json).unwrap(), budget);
+    }
+}
@@ -0,0 +1,188 @@
+//! Strict mode: invariants checked after every tick phase.
+//!
+//! A strict engine (`TickEngine::set_strict`) stops at the first phase that
+//! leaves the state broken and names it, instead of letting a NaN or a lost
+//! corpse surface hundreds of ticks later. Meant for local development and
+//! plugin authoring; normal play skips the checks entirely.
+
+use thiserror::Error;
+
+use crate::det;
+use crate::types::state::GameState;
+
+/// An invariant a tick phase broke
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ContractViolation {
+    #[error("after {phase}: resource {resource} is {amount}")]
+    NonFiniteResource {
+        phase: &'static str,
+        resource: String,
+        amount: f64,
+    },
+
+    #[error("after {phase}: entity {entity_id} has hunger {hunger}")]
+    NonFiniteHunger {
+        phase: &'static str,
+        entity_id: String,
+        hunger: f64,
+    },
+
+    #[error("after {phase}: entity {entity_id} stands on missing tile {tile}")]
+    UnknownTile {
+        phase: &'static str,
+        entity_id: String,
+        tile: String,
+    },
+
+    #[error("after {phase}: the dead went from {before} to {after} (corpses can't vanish)")]
+    LostCorpses {
+        phase: &'static str,
+        before: u64,
+        after: u64,
+    },
+
+    #[error("after {phase}: corpse of {entity_id} died at tick {death_tick}, after tick {tick}")]
+    CorpseFromFuture {
+        phase: &'static str,
+        entity_id: String,
+        death_tick: u64,
+        tick: u64,
+    },
+
+    #[error("at tick {tick}: {phase} drew {actual} times from the RNG, its budget says {expected}")]
+    RngBudgetExceeded {
+        tick: u64,
+        phase: String,
+        expected: u64,
+        actual: u64,
+    },
+}
+
+/// Checks one tick's phases against the state they started from
+#[derive(Debug, Clone)]
+pub struct Contract {
+    /// Dead accounted for so far (see `accounted_dead`)
+    dead: u64,
+}
+
+impl Contract {
+    pub fn new(state: &GameState) -> Self {
+        Self { dead: accounted_dead(state) }
+    }
+
+    /// Check `state` as `phase` left it
+    pub fn check(&mut self, state: &GameState, phase: &'static str) -> Result<(), ContractViolation> {
+        // Resources, colony-wide and in every stockpile
+        let pools = std::iter::once(&state.resources).chain(state.stockpiles.values());
+        for pool in pools {
+            if let Some((resource, amount)) = det::entries(pool.amounts.iter()).into_iter().find(|(_, amount)| !amount.is_finite()) {
+                return Err(ContractViolation::NonFiniteResource {
+                    phase,
+                    resource: resource.to_string(),
+                    amount: *amount,
+                });
+            }
+        }
+
+        for entity in state.entities.iter() {
+            if !entity.hunger.is_finite() {
+                return Err(ContractViolation::NonFiniteHunger {
+                    phase,
+                    entity_id: entity.id.clone(),
+                    hunger: entity.hunger,
+                });
+            }
+            if state.map.get_tile(&entity.tile).is_none() {
+                return Err(ContractViolation::UnknownTile {
+                    phase,
+                    entity_id: entity.id.clone(),
+                    tile: entity.tile.clone(),
+                });
+            }
+        }
+
+        // Corpses move from the graveyard to an undertaker to the processed
+        // count, but the total only ever grows
+        let dead = accounted_dead(state);
+        if dead < self.dead {
+            return Err(ContractViolation::LostCorpses { phase, before: self.dead, after: dead });
+        }
+        self.dead = dead;
+
+        if let Some(corpse) = state.graveyard.corpses.iter().find(|c| c.death_tick > state.tick) {
+            return Err(ContractViolation::CorpseFromFuture {
+                phase,
+                entity_id: corpse.entity_id.clone(),
+                death_tick: corpse.death_tick,
+                tick: state.tick,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Corpses waiting, being carried, and already processed
+pub fn accounted_dead(state: &GameState) -> u64 {
+    let carried = state.entities.iter()
+        .filter(|e| e.processing_corpse == Some(true))
+        .count();
+    state.graveyard.total_processed + state.graveyard.corpses.len() as u64 + carried as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::TickEngine;
+    use crate::types::entity::Entity;
+    use crate::types::system::System;
+    use crate::types::tile::Tile;
+    use std::collections::HashMap;
+
+    fn colony() -> GameState {
+        let mut state = GameState::default();
+        state.map.tiles.insert("origin".into(), Tile::new_empty("Origin".to_string(), 0, 0));
+        state.resources.set("fungus", 50.0);
+        state.entities.push(Entity::new_worker("w".to_string(), "origin".to_string()));
+        state.entities.push(Entity::new_undertaker("u".to_string(), "origin".to_string()));
+        state
+    }
+
+    #[test]
+    fn test_sound_colony_passes() {
+        let mut engine = TickEngine::new(7);
+        engine.set_strict(true);
+        let mut state = colony();
+        for _ in 0..1000 {
+            engine.try_tick(&mut state).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_violation_names_the_phase() {
+        let mut engine = TickEngine::new(7);
+        engine.set_strict(true);
+        let mut state = colony();
+        state.systems.insert(
+            "leak".to_string(),
+            System::new_generator("Leak".to_string(), HashMap::from([("dirt".to_string(), f64::NAN)])),
+        );
+        assert!(matches!(
+            engine.try_tick(&mut state),
+            Err(ContractViolation::NonFiniteResource { phase: "systems", ref resource, .. }) if resource == "dirt"
+        ));
+
+        let mut state = colony();
+        state.entities.push(Entity::new_worker("lost".to_string(), "nowhere".to_string()));
+        let violation = engine.try_tick(&mut state).unwrap_err();
+        assert_eq!(violation, ContractViolation::UnknownTile {
+            phase: "actions",
+            entity_id: "lost".to_string(),
+            tile: "nowhere".to_string(),
+        });
+
+        // Without strict mode the same state ticks on
+        engine.set_strict(false);
+        assert!(engine.try_tick(&mut state).is_ok());
+    }
+}
@@ -0,0 +1,104 @@
+//! Deterministic ordering: how the core breaks ties.
+//!
+//! Any phase that walks a collection without a fixed order (anything in a
+//! `HashMap`) or picks one of several equally good candidates goes through
+//! these helpers, so the outcome never depends on hash seeds, insertion
+//! order or platform. The rules are the same everywhere:
+//!
+//! - Collections are walked in ascending ID order.
+//! - Picking the smallest or largest by some key breaks ties on the lowest
+//!   ID; sorting by a key does too, and is stable.
+//! - Floats are compared with `f64::total_cmp` (wrap them in `OrdF64`), so
+//!   NaN sorts above everything instead of panicking or comparing equal.
+
+use std::cmp::Ordering;
+
+/// `items`, in ascending order
+pub fn sorted<T: Ord>(items: impl IntoIterator<Item = T>) -> Vec<T> {
+    let mut items: Vec<T> = items.into_iter().collect();
+    items.sort();
+    items
+}
+
+/// Map entries (`map.iter()`) in ascending key order
+pub fn entries<K: Ord, V>(entries: impl IntoIterator<Item = (K, V)>) -> Vec<(K, V)> {
+    let mut entries: Vec<(K, V)> = entries.into_iter().collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    entries
+}
+
+/// Sort `items` in ascending `id` order
+pub fn sort_by_id<T, I: Ord>(items: &mut [T], id: impl FnMut(&T) -> I) {
+    items.sort_by_key(id);
+}
+
+/// Sort `items` by `key`, ties by lowest `id`
+pub fn sort_by_key<T, K: Ord, I: Ord>(items: &mut [T], key: impl Fn(&T) -> K, id: impl Fn(&T) -> I) {
+    items.sort_by(|a, b| key(a).cmp(&key(b)).then_with(|| id(a).cmp(&id(b))));
+}
+
+/// The item with the smallest `key`, ties by lowest `id`
+pub fn min_by_key<T, K: Ord, I: Ord>(
+    items: impl IntoIterator<Item = T>,
+    key: impl Fn(&T) -> K,
+    id: impl Fn(&T) -> I,
+) -> Option<T> {
+    items.into_iter().min_by(|a, b| key(a).cmp(&key(b)).then_with(|| id(a).cmp(&id(b))))
+}
+
+/// The item with the largest `key`, ties by lowest `id`
+pub fn max_by_key<T, K: Ord, I: Ord>(
+    items: impl IntoIterator<Item = T>,
+    key: impl Fn(&T) -> K,
+    id: impl Fn(&T) -> I,
+) -> Option<T> {
+    items.into_iter().min_by(|a, b| key(b).cmp(&key(a)).then_with(|| id(a).cmp(&id(b))))
+}
+
+/// An `f64` with a total order, for sort and selection keys
+#[derive(Debug, Clone, Copy)]
+pub struct OrdF64(pub f64);
+
+impl PartialEq for OrdF64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OrdF64 {}
+
+impl PartialOrd for OrdF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrdF64 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Reverse;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_ties_go_to_the_lowest_id() {
+        let loads = HashMap::from([("c", 1), ("a", 2), ("b", 1), ("d", 2)]);
+
+        assert_eq!(entries(loads.iter()).into_iter().map(|(id, _)| *id).collect::<Vec<_>>(), ["a", "b", "c", "d"]);
+        assert_eq!(min_by_key(loads.iter(), |(_, n)| **n, |(id, _)| **id), Some((&"b", &1)));
+        assert_eq!(max_by_key(loads.iter(), |(_, n)| **n, |(id, _)| **id), Some((&"a", &2)));
+
+        let mut ranked: Vec<_> = loads.into_iter().collect();
+        sort_by_key(&mut ranked, |(_, n)| Reverse(*n), |(id, _)| *id);
+        assert_eq!(ranked, [("a", 2), ("d", 2), ("b", 1), ("c", 1)]);
+
+        let mut floats = [OrdF64(f64::NAN), OrdF64(1.5), OrdF64(-0.0), OrdF64(0.0)];
+        floats.sort();
+        assert_eq!(floats.iter().map(|f| f.0.to_string()).collect::<Vec<_>>(), ["-0", "0", "1.5", "NaN"]);
+    }
+}
@@ -0,0 +1,43 @@
+//! Stable digests of serializable data.
+//!
+//! Values are hashed through their canonical JSON form (object keys
+//! sorted), so the digest doesn't depend on HashMap order, platform, or
+//! Rust version. FNV-1a is plenty for spotting corruption; it is not a
+//! cryptographic hash.
+
+use serde::Serialize;
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// 64-bit FNV-1a of `bytes`
+pub fn fnv1a64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET, |hash, &b| (hash ^ b as u64).wrapping_mul(FNV_PRIME))
+}
+
+/// The canonical JSON text of `value`: compact, with object keys sorted
+pub fn canonical_json<T: Serialize + ?Sized>(value: &T) -> Result<String, serde_json::Error> {
+    // serde_json's Map is ordered by key unless `preserve_order` is on
+    serde_json::to_value(value).and_then(|v| serde_json::to_string(&v))
+}
+
+/// Digest of `value`'s canonical JSON
+pub fn digest<T: Serialize + ?Sized>(value: &T) -> Result<u64, serde_json::Error> {
+    canonical_json(value).map(|json| fnv1a64(json.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_digest_ignores_map_order() {
+        let a: HashMap<_, _> = (0..50).map(|i| (format!("k{}", i), i)).collect();
+        let b: HashMap<_, _> = (0..50).rev().map(|i| (format!("k{}", i), i)).collect();
+        assert_eq!(digest(&a).unwrap(), digest(&b).unwrap());
+
+        // Known FNV-1a vector
+        assert_eq!(fnv1a64(b"a"), 0xaf63dc4c8601ec8c);
+    }
+}
@@ -4,28 +4,155 @@
 //! No I/O, no printing, no decisions about "what's interesting."
 //! Just pure state → state transformations that emit events.
 
-use std::collections::HashMap;
-
-use crate::events::{EventKind, TickEvents};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
+
+use crate::events::{resource_deltas, BatchEvents, Event, EventFilter, EventKind, EventKindTag, TickCounters, TickEvents, TickMetrics, TickReport, Verbosity};
+use crate::budget::RngBudget;
+use crate::contract::{Contract, ContractViolation};
+use crate::det;
+use crate::guard;
+use crate::advance::{self, TickRefused};
+use crate::digest::{digest, fnv1a64};
 use crate::rng::SeededRng;
-use crate::types::entity::{AntRole, DeathCause, Entity, EntityType, VisitorType};
-use crate::types::graveyard::Corpse;
+use crate::timeline::{OfflineDeath, OfflineReport, TimeAnomaly, TimeContext, Timeline};
+use crate::input::{Rejected, TickInput, TickOutput};
+use crate::types::heredity::Trait;
+use crate::types::entity::{timer, AntRole, DeathCause, Entity, EntityId, EntityType, Harm, LifeStage, VisitorType};
+use crate::types::expedition::{Expedition, ExpeditionError, EXPEDITION_ACTION};
+use crate::types::demographics;
+use crate::types::graveyard::{Corpse, Graveyard};
+use crate::types::legacy::{AbandonError, Ghost, LegacySeed, Ruin, RuinError};
+use crate::types::key::Key;
+use crate::types::logistics::{Depot, HaulJob, LogisticsMode, Route};
+use crate::types::resource::Resources;
+use crate::types::outpost::{Outpost, OutpostError, SUPPLY_ACTION};
+use crate::types::hosting::{self, HostingEnd, HostingError, HOST_ACTION};
+use crate::types::naming::{self, Named};
+use crate::types::morale::{self, Mood};
+use crate::types::convergence;
+use crate::types::pheromone;
+use crate::types::daynight::{self, DayPhase};
+use crate::types::hazard::{Hazard, Struck};
+use crate::types::weather::Sky;
+use crate::types::pause::Subsystem;
+use crate::types::policy::{Task, WorkPolicy};
+use crate::types::config::SimConfig;
+use crate::types::meals::{MealReports, MealTally};
+use crate::types::silence::SilenceStage;
+use crate::types::memory::Experience;
+use crate::types::reflection::{Reflection, ReflectionRef};
 use crate::types::state::GameState;
-use crate::types::system::CorpseBoost;
-
-/// Configuration constants for the simulation
+use crate::types::feature;
+use crate::types::bookkeeping::Bookkeeping;
+use crate::types::wonder::Wonder;
+use crate::types::threat::{Odds, Threat};
+use crate::types::whisper::{Promise, Whisper};
+use crate::types::scenario::{Outcome, Scenario};
+use crate::types::action::{check_free_form, Action, ActionEffects};
+use crate::types::blueprint::{ActionKind, BlueprintError};
+use crate::types::command::{Command, CommandError};
+use crate::types::group::{Group, GroupError, Intent};
+use crate::types::brood::{Brood, BroodLoss};
+use crate::types::transmission::Transmission;
+use crate::types::excavation::{self, Excavation, ExcavationError, Vein};
+use crate::types::system::{CorpseBoost, Suspicion, System, SystemType};
+use crate::types::tile::{GameMap, MapError, Tile, TileType, ORIGIN};
+
+/// Configuration constants for the simulation.
+///
+/// Durations (ticks, intervals, cooldowns) are given at the reference rate
+/// of 1 tick per second; the engine scales them to the state's tick rate
+/// with `GameState::ticks_for`.
 pub mod constants {
     // Entity lifecycle
     pub const DEFAULT_MAX_AGE: u64 = 7200; // 2 hours
     pub const HUNGER_THRESHOLD_EAT: f64 = 50.0;
     pub const HUNGER_GAIN_FROM_EATING: f64 = 30.0;
+    pub const MEAL_SHORTAGE_STOCK: f64 = 10.0; // meals below this stock count as a shortage
     pub const MAX_HUNGER: f64 = 100.0;
+    pub const MAX_HEALTH: f64 = 100.0;
+    pub const STARVATION_DAMAGE: f64 = 1.0; // health per second with an empty stomach
+    pub const BLIGHT_DAMAGE: f64 = 5.0; // health per second on a blighted tile
+    pub const SICKNESS_DAMAGE: f64 = 10.0; // health per second on a fully contaminated tile
+    pub const HEALTH_REGEN: f64 = 0.5; // health per second while well fed and unharmed
+    pub const MAX_MORALE: f64 = 100.0;
+    pub const MORALE_DRIFT: f64 = 0.01; // share of the gap to sanity closed per second
+    pub const FAMINE_MORALE_DRAIN: f64 = 2.0; // morale per second while famished
+    pub const DEATH_MORALE_SHOCK: f64 = 10.0; // morale per death on the ant's tile
+    pub const DESERTION_CHANCE: f64 = 0.01; // per second, at no morale
+    pub const MEMORY_CAPACITY: usize = 8; // memories an entity keeps; older ones fade
+    pub const FAMINE_HUNGER: f64 = 10.0; // eating from below this is remembered
 
     // Queen spawning
     pub const SPAWN_INTERVAL_TICKS: u64 = 1800; // 30 minutes
-    pub const SPAWN_COST_NUTRIENTS: f64 = 10.0;
-    pub const SPAWN_COST_FUNGUS: f64 = 10.0;
-    pub const MIN_RESOURCES_TO_SPAWN: f64 = 15.0;
+    pub const SPAWN_RESERVE: f64 = 5.0; // kept back on top of each spawn cost (costs live in SimConfig)
+    pub const BROOD_DEVELOPMENT_TICKS: u64 = 300; // 5 minutes of feeding
+    pub const EGG_TICKS: u64 = 120; // before an egg becomes a larva
+    pub const PUPA_TICKS: u64 = 180; // before a pupa emerges
+    pub const EGG_FAILURE_CHANCE: f64 = 0.05;
+    pub const LARVA_STARVATION_LIMIT: u64 = 600; // seconds unfed before a larva dies
+    pub const BROOD_FOOD_PER_TICK: f64 = 0.01; // fungus per larva per second
+    pub const NURSE_CAPACITY: u64 = 4; // larvae one nurse feeds each tick
+    pub const UNTENDED_BROOD_SLOWDOWN: u64 = 4; // untended larvae grow one tick in this many
+    pub const BROOD_NEGLECT_LIMIT: u64 = 3600; // seconds untended before a larva dies
+    pub const UNTENDED_QUEEN_SLOWDOWN: u64 = 2; // laying interval multiplier with no nurse about
+    pub const QUEEN_LIFESPAN: u64 = 43200; // 12 hours from her crowning
+    pub const SUCCESSION_WINDOW: u64 = 3600; // a princess is laid this long before the queen's end
+    pub const TRAIT_INHERIT_CHANCE: f64 = 0.5; // each of the queen's traits
+    pub const TRAIT_MUTATION_CHANCE: f64 = 0.05; // each trait she lacks
+    pub const FOOD_RUNWAY_WARNING: f64 = 300.0; // seconds of food left before warning
+    pub const HEALTH_FOOD_HORIZON: f64 = 3600.0; // seconds of food runway that count as fully fed
+    pub const HEALTH_CHANGE_THRESHOLD: f64 = 5.0; // points the health score moves before it is reported
+    pub const SUMMARY_TOP_RESOURCES: usize = 5; // stores listed in a state summary
+    pub const EVENT_QUEUE_CAPACITY: usize = 1024; // plugin events held before enqueueing is refused
+
+    // Work
+    pub const DIG_YIELD: f64 = 0.01; // dirt per digger per second
+    pub const HARVEST_YIELD: f64 = 0.02; // resource per forager per second, from a resource tile
+
+    // Movement
+    pub const STEP_SECONDS: u64 = 5; // to walk from one tile to the next
+    pub const TRAIL_DEPOSIT: f64 = 1.0; // trail laid per step
+    pub const TRAIL_DECAY: f64 = 0.02; // share of a trail that fades each second
+
+    // Weather
+    pub const SEASON_LENGTH: u64 = 7200; // 2 hours a season, 8 a year
+    pub const WEATHER_SPELL: u64 = 600; // 10 minutes between rolls of the sky
+    pub const RAIN_CHANCE: f64 = 0.2; // per spell, before the season's odds
+    pub const DROUGHT_CHANCE: f64 = 0.1; // likewise
+    pub const WEATHER_STREAM: u64 = 0x7261696e; // keeps the sky off the Outside's own weather
+
+    // Day and night
+    pub const DAY_LENGTH: u64 = 1200; // 20 minutes from dawn to dawn
+    pub const NIGHT_SHARE: f64 = 0.4; // of each day
+    pub const NIGHT_WORK_FACTOR: f64 = 0.5; // share of their work ants do in the dark
+
+    // Floods and cave-ins
+    pub const FLOOD_CHANCE: f64 = 1.0 / 3600.0; // per second per low tile, while it rains
+    pub const FLOOD_DURATION: u64 = 600; // seconds a flood holds its tile
+    pub const CAVE_IN_CHANCE: f64 = 1.0 / 1800.0; // per second per fresh tile
+    pub const CAVE_IN_WINDOW: u64 = 1800; // seconds a dug tile stays fresh
+    pub const CAVE_IN_DURATION: u64 = 300; // seconds to dig a cave-in back out
+    pub const HAZARD_LETHALITY: f64 = 0.5; // chance each entity caught in one dies
+    pub const HAZARD_STREAM: u64 = 0x68617a64; // keeps flood and cave-in rolls off the main RNG stream
+
+    // Excavation
+    pub const EXCAVATION_WORK: f64 = 10.0; // dirt's worth of digging to open a tile, about 17 minutes for one digger
+    pub const ORE_VEIN_CHANCE: f64 = 0.2; // of a dug square holding ore
+    pub const CRYSTAL_POCKET_CHANCE: f64 = 0.05; // likewise, crystals
+    pub const ORE_VEIN_STOCK: f64 = 200.0;
+    pub const CRYSTAL_POCKET_STOCK: f64 = 40.0;
+    pub const EXCAVATION_STREAM: u64 = 0x64696767; // salts the per-square vein rolls
+
+    // Generated maps
+    pub const MAP_SIZE: usize = 12; // tiles in a generated cavern, the origin included
+    pub const MAP_MAX_SIZE: usize = 4096; // the most a generated cavern grows to, whatever it's asked for
+    pub const MAP_RESOURCE_SHARE: f64 = 0.3; // of its tiles past the heap holding a vein
+    pub const MAP_FUNGUS_STOCK: f64 = 100.0;
+    pub const MAP_LOOP_CHANCE: f64 = 0.2; // per side-by-side pair not already joined
+    pub const MAPGEN_STREAM: u64 = 0x6d617073; // keeps generation apart from the engine seeded alike
 
     // Undertaker
     pub const CORPSE_PROCESSING_TICKS: u64 = 120;
@@ -33,14 +160,66 @@ pub mod constants {
     pub const CORPSE_BOOST_DURATION: u64 = 600;
     pub const CONTAMINATION_PER_CORPSE: f64 = 0.01;
     pub const BLIGHT_DURATION: u64 = 300;
+    pub const BLIGHT_RISK_WARNING: f64 = 0.01; // per-tick blight chance worth a warning
+
+    // Expeditions
+    pub const EXPEDITION_LOSS_CHANCE: f64 = 0.15; // per member
+    pub const EXPEDITION_DISCOVERY_CHANCE: f64 = 0.2;
+    pub const EXPEDITION_LOOT: [(&str, f64); 4] = [
+        ("dirt", 10.0),
+        ("ore", 3.0),
+        ("crystals", 1.0),
+        ("strange_matter", 0.2),
+    ]; // base amount per surviving member
+    pub const REMOTE_TILE_MIN_DISTANCE: u64 = 5;
+    pub const REMOTE_TILE_MAX_DISTANCE: u64 = 12;
+    pub const REMOTE_TILE_STOCK: f64 = 100.0; // what a discovered hollow holds
+
+    // Outposts
+    pub const OUTPOST_MIN_DISTANCE: u64 = 5; // grid distance from origin
+    pub const OUTPOST_ESTABLISH_COST_FUNGUS: f64 = 10.0;
+    pub const OUTPOST_PRODUCTION_RATE: f64 = 0.02;
+    pub const OUTPOST_SUPPLY_COST_FUNGUS: f64 = 5.0;
+    pub const OUTPOST_SUPPLY_DURATION: u64 = 1800; // 30 minutes per delivery
+    pub const OUTPOST_HAUL_TICKS_PER_DISTANCE: u64 = 30;
+    pub const OUTPOST_DECAY_PER_TICK: f64 = 1.0 / 600.0; // lost 10 minutes after supplies run out
+
+    // Hosting visitors
+    pub const HOST_INTERVAL: u64 = 300; // 5 minutes between rounds
+    pub const HOST_COST_FUNGUS: f64 = 10.0;
+    pub const HOST_COST_CRYSTALS: f64 = 1.0;
+    pub const HOST_EXTENSION: u64 = 600; // the first round; later ones add less
+    pub const HOST_MAX_EXTENSION: u64 = 3600; // in all, per visitor
+
+    // Logistics
+    pub const HAULER_CAPACITY: f64 = 1.0; // units moved per hauler per tick
+    pub const CARRY_CAPACITY: f64 = 5.0; // goods one ant carries at once
+    pub const PRODUCTION_HISTORY: usize = 60; // ticks of output each system remembers
+    pub const LOGISTICS_BUFFER_TICKS: f64 = 60.0; // input kept on hand at each tile
+
+    // Shrine
+    pub const SHRINE_WINDOW_TICKS: u64 = 600; // 10 minutes
+    pub const SHRINE_HALF_EFFICIENCY_VOLUME: f64 = 5.0; // volume at which output halves
 
     // Receiver
     pub const SUMMON_COST: f64 = 2.0;
     pub const SUMMON_COOLDOWN: u64 = 600; // 10 minutes
     pub const SUMMON_CHANCE: f64 = 0.3;
     pub const LISTENING_DRAIN: f64 = 0.0005;
+    pub const OUTSIDE_PERIOD: u64 = 1800; // seconds between independent Outside readings
+    pub const STATIC_SURGE_LEVEL: f64 = 0.8;
+    pub const CONJUNCTION_LEVEL: f64 = 0.9; // Outside alignment at which it's in conjunction
+    pub const CONVERGENCE_ANTENNAS: usize = 2; // antenna tiles needed to hear a convergence
+    pub const CONVERGENCE_THIRD_CHANCE: f64 = 0.5; // a convergence brings three rather than two
+    pub const WANDERER_GIFT: f64 = 1.0; // strange matter a wanderer leaves behind
+    pub const MAX_TRANSMISSIONS: usize = 100;
     pub const MAINTENANCE_INTERVAL: u64 = 3600;
     pub const MAINTENANCE_COST_STRANGE_MATTER: f64 = 1.0;
+    pub const MAINTENANCE_WARNING: u64 = 300; // warn this long before unaffordable maintenance
+    pub const RECEIVER_DORMANT_AFTER: u64 = 7200; // silent this long, the receiver goes dormant
+    pub const RECEIVER_DERELICT_AFTER: u64 = 86400; // and after a day, derelict
+    pub const RECEIVER_DORMANT_COST_SCALE: f64 = 3.0; // restoration costs, times the maintenance cost
+    pub const RECEIVER_DERELICT_COST_SCALE: f64 = 10.0;
 
     // Hungry visitor
     pub const HUNGRY_INFLUENCE_CONSUME: f64 = 0.1;
@@ -50,11 +229,71 @@ pub mod constants {
     // Boredom
     pub const BOREDOM_THRESHOLD: u64 = 60;
 
+    // Perception (sanity-gated unreliable events)
+    pub const PERCEPTION_SANITY_THRESHOLD: f64 = 30.0;
+    pub const PERCEPTION_GLITCH_CHANCE: f64 = 0.02; // at zero sanity
+    pub const PERCEPTION_MAX_DISTORTION: f64 = 0.5;
+
+    // Reflections
+    pub const REFLECTION_STILLNESS_TICKS: u64 = 3000;
+    pub const REFLECTION_AFTERMATH_DELAY: u64 = 300;
+    pub const REFLECTION_MAJOR_THRESHOLD: f64 = 100.0;
+    pub const MAX_REFLECTION_REFS: usize = 8;
+    pub const MAX_REFLECTIONS: usize = 200;
+
+    // Demographics
+    pub const DEMOGRAPHIC_BUCKET_TICKS: u64 = 1800; // 30-minute age brackets
+
+    // Abandonment
+    pub const LEGACY_RESIDUAL_FRACTION: f64 = 0.1; // share of stores left in the ruins
+    pub const LEGACY_MAX_GHOSTS: usize = 32;
+
+    // Ruins
+    pub const RUIN_GAP: i32 = 2; // columns between the map's east edge and ingested ruins
+    pub const RUIN_ECHO_CHANCE: f64 = 0.001; // per haunted ruin per second
+    pub const RUIN_ECHO_STREAM: u64 = 0x6563686f; // keeps echo rolls off the main RNG stream
+
+    // Wonders
+    pub const WONDER_CHANCE: f64 = 1.0 / 1_209_600.0; // per second: about one per two weeks
+    pub const WONDER_STREAM: u64 = 0x776f6e64; // keeps wonder rolls off the main RNG stream
+
+    // Threats
+    pub const RAID_CHANCE: f64 = 1.0 / 21_600.0; // per second at reference wealth: about one every six hours
+    pub const RAID_COOLDOWN: u64 = 3600; // seconds of peace after an incursion
+    pub const RAID_WEALTH_REFERENCE: f64 = 500.0; // stores worth this draw raids at the base chance and strength
+    pub const RAID_MAX_PRESSURE: f64 = 4.0; // richer colonies draw no more than this times the base
+    pub const RAID_STRENGTH: f64 = 3.0; // an incursion at reference wealth, before the roll
+    pub const SOLDIER_STRENGTH: f64 = 1.0;
+    pub const SOLDIER_ORE_COST: f64 = 5.0; // ore a soldier costs to lay, on top of food
+    pub const CIVILIAN_STRENGTH: f64 = 0.1; // any other waking ant that stands and fights
+    pub const RAID_CASUALTY_CHANCE: f64 = 0.2; // per defender at even odds
+    pub const RAID_PLUNDER: f64 = 0.25; // share of each store raiders carry off from an undefended colony
+    pub const THREAT_STREAM: u64 = 0x72616964; // keeps threat rolls off the main RNG stream
+
+    // Influence and whispers
+    pub const INFLUENCE_SOFT_CAP: f64 = 100.0; // influence past this drains away
+    pub const INFLUENCE_DECAY: f64 = 0.001; // share of the excess lost per second
+    pub const WHISPER_CHANCE: f64 = 1.0 / 3600.0; // per second: about one an hour, while influence lasts
+    pub const WHISPER_COST: f64 = 1.0; // influence a whisper spends
+    pub const WHISPER_INSIGHT: f64 = 0.5; // insight an insightful whisper gives
+    pub const WHISPER_PROMISE_WORTH: f64 = 10.0; // a promised gift's worth when whispered
+    pub const WHISPER_PROMISE_DELAY: u64 = 1800; // seconds until a promise comes due
+    pub const WHISPER_GIFTS: [&str; 4] = ["nutrients", "ore", "crystals", "strange_matter"];
+    pub const WHISPER_STREAM: u64 = 0x77686973; // keeps whisper rolls off the main RNG stream
+
     // Thresholds to check
     pub const RESOURCE_THRESHOLDS: [f64; 7] = [10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0];
 
     // Offline Progress
     pub const MAX_OFFLINE_TICKS: u64 = 3600;
+    pub const TIME_TOLERANCE: f64 = 5.0; // seconds of backwards clock drift to ignore
+    pub const TIME_JUMP_LIMIT: f64 = 30.0 * 86400.0; // longer absences are flagged as suspicious
+
+    // Parallel entity phase (`parallel` feature): below this, threads cost more than they save
+    pub const PARALLEL_ENTITY_THRESHOLD: usize = 1024;
+
+    // Speed multipliers (fixed-point, so fractional speeds never drift)
+    pub const SUB_TICKS_PER_TICK: u64 = 1000;
 }
 
 /// The tick engine processes one tick at a time
@@ -67,6 +306,29 @@ pub struct TickEngine {
 
     /// Last summon attempt tick (for receiver)
     last_summon_tick: u64,
+
+    /// Config stamped onto every state this engine ticks; None leaves each
+    /// state's own `config` in charge
+    config: Option<SimConfig>,
+
+    /// Check invariants after every phase (see `contract`)
+    strict: bool,
+
+    /// Repair invalid external edits before every tick (see `guard`)
+    soft_fail: bool,
+
+    /// Events every tick keeps; the verbosity passed to `tick_filtered`
+    /// narrows it further
+    filter: EventFilter,
+
+    /// RNG budget being recorded or checked (see `budget`)
+    rng_audit: Option<RngAudit>,
+}
+
+/// What an engine does with each tick's RNG draws
+enum RngAudit {
+    Record(RngBudget),
+    Check(RngBudget),
 }
 
 impl TickEngine {
@@ -76,851 +338,6959 @@ impl TickEngine {
             seed,
             last_spawn_tick: 0,
             last_summon_tick: 0,
+            config: None,
+            strict: false,
+            soft_fail: false,
+            filter: EventFilter::all(),
+            rng_audit: None,
+        }
+    }
+
+    /// Create a tick engine that runs every state under `config`. The
+    /// config is written into the state on each tick, so it is saved
+    /// alongside it.
+    pub fn new_with_config(seed: u64, config: SimConfig) -> Self {
+        Self {
+            config: Some(config),
+            ..Self::new(seed)
+        }
+    }
+
+    /// The base seed every tick's RNG derives from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Turn strict mode on or off. A strict engine checks the state's
+    /// invariants after every phase: `try_tick` returns the first violation
+    /// and `tick` panics with it.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Turn soft-fail mode on or off. An engine in soft-fail mode fixes
+    /// invalid edits made between ticks (by plugins or hosts) before
+    /// running a tick, emitting `ExternalEditCorrected` for each.
+    pub fn set_soft_fail(&mut self, soft_fail: bool) {
+        self.soft_fail = soft_fail;
+    }
+
+    pub fn is_soft_fail(&self) -> bool {
+        self.soft_fail
+    }
+
+    /// Keep only the events `filter` allows from now on. Ticks still raise
+    /// every event, and phases that read them (reflections) see them all;
+    /// the filter only trims what a tick hands back, so the simulation is
+    /// the same under any filter.
+    pub fn set_event_filter(&mut self, filter: EventFilter) {
+        self.filter = filter;
+    }
+
+    pub fn event_filter(&self) -> &EventFilter {
+        &self.filter
+    }
+
+    /// Start recording how many RNG draws each phase makes, tick by tick,
+    /// into a fresh budget. `take_rng_budget` hands it back.
+    pub fn record_rng_budget(&mut self) {
+        self.rng_audit = Some(RngAudit::Record(RngBudget::new()));
+    }
+
+    /// Check every tick's draws against `budget` from now on. A phase that
+    /// drifts fails the tick if the engine is strict, and emits
+    /// `RngBudgetExceeded` otherwise.
+    pub fn check_rng_budget(&mut self, budget: RngBudget) {
+        self.rng_audit = Some(RngAudit::Check(budget));
+    }
+
+    /// Stop recording or checking, returning the budget
+    pub fn take_rng_budget(&mut self) -> Option<RngBudget> {
+        self.rng_audit.take().map(|audit| match audit {
+            RngAudit::Record(budget) | RngAudit::Check(budget) => budget,
+        })
+    }
+
+    /// The config this engine imposes, if any
+    pub fn config(&self) -> Option<&SimConfig> {
+        self.config.as_ref()
+    }
+
+    /// Digest of everything that shapes how this engine runs a state, for
+    /// save envelopes
+    pub fn config_digest(&self) -> u64 {
+        match self.config.as_ref().and_then(|config| digest(config).ok()) {
+            Some(config) => fnv1a64(&[self.seed.to_le_bytes(), config.to_le_bytes()].concat()),
+            None => fnv1a64(&self.seed.to_le_bytes()),
+        }
+    }
+
+    /// An engine built with a config runs every state under it
+    fn impose_config(&self, state: &mut GameState) {
+        if let Some(config) = &self.config {
+            if state.config != *config {
+                state.config = config.clone();
+            }
         }
     }
 
+    /// Tick of the last queen spawn (0 if the queen hasn't started yet)
+    pub fn last_spawn_tick(&self) -> u64 {
+        self.last_spawn_tick
+    }
+
+    /// Tick of the last summon attempt (0 if none yet)
+    pub fn last_summon_tick(&self) -> u64 {
+        self.last_summon_tick
+    }
+
     /// Process a single tick, returning events that occurred
     pub fn tick(&mut self, state: &mut GameState) -> TickEvents {
+        self.tick_filtered(state, Verbosity::Debug)
+    }
+
+    /// Process a single tick, keeping only events at `min` or above. The
+    /// simulation is identical at every level; quieter levels just hand
+    /// back less of the chatter.
+    ///
+    /// Panics if the engine is strict and a phase breaks an invariant.
+    pub fn tick_filtered(&mut self, state: &mut GameState, min: Verbosity) -> TickEvents {
+        match self.run_tick(state, min) {
+            Ok(events) => events,
+            Err(violation) => panic!("strict mode: {violation}"),
+        }
+    }
+
+    /// Process a single tick if the state is at `expected` and fit to run,
+    /// refusing otherwise (see `advance`): an ended or abandoned run, a
+    /// state already advanced past `expected`, or one whose clock was moved
+    /// since it was last ticked. A refused tick changes nothing.
+    pub fn advance(&mut self, state: &mut GameState, expected: u64) -> Result<TickEvents, TickRefused> {
+        advance::check(state, Some(expected))?;
+        Ok(self.tick(state))
+    }
+
+    /// Process a single tick, returning the first broken invariant instead
+    /// of panicking when the engine is strict. Without strict mode this
+    /// never fails.
+    pub fn try_tick(&mut self, state: &mut GameState) -> Result<TickEvents, ContractViolation> {
+        self.run_tick(state, Verbosity::Debug)
+    }
+
+    /// Process a single tick keeping every event, whatever the filter;
+    /// for reports that have to see what the filter would drop
+    fn tick_unfiltered(&mut self, state: &mut GameState) -> TickEvents {
+        match self.run_tick_unfiltered(state) {
+            Ok(events) => events,
+            Err(violation) => panic!("strict mode: {violation}"),
+        }
+    }
+
+    fn run_tick(&mut self, state: &mut GameState, min: Verbosity) -> Result<TickEvents, ContractViolation> {
+        let mut events = self.run_tick_unfiltered(state)?;
+        events.retain(&self.filter.clone().at_least(min));
+        Ok(events)
+    }
+
+    /// One tick with every event it raises. Nothing the tick does may
+    /// depend on the engine's filter, which is applied to the result.
+    fn run_tick_unfiltered(&mut self, state: &mut GameState) -> Result<TickEvents, ContractViolation> {
         let mut events = TickEvents::new();
+        if state.is_abandoned() || state.is_ended() {
+            return Ok(events);
+        }
+        let mut checks = Checkpoints {
+            contract: self.strict.then(|| Contract::new(state)),
+            draws: self.rng_audit.is_some().then(Vec::new),
+            calls: 0,
+        };
+        if let Some(bookkeeping) = &state.bookkeeping {
+            self.last_spawn_tick = bookkeeping.last_spawn_tick;
+            self.last_summon_tick = bookkeeping.last_summon_tick;
+        }
+        // A clock wound back behind the engine's memory restarts the
+        // timers from now rather than underflowing them
+        self.last_spawn_tick = self.last_spawn_tick.min(state.tick);
+        self.last_summon_tick = self.last_summon_tick.min(state.tick);
         let tick = state.tick + 1;
         state.tick = tick;
 
         // Create RNG for this tick
         let mut rng = SeededRng::from_tick(self.seed, tick);
 
+        // Unseeded Outside weather follows the engine's seed from here on
+        if state.outside_seed == 0 {
+            state.outside_seed = self.seed;
+        }
+
+        self.impose_config(state);
+
+        // Fix what plugins broke between ticks before any phase sees it
+        if self.soft_fail {
+            for correction in guard::repair(state) {
+                events.push(tick, EventKind::ExternalEditCorrected { correction });
+            }
+        }
+
         // Store previous resource amounts for threshold checking
-        let prev_resources: HashMap<String, f64> = state.resources.amounts.clone();
+        let prev_resources: HashMap<Key, f64> = state.resources.amounts.clone();
 
         // 1. Process action queue
-        self.process_actions(state, &mut events);
+        if state.is_running(Subsystem::Actions) {
+            self.process_actions(state, &mut events, &mut rng);
+        }
+        checks.after(state, &rng, "actions")?;
 
         // 2. Process systems (resource generation/consumption)
-        self.process_systems(state, &mut events);
+        if state.is_running(Subsystem::Systems) {
+            self.process_systems(state, &mut events);
+        }
+        checks.after(state, &rng, "systems")?;
 
         // 3. Process entities (aging, hunger, eating, death)
-        self.process_entities(state, &mut events);
+        if state.is_running(Subsystem::Entities) {
+            self.process_entities(state, &mut events);
+        }
+        checks.after(state, &rng, "entities")?;
 
         // 4. Process undertakers (corpse collection)
-        self.process_undertakers(state, &mut events, &mut rng);
+        if state.is_running(Subsystem::Undertakers) {
+            self.process_undertakers(state, &mut events, &mut rng);
+        }
+        checks.after(state, &rng, "undertakers")?;
 
         // 5. Process contamination and blight
-        self.process_blight(state, &mut events, &mut rng);
+        if state.is_running(Subsystem::Blight) {
+            self.process_blight(state, &mut events, &mut rng);
+        }
+        checks.after(state, &rng, "blight")?;
 
         // 6. Process queen spawning
-        self.process_queen(state, &mut events, &mut rng);
+        if state.is_running(Subsystem::Queen) {
+            self.process_queen(state, &mut events, &mut rng);
+        }
+        checks.after(state, &rng, "queen")?;
 
         // 7. Process receiver and visitors
-        self.process_receiver(state, &mut events, &mut rng);
+        if state.is_running(Subsystem::Receiver) {
+            self.process_receiver(state, &mut events, &mut rng);
+        }
+        checks.after(state, &rng, "receiver")?;
 
         // 8. Process visitor behaviors
-        self.process_visitors(state, &mut events);
+        if state.is_running(Subsystem::Visitors) {
+            self.process_visitors(state, &mut events);
+        }
+        checks.after(state, &rng, "visitors")?;
 
-        // 9. Check resource thresholds
+        // 9. Process outposts (supplied production, unsupplied decay)
+        if state.is_running(Subsystem::Outposts) {
+            self.process_outposts(state, &mut events);
+        }
+        checks.after(state, &rng, "outposts")?;
+
+        // 10. Process hauling (local logistics only)
+        if state.is_running(Subsystem::Hauling) {
+            self.process_hauling(state, &mut events);
+        }
+        checks.after(state, &rng, "hauling")?;
+
+        // 11. Check resource thresholds (never paused)
         self.check_thresholds(state, &prev_resources, &mut events);
+        checks.after(state, &rng, "thresholds")?;
 
-        // 10. Process boredom
-        self.process_boredom(state, &mut events);
+        // 12. Process boredom
+        if state.is_running(Subsystem::Boredom) {
+            self.process_boredom(state, &mut events);
+        }
+        checks.after(state, &rng, "boredom")?;
 
-        events
-    }
+        // 13. Process perception (unreliable events at low sanity)
+        if state.is_running(Subsystem::Perception) {
+            self.process_perception(state, &mut events, &mut rng);
+        }
+        checks.after(state, &rng, "perception")?;
 
-    /// Process offline progress
-    pub fn process_offline_progress(&mut self, state: &mut GameState, current_timestamp: f64) -> TickEvents {
-        let events = TickEvents::new();
+        // 14. Process reflection triggers
+        if state.is_running(Subsystem::Reflections) {
+            self.process_reflections(state, &mut events);
+        }
+        checks.after(state, &rng, "reflections")?;
 
-        let last_save = match state.last_save_timestamp {
-            Some(ts) => ts,
-            None => return events,
-        };
+        // 15. Process echoes from past runs' ruins
+        if state.is_running(Subsystem::Echoes) {
+            self.process_echoes(state, &mut events);
+        }
+        checks.after(state, &rng, "echoes")?;
 
-        let elapsed_seconds = current_timestamp - last_save;
-        if elapsed_seconds <= 0.0 {
-            return events;
+        // 16. Roll for a wonder
+        if state.is_running(Subsystem::Wonders) && state.has_feature(feature::WONDERS) {
+            self.process_wonders(state, &mut events);
         }
+        checks.after(state, &rng, "wonders")?;
 
-        let ticks_to_apply = (elapsed_seconds as u64).min(constants::MAX_OFFLINE_TICKS);
+        // 17. Carry out group intents
+        if state.is_running(Subsystem::Groups) {
+            self.process_groups(state, &mut events);
+        }
+        checks.after(state, &rng, "groups")?;
 
-        if ticks_to_apply < 10 {
-            return events;
+        // 18. Assign idle workers and let them work
+        if state.is_running(Subsystem::Work) {
+            self.process_work(state, &mut events);
         }
+        checks.after(state, &rng, "work")?;
 
-        // Apply simplified ticks (resource generation only, no entity processing)
-        for _ in 0..ticks_to_apply {
-            let tick = state.tick + 1;
-            state.tick = tick;
-
-            // Process passive resource generation/consumption from systems
-            // This replicates the Python logic which does simplified system processing
-            // It manually checks consumes/generates instead of calling process_systems
-
-             // Collect system operations first to avoid borrow issues
-            let operations: Vec<_> = state.systems.iter()
-                .filter(|(_, system)| !system.is_disabled())
-                .filter_map(|(id, system)| {
-                    // Check if system can run
-                    if !system.can_run(&state.resources) {
-                        return None;
-                    }
+        // 19. Foragers harvest resource tiles
+        if state.is_running(Subsystem::Harvest) {
+            self.process_harvest(state, &mut events);
+        }
+        checks.after(state, &rng, "harvest")?;
 
-                    let consumes = system.consumes.clone().unwrap_or_default();
-                    let generates = system.generates.clone().unwrap_or_default();
-
-                     // Add corpse boost bonus for compost heap - Python doesn't do this in offline mode explicitly
-                     // but to be "better", maybe we should?
-                     // The Python code is:
-                     /*
-                        for system_id, system in state["systems"].items():
-                            can_run = True
-                            if "consumes" in system:
-                                ...
-                            if can_run:
-                                if "consumes" in system: ...
-                                if "generates" in system: ...
-                     */
-                     // It does NOT invoke the full system logic (which might have side effects).
-                     // However, the Rust system logic is mostly resources.
-                     // The main difference is "corpse boost" which is dynamic in Rust.
-
-                     // I will stick to the simplified logic as requested by "move offline progress calculation into the core"
-                     // The Python code doesn't seem to account for corpse boost in offline mode explicitly?
-                     // Wait, the Python code accesses `system["generates"]` directly.
-                     // If corpse boost modifies `generates` in place in Python, then it works.
-                     // In Rust, corpse boost is calculated dynamically in `process_systems`.
-                     // I'll stick to basic `generates` to match Python behavior unless I want to improve it.
-                     // I'll match Python behavior for now.
-
-                    Some((id.clone(), consumes, generates))
-                })
-                .collect();
+        // 20. Walk ants toward where they're needed
+        if state.is_running(Subsystem::Movement) && state.has_feature(feature::MOVEMENT) {
+            self.process_movement(state, &mut events);
+        }
+        checks.after(state, &rng, "movement")?;
 
-            // Apply operations
-            for (_system_id, consumes, generates) in operations {
-                // Consume resources
-                for (resource, amount) in &consumes {
-                    state.resources.add(resource, -amount);
-                }
+        // 21. Roll for an incursion and defend against it
+        if state.is_running(Subsystem::Threats) && state.has_feature(feature::RAIDS) && state.config.raids {
+            self.process_threats(state, &mut events);
+        }
+        checks.after(state, &rng, "threats")?;
 
-                // Generate resources
-                for (resource, amount) in &generates {
-                    state.resources.add(resource, *amount);
-                }
-            }
+        // 22. Drain surplus influence, keep promises, roll for a whisper
+        if state.is_running(Subsystem::Whispers) && state.has_feature(feature::WHISPERS) {
+            self.process_whispers(state, &mut events);
+        }
+        checks.after(state, &rng, "whispers")?;
+
+        // 23. Move the ants' morale; the despairing may desert
+        if state.is_running(Subsystem::Morale) && state.has_feature(feature::MORALE) {
+            self.process_morale(state, &mut events);
+        }
+        checks.after(state, &rng, "morale")?;
+
+        // 24. Turn the weather, which sets the next tick's output and hunger
+        if state.is_running(Subsystem::Weather) && state.has_feature(feature::SEASONS) {
+            self.process_weather(state, &mut events);
+        }
+        checks.after(state, &rng, "weather")?;
 
-            // Process entity hunger (reduced rate)
-            // Python:
-            // entity["age"] = entity.get("age", 0) + 1
-            // entity["hunger"] = entity.get("hunger", 100) - (entity.get("hunger_rate", 0.1) * 0.5)
-            // if entity["hunger"] < 50: eat...
+        // 25. Note dawn and dusk
+        if state.is_running(Subsystem::DayNight) && state.has_feature(feature::DAY_NIGHT) {
+            Self::process_daynight(state, &mut events);
+        }
+        checks.after(state, &rng, "daynight")?;
+
+        // 26. Pass floods and cave-ins that are over, and roll for new ones
+        if state.is_running(Subsystem::Hazards) && state.has_feature(feature::HAZARDS) {
+            self.process_hazards(state, &mut events);
+        }
+        checks.after(state, &rng, "hazards")?;
+
+        // Has the run reached one of its scenario's ends?
+        self.check_scenario(state, &mut events);
+        self.report_health(state, &mut events);
+        if let Some(draws) = &checks.draws {
+            self.audit_rng(tick, draws, &mut events)?;
+        }
+
+        state.bookkeeping = Some(Bookkeeping {
+            last_spawn_tick: self.last_spawn_tick,
+            last_summon_tick: self.last_summon_tick,
+            tick: Some(tick),
+        });
+        events.set_warnings(state.warnings());
+        Ok(events)
+    }
+
+    /// Record `tick`'s per-phase draws, or hold them to the budget
+    fn audit_rng(&mut self, tick: u64, draws: &[(&'static str, u64)], events: &mut TickEvents) -> Result<(), ContractViolation> {
+        let overdraw = match &mut self.rng_audit {
+            Some(RngAudit::Record(budget)) => {
+                budget.record(tick, draws);
+                None
+            }
+            Some(RngAudit::Check(budget)) => budget.check(tick, draws),
+            None => None,
+        };
+        let Some(overdraw) = overdraw else {
+            return Ok(());
+        };
+        if self.strict {
+            return Err(ContractViolation::RngBudgetExceeded {
+                tick,
+                phase: overdraw.phase,
+                expected: overdraw.expected,
+                actual: overdraw.actual,
+            });
+        }
+        events.push(tick, EventKind::RngBudgetExceeded {
+            phase: overdraw.phase,
+            expected: overdraw.expected,
+            actual: overdraw.actual,
+        });
+        Ok(())
+    }
 
-            // In Rust we need to handle this carefully.
-            for entity in &mut state.entities {
-                 entity.age += 1;
+    /// Take one step of the state machine: catch up to `input.timestamp`
+    /// (re-anchoring the state there), apply `input.commands` in order,
+    /// then tick. Refused commands are reported and skipped; they never
+    /// stop the step.
+    ///
+    /// This is the whole external contract. Given the same starting state
+    /// and the same inputs, every host ends in the same state with the
+    /// same outputs; an `InputLog` of the inputs replays a session.
+    pub fn step(&mut self, state: &mut GameState, input: &TickInput) -> TickOutput {
+        let mut output = TickOutput::default();
+        if let Some(now) = input.timestamp {
+            output.events.extend(self.process_offline_progress(state, now).events);
+            if now.is_finite() {
+                state.last_save_timestamp = Some(now);
+            }
+        }
+        for (index, command) in input.commands.iter().enumerate() {
+            match self.apply_command(state, command.clone()) {
+                Ok(events) => output.events.extend(events),
+                Err(error) => output.rejected.push(Rejected { index, error: error.to_string() }),
+            }
+        }
 
-                 // Hunger decreases at half rate
-                 entity.hunger -= entity.hunger_rate * 0.5;
+        let mut events = self.tick(state);
+        output.warnings = events.take_warnings();
+        output.events.extend(events.into_events());
+        output.tick = state.tick;
+        output
+    }
 
-                 // Auto-eat
-                 if entity.hunger < constants::HUNGER_THRESHOLD_EAT {
-                      if let Some(food) = &entity.food {
-                           // Simplified check compared to full tick
-                           if state.resources.get(food) >= 1.0 {
-                               state.resources.add(food, -1.0);
-                               entity.hunger = (entity.hunger + constants::HUNGER_GAIN_FROM_EATING).min(constants::MAX_HUNGER);
-                           }
-                      }
-                 }
+    /// Process a single tick, returning its events, warnings and counter
+    /// changes together; with `metrics`, also colony-wide numbers after
+    /// the tick. The simulation is the same as `tick()`.
+    pub fn tick_report(&mut self, state: &mut GameState, metrics: bool) -> TickReport {
+        let resources = state.resources.amounts.clone();
+        let population = state.entities.len();
+
+        let mut events = self.tick(state);
+        let counters = TickCounters {
+            resource_deltas: resource_deltas(&resources, &state.resources.amounts),
+            population_delta: state.entities.len() as i64 - population as i64,
+            deaths: events.raised(EventKindTag::EntityDied),
+        };
+        let metrics = metrics.then(|| {
+            let query = state.query();
+            TickMetrics {
+                population: state.entities.len(),
+                ants: query.entities().ants().count(),
+                visitors: query.entities().visitors().count(),
+                corpses: state.graveyard.corpses.len(),
+                state_hash: state.hash(),
             }
+        });
 
-            // Remove entities that died offline
-            // Python: state["entities"] = [e for e in state["entities"] if e.get("hunger", 100) > 0 and e.get("age", 0) < e.get("max_age", 7200)]
+        TickReport {
+            tick: state.tick,
+            warnings: events.take_warnings(),
+            events: events.into_events(),
+            counters,
+            metrics,
+        }
+    }
 
-             state.entities.retain(|e| {
-                 let alive = e.hunger > 0.0 && e.age < constants::DEFAULT_MAX_AGE;
-                 if !alive {
-                     // Unlike full tick, we don't add to graveyard or emit death events in the loop?
-                     // Python:
-                     /*
-                        # Remove entities that died offline
-                        state["entities"] = [e for e in state["entities"] if e.get("hunger", 100) > 0 and e.get("age", 0) < e.get("max_age", 7200)]
-                     */
-                     // Python code does NOT add to graveyard during offline progress loop. It just removes them.
-                 }
-                 alive
-             });
+    /// Run `n` ticks, returning their events grouped by tick along with a
+    /// summary of the batch. The same as calling `tick()` `n` times, for
+    /// hosts that fast-forward and would rather not cross the FFI boundary
+    /// once per tick.
+    pub fn tick_n(&mut self, state: &mut GameState, n: u64) -> BatchEvents {
+        let before = state.resources.amounts.clone();
+        let mut batch = BatchEvents::default();
+        for _ in 0..n {
+            let events = self.tick(state);
+            batch.push(state.tick, events);
         }
+        batch.set_resource_deltas(&before, &state.resources.amounts);
+        batch
+    }
 
+    /// Advance `frames` host frames at `speed`x, returning all events.
+    ///
+    /// A batch is exactly a sequence of `tick()` calls: every tick draws
+    /// from its own per-tick RNG stream, so 10 frames at 5x is bit-identical
+    /// to 50 frames at 1x. Speed is rounded to 1/`SUB_TICKS_PER_TICK`; the
+    /// fractional remainder carries over in `state.sub_tick` (so 0.5x runs a
+    /// tick every other frame). Negative or NaN speeds run nothing.
+    pub fn tick_scaled(&mut self, state: &mut GameState, frames: u64, speed: f64) -> TickEvents {
+        let step = (speed.max(0.0) * constants::SUB_TICKS_PER_TICK as f64).round() as u64;
+        let budget = state.sub_tick + frames.saturating_mul(step);
+        state.sub_tick = budget % constants::SUB_TICKS_PER_TICK;
+
+        let mut events = TickEvents::new();
+        for _ in 0..budget / constants::SUB_TICKS_PER_TICK {
+            events.extend(self.tick(state));
+        }
         events
     }
 
-    /// Process the action queue
-    fn process_actions(&self, state: &mut GameState, events: &mut TickEvents) {
-        let tick = state.tick;
-        let mut remaining = Vec::new();
+    /// Replace the colony's work policy. Every worker is released and
+    /// reassigned under the new policy on the next tick.
+    pub fn set_work_policy(&self, state: &mut GameState, policy: WorkPolicy) -> TickEvents {
+        let mut events = TickEvents::new();
+        if state.policy == policy {
+            return events;
+        }
+        for entity in state.entities.iter_mut().filter(|e| e.task.is_some()) {
+            entity.task = None;
+        }
+        events.push(state.tick, EventKind::PolicyChanged {
+            priorities: policy.priorities.clone(),
+        });
+        state.policy = policy;
+        events
+    }
 
-        for mut action in state.queues.actions.drain(..) {
-            if action.ticks_remaining <= 1 {
-                // Action complete
-                events.push(tick, EventKind::ActionComplete {
-                    action_id: action.id.clone(),
-                    action_type: action.action_type.clone(),
-                });
+    /// Apply a command from outside the tick. Commands are validated first;
+    /// a refused command leaves the state untouched.
+    pub fn apply_command(&self, state: &mut GameState, command: Command) -> Result<Vec<Event>, CommandError> {
+        if let Some(ending) = &state.ended {
+            return Err(CommandError::RunEnded(ending.tick));
+        }
+        let events = match command {
+            Command::BuildSystem { id, system } => self.build_system(state, id, *system)?,
+            Command::AssignRole { entity_id, role } => self.assign_role(state, &entity_id, role)?,
+            Command::EnqueueAction { action } => self.enqueue_action(state, action)?,
+            Command::StartAction { id, kind, target } => self.start_action(state, &id, kind, target.as_deref())?,
+            Command::AddTile { id, tile, connect_to } => self.add_tile(state, &id, *tile, &connect_to)?,
+            Command::ConnectTiles { a, b } => self.connect_tiles(state, &a, &b)?,
+            Command::DisconnectTiles { a, b } => self.disconnect_tiles(state, &a, &b)?,
+            Command::DemolishTile { tile } => self.demolish_tile(state, &tile)?,
+            Command::MoveEntity { entity_id, tile } => self.move_entity(state, &entity_id, &tile)?,
+            Command::RenameTile { tile, name } => self.rename_tile(state, &tile, &name)?,
+            Command::RenameEntity { entity_id, name } => self.rename_entity(state, &entity_id, &name)?,
+            Command::SetWorkPolicy { policy } => self.set_work_policy(state, policy),
+            Command::SetPaused { subsystem, paused } => self.set_paused(state, subsystem, paused),
+            Command::SetFeature { feature, enabled } => self.set_feature(state, &feature, enabled),
+            Command::FormGroup { group, members } => self.form_group(state, &group, &members)?,
+            Command::SetGroupIntent { group, intent } => self.set_group_intent(state, &group, intent)?,
+            Command::DisbandGroup { group } => self.disband_group(state, &group)?,
+            Command::LaunchExpedition { members, duration_ticks } => {
+                self.launch_expedition(state, &members, duration_ticks)?
+            }
+            Command::EstablishOutpost { tile } => self.establish_outpost(state, &tile)?,
+            Command::DispatchSupplies { tile } => self.dispatch_supplies(state, &tile)?,
+            Command::DigTile { id, from } => self.dig_tile(state, &id, &from)?,
+            Command::ExcavateRuin { tile } => self.excavate_ruin(state, &tile)?,
+            Command::AbandonColony => self.abandon_colony(state)?,
+            Command::HostVisitor { visitor_id } => self.host_visitor(state, &visitor_id)?,
+            Command::StopHosting { visitor_id } => self.stop_hosting(state, &visitor_id)?,
+            Command::StartScenario { scenario } => self.start_scenario(state, *scenario),
+        };
+        Ok(events.into_events())
+    }
 
-                // Apply effects
-                if let Some(effects) = &action.effects {
-                    if let Some(resources) = &effects.resources {
-                        state.resources.add_all(resources);
-                    }
-                }
-            } else {
-                action.ticks_remaining -= 1;
-                remaining.push(action);
+    fn build_system(&self, state: &mut GameState, id: String, system: System) -> Result<TickEvents, CommandError> {
+        if state.systems.contains_key(&id) {
+            return Err(CommandError::SystemExists(id));
+        }
+        if let Some(tile) = &system.tile {
+            if state.map.get_tile(tile).is_none() {
+                return Err(CommandError::UnknownTile(tile.clone()));
             }
         }
 
-        state.queues.actions = remaining;
+        let mut events = TickEvents::new();
+        events.push(state.tick, EventKind::SystemBuilt {
+            system_id: id.clone(),
+            name: system.name.clone(),
+            tile: system.tile.as_deref().map(Key::new),
+        });
+        state.systems.insert(id, system);
+        Ok(events)
     }
 
-    /// Process production systems
-    fn process_systems(&self, state: &mut GameState, events: &mut TickEvents) {
+    fn assign_role(&self, state: &mut GameState, entity_id: &str, role: AntRole) -> Result<TickEvents, CommandError> {
         let tick = state.tick;
+        let entity = state.get_entity_mut(entity_id)
+            .ok_or_else(|| CommandError::UnknownEntity(entity_id.to_string()))?;
+        if entity.entity_type != EntityType::Ant {
+            return Err(CommandError::NotAnAnt(entity_id.to_string()));
+        }
+        let mut events = TickEvents::new();
+        if entity.role == Some(role.clone()) {
+            return Ok(events);
+        }
+        if entity.processing_corpse == Some(true) {
+            return Err(CommandError::CarryingCorpse(entity_id.to_string()));
+        }
+        if role.is_royal() || entity.role.as_ref().is_some_and(AntRole::is_royal) {
+            return Err(CommandError::Royal(entity_id.to_string()));
+        }
 
-        // Collect system operations first to avoid borrow issues
-        let operations: Vec<_> = state.systems.iter()
-            .filter(|(_, system)| !system.is_disabled())
-            .filter_map(|(id, system)| {
-                // Check if system can run
-                if !system.can_run(&state.resources) {
-                    return None;
-                }
-
-                let consumes = system.consumes.clone().unwrap_or_default();
-                let mut generates = system.generates.clone().unwrap_or_default();
+        // Shed the old role's working state
+        entity.task = None;
+        entity.clear_timer(timer::PROCESSING);
+        entity.processing_corpse = (role == AntRole::Undertaker).then_some(false);
 
-                // Add corpse boost bonus for compost heap
-                if id == "compost_heap" {
-                    let bonus = system.total_corpse_bonus(tick);
-                    if bonus > 0.0 {
-                        *generates.entry("nutrients".to_string()).or_default() += bonus;
-                    }
-                }
+        events.push(tick, EventKind::RoleAssigned {
+            entity_id: entity_id.to_string(),
+            from: entity.role.replace(role.clone()),
+            to: role,
+        });
+        Ok(events)
+    }
 
-                Some((id.clone(), consumes, generates))
-            })
-            .collect();
+    fn enqueue_action(&self, state: &mut GameState, action: Action) -> Result<TickEvents, CommandError> {
+        check_free_form(&action.action_type)?;
+        if action.ticks_remaining == 0 {
+            return Err(CommandError::ZeroDurationAction(action.id));
+        }
+        if state.queues.actions.iter().any(|a| a.id == action.id) {
+            return Err(CommandError::ActionExists(action.id));
+        }
 
-        // Apply operations
-        for (system_id, consumes, generates) in operations {
-            // Consume resources
-            for (resource, amount) in &consumes {
-                state.resources.add(resource, -amount);
-            }
+        let mut events = TickEvents::new();
+        events.push(state.tick, EventKind::ActionQueued {
+            action_id: action.id.clone(),
+            action_type: action.action_type.clone(),
+            ticks: action.ticks_remaining,
+        });
+        state.queues.enqueue_action(action);
+        Ok(events)
+    }
 
-            // Generate resources
-            for (resource, amount) in &generates {
-                state.resources.add(resource, *amount);
+    /// Queue an action of a typed kind under `id`, aimed at `target`. The
+    /// save's blueprint for `kind` decides where it may be aimed, what it
+    /// costs (paid now) and how long it takes; its yields arrive on
+    /// completion.
+    pub fn start_action(&self, state: &mut GameState, id: &str, kind: ActionKind, target: Option<&str>) -> Result<TickEvents, BlueprintError> {
+        let blueprint = state.config.blueprints.get(kind).ok_or(BlueprintError::NoBlueprint(kind))?;
+        if state.queues.actions.iter().any(|a| a.id == id) {
+            return Err(BlueprintError::ActionExists(id.to_string()));
+        }
+        match target {
+            Some(tile_id) => {
+                let tile = state.map.get_tile(tile_id)
+                    .ok_or_else(|| BlueprintError::UnknownTile(tile_id.to_string()))?;
+                if !blueprint.allows(&tile.tile_type) {
+                    return Err(BlueprintError::WrongContext {
+                        kind,
+                        tile: tile_id.to_string(),
+                        tile_type: tile.tile_type.clone(),
+                    });
+                }
             }
-
-            if !consumes.is_empty() || !generates.is_empty() {
-                events.push(tick, EventKind::SystemProduced {
-                    system_id,
-                    produced: generates,
-                    consumed: consumes,
-                });
+            None if !blueprint.contexts.is_empty() => return Err(BlueprintError::NeedsTarget(kind)),
+            None => {}
+        }
+        for (resource, &needed) in &blueprint.cost {
+            let available = state.resources.get(resource);
+            if available < needed {
+                return Err(BlueprintError::InsufficientResources { resource: *resource, needed, available });
             }
         }
 
-        // Expire old corpse boosts
-        for system in state.systems.values_mut() {
-            system.expire_corpse_boosts(tick);
+        let blueprint = blueprint.clone();
+        for (resource, amount) in &blueprint.cost {
+            state.resources.add(resource, -amount);
         }
+        let ticks = state.ticks_for(blueprint.duration).max(1);
+        let yields = (!blueprint.yields.is_empty()).then(|| ActionEffects {
+            resources: Some(blueprint.yields.iter().map(|(r, a)| (r.to_string(), *a)).collect()),
+        });
+        state.queues.enqueue_action(Action {
+            id: id.to_string(),
+            action_type: kind.as_str().to_string(),
+            ticks_remaining: ticks,
+            target: target.map(str::to_string),
+            effects: yields,
+        });
+
+        let mut events = TickEvents::new();
+        events.push(state.tick, EventKind::ActionQueued {
+            action_id: id.to_string(),
+            action_type: kind.as_str().to_string(),
+            ticks,
+        });
+        Ok(events)
     }
 
-    /// Process entity lifecycle (aging, hunger, eating, death)
-    fn process_entities(&self, state: &mut GameState, events: &mut TickEvents) {
+    fn move_entity(&self, state: &mut GameState, entity_id: &str, tile: &str) -> Result<TickEvents, CommandError> {
+        if state.map.get_tile(tile).is_none() {
+            return Err(CommandError::UnknownTile(tile.to_string()));
+        }
         let tick = state.tick;
-        let mut surviving = Vec::new();
+        let entity = state.get_entity_mut(entity_id)
+            .ok_or_else(|| CommandError::UnknownEntity(entity_id.to_string()))?;
 
-        for mut entity in state.entities.drain(..) {
-            // Age
-            entity.age += 1;
+        let mut events = TickEvents::new();
+        if entity.tile != tile {
+            let from = std::mem::replace(&mut entity.tile, tile.to_string());
+            events.push(tick, EventKind::EntityMoved {
+                entity_id: entity_id.to_string(),
+                from: Key::new(&from),
+                to: Key::new(tile),
+            });
+        }
+        Ok(events)
+    }
 
-            // Hunger decreases
-            entity.hunger -= entity.hunger_rate;
+    /// Give tile `id` a player's name
+    pub fn rename_tile(&self, state: &mut GameState, id: &str, name: &str) -> Result<TickEvents, CommandError> {
+        let name = naming::validate(name)?;
+        let tile = state.map.get_tile_mut(id).ok_or_else(|| CommandError::UnknownTile(id.to_string()))?;
+        let mut events = TickEvents::new();
+        if tile.name != name {
+            let from = std::mem::replace(&mut tile.name, name.clone());
+            events.push(state.tick, EventKind::Renamed {
+                subject: Named::Tile { tile: Key::new(id) },
+                from: Some(from),
+                to: name,
+            });
+        }
+        Ok(events)
+    }
 
-            // Try to eat if hungry
-            if entity.hunger < constants::HUNGER_THRESHOLD_EAT {
-                if let Some(food) = &entity.food {
-                    // Special case: hungry visitors eat influence
-                    if food == "influence" && entity.subtype == Some(VisitorType::Hungry) {
-                        if state.resources.get("influence") >= constants::HUNGRY_INFLUENCE_CONSUME {
-                            state.resources.add("influence", -constants::HUNGRY_INFLUENCE_CONSUME);
-                            entity.hunger = (entity.hunger + constants::HUNGRY_HUNGER_GAIN).min(constants::MAX_HUNGER);
+    /// Give entity `entity_id` a player's name
+    pub fn rename_entity(&self, state: &mut GameState, entity_id: &str, name: &str) -> Result<TickEvents, CommandError> {
+        let name = naming::validate(name)?;
+        let tick = state.tick;
+        let entity = state.get_entity_mut(entity_id)
+            .ok_or_else(|| CommandError::UnknownEntity(entity_id.to_string()))?;
+        let mut events = TickEvents::new();
+        if entity.name.as_deref() != Some(name.as_str()) {
+            let from = entity.name.replace(name.clone());
+            events.push(tick, EventKind::Renamed {
+                subject: Named::Entity { entity_id: entity_id.to_string() },
+                from,
+                to: name,
+            });
+        }
+        Ok(events)
+    }
 
-                            // Transform influence into strange_matter
-                            if entity.transforms == Some(true) {
-                                state.resources.add("strange_matter", constants::HUNGRY_STRANGE_MATTER_PRODUCE);
-                                events.push(tick, EventKind::InfluenceTransformed {
-                                    visitor_id: entity.id.clone(),
-                                    influence_consumed: constants::HUNGRY_INFLUENCE_CONSUME,
-                                    strange_matter_produced: constants::HUNGRY_STRANGE_MATTER_PRODUCE,
-                                });
-                            }
-                        }
-                    } else if state.resources.get(food) >= 1.0 {
-                        state.resources.add(food, -1.0);
-                        entity.hunger = (entity.hunger + constants::HUNGER_GAIN_FROM_EATING).min(constants::MAX_HUNGER);
+    /// Add `tile` to the map under `id`, connected to `connect_to` (which
+    /// must be reachable from the origin)
+    pub fn add_tile(&self, state: &mut GameState, id: &str, mut tile: Tile, connect_to: &str) -> Result<TickEvents, MapError> {
+        tile.dug_at.get_or_insert(state.tick);
+        state.map.add_tile(id, tile, connect_to)?;
+        let mut events = TickEvents::new();
+        events.push(state.tick, EventKind::TileAdded { tile: Key::new(id), connected_to: Key::new(connect_to) });
+        Ok(events)
+    }
 
-                        events.push(tick, EventKind::EntityAte {
-                            entity_id: entity.id.clone(),
-                            food: food.clone(),
-                            hunger_after: entity.hunger,
-                        });
-                    }
-                }
-            }
+    /// Start digging a new tile `id` beside `from`, on the first free square
+    /// east, south, west or north of it. The colony's diggers open it over
+    /// the ticks that follow (see `excavation`).
+    pub fn dig_tile(&self, state: &mut GameState, id: &str, from: &str) -> Result<TickEvents, ExcavationError> {
+        let tick = state.tick;
+        let origin = state.map.get_tile(from)
+            .ok_or_else(|| ExcavationError::UnknownTile(from.to_string()))?;
+        if !state.map.reachable_from(ORIGIN).contains(from) {
+            return Err(ExcavationError::Unreachable(from.to_string()));
+        }
+        if state.map.get_tile(id).is_some() {
+            return Err(ExcavationError::TileExists(id.to_string()));
+        }
+        if state.excavations.contains_key(id) {
+            return Err(ExcavationError::AlreadyDigging(id.to_string()));
+        }
+        let digs = &state.excavations;
+        let (x, y) = excavation::free_square(&state.map, origin, |x, y| {
+            digs.values().any(|dig| (dig.x, dig.y) == (x, y))
+        })
+        .ok_or_else(|| ExcavationError::NoRoom(from.to_string()))?;
+
+        let work_needed = state.config.tuning.excavation_work;
+        state.excavations.insert(id.to_string(), Excavation {
+            from: Key::new(from),
+            x,
+            y,
+            started_at: tick,
+            work_done: 0.0,
+            work_needed,
+        });
 
-            // Check for death
-            if let Some(cause) = entity.cause_of_death() {
-                // Visitors just disappear (handled separately for gifts)
-                if entity.entity_type == EntityType::Visitor {
-                    let gift = entity.gift_on_death.clone();
-                    if let Some(ref g) = gift {
-                        state.resources.add_all(g);
-                    }
-                    events.push(tick, EventKind::VisitorDeparted {
-                        visitor_id: entity.id.clone(),
-                        visitor_type: entity.subtype.clone().unwrap_or(VisitorType::Wanderer),
-                        name: entity.name.clone().unwrap_or_default(),
-                        gift,
-                    });
-                } else {
-                    // Add to graveyard
-                    state.graveyard.add_corpse(Corpse {
-                        entity_id: entity.id.clone(),
-                        entity_type: format!("{:?}", entity.entity_type).to_lowercase(),
-                        death_tick: tick,
-                        cause: cause.clone(),
-                        tile: entity.tile.clone(),
-                    });
+        let mut events = TickEvents::new();
+        events.push(tick, EventKind::ExcavationStarted {
+            tile: Key::new(id),
+            from: Key::new(from),
+            x,
+            y,
+            work_needed,
+        });
+        Ok(events)
+    }
 
-                    events.push(tick, EventKind::EntityDied {
-                        entity_id: entity.id.clone(),
-                        entity_type: format!("{:?}", entity.entity_type).to_lowercase(),
-                        cause,
-                        tile: entity.tile.clone(),
-                    });
-                }
-            } else {
-                surviving.push(entity);
+    /// Connect two tiles, one of which must be reachable from the origin
+    pub fn connect_tiles(&self, state: &mut GameState, a: &str, b: &str) -> Result<TickEvents, MapError> {
+        state.map.connect(a, b)?;
+        let mut events = TickEvents::new();
+        events.push(state.tick, EventKind::TilesConnected { a: Key::new(a), b: Key::new(b) });
+        Ok(events)
+    }
+
+    /// Remove a connection, unless that would cut any tile (and whoever is
+    /// on it) off from the origin
+    pub fn disconnect_tiles(&self, state: &mut GameState, a: &str, b: &str) -> Result<TickEvents, MapError> {
+        state.map.disconnect(a, b)?;
+        let mut events = TickEvents::new();
+        events.push(state.tick, EventKind::TilesDisconnected { a: Key::new(a), b: Key::new(b) });
+        Ok(events)
+    }
+
+    /// Remove a tile nothing stands on or points at, unless other tiles
+    /// reach the origin only through it
+    pub fn demolish_tile(&self, state: &mut GameState, tile: &str) -> Result<TickEvents, MapError> {
+        if let Some(what) = state.tile_in_use(tile) {
+            return Err(MapError::TileInUse { tile: tile.to_string(), what });
+        }
+        state.map.demolish(tile)?;
+        state.activity.remove(tile);
+        state.stockpiles.remove(tile);
+
+        let mut events = TickEvents::new();
+        events.push(state.tick, EventKind::TileDemolished { tile: Key::new(tile) });
+        Ok(events)
+    }
+
+    /// Form a group of ants under `name`, holding where they are
+    pub fn form_group(&self, state: &mut GameState, name: &str, members: &[EntityId]) -> Result<TickEvents, GroupError> {
+        if state.groups.contains_key(name) {
+            return Err(GroupError::GroupExists(name.to_string()));
+        }
+        if members.is_empty() {
+            return Err(GroupError::NoMembers);
+        }
+        for id in members {
+            let is_ant = state.get_entity(id).is_some_and(|e| e.entity_type == EntityType::Ant);
+            if !is_ant {
+                return Err(GroupError::NotAnAnt(id.clone()));
+            }
+            if let Some(group) = state.group_of(id) {
+                return Err(GroupError::AlreadyGrouped { entity_id: id.clone(), group: group.to_string() });
             }
         }
 
-        state.entities = surviving;
+        let group = Group::new(members.iter().cloned());
+        let mut events = TickEvents::new();
+        events.push(state.tick, EventKind::GroupFormed {
+            group: name.to_string(),
+            members: group.members.iter().cloned().collect(),
+        });
+        state.groups.insert(name.to_string(), group);
+        Ok(events)
     }
 
-    /// Process undertaker corpse collection
-    fn process_undertakers(&self, state: &mut GameState, events: &mut TickEvents, _rng: &mut SeededRng) {
-        let tick = state.tick;
+    /// Give a group a new intent. Members working for the group are
+    /// released back to the work policy. Emits an event only if anything
+    /// changed.
+    pub fn set_group_intent(&self, state: &mut GameState, name: &str, intent: Intent) -> Result<TickEvents, GroupError> {
+        match &intent {
+            Intent::GoTo { tile } if state.map.get_tile(tile).is_none() => {
+                return Err(GroupError::UnknownTile(tile.clone()));
+            }
+            Intent::Explore { duration_ticks: 0 } => return Err(GroupError::ZeroDuration),
+            _ => {}
+        }
+        let group = state.groups.get(name).ok_or_else(|| GroupError::UnknownGroup(name.to_string()))?;
 
-        // Check if compost tile is blighted
-        let compost_blighted = state.map.get_tile("compost")
-            .map(|t| t.is_blighted())
-            .unwrap_or(false);
+        let mut events = TickEvents::new();
+        if group.intent == intent {
+            return Ok(events);
+        }
+        release_group_workers(state, name);
+        events.push(state.tick, EventKind::GroupIntentChanged { group: name.to_string(), intent: intent.clone() });
+        if let Some(group) = state.groups.get_mut(name) {
+            group.intent = intent;
+        }
+        Ok(events)
+    }
 
-        if compost_blighted {
-            return;
+    /// Break up a group, releasing its workers back to the work policy
+    pub fn disband_group(&self, state: &mut GameState, name: &str) -> Result<TickEvents, GroupError> {
+        if !state.groups.contains_key(name) {
+            return Err(GroupError::UnknownGroup(name.to_string()));
         }
+        release_group_workers(state, name);
+        state.groups.remove(name);
 
-        // Find undertaker entities
-        let undertaker_ids: Vec<String> = state.entities.iter()
-            .filter(|e| e.role == Some(AntRole::Undertaker))
-            .map(|e| e.id.clone())
-            .collect();
+        let mut events = TickEvents::new();
+        events.push(state.tick, EventKind::GroupDisbanded { group: name.to_string() });
+        Ok(events)
+    }
 
-        for undertaker_id in undertaker_ids {
-            let undertaker = match state.entities.iter_mut().find(|e| e.id == undertaker_id) {
-                Some(e) => e,
-                None => continue,
+    /// Pause or resume a subsystem. Emits an event only if anything changed.
+    pub fn set_paused(&self, state: &mut GameState, subsystem: Subsystem, paused: bool) -> TickEvents {
+        let mut events = TickEvents::new();
+        if state.paused.set(subsystem, paused) {
+            let kind = if paused {
+                EventKind::SubsystemPaused { subsystem }
+            } else {
+                EventKind::SubsystemResumed { subsystem }
             };
+            events.push(state.tick, kind);
+        }
+        events
+    }
 
-            let processing = undertaker.processing_corpse.unwrap_or(false);
-            let ticks = undertaker.processing_ticks.unwrap_or(0);
+    /// Opt the save in to or out of feature `name`. Emits an event only if
+    /// anything changed.
+    pub fn set_feature(&self, state: &mut GameState, name: &str, enabled: bool) -> TickEvents {
+        let mut events = TickEvents::new();
+        if state.set_feature(name, enabled) {
+            events.push(state.tick, EventKind::FeatureChanged { feature: name.to_string(), enabled });
+        }
+        events
+    }
 
-            if processing {
-                // Continue processing
-                undertaker.processing_ticks = Some(ticks + 1);
+    /// Play for `scenario`'s goal from this tick on, replacing any scenario
+    /// the run had
+    pub fn start_scenario(&self, state: &mut GameState, mut scenario: Scenario) -> TickEvents {
+        let mut events = TickEvents::new();
+        scenario.started_at = state.tick;
+        events.push(state.tick, EventKind::ScenarioStarted { name: scenario.name.clone() });
+        state.scenario = Some(scenario);
+        events
+    }
 
-                if ticks + 1 >= constants::CORPSE_PROCESSING_TICKS {
-                    // Corpse delivered
-                    undertaker.processing_corpse = Some(false);
-                    undertaker.processing_ticks = Some(0);
+    /// End the run if its scenario's win or lose condition is met
+    fn check_scenario(&self, state: &mut GameState, events: &mut TickEvents) {
+        let Some(scenario) = &state.scenario else {
+            return;
+        };
+        let Some(ending) = scenario.evaluate(state) else {
+            return;
+        };
+        let name = scenario.name.clone();
+        let condition = ending.condition.clone();
+        events.push(state.tick, match ending.outcome {
+            Outcome::Completed => EventKind::ScenarioCompleted { name, condition },
+            Outcome::Failed => EventKind::ScenarioFailed { name, condition },
+        });
+        state.ended = Some(ending);
+    }
 
-                    // Add boost to compost heap
-                    if let Some(system) = state.systems.get_mut("compost_heap") {
-                        system.corpse_boosts.push(CorpseBoost {
-                            expires_at_tick: tick + constants::CORPSE_BOOST_DURATION,
-                            bonus: constants::CORPSE_NUTRIENT_BOOST,
-                        });
-                    }
+    /// Report the health score if it moved far enough from the last one
+    /// reported (or none has been yet)
+    fn report_health(&self, state: &mut GameState, events: &mut TickEvents) {
+        let score = state.health().score;
+        let previous = state.reported_health;
+        if previous.is_some_and(|p| (score - p).abs() < constants::HEALTH_CHANGE_THRESHOLD) {
+            return;
+        }
+        events.push(state.tick, EventKind::HealthChanged { score, previous });
+        state.reported_health = Some(score);
+    }
 
-                    // Add contamination
-                    if let Some(tile) = state.map.get_tile_mut("compost") {
-                        tile.add_contamination(constants::CONTAMINATION_PER_CORPSE);
+    /// Process offline progress up to `current_timestamp`, with the default
+    /// clock checks (see `catch_up`)
+    pub fn process_offline_progress(&mut self, state: &mut GameState, current_timestamp: f64) -> OfflineReport {
+        self.catch_up(state, TimeContext::at(current_timestamp))
+    }
 
-                        let contamination = tile.contamination.unwrap_or(0.0);
-                        state.graveyard.mark_processed();
+    /// Process offline progress from the last save to `time.now`, reporting
+    /// what happened while the player was away.
+    ///
+    /// A clock that went backwards beyond the tolerance, or a non-finite
+    /// reading, simulates nothing and emits `TimeAnomaly`; going backwards
+    /// also re-anchors `last_save_timestamp` so the time isn't counted twice
+    /// when the clock recovers. An absence longer than `TIME_JUMP_LIMIT` is
+    /// flagged too, and like any absence is capped at `time.max_catchup`.
+    ///
+    /// The catch-up runs the full tick pipeline, so it is bit-identical to
+    /// ticking live for the same number of ticks; only events below
+    /// `time.verbosity` are left out of the report's events.
+    pub fn catch_up(&mut self, state: &mut GameState, time: TimeContext) -> OfflineReport {
+        let mut report = OfflineReport::default();
 
-                        events.push(tick, EventKind::CorpseProcessed {
-                            undertaker_id: undertaker_id.clone(),
-                            total_processed: state.graveyard.total_processed,
-                            contamination,
-                        });
-                    }
+        let last_save = match state.last_save_timestamp {
+            Some(ts) if !state.is_abandoned() && !state.is_ended() => ts,
+            _ => return report,
+        };
+
+        let elapsed_seconds = time.now - last_save;
+        let anomaly = if !elapsed_seconds.is_finite() {
+            Some(TimeAnomaly::NotFinite)
+        } else if elapsed_seconds < -time.tolerance {
+            state.last_save_timestamp = Some(time.now);
+            Some(TimeAnomaly::Backwards)
+        } else if elapsed_seconds > constants::TIME_JUMP_LIMIT {
+            Some(TimeAnomaly::Jump)
+        } else {
+            None
+        };
+        if let Some(anomaly) = anomaly {
+            report.anomaly = Some(anomaly);
+            report.events.push(Event::new(state.tick, EventKind::TimeAnomaly { anomaly, last_save, now: time.now }));
+            if anomaly != TimeAnomaly::Jump {
+                return report;
+            }
+        }
+        if elapsed_seconds <= 0.0 {
+            return report;
+        }
+        report.elapsed_seconds = elapsed_seconds;
+        self.impose_config(state);
+
+        // Clamped first: a clock jumped far enough overflows a Duration
+        let timeline = Timeline::new(state.ticks_per_second);
+        let away = Duration::from_secs_f64(elapsed_seconds.min(time.max_catchup as f64));
+        let ticks_to_apply = timeline.ticks(away).min(state.ticks_for(time.max_catchup));
+
+        if ticks_to_apply < state.ticks_for(10) {
+            return report;
+        }
+
+        // The real pipeline, so time away plays out exactly as it would
+        // have live
+        let resources = state.resources.amounts.clone();
+        let filter = self.filter.clone().at_least(time.verbosity);
+        for _ in 0..ticks_to_apply {
+            // Deaths are read before the filter can drop them
+            let mut events = self.tick_unfiltered(state);
+            report.deaths.extend(events.events().iter().filter_map(|event| match &event.kind {
+                EventKind::EntityDied { entity_id, entity_type, cause, .. } => Some(OfflineDeath {
+                    entity_id: entity_id.clone(),
+                    entity_type: entity_type.clone(),
+                    cause: cause.clone(),
+                    tick: event.tick,
+                }),
+                _ => None,
+            }));
+            events.retain(&filter);
+            // Order-free: tallied into a BTreeMap
+            for (id, system) in state.systems.iter() {
+                if system.starved_since.is_some() {
+                    *report.stalled_systems.entry(id.clone()).or_default() += 1;
                 }
-            } else if state.graveyard.has_corpses() {
-                // Start processing a new corpse
-                state.graveyard.take_corpse();
-                undertaker.processing_corpse = Some(true);
-                undertaker.processing_ticks = Some(0);
             }
+            report.warnings = events.take_warnings();
+            report.events.extend(events.into_events());
         }
+        report.ticks = ticks_to_apply;
+        report.resource_deltas = resource_deltas(&resources, &state.resources.amounts);
+        report
     }
 
-    /// Process contamination and blight
-    fn process_blight(&self, state: &mut GameState, events: &mut TickEvents, rng: &mut SeededRng) {
+    /// Walk away from the colony for good.
+    ///
+    /// Distills the state into a `LegacySeed`, stores it in `state.legacy`
+    /// and freezes the state: further ticks do nothing. The seed depends only
+    /// on the engine seed and the final state.
+    pub fn abandon_colony(&self, state: &mut GameState) -> Result<TickEvents, AbandonError> {
+        if let Some(legacy) = &state.legacy {
+            return Err(AbandonError::AlreadyAbandoned(legacy.abandoned_at));
+        }
         let tick = state.tick;
 
-        // Handle active blight ticking down
-        if let Some(tile) = state.map.get_tile_mut("compost") {
-            if tile.is_blighted() {
-                if tile.tick_blight() {
-                    events.push(tick, EventKind::BlightCleared {
-                        tile: "compost".to_string(),
-                    });
-
-                    // Re-enable compost system
-                    if let Some(system) = state.systems.get_mut("compost_heap") {
-                        system.enable();
-                    }
+        let id = digest(state)
+            .map(|d| fnv1a64(&[self.seed.to_le_bytes(), d.to_le_bytes()].concat()))
+            .unwrap_or(self.seed);
+
+        // Order-free: sorted by tile below
+        let mut ruins: Vec<Ruin> = state.map.tiles.iter()
+            .map(|(tile_id, tile)| {
+                let systems = det::sorted(state.systems.iter()
+                    .filter(|(_, s)| s.tile.as_deref() == Some(tile_id.as_str()))
+                    .map(|(id, _)| id.clone()));
+                Ruin {
+                    tile: *tile_id,
+                    name: tile.name.clone(),
+                    tile_type: tile.tile_type.clone(),
+                    x: tile.x,
+                    y: tile.y,
+                    systems,
                 }
-                return; // Don't roll for new blight while blighted
-            }
+            })
+            .collect();
+        ruins.sort_by_key(|r| r.tile);
 
-            // Roll for blight based on contamination
-            let contamination = tile.contamination.unwrap_or(0.0);
-            if contamination > 0.0 && rng.chance(contamination) {
-                // Blight strikes!
-                tile.start_blight(constants::BLIGHT_DURATION);
+        // Order-free: collected into a BTreeMap
+        let residual: BTreeMap<Key, f64> = state.resources.amounts.iter()
+            .filter(|(_, amount)| **amount > 0.0)
+            .map(|(resource, amount)| (*resource, amount * constants::LEGACY_RESIDUAL_FRACTION))
+            .collect();
 
-                events.push(tick, EventKind::BlightStruck {
-                    tile: "compost".to_string(),
-                    contamination,
-                    duration_ticks: constants::BLIGHT_DURATION,
-                });
+        let mut ghosts: Vec<Ghost> = state.entities.iter()
+            .map(|e| Ghost {
+                entity_id: e.id.clone(),
+                cohort: demographics::cohort(e),
+                tile: Key::new(&e.tile),
+                age: e.age,
+                unburied: false,
+            })
+            .chain(state.graveyard.corpses.iter().map(|c| Ghost {
+                entity_id: c.entity_id.clone(),
+                cohort: c.entity_type.clone(),
+                tile: Key::new(&c.tile),
+                age: 0,
+                unburied: true,
+            }))
+            .collect();
+        det::sort_by_key(&mut ghosts, |g| Reverse(g.age), |g| g.entity_id.clone());
+        ghosts.truncate(constants::LEGACY_MAX_GHOSTS);
 
-                // Disable compost system
-                if let Some(system) = state.systems.get_mut("compost_heap") {
-                    system.disable();
-                    system.corpse_boosts.clear();
-                }
+        let total_dead = state.graveyard.total_processed + state.graveyard.corpses.len() as u64;
 
-                // Kill entities on the tile
-                let mut surviving = Vec::new();
-                for entity in state.entities.drain(..) {
-                    if entity.tile == "compost" {
-                        events.push(tick, EventKind::BlightKill {
-                            entity_id: entity.id.clone(),
-                            tile: "compost".to_string(),
-                        });
+        let mut events = TickEvents::new();
+        events.push(tick, EventKind::ColonyAbandoned {
+            legacy_id: id,
+            ruins: ruins.len(),
+            ghosts: ghosts.len(),
+            residual: residual.clone(),
+        });
+        state.legacy = Some(LegacySeed {
+            id,
+            abandoned_at: tick,
+            ruins,
+            residual,
+            ghosts,
+            total_dead,
+        });
+        Ok(events)
+    }
 
-                        // Add to graveyard
-                        state.graveyard.add_corpse(Corpse {
-                            entity_id: entity.id.clone(),
-                            entity_type: format!("{:?}", entity.entity_type).to_lowercase(),
-                            death_tick: tick,
-                            cause: DeathCause::Blight,
-                            tile: entity.tile.clone(),
-                        });
-                    } else {
-                        surviving.push(entity);
-                    }
+    /// Dig up a ruin, adding its loot to the colony's stores. Each ruin can
+    /// be excavated once.
+    pub fn excavate_ruin(&self, state: &mut GameState, tile_id: &str) -> Result<TickEvents, RuinError> {
+        let tick = state.tick;
+        let stockpile = state.stockpile_for(Some(tile_id));
+        let site = state.ruins.get_mut(tile_id)
+            .ok_or_else(|| RuinError::NotARuin(tile_id.to_string()))?;
+        if let Some(at) = site.excavated_at {
+            return Err(RuinError::AlreadyExcavated { tile: tile_id.to_string(), tick: at });
+        }
+        site.excavated_at = Some(tick);
+        let loot = std::mem::take(&mut site.loot);
+
+        let store = match stockpile {
+            Some(id) => state.stockpiles.entry(id).or_default(),
+            None => &mut state.resources,
+        };
+        for (resource, amount) in &loot {
+            store.add(resource, *amount);
+        }
+
+        let mut events = TickEvents::new();
+        events.push(tick, EventKind::RuinExcavated {
+            tile: Key::new(tile_id),
+            legacy_id: state.ruins[tile_id].legacy_id,
+            loot,
+        });
+        Ok(events)
+    }
+
+    /// Send a group of ants to the Outside.
+    ///
+    /// Members leave `state.entities` immediately and come back when the
+    /// queued `expedition` action completes.
+    pub fn launch_expedition(
+        &self,
+        state: &mut GameState,
+        member_ids: &[EntityId],
+        duration_ticks: u64,
+    ) -> Result<TickEvents, ExpeditionError> {
+        let tick = state.tick;
+
+        if member_ids.is_empty() {
+            return Err(ExpeditionError::NoMembers);
+        }
+        if duration_ticks == 0 {
+            return Err(ExpeditionError::ZeroDuration);
+        }
+        for (i, id) in member_ids.iter().enumerate() {
+            if member_ids[..i].contains(id) {
+                return Err(ExpeditionError::DuplicateMember(id.clone()));
+            }
+            match state.get_entity(id) {
+                None => return Err(ExpeditionError::UnknownEntity(id.clone())),
+                Some(e) if e.entity_type != EntityType::Ant => {
+                    return Err(ExpeditionError::NotAnAnt(id.clone()));
                 }
-                state.entities = surviving;
+                Some(_) => {}
             }
         }
+
+        // Members keep the order they were given in
+        let members: Vec<Entity> = member_ids.iter()
+            .filter_map(|id| {
+                let idx = state.entities.iter().position(|e| &e.id == id)?;
+                Some(state.entities.remove(idx))
+            })
+            .collect();
+
+        let expedition = Expedition {
+            id: format!("exp_{}_{}", tick, member_ids[0]),
+            from_tile: members[0].tile.clone(),
+            members,
+            departed_tick: tick,
+            return_tick: tick + duration_ticks,
+        };
+
+        state.queues.enqueue_action(Action {
+            id: expedition.id.clone(),
+            action_type: EXPEDITION_ACTION.to_string(),
+            ticks_remaining: duration_ticks,
+            target: None,
+            effects: None,
+        });
+
+        let mut events = TickEvents::new();
+        events.push(tick, EventKind::ExpeditionDeparted {
+            expedition_id: expedition.id.clone(),
+            members: expedition.member_ids(),
+            return_tick: expedition.return_tick,
+        });
+
+        state.expeditions.push(expedition);
+        Ok(events)
     }
 
-    /// Process queen spawning
-    fn process_queen(&mut self, state: &mut GameState, events: &mut TickEvents, rng: &mut SeededRng) {
+    /// Bring an expedition home: roll losses, loot, and discoveries
+    fn resolve_expedition(
+        &self,
+        state: &mut GameState,
+        expedition: Expedition,
+        events: &mut TickEvents,
+        rng: &mut SeededRng,
+    ) {
         let tick = state.tick;
+        let mut survivors = Vec::new();
+        let mut lost = Vec::new();
+
+        for member in expedition.members {
+            if rng.chance(state.config.tuning.expedition_loss_chance) {
+                events.push(tick, EventKind::EntityDied {
+                    entity_id: member.id.clone(),
+                    entity_type: format!("{:?}", member.entity_type).to_lowercase(),
+                    cause: DeathCause::LostOutside,
+                    tile: Key::new(&expedition.from_tile),
+                    snapshot: Some(member.snapshot()),
+                });
+                lost.push(member.id);
+            } else {
+                survivors.push(member);
+            }
+        }
 
-        // Only spawn if queen chamber exists
-        if !state.has_system("queen_chamber") {
-            return;
+        // Each survivor carries back one haul from the loot table
+        let mut loot: HashMap<String, f64> = HashMap::new();
+        for _ in &survivors {
+            if let Some(idx) = rng.choose_index(constants::EXPEDITION_LOOT.len()) {
+                let (resource, base) = constants::EXPEDITION_LOOT[idx];
+                let amount = base * (0.5 + rng.random());
+                *loot.entry(resource.to_string()).or_default() += amount;
+            }
+        }
+        state.resources.add_all(&loot);
+
+        // Survivors may have found a way to somewhere new
+        let mut discovered_tile = None;
+        if !survivors.is_empty() && rng.chance(state.config.tuning.expedition_discovery_chance) {
+            let tile_id = format!("remote_{}", expedition.id);
+            let (x, y) = self.remote_coordinates(state, &expedition.from_tile, rng);
+            let resource_idx = rng.choose_index(constants::EXPEDITION_LOOT.len()).unwrap_or(0);
+
+            let resource = constants::EXPEDITION_LOOT[resource_idx].0;
+            let mut tile = Tile::new_resource("A Distant Hollow".to_string(), x, y, resource, constants::REMOTE_TILE_STOCK);
+            tile.description = Some("Found by an expedition. Far from home.".to_string());
+
+            state.map.tiles.insert(Key::new(&tile_id), tile);
+            state.map.connections.push((Key::new(&expedition.from_tile), Key::new(&tile_id)));
+            state.map.changed();
+            discovered_tile = Some(tile_id);
         }
 
-        let nutrients = state.resources.get("nutrients");
-        let fungus = state.resources.get("fungus");
-        let entity_count = state.entities.len();
+        let survivor_ids = survivors.iter().map(|e| e.id.clone()).collect();
+        state.entities.extend(survivors);
 
-        // Emergency spawn if colony is empty
-        let is_emergency = entity_count == 0
-            && nutrients >= constants::MIN_RESOURCES_TO_SPAWN
-            && fungus >= constants::MIN_RESOURCES_TO_SPAWN;
+        events.push(tick, EventKind::ExpeditionReturned {
+            expedition_id: expedition.id,
+            survivors: survivor_ids,
+            lost,
+            loot,
+            discovered_tile,
+        });
+    }
 
-        if is_emergency {
-            // Emergency spawn
-            let worker_id = rng.entity_id();
-            let undertaker_id = rng.entity_id();
+    /// Pick coordinates for a remote tile, a random distance from `from_tile`
+    fn remote_coordinates(&self, state: &GameState, from_tile: &str, rng: &mut SeededRng) -> (i32, i32) {
+        let (fx, fy) = state.map.get_tile(from_tile)
+            .map(|t| (t.x, t.y))
+            .unwrap_or((0, 0));
+        let distance = rng.range(constants::REMOTE_TILE_MIN_DISTANCE, constants::REMOTE_TILE_MAX_DISTANCE);
+        let dx = rng.range(0, distance);
+        let dy = distance - dx;
+        let sx = if rng.chance(0.5) { 1 } else { -1 };
+        let sy = if rng.chance(0.5) { 1 } else { -1 };
+        (fx + sx * dx as i32, fy + sy * dy as i32)
+    }
 
-            state.entities.push(Entity::new_worker(worker_id.clone(), "origin".to_string()));
-            state.entities.push(Entity::new_undertaker(undertaker_id.clone(), "origin".to_string()));
+    /// Establish an outpost on a remote resource tile.
+    ///
+    /// The outpost starts with one delivery's worth of supplies.
+    pub fn establish_outpost(&self, state: &mut GameState, tile_id: &str) -> Result<TickEvents, OutpostError> {
+        let tick = state.tick;
 
-            state.resources.add("nutrients", -constants::SPAWN_COST_NUTRIENTS);
-            state.resources.add("fungus", -constants::SPAWN_COST_FUNGUS);
+        let tile = state.map.get_tile(tile_id)
+            .ok_or_else(|| OutpostError::UnknownTile(tile_id.to_string()))?;
+        let resource = match (&tile.tile_type, &tile.resource) {
+            (TileType::Resource, Some(resource)) => resource.clone(),
+            _ => return Err(OutpostError::NotAResourceTile(tile_id.to_string())),
+        };
+        if state.outposts.contains_key(tile_id) {
+            return Err(OutpostError::AlreadyEstablished(tile_id.to_string()));
+        }
+        let distance = state.map.grid_distance("origin", tile_id).unwrap_or(0);
+        if distance < constants::OUTPOST_MIN_DISTANCE {
+            return Err(OutpostError::TooClose {
+                tile: tile_id.to_string(),
+                distance,
+                min: constants::OUTPOST_MIN_DISTANCE,
+            });
+        }
+        Self::pay_fungus(state, state.config.tuning.outpost_establish_cost_fungus)?;
+
+        state.outposts.insert(tile_id.to_string(), Outpost {
+            resource: resource.clone(),
+            rate: state.config.tuning.outpost_production_rate,
+            established_tick: tick,
+            supply_ticks: state.ticks_for(state.config.tuning.outpost_supply_duration),
+            condition: 1.0,
+        });
 
-            self.last_spawn_tick = tick;
+        let mut events = TickEvents::new();
+        events.push(tick, EventKind::OutpostEstablished {
+            tile: Key::new(tile_id),
+            resource: Key::new(&resource),
+        });
+        Ok(events)
+    }
 
-            events.push(tick, EventKind::EmergencySpawn {
-                worker_id,
-                undertaker_id,
-            });
+    /// Send a supply run to an outpost. Travel time scales with distance.
+    pub fn dispatch_supplies(&self, state: &mut GameState, tile_id: &str) -> Result<TickEvents, OutpostError> {
+        let tick = state.tick;
 
-            return;
+        if !state.outposts.contains_key(tile_id) {
+            return Err(OutpostError::NoOutpost(tile_id.to_string()));
         }
+        Self::pay_fungus(state, state.config.tuning.outpost_supply_cost_fungus)?;
 
-        // Normal spawn check
-        if self.last_spawn_tick == 0 {
-            self.last_spawn_tick = tick;
-            return;
-        }
+        let distance = state.map.grid_distance("origin", tile_id).unwrap_or(0);
+        let travel_ticks = state.ticks_for(distance * state.config.tuning.outpost_haul_ticks_per_distance).max(1);
 
-        let ticks_since_spawn = tick - self.last_spawn_tick;
-        if ticks_since_spawn < constants::SPAWN_INTERVAL_TICKS {
-            return;
-        }
+        state.queues.enqueue_action(Action {
+            id: format!("haul_{}_{}", tick, tile_id),
+            action_type: SUPPLY_ACTION.to_string(),
+            ticks_remaining: travel_ticks,
+            target: Some(tile_id.to_string()),
+            effects: None,
+        });
 
-        if nutrients < constants::MIN_RESOURCES_TO_SPAWN || fungus < constants::MIN_RESOURCES_TO_SPAWN {
-            return;
+        let mut events = TickEvents::new();
+        events.push(tick, EventKind::SupplyDispatched {
+            tile: Key::new(tile_id),
+            arrives_tick: tick + travel_ticks,
+        });
+        Ok(events)
+    }
+
+    /// Take fungus from the colony for an outpost operation
+    fn pay_fungus(state: &mut GameState, amount: f64) -> Result<(), OutpostError> {
+        let available = state.resources.get("fungus");
+        if !state.resources.try_consume("fungus", amount) {
+            return Err(OutpostError::InsufficientResources {
+                resource: "fungus".to_string(),
+                needed: amount,
+                available,
+            });
         }
+        Ok(())
+    }
 
-        // Spawn new ants
-        let worker_id = rng.entity_id();
-        let undertaker_id = rng.entity_id();
+    /// Start hosting a visitor: a `host_visitor` action that comes round
+    /// every `host_interval`, paying to extend their stay (see `hosting`)
+    pub fn host_visitor(&self, state: &mut GameState, visitor_id: &str) -> Result<TickEvents, HostingError> {
+        let visitor = state.get_entity(visitor_id)
+            .ok_or_else(|| HostingError::UnknownVisitor(visitor_id.to_string()))?;
+        if visitor.entity_type != EntityType::Visitor {
+            return Err(HostingError::NotAVisitor(visitor_id.to_string()));
+        }
+        let tuning = &state.config.tuning;
+        let (base, cap) = (state.ticks_for(tuning.host_extension), state.ticks_for(tuning.host_max_extension));
+        if hosting::stay_extension(visitor.timer(timer::HOSTED), base, cap) == 0 {
+            return Err(HostingError::Capped(visitor_id.to_string()));
+        }
+        let action_id = hosting::action_id(visitor_id);
+        if state.queues.actions.iter().any(|a| a.id == action_id) {
+            return Err(HostingError::AlreadyHosted(visitor_id.to_string()));
+        }
 
-        state.entities.push(Entity::new_worker(worker_id.clone(), "origin".to_string()));
-        state.entities.push(Entity::new_undertaker(undertaker_id.clone(), "origin".to_string()));
+        let ticks = state.ticks_for(state.config.tuning.host_interval).max(1);
+        state.queues.enqueue_action(Action {
+            id: action_id.clone(),
+            action_type: HOST_ACTION.to_string(),
+            ticks_remaining: ticks,
+            target: Some(visitor_id.to_string()),
+            effects: None,
+        });
 
-        state.resources.add("nutrients", -constants::SPAWN_COST_NUTRIENTS);
-        state.resources.add("fungus", -constants::SPAWN_COST_FUNGUS);
+        let mut events = TickEvents::new();
+        events.push(state.tick, EventKind::ActionQueued {
+            action_id,
+            action_type: HOST_ACTION.to_string(),
+            ticks,
+        });
+        Ok(events)
+    }
 
-        self.last_spawn_tick = tick;
+    /// Stop hosting a visitor; their stay keeps what it's been given
+    pub fn stop_hosting(&self, state: &mut GameState, visitor_id: &str) -> Result<TickEvents, HostingError> {
+        let action_id = hosting::action_id(visitor_id);
+        let Some(index) = state.queues.actions.iter().position(|a| a.id == action_id) else {
+            return Err(HostingError::NotHosted(visitor_id.to_string()));
+        };
+        state.queues.actions.remove(index);
 
-        events.push(tick, EventKind::AntsSpawned {
-            worker_id,
-            undertaker_id,
-            nutrients_consumed: constants::SPAWN_COST_NUTRIENTS,
-            fungus_consumed: constants::SPAWN_COST_FUNGUS,
+        let mut events = TickEvents::new();
+        events.push(state.tick, EventKind::HostingEnded {
+            visitor_id: visitor_id.to_string(),
+            reason: HostingEnd::Stopped,
         });
+        Ok(events)
+    }
+
+    /// A hosting round came round: pay, extend the visitor's stay, and go
+    /// again, unless they've left, the colony can't pay, or the cap is hit
+    fn host_round(state: &mut GameState, mut action: Action, events: &mut TickEvents) -> Option<Action> {
+        let tick = state.tick;
+        let visitor_id = action.target.clone().unwrap_or_default();
+        let tuning = &state.config.tuning;
+        let (base, cap) = (state.ticks_for(tuning.host_extension), state.ticks_for(tuning.host_max_extension));
+        let costs: BTreeMap<Key, f64> = [("fungus", tuning.host_cost_fungus), ("crystals", tuning.host_cost_crystals)]
+            .into_iter()
+            .filter(|(_, amount)| *amount > 0.0)
+            .map(|(resource, amount)| (Key::new(resource), amount))
+            .collect();
+        let interval = state.ticks_for(tuning.host_interval).max(1);
+
+        let ended = |reason| EventKind::HostingEnded { visitor_id: visitor_id.clone(), reason };
+        let Some(extended) = state.get_entity(&visitor_id).map(|v| v.timer(timer::HOSTED)) else {
+            events.push(tick, ended(HostingEnd::Departed));
+            return None;
+        };
+        let extended_by = hosting::stay_extension(extended, base, cap);
+        if extended_by == 0 {
+            events.push(tick, ended(HostingEnd::Capped));
+            return None;
+        }
+        if !costs.iter().all(|(resource, amount)| state.resources.has(resource, *amount)) {
+            events.push(tick, ended(HostingEnd::Unaffordable));
+            return None;
+        }
+        for (resource, amount) in &costs {
+            state.resources.add(resource, -amount);
+        }
+        let visitor = state.get_entity_mut(&visitor_id)?;
+        visitor.max_age += extended_by;
+        visitor.set_timer(timer::HOSTED, extended + extended_by);
+        let ticks_left = visitor.max_age.saturating_sub(visitor.age);
+        events.push(tick, EventKind::StayExtended { visitor_id: visitor_id.clone(), extended_by, ticks_left, costs });
+
+        action.ticks_remaining = interval;
+        Some(action)
+    }
+
+    /// Process ruins: haunted ones now and then echo a ghost of the past run.
+    /// Rolls come from their own stream so ingesting ruins doesn't disturb
+    /// the rest of the simulation's randomness.
+    fn process_echoes(&self, state: &mut GameState, events: &mut TickEvents) {
+        if state.ruins.is_empty() {
+            return;
+        }
+        let tick = state.tick;
+        let chance = state.config.tuning.ruin_echo_chance / state.ticks_per_second;
+        let mut rng = SeededRng::from_tick(self.seed ^ constants::RUIN_ECHO_STREAM, tick);
+
+        for (tile_id, site) in state.ruins.iter() {
+            if site.ghosts.is_empty() || !rng.chance(chance) {
+                continue;
+            }
+            let Some(ghost) = rng.choose_index(site.ghosts.len()).map(|i| &site.ghosts[i]) else {
+                continue;
+            };
+            events.push(tick, EventKind::EchoOfThePast {
+                tile: Key::new(tile_id),
+                legacy_id: site.legacy_id,
+                ghost_id: ghost.entity_id.clone(),
+                cohort: ghost.cohort.clone(),
+            });
+        }
+    }
+
+    /// Roll for a wonder the colony hasn't seen yet. Like echoes, the roll
+    /// has its own stream, so wonders never disturb the rest of the
+    /// simulation's randomness.
+    fn process_wonders(&self, state: &mut GameState, events: &mut TickEvents) {
+        let dark = !state.has_feature(feature::DAY_NIGHT) || state.time_of_day().is_night();
+        let unseen: Vec<Wonder> = Wonder::ALL.into_iter()
+            .filter(|wonder| !state.wonders.contains_key(wonder))
+            .filter(|wonder| dark || !daynight::is_overnight(*wonder))
+            .collect();
+        if unseen.is_empty() {
+            return;
+        }
+        let tick = state.tick;
+        let chance = state.config.tuning.wonder_chance / state.ticks_per_second;
+        let mut rng = SeededRng::from_tick(self.seed ^ constants::WONDER_STREAM, tick);
+        if !rng.chance(chance) {
+            return;
+        }
+        let Some(wonder) = rng.choose_index(unseen.len()).map(|i| unseen[i]) else {
+            return;
+        };
+
+        let rewards: BTreeMap<Key, f64> = wonder.rewards().iter()
+            .map(|&(resource, amount)| (Key::new(resource), amount))
+            .collect();
+        state.resources.add_all(&rewards);
+        state.wonders.insert(wonder, tick);
+        events.push(tick, EventKind::WonderOccurred { wonder, rewards });
+    }
+
+    /// Drop members that have died, then carry out each group's intent.
+    /// Members away on an expedition keep their place but stay put. With
+    /// movement, members walk to a `GoTo` tile and the group arrives once
+    /// every waking member is there; without it (or with no way there)
+    /// they step straight onto it. An exploring group sends every member
+    /// at home out on one expedition. Guards wait for the threat phase.
+    fn process_groups(&self, state: &mut GameState, events: &mut TickEvents) {
+        if state.groups.is_empty() {
+            return;
+        }
+        let tick = state.tick;
+        let walking = state.has_feature(feature::MOVEMENT);
+
+        let mut alive: HashSet<EntityId> = state.entities.iter().map(|e| e.id.clone()).collect();
+        alive.extend(state.expeditions.iter().flat_map(|x| x.member_ids()));
+        let mut emptied = Vec::new();
+        for (name, group) in state.groups.iter_mut() {
+            group.members.retain(|id| alive.contains(id));
+            if group.members.is_empty() {
+                emptied.push(name.clone());
+            }
+        }
+        for name in emptied {
+            state.groups.remove(&name);
+            events.push(tick, EventKind::GroupDisbanded { group: name });
+        }
+
+        let exploring: Vec<(String, u64)> = state.groups.iter()
+            .filter_map(|(name, group)| match group.intent {
+                Intent::Explore { duration_ticks } => Some((name.clone(), duration_ticks)),
+                _ => None,
+            })
+            .collect();
+        for (name, duration_ticks) in exploring {
+            let home: Vec<EntityId> = state.groups[&name].members.iter()
+                .filter(|id| state.get_entity(id).is_some())
+                .cloned()
+                .collect();
+            if let Ok(departed) = self.launch_expedition(state, &home, duration_ticks) {
+                events.extend(departed);
+            }
+            if let Some(group) = state.groups.get_mut(&name) {
+                group.intent = Intent::Hold;
+            }
+        }
+
+        let GameState { groups, entities, map, .. } = state;
+        for (name, group) in groups.iter_mut() {
+            let members = entities.iter_mut().filter(|e| group.members.contains(&e.id));
+            match &group.intent {
+                Intent::Hold | Intent::Explore { .. } | Intent::Defend => {}
+                Intent::GoTo { tile } => {
+                    if map.get_tile(tile).is_none() {
+                        continue;
+                    }
+                    let mut arrived = true;
+                    for entity in members {
+                        if !walking || in_place(map, &entity.tile, tile) {
+                            entity.tile.clone_from(tile);
+                        } else if !entity.is_dormant() {
+                            arrived = false;
+                        }
+                    }
+                    if !arrived {
+                        continue;
+                    }
+                    events.push(tick, EventKind::GroupArrived { group: name.clone(), tile: Key::new(tile) });
+                    group.intent = Intent::Hold;
+                }
+                Intent::Work { task } => {
+                    for entity in members.filter(|e| e.role == Some(AntRole::Worker) && !e.is_dormant()) {
+                        entity.task = Some(*task);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Hand idle workers a task per the work policy, then collect what the
+    /// diggers turned up. Foragers and haulers by assignment work in the
+    /// harvest and hauling phases. With movement, only diggers at a site
+    /// turn anything up (or anywhere, if no site can be reached).
+    fn process_work(&self, state: &mut GameState, events: &mut TickEvents) {
+        if !state.policy.is_empty() {
+            self.assign_idle_workers(state);
+        }
+
+        let walking = state.has_feature(feature::MOVEMENT);
+        let at_site = |e: &Entity, task: Task| {
+            !walking || work_site(&state.map, &e.tile, task).is_none_or(|site| site == e.tile)
+        };
+        let diggers = state.entities.iter()
+            .filter(|e| e.task == Some(Task::Dig) && !e.is_dormant() && at_site(e, Task::Dig))
+            .map(|e| e.trait_factor(Trait::work_factor) * morale::work_factor(e.morale))
+            .sum::<f64>();
+        let dug = diggers * state.shift_factor() * state.config.tuning.dig_yield / state.ticks_per_second;
+        if dug > 0.0 {
+            state.resources.add("dirt", dug);
+        }
+        if !state.excavations.is_empty() {
+            self.advance_excavations(state, events, dug.max(0.0));
+        }
+    }
+
+    /// Give up digs whose tile can no longer be reached, put the diggers'
+    /// `work` into the rest, oldest first, and open the ones that are done.
+    /// One whose ID a tile has taken in the meantime waits (with a
+    /// `Warning::ExcavationBlocked`) until that tile is gone.
+    fn advance_excavations(&self, state: &mut GameState, events: &mut TickEvents, mut work: f64) {
+        let tick = state.tick;
+        let reachable = state.map.reachable_from(ORIGIN);
+        let cut_off: Vec<String> = det::sorted(state.excavations.iter()
+            .filter(|(_, dig)| !reachable.contains(dig.from.as_str()))
+            .map(|(id, _)| id.clone()));
+        for id in cut_off {
+            let dig = state.excavations.remove(&id).expect("excavation listed above");
+            events.push(tick, EventKind::ExcavationCancelled { tile: Key::new(&id), from: dig.from, work_done: dig.work_done });
+        }
+
+        let mut order: Vec<(u64, String)> = state.excavations.iter()
+            .map(|(id, dig)| (dig.started_at, id.clone()))
+            .collect();
+        order.sort();
+        for (_, id) in order {
+            if work <= 0.0 {
+                break;
+            }
+            let dig = state.excavations.get_mut(&id).expect("excavation listed above");
+            let put = work.min(dig.work_needed - dig.work_done).max(0.0);
+            dig.work_done += put;
+            work -= put;
+        }
+
+        let done: Vec<String> = state.excavations.iter()
+            .filter(|(_, dig)| dig.is_done())
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in done {
+            let dig = &state.excavations[&id];
+            let from = dig.from;
+            let vein = Vein::at(state.outside_seed, dig.x, dig.y, &state.config.tuning);
+            let mut tile = vein.tile(dig.x, dig.y, &state.config.tuning);
+            tile.elevation = state.map.get_tile(&from).and_then(|from| from.elevation);
+            tile.dug_at = Some(tick);
+            if state.map.add_tile(&id, tile, &from).is_err() {
+                continue;
+            }
+            state.excavations.remove(&id);
+            events.push(tick, EventKind::TileExcavated { tile: Key::new(&id), from, vein });
+            events.push(tick, EventKind::TileAdded { tile: Key::new(&id), connected_to: from });
+        }
+    }
+
+    /// Hand idle workers the highest-priority task still under its limit
+    fn assign_idle_workers(&self, state: &mut GameState) {
+        let mut assigned: BTreeMap<Task, usize> = BTreeMap::new();
+        for entity in state.entities.iter() {
+            if let Some(task) = entity.task {
+                *assigned.entry(task).or_default() += 1;
+            }
+        }
+
+        let mut idle: Vec<usize> = state.entities.iter().enumerate()
+            .filter(|(_, e)| e.role == Some(AntRole::Worker) && e.task.is_none() && !e.is_dormant())
+            .map(|(i, _)| i)
+            .collect();
+        det::sort_by_id(&mut idle, |&i| &state.entities[i].id);
+
+        let policy = state.policy.clone();
+        for i in idle {
+            let open = policy.priorities.iter().copied().find(|task| {
+                let limit = policy.limits.get(task).copied().unwrap_or(usize::MAX);
+                assigned.get(task).copied().unwrap_or(0) < limit
+            });
+            if let Some(task) = open {
+                state.entities[i].task = Some(task);
+                *assigned.entry(task).or_default() += 1;
+            }
+        }
+    }
+
+    /// Foragers (and workers put on `Task::Forage`) harvest the resource
+    /// tile they're on into the colony's stores until its stock runs out.
+    /// Without movement (or with no way to one) they work the nearest as
+    /// the crow flies.
+    fn process_harvest(&self, state: &mut GameState, events: &mut TickEvents) {
+        let tick = state.tick;
+        let walking = state.has_feature(feature::MOVEMENT);
+        let carrying = state.has_feature(feature::CARRYING);
+        let rate = state.shift_factor() * state.config.tuning.harvest_yield / state.ticks_per_second;
+
+        let foragers = det::sorted(state.entities.iter()
+            .filter(|e| e.forages() && !e.is_dormant())
+            .map(|e| (e.id.clone(), e.tile.clone(), det::OrdF64(e.trait_factor(Trait::work_factor) * morale::work_factor(e.morale)))));
+        for (forager_id, tile, det::OrdF64(industry)) in foragers {
+            // Carrying foragers bring their load home before heading out again
+            let mut room = f64::INFINITY;
+            if carrying {
+                let Some(forager) = state.entities.iter().position(|e| e.id == forager_id) else {
+                    continue;
+                };
+                Self::deliver(state, forager, walking, events);
+                room = state.config.tuning.carry_capacity - state.entities[forager].carried();
+            }
+
+            let Some(site) = harvest_site(&state.map, &tile, walking) else {
+                continue;
+            };
+            let Some(site_tile) = state.map.get_tile_mut(&site) else {
+                continue;
+            };
+            let Some(resource) = site_tile.resource.as_deref().map(Key::new) else {
+                continue;
+            };
+            let amount = site_tile.harvest((rate * industry).min(room.max(0.0)));
+            let depleted = site_tile.is_depleted();
+            if amount <= 0.0 {
+                continue;
+            }
+            if carrying {
+                if let Some(forager) = state.entities.iter().position(|e| e.id == forager_id) {
+                    let route = Route { from: Depot::Tile(site.to_string()), to: Depot::Colony };
+                    state.entities[forager].load(&resource, amount, route);
+                    if !walking {
+                        Self::deliver(state, forager, walking, events);
+                    }
+                }
+            } else {
+                state.resources.add(&resource, amount);
+            }
+
+            events.push(tick, EventKind::ResourceHarvested { forager_id, tile: site, resource, amount });
+            if depleted {
+                events.push(tick, EventKind::ResourceDepleted { tile: site, resource });
+            }
+        }
+    }
+
+    /// Walk ants toward their destinations along the map's connections, one
+    /// connection every `step_seconds`. Ants with nowhere to be, or no way
+    /// there, stay put (and act in place). With `feature::TRAILS`, trails
+    /// fade, each step lays one, and of equally short ways an ant takes
+    /// the one with the strongest trail.
+    fn process_movement(&self, state: &mut GameState, events: &mut TickEvents) {
+        let tick = state.tick;
+        let step_ticks = state.step_ticks();
+        let trails = state.has_feature(feature::TRAILS);
+        let deposit = state.config.tuning.trail_deposit;
+        if trails {
+            state.trails.fade((1.0 - state.config.tuning.trail_decay).max(0.0).powf(1.0 / state.ticks_per_second));
+        }
+
+        let goto: HashMap<&str, &str> = state.groups.values()
+            .filter_map(|group| match &group.intent {
+                Intent::GoTo { tile } => Some((&group.members, tile.as_str())),
+                _ => None,
+            })
+            .flat_map(|(members, tile)| members.iter().map(move |id| (id.as_str(), tile)))
+            .collect();
+        let destinations: Vec<Option<Key>> = state.entities.iter()
+            .map(|entity| destination_for(state, entity, goto.get(entity.id.as_str()).copied()))
+            .collect();
+
+        for (entity, destination) in state.entities.iter_mut().zip(destinations) {
+            if entity.destination.as_deref() != destination.as_deref() {
+                entity.destination = destination.map(String::from);
+                entity.clear_timer(timer::WALKING);
+            }
+            let Some(to) = entity.destination.clone() else {
+                continue;
+            };
+            if entity.advance_timer(timer::WALKING) < step_ticks {
+                continue;
+            }
+            entity.clear_timer(timer::WALKING);
+            let Some(&next) = state.map.path(&entity.tile, &to).as_deref().and_then(<[Key]>::first) else {
+                continue;
+            };
+            let next = if trails {
+                let steps = state.map.steps_toward(&entity.tile, &to);
+                *state.trails.strongest(std::iter::once(&next).chain(&steps)).unwrap_or(&next)
+            } else {
+                next
+            };
+            let from = std::mem::replace(&mut entity.tile, next.to_string());
+            if next == to {
+                entity.destination = None;
+            }
+            if trails {
+                let laden = if entity.inventory.is_empty() { 1.0 } else { pheromone::LADEN_FACTOR };
+                state.trails.lay(next, deposit * laden);
+            }
+            events.push(tick, EventKind::EntityMoved {
+                entity_id: entity.id.clone(),
+                from: Key::new(&from),
+                to: next,
+            });
+        }
+    }
+
+    /// Read the weather for this tick into `state.weather`, reporting a new
+    /// season or a change in the sky (the first reading is just noted)
+    fn process_weather(&self, state: &mut GameState, events: &mut TickEvents) {
+        let tick = state.tick;
+        let weather = state.weather_at(tick);
+        let Some(before) = state.weather.replace(weather) else {
+            return;
+        };
+        if (before.season, before.year) != (weather.season, weather.year) {
+            events.push(tick, EventKind::SeasonChanged { season: weather.season, year: weather.year });
+        }
+        if before.sky != weather.sky {
+            events.push(tick, EventKind::WeatherEvent { sky: weather.sky, season: weather.season });
+        }
+    }
+
+    /// Report the day turning to night or back since the last tick
+    fn process_daynight(state: &GameState, events: &mut TickEvents) {
+        let tick = state.tick;
+        let now = state.time_of_day();
+        if now.phase == state.time_of_day_at(tick - 1).phase {
+            return;
+        }
+        events.push(tick, match now.phase {
+            DayPhase::Day => EventKind::DayBreak { day: now.day },
+            DayPhase::Night => EventKind::NightFall { day: now.day },
+        });
+    }
+
+    /// Now and then an incursion comes for the colony (own RNG stream), more
+    /// often and stronger the more its stores are worth. Every waking ant
+    /// stands against it, soldiers and guards (members of a `Defend`
+    /// group) hardest, and they fall first.
+    fn process_threats(&self, state: &mut GameState, events: &mut TickEvents) {
+        let tick = state.tick;
+        let tuning = &state.config.tuning;
+        if state.last_incursion.is_some_and(|last| tick < last + state.ticks_for(tuning.raid_cooldown)) {
+            return;
+        }
+        let pressure = state.raid_pressure();
+        let chance = tuning.raid_chance * state.config.raid_frequency_scale * pressure / state.ticks_per_second;
+        let mut rng = SeededRng::from_tick(self.seed ^ constants::THREAT_STREAM, tick);
+        if !rng.chance(chance) {
+            return;
+        }
+        let Some(threat) = rng.choose_index(Threat::ALL.len()).map(|i| Threat::ALL[i]) else {
+            return;
+        };
+
+        // Royalty are kept back in the chamber
+        let guards: HashSet<&EntityId> = state.groups.values()
+            .filter(|group| group.intent == Intent::Defend)
+            .flat_map(|group| &group.members)
+            .collect();
+        let defenders = det::sorted(state.entities.iter()
+            .filter(|e| e.entity_type == EntityType::Ant && !e.is_dormant())
+            .filter(|e| !e.role.as_ref().is_some_and(AntRole::is_royal))
+            .map(|e| (e.id.clone(), e.role == Some(AntRole::Soldier) || guards.contains(&e.id))));
+        let odds = Odds {
+            strength: tuning.raid_strength * pressure * (0.5 + rng.random()),
+            defense: defenders.iter()
+                .map(|(_, soldier)| if *soldier { tuning.soldier_strength } else { tuning.civilian_strength })
+                .sum(),
+        };
+        let repelled = odds.repelled();
+
+        // Soldiers take the brunt; predators that win take anyone
+        let casualty_chance = (tuning.raid_casualty_chance * 2.0 * odds.pressure()).min(1.0);
+        let exposed = |soldier: bool| soldier || (!repelled && threat.takes_ants());
+        let fallen: Vec<EntityId> = defenders.iter()
+            .filter(|(_, soldier)| exposed(*soldier))
+            .filter(|_| rng.chance(casualty_chance))
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        let mut stolen = BTreeMap::new();
+        if !repelled && !threat.takes_ants() {
+            let share = tuning.raid_plunder * odds.shortfall();
+            stolen = det::entries(state.resources.amounts.iter())
+                .into_iter()
+                .map(|(resource, amount)| (*resource, amount * share))
+                .filter(|(_, amount)| *amount > 0.0)
+                .collect();
+            for (resource, amount) in &stolen {
+                state.resources.add(resource, -amount);
+            }
+        }
+
+        state.last_incursion = Some(tick);
+        events.push(tick, EventKind::DefenseResolved {
+            threat,
+            strength: odds.strength,
+            defense: odds.defense,
+            repelled,
+            fallen: fallen.clone(),
+            stolen,
+        });
+
+        let stood: HashSet<&EntityId> = defenders.iter().map(|(id, _)| id).collect();
+        let mut surviving = Vec::new();
+        for mut entity in state.entities.drain(..) {
+            if !fallen.contains(&entity.id) {
+                if stood.contains(&entity.id) {
+                    entity.remember(tick, Experience::Defended { threat, repelled });
+                }
+                surviving.push(entity);
+                continue;
+            }
+            state.activity.entry(Key::new(&entity.tile)).or_default().deaths += 1;
+            drop_load(&mut entity, state.logistics, &mut state.resources, &mut state.stockpiles);
+            state.graveyard.add_corpse(Corpse {
+                entity_id: entity.id.clone(),
+                entity_type: format!("{:?}", entity.entity_type).to_lowercase(),
+                death_tick: tick,
+                cause: DeathCause::Slain,
+                tile: entity.tile.clone(),
+            });
+            events.push(tick, EventKind::EntityDied {
+                entity_id: entity.id.clone(),
+                entity_type: format!("{:?}", entity.entity_type).to_lowercase(),
+                cause: DeathCause::Slain,
+                tile: Key::new(&entity.tile),
+                snapshot: Some(entity.snapshot()),
+            });
+        }
+        state.entities = surviving.into();
+    }
+
+    /// Influence past the soft cap drains away and promises that have come
+    /// due are kept. Now and then (own RNG stream) a whisper spends a little
+    /// influence on insight, or on a promise of a gift to come.
+    fn process_whispers(&self, state: &mut GameState, events: &mut TickEvents) {
+        let tick = state.tick;
+        let tuning = &state.config.tuning;
+
+        let excess = state.resources.get("influence") - tuning.influence_soft_cap;
+        if excess > 0.0 {
+            state.resources.add("influence", -excess * tuning.influence_decay / state.ticks_per_second);
+        }
+
+        let (kept, pending): (Vec<Promise>, Vec<Promise>) = std::mem::take(&mut state.promises)
+            .into_iter()
+            .partition(|promise| promise.due <= tick);
+        state.promises = pending;
+        for Promise { resource, amount, .. } in kept {
+            state.resources.add(&resource, amount);
+            events.push(tick, EventKind::PromiseKept { resource, amount });
+        }
+
+        let cost = tuning.whisper_cost;
+        if state.resources.get("influence") < cost {
+            return;
+        }
+        let mut rng = SeededRng::from_tick(self.seed ^ constants::WHISPER_STREAM, tick);
+        if !rng.chance(tuning.whisper_chance / state.ticks_per_second) {
+            return;
+        }
+        state.resources.add("influence", -cost);
+        let whisper = if rng.chance(0.5) {
+            state.resources.add("insight", tuning.whisper_insight);
+            Whisper::Insight { amount: tuning.whisper_insight }
+        } else {
+            let gifts = constants::WHISPER_GIFTS;
+            let resource = Key::new(gifts[rng.choose_index(gifts.len()).unwrap_or(0)]);
+            let promise = Promise {
+                due: tick + state.ticks_for(tuning.whisper_promise_delay),
+                resource,
+                amount: state.valuation.quote(tuning.whisper_promise_worth, resource.as_str(), &state.resources),
+            };
+            state.promises.push(promise.clone());
+            Whisper::Promise(promise)
+        };
+        events.push(tick, EventKind::Whispered { influence_spent: cost, whisper });
+    }
+
+    /// Process outposts: supplied ones produce, unsupplied ones decay
+    fn process_outposts(&self, state: &mut GameState, events: &mut TickEvents) {
+        let tick = state.tick;
+        let decay = state.config.tuning.outpost_decay_per_tick / state.ticks_per_second;
+        let mut lost = Vec::new();
+
+        for (tile, outpost) in state.outposts.iter_mut() {
+            if outpost.is_supplied() {
+                state.resources.add(&outpost.resource, outpost.rate / state.ticks_per_second);
+                outpost.supply_ticks -= 1;
+                if outpost.supply_ticks == 0 {
+                    events.push(tick, EventKind::OutpostUnsupplied { tile: Key::new(tile) });
+                }
+            } else {
+                outpost.condition -= decay;
+                if outpost.condition <= 0.0 {
+                    lost.push(tile.clone());
+                }
+            }
+        }
+
+        for tile in lost {
+            state.outposts.remove(&tile);
+            events.push(tick, EventKind::OutpostLost { tile: tile.into() });
+        }
+    }
+
+    /// Work out what needs hauling, in the order it should be done.
+    ///
+    /// Inputs come first: each tile-bound system's stockpile is topped up to
+    /// `LOGISTICS_BUFFER_TICKS` of consumption from the colony store. Then any
+    /// stockpiled goods beyond that buffer are taken to the colony store.
+    fn haul_jobs(state: &GameState) -> Vec<HaulJob> {
+        // tile -> resource -> amount to keep on hand
+        let mut buffers: BTreeMap<&str, BTreeMap<&str, f64>> = BTreeMap::new();
+        for (_, system) in det::entries(state.systems.iter()) {
+            if let (Some(tile), Some(consumes), false) = (&system.tile, &system.consumes, system.is_disabled()) {
+                let buffer = buffers.entry(tile.as_str()).or_default();
+                for (resource, rate) in consumes {
+                    *buffer.entry(resource.as_str()).or_default() += rate * state.config.tuning.logistics_buffer_ticks;
+                }
+            }
+        }
+
+        let mut jobs = Vec::new();
+
+        // Supply runs: colony store -> tiles short of inputs
+        for (tile, wants) in &buffers {
+            for (resource, want) in wants {
+                let have = state.stockpiles.get(*tile).map(|p| p.get(resource)).unwrap_or(0.0);
+                if want - have > f64::EPSILON {
+                    jobs.push(HaulJob {
+                        resource: Key::new(resource),
+                        amount: want - have,
+                        from: Depot::Colony,
+                        to: Depot::Tile(tile.to_string()),
+                    });
+                }
+            }
+        }
+
+        // Collection runs: surplus at tiles -> colony store
+        for (tile, pile) in &state.stockpiles {
+            for resource in det::sorted(pile.amounts.keys()) {
+                let keep = buffers.get(tile.as_str())
+                    .and_then(|b| b.get(resource.as_str()))
+                    .copied()
+                    .unwrap_or(0.0);
+                let surplus = pile.get(resource) - keep;
+                if surplus > f64::EPSILON {
+                    jobs.push(HaulJob {
+                        resource: *resource,
+                        amount: surplus,
+                        from: Depot::Tile(tile.clone()),
+                        to: Depot::Colony,
+                    });
+                }
+            }
+        }
+
+        jobs
+    }
+
+    /// Process hauling: haulers (in ID order) work through the job list
+    fn process_hauling(&self, state: &mut GameState, events: &mut TickEvents) {
+        if state.logistics.is_global() {
+            return;
+        }
+        if state.has_feature(feature::CARRYING) {
+            return Self::carry_hauls(state, events);
+        }
+        let tick = state.tick;
+
+        let mut jobs = Self::haul_jobs(state);
+        if jobs.is_empty() {
+            return;
+        }
+
+        let haulers = det::sorted(state.entities.iter()
+            .filter(|e| (e.role == Some(AntRole::Hauler) || e.task == Some(Task::Haul)) && !e.is_dormant())
+            .map(|e| e.id.clone()));
+
+        let mut job_idx = 0;
+        for hauler_id in haulers {
+            let mut capacity = state.per_tick(state.config.tuning.hauler_capacity);
+
+            while capacity > f64::EPSILON && job_idx < jobs.len() {
+                let job = &mut jobs[job_idx];
+                let available = state.depot(&job.from).map(|d| d.get(&job.resource)).unwrap_or(0.0);
+                let amount = job.amount.min(capacity).min(available);
+                if amount <= 0.0 {
+                    job_idx += 1;
+                    continue;
+                }
+
+                state.depot_mut(&job.from).add(&job.resource, -amount);
+                state.depot_mut(&job.to).add(&job.resource, amount);
+                job.amount -= amount;
+                capacity -= amount;
+
+                events.push(tick, EventKind::GoodsHauled {
+                    hauler_id: hauler_id.clone(),
+                    resource: job.resource,
+                    amount,
+                    from: job.from.clone(),
+                    to: job.to.clone(),
+                });
+
+                if job.amount <= f64::EPSILON {
+                    job_idx += 1;
+                }
+            }
+        }
+    }
+
+    /// Hauling with `feature::CARRYING`: a loaded hauler delivers once it's
+    /// where the load is bound; an empty one picks up for the first jobs
+    /// (sharing one route) it's standing at the start of. Goods already on
+    /// the way count against the jobs they serve, so nobody doubles up.
+    fn carry_hauls(state: &mut GameState, events: &mut TickEvents) {
+        let walking = state.has_feature(feature::MOVEMENT);
+        let capacity = state.config.tuning.carry_capacity;
+
+        let mut jobs = Self::haul_jobs(state);
+        for entity in state.entities.iter() {
+            let Some(route) = &entity.route else {
+                continue;
+            };
+            for (resource, carried) in det::entries(entity.inventory.iter()) {
+                let mut left = *carried;
+                for job in jobs.iter_mut().filter(|j| j.resource == resource.as_str() && j.from == route.from && j.to == route.to) {
+                    let served = job.amount.min(left);
+                    job.amount -= served;
+                    left -= served;
+                }
+            }
+        }
+
+        let haulers = det::sorted(state.entities.iter()
+            .filter(|e| (e.role == Some(AntRole::Hauler) || e.task == Some(Task::Haul)) && !e.is_dormant())
+            .map(|e| e.id.clone()));
+        for hauler_id in haulers {
+            let Some(hauler) = state.entities.iter().position(|e| e.id == hauler_id) else {
+                continue;
+            };
+            if !state.entities[hauler].inventory.is_empty() {
+                Self::deliver(state, hauler, walking, events);
+                continue;
+            }
+
+            let tile = state.entities[hauler].tile.clone();
+            let mut room = capacity;
+            let mut loaded: Option<Route> = None;
+            for job in jobs.iter_mut().filter(|j| j.amount > f64::EPSILON) {
+                if walking && !in_place(&state.map, &tile, job.from.tile()) {
+                    continue;
+                }
+                let route = Route { from: job.from.clone(), to: job.to.clone() };
+                if loaded.as_ref().is_some_and(|r| *r != route) {
+                    continue;
+                }
+                let available = state.depot(&job.from).map(|d| d.get(&job.resource)).unwrap_or(0.0);
+                let amount = job.amount.min(room).min(available);
+                if amount <= 0.0 {
+                    continue;
+                }
+                state.depot_mut(&job.from).add(&job.resource, -amount);
+                state.entities[hauler].load(&job.resource, amount, route.clone());
+                loaded = Some(route);
+                job.amount -= amount;
+                room -= amount;
+                if room <= f64::EPSILON {
+                    break;
+                }
+            }
+            if loaded.is_some() && !walking {
+                Self::deliver(state, hauler, walking, events);
+            }
+        }
+    }
+
+    /// Hand over what the ant at `index` is carrying if it's where the load
+    /// is bound (anywhere, without movement)
+    fn deliver(state: &mut GameState, index: usize, walking: bool, events: &mut TickEvents) {
+        let carrier = &state.entities[index];
+        let Some(route) = &carrier.route else {
+            return;
+        };
+        if walking && !in_place(&state.map, &carrier.tile, route.to.tile()) {
+            return;
+        }
+        let (goods, route) = state.entities[index].unload();
+        let Some(route) = route else {
+            return;
+        };
+        let carrier_id = state.entities[index].id.clone();
+        for (resource, amount) in goods {
+            state.depot_mut(&route.to).add(&resource, amount);
+            events.push(state.tick, EventKind::GoodsHauled {
+                hauler_id: carrier_id.clone(),
+                resource: Key::new(&resource),
+                amount,
+                from: route.from.clone(),
+                to: route.to.clone(),
+            });
+        }
+    }
+
+    /// Process the action queue
+    fn process_actions(&self, state: &mut GameState, events: &mut TickEvents, rng: &mut SeededRng) {
+        let tick = state.tick;
+        let supply_duration = state.ticks_for(state.config.tuning.outpost_supply_duration);
+        let mut remaining = Vec::new();
+
+        for mut action in std::mem::take(&mut state.queues.actions) {
+            if action.ticks_remaining <= 1 && action.action_type == HOST_ACTION {
+                // Hosting comes round again rather than completing
+                remaining.extend(Self::host_round(state, action, events));
+            } else if action.ticks_remaining <= 1 {
+                // Action complete
+                events.push(tick, EventKind::ActionComplete {
+                    action_id: action.id.clone(),
+                    action_type: action.action_type.clone(),
+                });
+
+                // Apply effects
+                if let Some(effects) = &action.effects {
+                    if let Some(resources) = &effects.resources {
+                        state.resources.add_all(resources);
+                    }
+                }
+
+                if action.kind() == Some(ActionKind::Clean) {
+                    let tile = action.target.as_deref().and_then(|t| state.map.get_tile_mut(t));
+                    if let Some(tile) = tile.filter(|t| t.contamination.is_some()) {
+                        tile.contamination = Some(0.0);
+                    }
+                }
+
+                if action.action_type == SUPPLY_ACTION {
+                    let tile = action.target.clone().unwrap_or_default();
+                    if let Some(outpost) = state.outposts.get_mut(&tile) {
+                        outpost.supply_ticks += supply_duration;
+                        outpost.condition = 1.0;
+                        events.push(tick, EventKind::OutpostSupplied {
+                            tile: tile.into(),
+                            supply_ticks: outpost.supply_ticks,
+                        });
+                    }
+                }
+
+                if action.action_type == EXPEDITION_ACTION {
+                    if let Some(idx) = state.expeditions.iter().position(|x| x.id == action.id) {
+                        let expedition = state.expeditions.remove(idx);
+                        self.resolve_expedition(state, expedition, events, rng);
+                    }
+                }
+            } else {
+                action.ticks_remaining -= 1;
+                remaining.push(action);
+            }
+        }
+
+        state.queues.actions = remaining;
+    }
+
+    /// Set aside systems whose rates can't be right, saying so once for
+    /// each, and take back any that were fixed
+    fn audit_systems(state: &mut GameState, events: &mut TickEvents) {
+        let mut known = guard::known_resources(state);
+        known.extend(state.valuation.base.keys().map(|key| key.as_ref()));
+        // A disabled system declares no rates, so it's never suspect
+        let findings: Vec<(String, Option<Suspicion>)> = det::entries(state.systems.iter())
+            .into_iter()
+            .map(|(id, system)| (id.clone(), system.suspicion(&known)))
+            .collect();
+
+        for (system_id, suspicion) in findings {
+            let Some(system) = state.systems.get_mut(&system_id) else { continue };
+            if system.suspect == suspicion {
+                continue;
+            }
+            system.suspect = suspicion.clone();
+            if let Some(suspicion) = suspicion {
+                events.push(state.tick, EventKind::ConfigurationSuspect { system_id, suspicion });
+            }
+        }
+    }
+
+    /// Process production systems
+    fn process_systems(&self, state: &mut GameState, events: &mut TickEvents) {
+        Self::audit_systems(state, events);
+        let tick = state.tick;
+        let shrine_window = state.ticks_for(state.config.tuning.shrine_window_ticks);
+        let half_volume = state.config.tuning.shrine_half_efficiency_volume;
+        let weather = state.current_weather().map_or(1.0, |weather| weather.production_factor());
+        let per_tick = state.per_tick(1.0);
+        let empty = Resources::new();
+
+        // Collect system operations first to avoid borrow issues
+        let outcomes: Vec<_> = det::entries(state.systems.iter())
+            .into_iter()
+            .filter(|(_, system)| !system.is_disabled() && system.suspect.is_none())
+            .map(|(id, system)| {
+                // Tile-bound systems work out of their stockpile in local logistics
+                let tile = state.stockpile_for(system.tile.as_deref());
+                let pool = match &tile {
+                    Some(tile) => state.stockpiles.get(tile).unwrap_or(&empty),
+                    None => &state.resources,
+                };
+
+                // Rates are per second; a tick takes its share
+                let mut consumes = system.consumes.clone().unwrap_or_default();
+                for amount in consumes.values_mut() {
+                    *amount *= per_tick;
+                }
+                if !pool.can_consume_all(&consumes) {
+                    return (id.clone(), None);
+                }
+                let mut generates = system.generates.clone().unwrap_or_default();
+
+                // Corpse boosts add to whatever the system declared they amplify
+                if let Some(resource) = system.organic_boost {
+                    let bonus = system.total_corpse_bonus(tick);
+                    if bonus > 0.0 {
+                        *generates.entry(resource.as_str().to_string()).or_default() += bonus;
+                    }
+                }
+
+                // Shrines lose efficiency the more they convert per window
+                if system.system_type == SystemType::Shrine {
+                    let efficiency = Self::shrine_efficiency(
+                        system.window_volume(tick, shrine_window),
+                        half_volume,
+                    );
+                    for amount in generates.values_mut() {
+                        *amount *= efficiency;
+                    }
+                }
+
+                // The season and the sky bear on everything that grows
+                for amount in generates.values_mut() {
+                    *amount *= weather * per_tick;
+                }
+
+                (id.clone(), Some((tile, consumes, generates)))
+            })
+            .collect();
+
+        // Track how long each system has gone without its inputs, and what
+        // it turned out
+        let retention = state.config.tuning.production_history;
+        let mut operations = Vec::new();
+        for (system_id, operation) in outcomes {
+            let starved = operation.is_none();
+            if let Some(system) = state.systems.get_mut(&system_id) {
+                match (starved, system.starved_since) {
+                    (true, None) => system.starved_since = Some(tick),
+                    (false, Some(_)) => system.starved_since = None,
+                    _ => {}
+                }
+                let output = operation.as_ref()
+                    .map_or(0.0, |(_, _, generates)| det::entries(generates.iter()).into_iter().map(|(_, amount)| amount).sum());
+                system.record_output(output, retention);
+            }
+            if let Some((tile, consumes, generates)) = operation {
+                operations.push((system_id, tile, consumes, generates));
+            }
+        }
+
+        // Apply operations
+        for (system_id, tile, consumes, generates) in operations {
+            if let Some(system) = state.systems.get_mut(&system_id) {
+                if system.system_type == SystemType::Shrine {
+                    let volume: f64 = det::entries(consumes.iter()).into_iter().map(|(_, amount)| amount).sum();
+                    system.record_conversion(tick, shrine_window, volume);
+                }
+            }
+
+            let pool = state.pool_mut(tile.as_deref());
+
+            // Consume resources
+            for (resource, amount) in &consumes {
+                pool.add(resource, -amount);
+            }
+
+            // Generate resources
+            for (resource, amount) in &generates {
+                pool.add(resource, *amount);
+            }
+
+            if !consumes.is_empty() || !generates.is_empty() {
+                events.push(tick, EventKind::SystemProduced {
+                    system_id,
+                    produced: generates,
+                    consumed: consumes,
+                    stockpile: tile.map(Key::from),
+                });
+            }
+        }
+
+        // Expire old corpse boosts (order-free: each system only touches its own)
+        for system in state.systems.values_mut() {
+            system.expire_corpse_boosts(tick);
+        }
+    }
+
+    /// Shrine output multiplier for the volume already converted this window.
+    ///
+    /// 1.0 for a fresh window, 0.5 at `half_volume`, approaching zero as
+    /// volume grows.
+    pub fn shrine_efficiency(window_volume: f64, half_volume: f64) -> f64 {
+        1.0 / (1.0 + window_volume.max(0.0) / half_volume)
+    }
+
+    /// Process entity lifecycle (aging, hunger, eating, death)
+    fn process_entities(&self, state: &mut GameState, events: &mut TickEvents) {
+        let tick = state.tick;
+        let mut surviving = Vec::new();
+
+        // Aging and hunger touch nothing but the entity itself
+        let hunger_scale = state.per_tick(state.config.hunger_rate_scale);
+        let weather = state.current_weather().map_or(1.0, |weather| weather.hunger_factor());
+        for_each_entity(&mut state.entities, constants::PARALLEL_ENTITY_THRESHOLD, |entity| {
+            entity.age += 1;
+            // Visitors don't feel the colony's weather
+            let weather = if entity.entity_type == EntityType::Ant { weather } else { 1.0 };
+            entity.hunger -= entity.hunger_rate * hunger_scale * weather * entity.trait_factor(Trait::hunger_factor);
+        });
+
+        // Eating and death share the colony's resources, so they run in state order
+        let walking = state.has_feature(feature::MOVEMENT);
+        let injuries = state.has_feature(feature::INJURIES);
+        for mut entity in state.entities.drain(..) {
+            let activity = state.activity.entry(Key::new(&entity.tile)).or_default();
+            activity.visits += 1;
+            // Walking ants eat at the origin; visitors and sleepers where they are
+            let at_table = !walking
+                || entity.entity_type == EntityType::Visitor
+                || entity.is_dormant()
+                || in_place(&state.map, &entity.tile, ORIGIN);
+            // Try to eat if hungry
+            let mut survived_famine = false;
+            if at_table && entity.hunger < state.config.tuning.hunger_threshold_eat {
+                if let Some(food) = &entity.food {
+                    // Special case: hungry visitors eat influence
+                    if food == "influence" && entity.subtype == Some(VisitorType::Hungry) {
+                        if state.resources.get("influence") >= state.config.tuning.hungry_influence_consume {
+                            state.resources.add("influence", -state.config.tuning.hungry_influence_consume);
+                            entity.hunger = (entity.hunger + state.config.tuning.hungry_hunger_gain).min(state.config.tuning.max_hunger);
+                            activity.meals += 1;
+
+                            // Transform influence into strange_matter
+                            if entity.transforms == Some(true) {
+                                state.resources.add("strange_matter", state.config.tuning.hungry_strange_matter_produce);
+                                events.push(tick, EventKind::InfluenceTransformed {
+                                    visitor_id: entity.id.clone(),
+                                    influence_consumed: state.config.tuning.hungry_influence_consume,
+                                    strange_matter_produced: state.config.tuning.hungry_strange_matter_produce,
+                                });
+                            }
+                        }
+                    } else if state.resources.get(food) >= 1.0 {
+                        state.resources.add(food, -1.0);
+                        survived_famine = entity.hunger < state.config.tuning.famine_hunger && entity.entity_type == EntityType::Ant;
+                        entity.hunger = (entity.hunger + state.config.tuning.hunger_gain_from_eating).min(state.config.tuning.max_hunger);
+                        activity.meals += 1;
+
+                        match state.meal_reports {
+                            MealReports::Batched { .. } => {
+                                let tally = state.meal_tallies.entry(entity.id.clone())
+                                    .or_insert_with(|| MealTally { food: Key::new(food), meals: 0, hunger_after: 0.0 });
+                                tally.meals += 1;
+                                tally.hunger_after = entity.hunger;
+                            }
+                            MealReports::Shortages if state.resources.get(food) >= constants::MEAL_SHORTAGE_STOCK => {}
+                            _ => events.push(tick, EventKind::EntityAte {
+                                entity_id: entity.id.clone(),
+                                food: Key::new(food),
+                                hunger_after: entity.hunger,
+                            }),
+                        }
+                    }
+                }
+            }
+
+            if survived_famine {
+                entity.remember(tick, Experience::SurvivedFamine);
+            }
+
+            // Without starvation deaths, an empty stomach just means sleep
+            if !state.config.starvation_deaths {
+                if entity.hunger <= 0.0 {
+                    entity.hunger = 0.0;
+                    if !entity.is_dormant() {
+                        entity.dormant = Some(true);
+                        events.push(tick, EventKind::EntityDormant {
+                            entity_id: entity.id.clone(),
+                            tile: Key::new(&entity.tile),
+                        });
+                    }
+                } else if entity.is_dormant() {
+                    entity.dormant = None;
+                    events.push(tick, EventKind::EntityAwoke {
+                        entity_id: entity.id.clone(),
+                    });
+                }
+            }
+            let cause = if injuries && entity.entity_type == EntityType::Ant {
+                Self::wear(&mut entity, &state.map, &state.config, state.ticks_per_second, tick, events)
+            } else {
+                match entity.cause_of_death() {
+                    Some(DeathCause::Starvation) if entity.is_dormant() => {
+                        (entity.age >= entity.max_age).then_some(DeathCause::OldAge)
+                    }
+                    cause => cause,
+                }
+            };
+
+            // Check for death
+            if let Some(cause) = cause {
+                // Visitors just disappear (handled separately for gifts)
+                if entity.entity_type == EntityType::Visitor {
+                    let gift = entity.gift_on_death.clone();
+                    if let Some(ref g) = gift {
+                        state.resources.add_all(g);
+                    }
+                    events.push(tick, EventKind::VisitorDeparted {
+                        visitor_id: entity.id.clone(),
+                        visitor_type: entity.subtype.clone().unwrap_or(VisitorType::Wanderer),
+                        name: entity.name.clone().unwrap_or_default(),
+                        gift,
+                        snapshot: Some(entity.snapshot()),
+                    });
+                } else {
+                    activity.deaths += 1;
+                    drop_load(&mut entity, state.logistics, &mut state.resources, &mut state.stockpiles);
+
+                    // Add to graveyard
+                    state.graveyard.add_corpse(Corpse {
+                        entity_id: entity.id.clone(),
+                        entity_type: format!("{:?}", entity.entity_type).to_lowercase(),
+                        death_tick: tick,
+                        cause: cause.clone(),
+                        tile: entity.tile.clone(),
+                    });
+
+                    events.push(tick, EventKind::EntityDied {
+                        entity_id: entity.id.clone(),
+                        entity_type: format!("{:?}", entity.entity_type).to_lowercase(),
+                        cause,
+                        tile: Key::new(&entity.tile),
+                        snapshot: Some(entity.snapshot()),
+                    });
+                }
+            } else {
+                surviving.push(entity);
+            }
+        }
+
+        state.entities = surviving.into();
+
+        // Nurses feed the brood (without the flag, the queen's phase does)
+        if state.has_feature(feature::NURSES) {
+            Self::tend_brood(state, events);
+        }
+
+        // Send out a batch of meal summaries at the end of each window
+        if let MealReports::Batched { window } = state.meal_reports {
+            if tick.is_multiple_of(state.ticks_for(window).max(1)) {
+                for (entity_id, tally) in std::mem::take(&mut state.meal_tallies) {
+                    events.push(tick, EventKind::MealsEaten {
+                        entity_id,
+                        food: tally.food,
+                        meals: tally.meals,
+                        hunger_after: tally.hunger_after,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Wear an ant's health down for whatever is harming it (starving, a
+    /// blighted or contaminated tile), or heal it while it's well fed and
+    /// unharmed. Returns what it died of, if it did: the worst harm when
+    /// its health runs out, or old age.
+    fn wear(entity: &mut Entity, map: &GameMap, config: &SimConfig, ticks_per_second: f64, tick: u64, events: &mut TickEvents) -> Option<DeathCause> {
+        let tuning = &config.tuning;
+        entity.hunger = entity.hunger.max(0.0);
+        let tile = map.get_tile(&entity.tile);
+        let starving = config.starvation_deaths && entity.hunger <= 0.0;
+        let harms = [
+            (Harm::Starvation, if starving { tuning.starvation_damage } else { 0.0 }),
+            (Harm::Blight, if tile.is_some_and(Tile::is_blighted) { tuning.blight_damage } else { 0.0 }),
+            (Harm::Sickness, tile.and_then(|t| t.contamination).unwrap_or(0.0) * tuning.sickness_damage),
+        ];
+        let damage: f64 = harms.iter().map(|(_, amount)| amount).sum();
+        let full = entity.health >= constants::MAX_HEALTH;
+
+        if damage > 0.0 {
+            let (worst, _) = harms.into_iter()
+                .fold((Harm::Starvation, 0.0), |worst, harm| if harm.1 > worst.1 { harm } else { worst });
+            entity.health = (entity.health - damage / ticks_per_second).max(0.0);
+            if full {
+                events.push(tick, EventKind::EntityInjured {
+                    entity_id: entity.id.clone(),
+                    harm: worst,
+                    health: entity.health,
+                });
+            }
+            if entity.health <= 0.0 {
+                return Some(worst.cause());
+            }
+        } else if !full && entity.hunger >= tuning.hunger_threshold_eat {
+            entity.health = (entity.health + tuning.health_regen / ticks_per_second).min(constants::MAX_HEALTH);
+            if entity.health >= constants::MAX_HEALTH {
+                events.push(tick, EventKind::EntityRecovered { entity_id: entity.id.clone() });
+            }
+        }
+        (entity.age >= entity.max_age).then_some(DeathCause::OldAge)
+    }
+
+    /// Process undertaker corpse collection
+    fn process_undertakers(&self, state: &mut GameState, events: &mut TickEvents, _rng: &mut SeededRng) {
+        let tick = state.tick;
+        let processing_ticks = state.ticks_for(state.config.tuning.corpse_processing_ticks);
+        let boost_duration = state.ticks_for(state.config.tuning.corpse_boost_duration);
+
+        // Check if compost tile is blighted
+        let compost_blighted = state.map.get_tile("compost")
+            .map(|t| t.is_blighted())
+            .unwrap_or(false);
+
+        if compost_blighted {
+            return;
+        }
+        let walking = state.has_feature(feature::MOVEMENT);
+
+        // Find undertaker entities
+        let undertaker_ids: Vec<String> = state.entities.iter()
+            .filter(|e| e.role == Some(AntRole::Undertaker) && !e.is_dormant())
+            .map(|e| e.id.clone())
+            .collect();
+
+        for undertaker_id in undertaker_ids {
+            let undertaker = match state.entities.iter_mut().find(|e| e.id == undertaker_id) {
+                Some(e) => e,
+                None => continue,
+            };
+
+            let processing = undertaker.processing_corpse.unwrap_or(false);
+
+            if processing {
+                // Carry the corpse to the compost first, if there's a way
+                if walking && !in_place(&state.map, &undertaker.tile, "compost") {
+                    continue;
+                }
+                // Continue processing
+                if undertaker.advance_timer(timer::PROCESSING) >= processing_ticks {
+                    // Corpse delivered
+                    undertaker.processing_corpse = Some(false);
+                    undertaker.clear_timer(timer::PROCESSING);
+
+                    // Boost the organic system with the fewest boosts running
+                    // (lowest ID on ties)
+                    let target = det::min_by_key(
+                        state.systems.iter().filter(|(_, system)| system.organic_boost.is_some()),
+                        |(_, system)| system.corpse_boosts.len(),
+                        |(id, _)| *id,
+                    )
+                    .map(|(id, _)| id.clone());
+                    if let Some(system) = target.and_then(|id| state.systems.get_mut(&id)) {
+                        system.corpse_boosts.push(CorpseBoost {
+                            expires_at_tick: tick + boost_duration,
+                            bonus: state.config.tuning.corpse_nutrient_boost,
+                        });
+                    }
+
+                    // Add contamination
+                    if let Some(tile) = state.map.get_tile_mut("compost") {
+                        tile.add_contamination(state.config.tuning.contamination_per_corpse);
+
+                        let contamination = tile.contamination.unwrap_or(0.0);
+                        state.graveyard.mark_processed();
+
+                        events.push(tick, EventKind::CorpseProcessed {
+                            undertaker_id: undertaker_id.clone(),
+                            total_processed: state.graveyard.total_processed,
+                            contamination,
+                        });
+                    }
+                }
+            } else if state.graveyard.has_corpses() {
+                // Pick up a corpse: one lying here, or (walking) none until
+                // there, unless none can be reached
+                let taken = if !walking {
+                    state.graveyard.take_corpse()
+                } else if let Some(corpse) = state.graveyard.take_corpse_on(&undertaker.tile) {
+                    Some(corpse)
+                } else if corpse_to_fetch(&state.graveyard, &state.map, &undertaker.tile).is_none() {
+                    state.graveyard.take_corpse()
+                } else {
+                    None
+                };
+                if taken.is_none() {
+                    continue;
+                }
+                undertaker.processing_corpse = Some(true);
+                undertaker.set_timer(timer::PROCESSING, 0);
+            }
+        }
+    }
+
+    /// Process contamination and blight
+    fn process_blight(&self, state: &mut GameState, events: &mut TickEvents, rng: &mut SeededRng) {
+        let tick = state.tick;
+        let duration = state.ticks_for(state.config.tuning.blight_duration);
+        let blight_scale = state.config.blight_chance_scale;
+
+        // Leave the map untouched (and shared with snapshots) when there's nothing to do
+        let active = state.map.get_tile("compost")
+            .is_some_and(|t| t.is_blighted() || t.contamination.unwrap_or(0.0) > 0.0);
+        if !active {
+            return;
+        }
+
+        // Handle active blight ticking down
+        let Some(tile) = state.map.get_tile_mut("compost") else {
+            return;
+        };
+        if tile.is_blighted() {
+            if tile.tick_blight() {
+                events.push(tick, EventKind::BlightCleared {
+                    tile: Key::new("compost"),
+                });
+
+                // Restart the compost heap, if the blight was what stopped it
+                let stopped = tile.blight_stopped.take().unwrap_or_else(|| vec!["compost_heap".to_string()]);
+                Self::release(state, &stopped);
+            }
+            return; // Don't roll for new blight while blighted
+        }
+
+        // Roll for blight based on contamination
+        let contamination = tile.contamination.unwrap_or(0.0);
+        if !(state.config.blight && contamination > 0.0 && rng.chance(contamination * blight_scale)) {
+            return;
+        }
+        tile.start_blight(duration);
+        events.push(tick, EventKind::BlightStruck {
+            tile: Key::new("compost"),
+            contamination,
+            duration_ticks: duration,
+        });
+
+        // It kills everyone on the heap (with injuries, it wears them down
+        // instead)
+        let lethality = if state.has_feature(feature::INJURIES) { 0.0 } else { 1.0 };
+        let stopped = Self::strike(state, events, Hazard::Blight, "compost", &["compost_heap".to_string()], lethality, rng);
+        if let Some(tile) = state.map.get_tile_mut("compost") {
+            tile.blight_stopped = Some(stopped);
+        }
+    }
+
+    /// Restart those of `systems` no hazard holds any more. Systems stopped
+    /// for another reason (the guard's quarantine) were never handed to a
+    /// hazard, so they stay stopped.
+    fn release(state: &mut GameState, systems: &[String]) {
+        for id in systems {
+            // Order-free: any tile holding it will do
+            let held = state.map.tiles.values().any(|tile| {
+                let blight = tile.is_blighted() && tile.blight_stopped.as_ref().is_some_and(|s| s.contains(id));
+                let hazard = tile.hazard.as_ref().and_then(|h| h.stopped.as_ref()).is_some_and(|s| s.contains(id));
+                blight || hazard
+            });
+            if let Some(system) = state.systems.get_mut(id).filter(|_| !held) {
+                system.enable();
+            }
+        }
+    }
+
+    /// Let `hazard` strike `tile`: its `systems` stop, each entity standing
+    /// there dies with `lethality` (rolled on `rng` only when it's between
+    /// 0 and 1), and the ants left remember it. Returns the systems it
+    /// stopped, leaving out any already stopped.
+    fn strike(
+        state: &mut GameState,
+        events: &mut TickEvents,
+        hazard: Hazard,
+        tile: &str,
+        systems: &[String],
+        lethality: f64,
+        rng: &mut SeededRng,
+    ) -> Vec<String> {
+        let tick = state.tick;
+        let mut stopped = Vec::new();
+        for id in systems {
+            if let Some(system) = state.systems.get_mut(id) {
+                if !system.is_disabled() {
+                    stopped.push(id.clone());
+                }
+                system.disable();
+                system.corpse_boosts.clear();
+            }
+        }
+
+        let cause = hazard.cause();
+        let mut surviving = Vec::new();
+        for mut entity in state.entities.drain(..) {
+            let caught = entity.tile == tile && match lethality {
+                certain if certain >= 1.0 => true,
+                none if none <= 0.0 => false,
+                odds => rng.chance(odds),
+            };
+            if !caught {
+                surviving.push(entity);
+                continue;
+            }
+            state.activity.entry(Key::new(tile)).or_default().deaths += 1;
+            drop_load(&mut entity, state.logistics, &mut state.resources, &mut state.stockpiles);
+            let entity_type = format!("{:?}", entity.entity_type).to_lowercase();
+            let snapshot = Some(entity.snapshot());
+            events.push(tick, match hazard {
+                Hazard::Blight => EventKind::BlightKill { entity_id: entity.id.clone(), tile: Key::new(tile), snapshot },
+                _ => EventKind::EntityDied {
+                    entity_id: entity.id.clone(),
+                    entity_type: entity_type.clone(),
+                    cause: cause.clone(),
+                    tile: Key::new(tile),
+                    snapshot,
+                },
+            });
+            state.graveyard.add_corpse(Corpse {
+                entity_id: entity.id.clone(),
+                entity_type,
+                death_tick: tick,
+                cause: cause.clone(),
+                tile: entity.tile.clone(),
+            });
+        }
+
+        let witnessed = match hazard {
+            Hazard::Blight => Experience::WitnessedBlight { tile: Key::new(tile) },
+            hazard => Experience::WitnessedHazard { hazard, tile: Key::new(tile) },
+        };
+        for entity in surviving.iter_mut().filter(|e| e.entity_type == EntityType::Ant) {
+            entity.remember(tick, witnessed.clone());
+        }
+        state.entities = surviving.into();
+        stopped
+    }
+
+    /// Floods and cave-ins (own RNG stream): those that have run their
+    /// course pass and their tiles start up again, then low tiles roll for
+    /// a flood while it rains and freshly dug ones for a cave-in
+    fn process_hazards(&self, state: &mut GameState, events: &mut TickEvents) {
+        let tick = state.tick;
+        let tuning = &state.config.tuning;
+        let raining = state.current_weather().is_some_and(|weather| weather.sky == Sky::Rain);
+        let fresh_for = state.ticks_for(tuning.cave_in_window);
+        let lethality = tuning.hazard_lethality;
+        let flood = (tuning.flood_chance / state.ticks_per_second, state.ticks_for(tuning.flood_duration));
+        let cave_in = (tuning.cave_in_chance / state.ticks_per_second, state.ticks_for(tuning.cave_in_duration));
+
+        let mut passed = Vec::new();
+        let mut at_risk = Vec::new();
+        for (id, tile) in det::entries(state.map.tiles.iter()) {
+            match &tile.hazard {
+                Some(struck) if struck.has_passed(tick) => passed.push((*id, struck.hazard)),
+                Some(_) => {}
+                None => {
+                    if raining && tile.is_low() {
+                        at_risk.push((*id, Hazard::Flood));
+                    }
+                    if tile.dug_at.is_some_and(|dug| tick < dug + fresh_for) {
+                        at_risk.push((*id, Hazard::CaveIn));
+                    }
+                }
+            }
+        }
+        let systems_on = |state: &GameState, tile: &Key| -> Vec<String> {
+            det::sorted(state.systems.iter().filter(|(_, s)| s.tile.as_deref() == Some(tile.as_str())).map(|(id, _)| id.clone()))
+        };
+
+        for (tile, hazard) in passed {
+            let stopped = state.map.get_tile_mut(&tile)
+                .and_then(|tile| tile.hazard.take())
+                .and_then(|struck| struck.stopped)
+                .unwrap_or_else(|| systems_on(state, &tile));
+            Self::release(state, &stopped);
+            events.push(tick, EventKind::HazardCleared { hazard, tile });
+        }
+
+        if at_risk.is_empty() {
+            return;
+        }
+        let mut rng = SeededRng::from_tick(self.seed ^ constants::HAZARD_STREAM, tick);
+        for (tile, hazard) in at_risk {
+            let (chance, duration) = if hazard == Hazard::Flood { flood } else { cave_in };
+            if !rng.chance(chance) {
+                continue;
+            }
+            // A tile flooded this tick can't cave in too
+            let Some(struck) = state.map.get_tile_mut(&tile).filter(|t| t.hazard.is_none()) else {
+                continue;
+            };
+            struck.hazard = Some(Struck { hazard, until: tick + duration, stopped: None });
+            events.push(tick, EventKind::HazardStruck { hazard, tile, duration_ticks: duration });
+            let systems = systems_on(state, &tile);
+            let stopped = Self::strike(state, events, hazard, &tile, &systems, lethality, &mut rng);
+            if let Some(struck) = state.map.get_tile_mut(&tile).and_then(|t| t.hazard.as_mut()) {
+                struck.stopped = Some(stopped);
+            }
+        }
+    }
+
+    /// Process queen spawning
+    fn process_queen(&mut self, state: &mut GameState, events: &mut TickEvents, rng: &mut SeededRng) {
+        let tick = state.tick;
+
+        // Brood develop whether or not the queen is still laying
+        if !state.has_feature(feature::NURSES) {
+            Self::feed_brood(state, events);
+        }
+
+        // Only spawn if queen chamber exists
+        if !state.has_system("queen_chamber") {
+            return;
+        }
+
+        // With succession, only a living queen lays
+        if state.has_feature(feature::SUCCESSION) && !Self::succession(state, events, rng) {
+            return;
+        }
+
+        let reserve = state.config.tuning.spawn_reserve;
+        let affords = |state: &GameState, cost: &BTreeMap<Key, f64>| {
+            cost.iter().all(|(resource, amount)| state.resources.has(resource, amount + reserve))
+        };
+        let pair = [AntRole::Worker, AntRole::Undertaker];
+        let mut cost = state.config.spawn_cost(&pair);
+        let affordable = affords(state, &cost);
+
+        // Emergency spawn if colony is empty (but for royalty): adults, no
+        // waiting on brood
+        let is_emergency = state.entities.iter().all(|e| e.role.as_ref().is_some_and(AntRole::is_royal))
+            && state.brood.is_empty()
+            && affordable;
+
+        if is_emergency {
+            // Emergency spawn
+            let worker_id = rng.entity_id();
+            let undertaker_id = rng.entity_id();
+
+            let worker = Entity::new_worker(worker_id.clone(), "origin".to_string());
+            let undertaker = Entity::new_undertaker(undertaker_id.clone(), "origin".to_string());
+            for ant in [worker, undertaker] {
+                let traits = state.newborn_traits(&ant.id);
+                let ant = state.spawned(ant);
+                Self::welcome(state, events, ant.with_traits(traits));
+            }
+
+            for (resource, amount) in &cost {
+                state.resources.add(resource, -amount);
+            }
+
+            self.last_spawn_tick = tick;
+
+            events.push(tick, EventKind::EmergencySpawn {
+                worker_id,
+                undertaker_id,
+            });
+
+            return;
+        }
+
+        // Normal spawn check
+        if self.last_spawn_tick == 0 {
+            self.last_spawn_tick = tick;
+            return;
+        }
+
+        let ticks_since_spawn = tick - self.last_spawn_tick;
+        if ticks_since_spawn < state.spawn_interval() {
+            return;
+        }
+
+        if !affordable {
+            return;
+        }
+
+        // With nurses, the clutch brings one along whenever there's none
+        // grown or growing to tend the brood, if the colony can pay for it
+        let mut clutch = pair.to_vec();
+        let nursed = state.entities.iter().any(|e| e.role == Some(AntRole::Nurse))
+            || state.brood.iter().any(|b| b.role == AntRole::Nurse);
+        if state.has_feature(feature::NURSES) && !nursed {
+            let with_nurse = state.config.spawn_cost(&[AntRole::Worker, AntRole::Undertaker, AntRole::Nurse]);
+            if affords(state, &with_nurse) {
+                clutch.push(AntRole::Nurse);
+                cost = with_nurse;
+            }
+        }
+
+        // Lay brood; they hatch once they've been fed long enough
+        let stage = if state.has_feature(feature::LIFECYCLE) { LifeStage::Egg } else { LifeStage::Larva };
+        let development = state.stage_ticks(stage);
+        let ids: Vec<EntityId> = clutch.iter().map(|_| rng.entity_id()).collect();
+        for (role, id) in clutch.into_iter().zip(&ids) {
+            let traits = state.newborn_traits(id);
+            state.brood.push(Brood {
+                id: id.clone(),
+                role,
+                tile: "origin".to_string(),
+                laid_at: tick,
+                stage,
+                ticks_remaining: development,
+                neglected: 0,
+                starving: 0,
+                traits,
+            });
+        }
+
+        for (resource, amount) in &cost {
+            state.resources.add(resource, -amount);
+        }
+
+        self.last_spawn_tick = tick;
+
+        events.push(tick, EventKind::AntsSpawned {
+            worker_id: ids[0].clone(),
+            undertaker_id: ids[1].clone(),
+            nurse_id: ids.get(2).cloned(),
+            nutrients_consumed: cost.get("nutrients").copied().unwrap_or(0.0),
+            fungus_consumed: cost.get("fungus").copied().unwrap_or(0.0),
+            costs: cost,
+        });
+    }
+
+    /// Keep the royal line: note the queen's death, crown the eldest grown
+    /// princess in her place (or the founding queen, if there never was
+    /// one), and lay a princess once the queen is within
+    /// `succession_window` of her end. Returns whether a waking queen
+    /// reigns to lay.
+    fn succession(state: &mut GameState, events: &mut TickEvents, rng: &mut SeededRng) -> bool {
+        let tick = state.tick;
+        let eldest_princess = |state: &GameState| {
+            state.entities.iter()
+                .filter(|e| e.role == Some(AntRole::Princess))
+                .max_by(|a, b| a.age.cmp(&b.age).then_with(|| b.id.cmp(&a.id)))
+                .map(|e| e.id.clone())
+        };
+
+        if let Some(queen_id) = state.queen.clone() {
+            if state.get_entity(&queen_id).is_none() {
+                state.queen = None;
+                let heir = eldest_princess(state).or_else(|| {
+                    state.brood.iter().find(|b| b.role == AntRole::Princess).map(|b| b.id.clone())
+                });
+                events.push(tick, EventKind::QueenLost { queen_id, heir });
+            }
+        }
+
+        if state.queen.is_none() {
+            let lifespan = state.ticks_for(state.config.tuning.queen_lifespan);
+            let queen_id = if let Some(id) = eldest_princess(state) {
+                if let Some(princess) = state.get_entity_mut(&id) {
+                    princess.crown(lifespan);
+                }
+                id
+            } else if state.reign == 0 {
+                let id = rng.entity_id();
+                let founder = Entity { max_age: lifespan, ..state.spawned(Entity::new_queen(id.clone(), "origin".to_string())) };
+                let traits = state.newborn_traits(&id);
+                Self::welcome(state, events, founder.with_traits(traits));
+                id
+            } else {
+                return false;
+            };
+            state.reign += 1;
+            state.queen = Some(queen_id.clone());
+            events.push(tick, EventKind::QueenCrowned { queen_id, reign: state.reign });
+        }
+
+        let Some(queen) = state.queen.as_deref().and_then(|id| state.get_entity(id)) else {
+            return false;
+        };
+        let (ticks_left, waking) = (queen.max_age.saturating_sub(queen.age), !queen.is_dormant());
+
+        // Raise an heir while there's time
+        let heir_raised = state.entities.iter().any(|e| e.role == Some(AntRole::Princess))
+            || state.brood.iter().any(|b| b.role == AntRole::Princess);
+        if !heir_raised && ticks_left <= state.ticks_for(state.config.tuning.succession_window) {
+            let cost = state.config.spawn_cost(&[AntRole::Princess]);
+            let affordable = cost.iter()
+                .all(|(resource, amount)| state.resources.has(resource, amount + state.config.tuning.spawn_reserve));
+            if affordable {
+                for (resource, amount) in &cost {
+                    state.resources.add(resource, -amount);
+                }
+                let stage = if state.has_feature(feature::LIFECYCLE) { LifeStage::Egg } else { LifeStage::Larva };
+                let princess_id = rng.entity_id();
+                let traits = state.newborn_traits(&princess_id);
+                state.brood.push(Brood {
+                    id: princess_id.clone(),
+                    role: AntRole::Princess,
+                    tile: "origin".to_string(),
+                    laid_at: tick,
+                    stage,
+                    ticks_remaining: state.stage_ticks(stage),
+                    neglected: 0,
+                    starving: 0,
+                    traits,
+                });
+                events.push(tick, EventKind::PrincessLaid { princess_id, costs: cost });
+            }
+        }
+
+        waking
+    }
+
+    /// Feed developing brood in laying order; fed ones grow, and move on
+    /// (or hatch) when done. Unfed brood just wait, unless they starve.
+    fn feed_brood(state: &mut GameState, events: &mut TickEvents) {
+        if state.brood.is_empty() {
+            return;
+        }
+        let ration = state.config.tuning.brood_food_per_tick / state.ticks_per_second;
+
+        let mut developing = Vec::new();
+        for mut larva in std::mem::take(&mut state.brood) {
+            let fed = larva.stage != LifeStage::Larva || state.resources.try_consume("fungus", ration);
+            if fed {
+                larva.ticks_remaining = larva.ticks_remaining.saturating_sub(1);
+            }
+            developing.extend(Self::develop(state, events, larva, fed));
+        }
+        state.brood = developing;
+    }
+
+    /// Nurses feed the larvae in laying order, up to `nurse_capacity` each
+    /// (on their own tile, when walking); tended larvae grow every tick
+    /// they're fed. Untended ones feed and grow one tick in
+    /// `untended_brood_slowdown`, and die once neglected for
+    /// `brood_neglect_limit`. Eggs and pupae need no tending.
+    fn tend_brood(state: &mut GameState, events: &mut TickEvents) {
+        if state.brood.is_empty() {
+            return;
+        }
+        let tick = state.tick;
+        let ration = state.config.tuning.brood_food_per_tick / state.ticks_per_second;
+        let slowdown = state.config.tuning.untended_brood_slowdown.max(1);
+        let neglect_limit = state.ticks_for(state.config.tuning.brood_neglect_limit);
+        let walking = state.has_feature(feature::MOVEMENT);
+
+        let capacity = state.config.tuning.nurse_capacity;
+        let mut nurses = det::sorted(state.entities.iter()
+            .filter(|e| e.role == Some(AntRole::Nurse) && !e.is_dormant())
+            .map(|e| (e.id.clone(), e.tile.clone(), capacity)));
+        let mut rounds: BTreeMap<EntityId, u64> = BTreeMap::new();
+
+        let mut developing = Vec::new();
+        for mut larva in std::mem::take(&mut state.brood) {
+            if larva.stage != LifeStage::Larva {
+                larva.ticks_remaining = larva.ticks_remaining.saturating_sub(1);
+                developing.extend(Self::develop(state, events, larva, true));
+                continue;
+            }
+
+            let nurse = nurses.iter_mut()
+                .find(|(_, tile, room)| *room > 0 && (!walking || in_place(&state.map, tile, &larva.tile)));
+            let tended = match nurse {
+                Some((nurse_id, _, room)) => {
+                    *room -= 1;
+                    *rounds.entry(nurse_id.clone()).or_default() += 1;
+                    true
+                }
+                None => false,
+            };
+            larva.neglected = if tended { 0 } else { larva.neglected + 1 };
+
+            let grows = tended || larva.neglected.is_multiple_of(slowdown);
+            let fed = grows && state.resources.try_consume("fungus", ration);
+            if fed {
+                larva.ticks_remaining = larva.ticks_remaining.saturating_sub(1);
+            }
+            if larva.ticks_remaining > 0 && larva.neglected >= neglect_limit {
+                Self::lose_brood(state, events, larva, BroodLoss::Neglected);
+                continue;
+            }
+            developing.extend(Self::develop(state, events, larva, fed));
+        }
+        state.brood = developing;
+
+        for (nurse_id, larvae) in rounds {
+            events.push(tick, EventKind::BroodTended { nurse_id, larvae });
+        }
+    }
+
+    /// After a tick's feeding: a larva unfed too long starves (with
+    /// `feature::LIFECYCLE`), and brood done with a stage move on to the
+    /// next, or hatch. Eggs that were never going to hatch are lost as they
+    /// turn. Returns the brood if it's still developing.
+    fn develop(state: &mut GameState, events: &mut TickEvents, mut larva: Brood, fed: bool) -> Option<Brood> {
+        let lifecycle = state.has_feature(feature::LIFECYCLE);
+        if lifecycle && larva.stage == LifeStage::Larva {
+            larva.starving = if fed { 0 } else { larva.starving + 1 };
+            if larva.starving >= state.ticks_for(state.config.tuning.larva_starvation_limit) {
+                Self::lose_brood(state, events, larva, BroodLoss::Starved);
+                return None;
+            }
+        }
+        if larva.ticks_remaining > 0 {
+            return Some(larva);
+        }
+
+        let next = if lifecycle { larva.stage.next() } else { LifeStage::Adult };
+        if next == LifeStage::Adult {
+            Self::hatch(state, events, larva);
+            return None;
+        }
+        if larva.stage == LifeStage::Egg && addled(&larva.id, state.config.tuning.egg_failure_chance) {
+            Self::lose_brood(state, events, larva, BroodLoss::Addled);
+            return None;
+        }
+        larva.stage = next;
+        larva.ticks_remaining = state.stage_ticks(next);
+        larva.starving = 0;
+        events.push(state.tick, EventKind::BroodStageChanged {
+            entity_id: larva.id.clone(),
+            role: larva.role.clone(),
+            stage: next,
+        });
+        Some(larva)
+    }
+
+    /// A developing ant dies before it hatches
+    fn lose_brood(state: &mut GameState, events: &mut TickEvents, larva: Brood, cause: BroodLoss) {
+        events.push(state.tick, EventKind::BroodDied {
+            entity_id: larva.id,
+            role: larva.role,
+            stage: larva.stage,
+            cause,
+        });
+    }
+
+    /// A brood that's done developing joins the colony as an adult
+    fn hatch(state: &mut GameState, events: &mut TickEvents, larva: Brood) {
+        events.push(state.tick, EventKind::BroodHatched {
+            entity_id: larva.id.clone(),
+            role: larva.role.clone(),
+        });
+        let adult = state.spawned(Entity::new_ant(larva.role, larva.id, larva.tile));
+        Self::welcome(state, events, adult.with_traits(larva.traits));
+    }
+
+    /// Bring a newborn or newcomer into the colony, carrying out its
+    /// baseline's spawn hooks (see `spawn`)
+    fn welcome(state: &mut GameState, events: &mut TickEvents, entity: Entity) {
+        let hooks = match state.config.baseline(&entity) {
+            Some(baseline) if !baseline.on_spawn.is_empty() => baseline.on_spawn.clone(),
+            _ => {
+                state.entities.push(entity);
+                return;
+            }
+        };
+        let entity = hooks.with_traits(entity);
+        for (resource, amount) in &hooks.bundled {
+            state.resources.add(resource, *amount);
+        }
+        let action_id = hooks.scheduled.as_ref().and_then(|hook| {
+            // Checked as the config loaded too, but a config edited in
+            // place may have skipped that
+            let action = hook.action(&entity.id, state.ticks_for(hook.delay));
+            if check_free_form(&action.action_type).is_err() || state.queues.actions.iter().any(|a| a.id == action.id) {
+                return None;
+            }
+            let id = action.id.clone();
+            state.queues.enqueue_action(action);
+            Some(id)
+        });
+        events.push(state.tick, EventKind::SpawnHooksApplied {
+            entity_id: entity.id.clone(),
+            traits: hooks.traits,
+            bundled: hooks.bundled,
+            action_id,
+        });
+        state.entities.push(entity);
+    }
+
+    /// Process receiver and summoning
+    fn process_receiver(&mut self, state: &mut GameState, events: &mut TickEvents, rng: &mut SeededRng) {
+        let tick = state.tick;
+
+        // Only operate if receiver exists
+        if !state.has_system("receiver") {
+            return;
+        }
+
+        // Check maintenance
+        self.check_receiver_maintenance(state, events);
+
+        // If receiver is silent, it doesn't work
+        if state.meta.receiver_silent {
+            return;
+        }
+
+        let outside = state.outside_conditions();
+        if outside.is_static_surge() && !state.outside_conditions_at(tick - 1).is_static_surge() {
+            events.push(tick, EventKind::StaticSurge {
+                level: outside.static_level,
+            });
+        }
+
+        // Passive listening drain
+        let drain = state.per_tick(state.config.tuning.listening_drain);
+        if state.resources.get("influence") > drain {
+            state.resources.add("influence", -drain);
+        }
+
+        // Attempt summoning
+        let influence = state.resources.get("influence");
+        if influence < state.config.tuning.summon_cost {
+            return;
+        }
+
+        // Check cooldown
+        if self.last_summon_tick > 0 && (tick - self.last_summon_tick) < state.ticks_for(state.config.tuning.summon_cooldown) {
+            return;
+        }
+
+        // Spend influence
+        state.resources.add("influence", -state.config.tuning.summon_cost);
+        self.last_summon_tick = tick;
+
+        // Roll for success
+        let success = rng.chance(state.config.tuning.summon_chance * outside.summon_modifier());
+
+        events.push(tick, EventKind::InfluenceSpent {
+            amount: state.config.tuning.summon_cost,
+            success,
+        });
+
+        let mut transmission = Transmission {
+            tick,
+            cost: state.config.tuning.summon_cost,
+            success,
+            visitor_type: None,
+            visitor_id: None,
+            clarity: outside.clarity,
+        };
+
+        if success {
+            // Something answers - the Outside decides what, and during a
+            // conjunction a well-equipped receiver may hear several at once
+            // (order-free: only the antennas are counted)
+            let antennas = state.map.tiles.values().filter(|t| t.tile_type == TileType::Antenna).count();
+            let converging = state.has_feature(feature::CONVERGENCES)
+                && outside.is_conjunction()
+                && antennas >= state.config.tuning.convergence_antennas;
+            let count = match converging {
+                true if rng.chance(state.config.tuning.convergence_third_chance) => 3,
+                true => 2,
+                false => 1,
+            };
+            let mut wave: Vec<Entity> = (0..count).map(|_| Self::answer_summons(state, rng)).collect();
+            if converging {
+                convergence::interplay(&mut wave);
+            }
+
+            transmission.visitor_type = wave[0].subtype.clone();
+            transmission.visitor_id = Some(wave[0].id.clone());
+            for visitor in &wave {
+                let met = Experience::MetVisitor {
+                    visitor_id: visitor.id.clone(),
+                    name: visitor.name.clone().unwrap_or_default(),
+                };
+                for ant in state.entities.iter_mut().filter(|e| e.entity_type == EntityType::Ant && e.tile == visitor.tile) {
+                    ant.remember(tick, met.clone());
+                }
+                events.push(tick, EventKind::VisitorArrived {
+                    visitor_id: visitor.id.clone(),
+                    visitor_type: visitor.subtype.clone().unwrap_or(VisitorType::Wanderer),
+                    name: visitor.name.clone().unwrap_or_default(),
+                    snapshot: Some(visitor.snapshot()),
+                });
+            }
+            if converging {
+                events.push(tick, EventKind::Convergence {
+                    visitor_ids: wave.iter().map(|v| v.id.clone()).collect(),
+                    alignment: outside.alignment,
+                });
+            }
+
+            for visitor in wave {
+                Self::welcome(state, events, visitor);
+            }
+        } else {
+            events.push(tick, EventKind::SummoningFailed);
+        }
+        state.record_transmission(transmission);
+    }
+
+    /// A visitor answering a summons, of the type the Outside's weights pick
+    fn answer_summons(state: &GameState, rng: &mut SeededRng) -> Entity {
+        let weights = state.visitor_weights();
+        let roll = rng.random() * weights.total();
+        let subtype = if roll < weights.wanderer {
+            VisitorType::Wanderer
+        } else if roll < weights.wanderer + weights.observer {
+            VisitorType::Observer
+        } else {
+            VisitorType::Hungry
+        };
+        let mut visitor = state.spawned(Entity::new_visitor(subtype, rng.visitor_id()));
+        if visitor.subtype == Some(VisitorType::Wanderer) {
+            visitor.gift_on_death = Some(HashMap::from([("strange_matter".to_string(), state.config.tuning.wanderer_gift)]));
+        }
+        visitor
+    }
+
+    /// Check receiver maintenance status
+    fn check_receiver_maintenance(&self, state: &mut GameState, events: &mut TickEvents) {
+        let tick = state.tick;
+
+        // Get maintenance goal if it exists
+        let maint_goal = state.meta.goals.get("receiver_maintenance").cloned();
+        if maint_goal.is_none() {
+            return;
+        }
+
+        let maint_goal = maint_goal.unwrap();
+        let last_maintained = maint_goal.get("last_maintained")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(tick);
+        let interval = maint_goal.get("maintenance_interval_ticks")
+            .and_then(|v| v.as_u64())
+            .unwrap_or_else(|| state.default_maintenance_interval());
+        let cost = state.maintenance_cost();
+
+        let ticks_since_maint = tick.saturating_sub(last_maintained);
+
+        // Auto-maintain if we have strange_matter and need maintenance
+        if ticks_since_maint >= interval {
+            let strange_matter = state.resources.get("strange_matter");
+
+            if strange_matter >= cost {
+                // Consume strange_matter
+                state.resources.add("strange_matter", -cost);
+
+                // Update maintenance timestamp
+                if let Some(goal) = state.meta.goals.get_mut("receiver_maintenance") {
+                    goal["last_maintained"] = serde_json::json!(tick);
+                }
+            } else if !state.meta.receiver_silent {
+                // No fuel - receiver goes silent
+                state.meta.receiver_silent = true;
+                state.meta.receiver_failed_tick = Some(tick);
+                state.meta.receiver_stage = Some(SilenceStage::Quiet);
+                events.push(tick, EventKind::ReceiverSilent);
+            }
+        }
+
+        // The longer it's silent, the further it slides
+        if state.meta.receiver_silent {
+            let silent_for = tick.saturating_sub(state.meta.receiver_failed_tick.unwrap_or(tick));
+            let stage = state.silence_stage_after(silent_for);
+            if Some(stage) > state.meta.receiver_stage {
+                state.meta.receiver_stage = Some(stage);
+                if stage > SilenceStage::Quiet {
+                    events.push(tick, EventKind::ReceiverSilenceDeepened { stage, silent_for });
+                }
+            }
+        }
+
+        // If silent and we now have strange_matter, restore
+        let restore_cost = state.receiver_restore_cost();
+        if state.meta.receiver_silent && state.resources.get("strange_matter") >= restore_cost {
+            state.resources.add("strange_matter", -restore_cost);
+            state.meta.receiver_silent = false;
+            state.meta.receiver_stage = None;
+
+            if let Some(goal) = state.meta.goals.get_mut("receiver_maintenance") {
+                goal["last_maintained"] = serde_json::json!(tick);
+            }
+
+            events.push(tick, EventKind::ReceiverRestored);
+        }
+    }
+
+    /// Process visitor-specific behaviors
+    fn process_visitors(&self, state: &mut GameState, events: &mut TickEvents) {
+        let tick = state.tick;
+
+        // Find visitors that generate resources
+        for entity in &state.entities {
+            if entity.entity_type != EntityType::Visitor {
+                continue;
+            }
+
+            if let Some(generates) = &entity.generates {
+                for (resource, rate) in generates {
+                    let amount = rate / state.ticks_per_second;
+                    state.resources.add(resource, amount);
+                    events.push(tick, EventKind::PassiveGeneration {
+                        entity_id: entity.id.clone(),
+                        resource: Key::new(resource),
+                        amount,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Check resource thresholds, in key order: the events feed the saved
+    /// reflection refs
+    fn check_thresholds(&self, state: &GameState, prev_resources: &HashMap<Key, f64>, events: &mut TickEvents) {
+        let tick = state.tick;
+
+        for (resource, &current) in det::entries(state.resources.amounts.iter()) {
+            let prev = prev_resources.get(resource).copied().unwrap_or(0.0);
+
+            for threshold in state.config.thresholds.crossed(prev, current) {
+                events.push(tick, EventKind::ThresholdCrossed {
+                    resource: *resource,
+                    threshold,
+                    current,
+                });
+            }
+        }
+    }
+
+    /// Process boredom tracking
+    fn process_boredom(&self, state: &mut GameState, events: &mut TickEvents) {
+        let tick = state.tick;
+
+        // Increase boredom if nothing's happening: no actions under way
+        // and nothing notable so far this tick. (The plugin event queue
+        // keeps what no one has read yet, so it says nothing about now.)
+        if !state.queues.has_actions() && !events.raised_any(Verbosity::Notable) {
+            state.meta.boredom += 1;
+        } else {
+            state.meta.boredom = state.meta.boredom.saturating_sub(1);
+        }
+
+        // Emit if boredom is high
+        if state.meta.boredom >= state.ticks_for(state.config.tuning.boredom_threshold) {
+            events.push(tick, EventKind::BoredomHigh {
+                level: state.meta.boredom,
+            });
+            state.meta.boredom = 0; // Reset after emitting
+        }
+    }
+
+    /// Move every ant's morale: toward the colony's sanity, down while it's
+    /// famished, and down for each death on its tile not yet mourned. Ants
+    /// whose mood changes say so; despairing ones (the queen and princesses
+    /// aside) may desert, leaving no corpse.
+    fn process_morale(&self, state: &mut GameState, events: &mut TickEvents) {
+        let tick = state.tick;
+        let tuning = &state.config.tuning;
+        let per_second = 1.0 / state.ticks_per_second;
+
+        let mut mourning: BTreeMap<String, u64> = BTreeMap::new();
+        for (tile, activity) in state.activity.iter_mut() {
+            if activity.deaths > activity.mourned {
+                mourning.insert(tile.to_string(), activity.deaths - activity.mourned);
+                activity.mourned = activity.deaths;
+            }
+        }
+
+        let sanity = state.meta.sanity;
+        let mut deserters = Vec::new();
+        for entity in state.entities.iter_mut().filter(|e| e.entity_type == EntityType::Ant) {
+            let before = Mood::of(entity.morale);
+            let mut morale = entity.morale + (sanity - entity.morale) * tuning.morale_drift * per_second;
+            if entity.hunger < tuning.famine_hunger {
+                morale -= tuning.famine_morale_drain * per_second;
+            }
+            if let Some(deaths) = mourning.get(&entity.tile) {
+                morale -= *deaths as f64 * tuning.death_morale_shock;
+            }
+            entity.morale = morale.clamp(0.0, constants::MAX_MORALE);
+
+            let mood = Mood::of(entity.morale);
+            if mood != before {
+                events.push(tick, EventKind::MoraleChanged {
+                    entity_id: entity.id.clone(),
+                    mood,
+                    morale: entity.morale,
+                });
+            }
+            let despair = 1.0 - entity.morale / morale::DESPAIR;
+            if mood == Mood::Despairing && !entity.role.as_ref().is_some_and(AntRole::is_royal)
+                && morale::deserts(&entity.id, tick, tuning.desertion_chance * despair * per_second)
+            {
+                deserters.push(entity.id.clone());
+            }
+        }
+
+        for id in deserters {
+            let Some(index) = state.entities.iter().position(|e| e.id == id) else {
+                continue;
+            };
+            let mut deserter = state.entities.remove(index);
+            drop_load(&mut deserter, state.logistics, &mut state.resources, &mut state.stockpiles);
+            events.push(tick, EventKind::EntityDied {
+                entity_id: deserter.id.clone(),
+                entity_type: format!("{:?}", deserter.entity_type).to_lowercase(),
+                cause: DeathCause::Desertion,
+                tile: Key::new(&deserter.tile),
+                snapshot: Some(deserter.snapshot()),
+            });
+        }
+    }
+
+    /// Process sanity-gated perception glitches.
+    ///
+    /// Below the sanity threshold the colony starts "seeing things": phantom
+    /// visitor signals and miscounted resources. These are reported as events
+    /// only; the actual state is never touched.
+    fn process_perception(&self, state: &GameState, events: &mut TickEvents, rng: &mut SeededRng) {
+        let tick = state.tick;
+        let sanity = state.meta.sanity;
+
+        if sanity >= state.config.tuning.perception_sanity_threshold {
+            return;
+        }
+
+        // 0.0 at the threshold, 1.0 at zero sanity
+        let severity = (1.0 - sanity / state.config.tuning.perception_sanity_threshold).clamp(0.0, 1.0);
+        if !rng.chance(state.config.tuning.perception_glitch_chance * severity) {
+            return;
+        }
+
+        // Phantom signals only make sense if there's something listening
+        let can_hear_phantoms = state.has_system("receiver");
+        if can_hear_phantoms && rng.chance(0.5) {
+            let visitor_type = match rng.range(0, 2) {
+                0 => VisitorType::Wanderer,
+                1 => VisitorType::Observer,
+                _ => VisitorType::Hungry,
+            };
+            events.push(tick, EventKind::PhantomVisitorSignal {
+                visitor_type,
+                sanity,
+            });
+            return;
+        }
+
+        // Sorted, so the choice doesn't depend on HashMap order
+        let names = det::sorted(state.resources.amounts.keys().copied());
+
+        let idx = match rng.choose_index(names.len()) {
+            Some(idx) => idx,
+            None => return,
+        };
+        let resource = names[idx];
+        let actual = state.resources.get(&resource);
+
+        // Distortion in [-max, +max], scaled by how far gone the colony is
+        let distortion = (rng.random() * 2.0 - 1.0) * state.config.tuning.perception_max_distortion * severity;
+        let perceived = (actual * (1.0 + distortion)).max(0.0);
+
+        events.push(tick, EventKind::PerceptionGlitch {
+            resource,
+            perceived,
+            actual,
+            sanity,
+        });
+    }
+
+    /// Track significant events and prompt reflections when due.
+    ///
+    /// Due prompts are stored as unanswered reflections and announced with
+    /// `ReflectionDue`; the layer above writes the text.
+    fn process_reflections(&self, state: &mut GameState, events: &mut TickEvents) {
+        let tick = state.tick;
+
+        let refs: Vec<ReflectionRef> = events.events().iter()
+            .filter_map(|e| match &e.kind {
+                EventKind::EntityDied { entity_id, .. } => Some(ReflectionRef::Entity {
+                    id: entity_id.clone(),
+                }),
+                EventKind::BlightStruck { tile, .. } | EventKind::BlightCleared { tile } => {
+                    Some(ReflectionRef::Tile { id: tile.to_string() })
+                }
+                EventKind::ThresholdCrossed { resource, threshold, .. }
+                    if *threshold >= state.config.tuning.reflection_major_threshold =>
+                {
+                    Some(ReflectionRef::Resource {
+                        name: resource.to_string(),
+                        threshold: *threshold,
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+
+        let stillness_ticks = state.ticks_for(state.config.tuning.reflection_stillness_ticks);
+        let aftermath_delay = state.ticks_for(state.config.tuning.reflection_aftermath_delay);
+
+        let triggers = &mut state.meta.reflection_triggers;
+        if !refs.is_empty() {
+            triggers.note_significant(tick, refs, constants::MAX_REFLECTION_REFS);
+        }
+
+        let last_reflection_tick = state.meta.reflections.last().map(|r| r.tick).unwrap_or(0);
+        let trigger = match triggers.due(
+            tick,
+            last_reflection_tick,
+            stillness_ticks,
+            aftermath_delay,
+        ) {
+            Some(trigger) => trigger,
+            None => return,
+        };
+
+        let refs = triggers.mark_prompted(tick);
+        state.meta.add_reflection(Reflection::new(tick, trigger.clone(), refs.clone()));
+
+        events.push(tick, EventKind::ReflectionDue { trigger, refs });
+    }
+
+    /// Initialize from an existing game state (for resuming). Saves with
+    /// bookkeeping are picked up exactly; older ones are inferred from.
+    pub fn init_from_state(&mut self, state: &GameState) {
+        if let Some(bookkeeping) = &state.bookkeeping {
+            self.last_spawn_tick = bookkeeping.last_spawn_tick;
+            self.last_summon_tick = bookkeeping.last_summon_tick;
+            return;
+        }
+
+        // Try to infer last spawn tick from entity ages
+        if !state.entities.is_empty() {
+            let youngest_age = state.entities.iter()
+                .filter(|e| e.entity_type == EntityType::Ant)
+                .map(|e| e.age)
+                .min()
+                .unwrap_or(0);
+            self.last_spawn_tick = state.tick.saturating_sub(youngest_age);
+        }
+
+        // The transmission log knows exactly when the receiver last tried
+        if let Some(last) = state.transmissions.last() {
+            self.last_summon_tick = last.tick;
+        }
+    }
+}
+
+/// Whether the egg laid as `id` is one that never hatches. Worked out from
+/// the ID, like a name, so it draws nothing from the stream.
+fn addled(id: &str, chance: f64) -> bool {
+    let roll = fnv1a64(format!("{id}/egg").as_bytes()) as f64 / u64::MAX as f64;
+    roll < chance
+}
+
+/// Leave what a dead or departing ant was carrying where it was: in its
+/// tile's stockpile under local logistics, for haulers to collect, or in the
+/// colony store under the global pool
+fn drop_load(entity: &mut Entity, logistics: LogisticsMode, store: &mut Resources, stockpiles: &mut BTreeMap<String, Resources>) {
+    if entity.inventory.is_empty() {
+        return;
+    }
+    let (goods, _) = entity.unload();
+    let depot = match logistics {
+        LogisticsMode::GlobalPool => store,
+        LogisticsMode::Local => stockpiles.entry(entity.tile.clone()).or_default(),
+    };
+    for (resource, amount) in goods {
+        depot.add(&resource, amount);
+    }
+}
+
+/// Whether an entity on `from` acts on `to` without walking: it's already
+/// there, or there's no way there and it makes do where it is
+fn in_place(map: &GameMap, from: &str, to: &str) -> bool {
+    from == to || map.path(from, to).is_none()
+}
+
+/// The nearest tile reachable from `from` where `task` is done (lowest ID
+/// on ties)
+fn work_site(map: &GameMap, from: &str, task: Task) -> Option<Key> {
+    let site = task.site()?;
+    let sites = map.tiles.iter()
+        .filter(|(_, tile)| tile.tile_type == site)
+        .filter_map(|(id, _)| Some((*id, map.path(from, id)?.len())));
+    det::min_by_key(sites, |(_, steps)| *steps, |(id, _)| *id).map(|(id, _)| id)
+}
+
+/// The nearest tile reachable from `from` with something left to harvest
+/// (lowest ID on ties)
+fn resource_site(map: &GameMap, from: &str) -> Option<Key> {
+    let sites = map.tiles.iter()
+        .filter(|(_, tile)| tile.is_harvestable())
+        .filter_map(|(id, _)| Some((*id, map.path(from, id)?.len())));
+    det::min_by_key(sites, |(_, steps)| *steps, |(id, _)| *id).map(|(id, _)| id)
+}
+
+/// The tile a forager on `from` harvests this tick: the one it's on, none
+/// while it's walking to one, or (not walking, or no way to any) the
+/// nearest on the grid
+fn harvest_site(map: &GameMap, from: &str, walking: bool) -> Option<Key> {
+    if map.get_tile(from).is_some_and(Tile::is_harvestable) {
+        return Some(Key::new(from));
+    }
+    if walking && resource_site(map, from).is_some() {
+        return None;
+    }
+    let sites = map.tiles.iter().filter(|(_, tile)| tile.is_harvestable());
+    det::min_by_key(sites, |(id, _)| map.grid_distance(from, id).unwrap_or(u64::MAX), |(id, _)| **id)
+        .map(|(id, _)| *id)
+}
+
+/// The oldest corpse an undertaker on `from` can walk to
+fn corpse_to_fetch<'a>(graveyard: &'a Graveyard, map: &GameMap, from: &str) -> Option<&'a Corpse> {
+    graveyard.corpses.iter().find(|corpse| map.path(from, &corpse.tile).is_some())
+}
+
+/// Where an ant should be heading, if anywhere it can get to: the origin to
+/// eat, its group's `GoTo` tile, the compost with a corpse, a corpse to
+/// fetch, a resource tile to harvest, or a site of its task, in that order.
+/// Visitors and sleepers don't walk.
+fn destination_for(state: &GameState, entity: &Entity, goto: Option<&str>) -> Option<Key> {
+    if entity.entity_type != EntityType::Ant || entity.is_dormant() {
+        return None;
+    }
+    let map = &state.map;
+    let reachable = |to: &str| (to != entity.tile && map.path(&entity.tile, to).is_some()).then(|| Key::new(to));
+
+    if entity.hunger < state.config.tuning.hunger_threshold_eat && entity.food.is_some() {
+        if let Some(origin) = reachable(ORIGIN) {
+            return Some(origin);
+        }
+    }
+    if let Some(tile) = goto.and_then(reachable) {
+        return Some(tile);
+    }
+    if state.has_feature(feature::CARRYING) {
+        // Haulers take a load straight where it's bound; foragers once
+        // they're full or there's nothing left to harvest
+        let hauler = entity.role == Some(AntRole::Hauler) || entity.task == Some(Task::Haul);
+        if let Some(route) = entity.route.as_ref().filter(|_| !entity.inventory.is_empty()) {
+            let full = entity.carried() >= state.config.tuning.carry_capacity - f64::EPSILON;
+            if hauler || full || resource_site(map, &entity.tile).is_none() {
+                return reachable(route.to.tile());
+            }
+        } else if hauler && !state.logistics.is_global() {
+            return TickEngine::haul_jobs(state).first().and_then(|job| reachable(job.from.tile()));
+        }
+    }
+    match entity.role {
+        Some(AntRole::Undertaker) if entity.processing_corpse == Some(true) => reachable("compost"),
+        Some(AntRole::Undertaker) => corpse_to_fetch(&state.graveyard, map, &entity.tile).and_then(|c| reachable(&c.tile)),
+        _ if entity.forages() => resource_site(map, &entity.tile).filter(|site| *site != entity.tile),
+        Some(AntRole::Nurse) => state.brood.first().and_then(|larva| reachable(&larva.tile)),
+        Some(AntRole::Worker) => entity.task
+            .and_then(|task| work_site(map, &entity.tile, task))
+            .filter(|site| *site != entity.tile),
+        _ => None,
+    }
+}
+
+/// Hand back to the work policy the workers a group has on its task
+fn release_group_workers(state: &mut GameState, name: &str) {
+    let Some(Group { members, intent: Intent::Work { task } }) = state.groups.get(name) else {
+        return;
+    };
+    let task = *task;
+    let members = members.clone();
+    for entity in state.entities.iter_mut().filter(|e| members.contains(&e.id) && e.task == Some(task)) {
+        entity.task = None;
+    }
+}
+
+/// What a tick notes after each phase: invariants under a contract, and
+/// the phase's RNG draws while a budget is kept
+struct Checkpoints {
+    contract: Option<Contract>,
+    draws: Option<Vec<(&'static str, u64)>>,
+    /// Draws on the tick's stream before the current phase
+    calls: u64,
+}
+
+impl Checkpoints {
+    fn after(&mut self, state: &GameState, rng: &SeededRng, phase: &'static str) -> Result<(), ContractViolation> {
+        if let Some(draws) = &mut self.draws {
+            draws.push((phase, rng.calls() - self.calls));
+            self.calls = rng.calls();
+        }
+        match &mut self.contract {
+            Some(contract) => contract.check(state, phase),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Apply `f` to every entity. With the `parallel` feature, lists of at least
+/// `threshold` entities are split across threads; since `f` sees only its own
+/// entity, the result is identical to the serial loop.
+#[cfg(feature = "parallel")]
+fn for_each_entity<F>(entities: &mut [Entity], threshold: usize, f: F)
+where
+    F: Fn(&mut Entity) + Sync + Send,
+{
+    use rayon::prelude::*;
+
+    if entities.len() >= threshold {
+        entities.par_iter_mut().for_each(f);
+    } else {
+        entities.iter_mut().for_each(f);
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+fn for_each_entity<F>(entities: &mut [Entity], _threshold: usize, f: F)
+where
+    F: Fn(&mut Entity),
+{
+    entities.iter_mut().for_each(f);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basic_tick() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+
+        let events = engine.tick(&mut state);
+        assert_eq!(state.tick, 1);
+        assert!(events.is_empty() || !events.is_empty()); // Just checking it runs
+    }
+
+    #[test]
+    fn test_entity_aging() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+
+        state.entities.push(Entity::new_worker("test".to_string(), "origin".to_string()));
+
+        engine.tick(&mut state);
+
+        assert_eq!(state.entities[0].age, 1);
+        assert!(state.entities[0].hunger < 100.0);
+    }
+
+    #[test]
+    fn test_paused_subsystem_is_skipped() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.entities.push(Entity::new_worker("test".to_string(), "origin".to_string()));
+
+        let events = engine.set_paused(&mut state, Subsystem::Entities, true);
+        assert!(matches!(
+            events.events()[0].kind,
+            EventKind::SubsystemPaused { subsystem: Subsystem::Entities }
+        ));
+        assert!(engine.set_paused(&mut state, Subsystem::Entities, true).is_empty());
+
+        engine.tick(&mut state);
+        assert_eq!(state.entities[0].age, 0, "Paused entities shouldn't age");
+        assert_eq!(state.tick, 1, "Time still passes");
+
+        // The toggle survives a save
+        let mut state = GameState::from_json(&state.to_json().unwrap()).unwrap();
+        assert!(!state.is_running(Subsystem::Entities));
+
+        let events = engine.set_paused(&mut state, Subsystem::Entities, false);
+        assert!(matches!(events.events()[0].kind, EventKind::SubsystemResumed { .. }));
+        engine.tick(&mut state);
+        assert_eq!(state.entities[0].age, 1);
+    }
+
+    #[test]
+    fn test_wonders_are_rare_and_happen_once() {
+        use crate::types::wonder::Wonder;
+
+        // About one per two weeks of play
+        let per_day = constants::WONDER_CHANCE * 86_400.0;
+        assert!((0.05..0.1).contains(&per_day));
+
+        let run = |chance: f64| {
+            let mut engine = TickEngine::new(42);
+            let mut state = GameState::new();
+            state.config.tuning.wonder_chance = chance;
+            // Overnight wonders would wait for dark (see test_day_and_night_turn)
+            state.features.remove(feature::DAY_NIGHT);
+            let mut wonders = Vec::new();
+            for _ in 0..10 {
+                wonders.extend(engine.tick(&mut state).into_events().into_iter().filter_map(|e| match e.kind {
+                    EventKind::WonderOccurred { wonder, rewards } => Some((e.tick, wonder, rewards)),
+                    _ => None,
+                }));
+            }
+            (state, wonders)
+        };
+
+        // A sure thing: one a tick until every wonder has been seen
+        let (state, wonders) = run(1.0);
+        assert_eq!(wonders.len(), Wonder::ALL.len());
+        assert_eq!(state.wonders.len(), Wonder::ALL.len());
+        for (tick, wonder, rewards) in &wonders {
+            assert_eq!(state.wonders[wonder], *tick);
+            assert_eq!(rewards.len(), wonder.rewards().len());
+        }
+        assert_eq!(state.resources.get("crystals"), 200.0);
+        assert_eq!(run(1.0).1, wonders, "Wonders are seeded");
+
+        // Rolls come from their own stream: wonders never shift anything else
+        let (mut quiet, _) = run(0.0);
+        let (mut lucky, _) = run(1.0);
+        for state in [&mut quiet, &mut lucky] {
+            state.wonders.clear();
+            state.resources = Resources::new();
+            state.config.tuning.wonder_chance = 0.0;
+        }
+        assert_eq!(quiet.hash(), lucky.hash());
+
+        // Saves without the flag never roll
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.config.tuning.wonder_chance = 1.0;
+        engine.tick(&mut state);
+        assert!(state.wonders.is_empty());
+    }
+
+    #[test]
+    fn test_feature_flags_are_per_save() {
+        use crate::types::feature;
+
+        let engine = TickEngine::new(42);
+        let mut state = GameState::from_json(&GameState::default().to_json().unwrap()).unwrap();
+        assert!(!state.has_feature(feature::RAIDS), "Old saves keep legacy behavior");
+        assert!(GameState::new().has_feature(feature::RAIDS), "New games opt in");
+
+        let events = engine.set_feature(&mut state, feature::RAIDS, true);
+        assert!(matches!(
+            &events.events()[0].kind,
+            EventKind::FeatureChanged { feature, enabled: true } if feature == "raids"
+        ));
+        assert!(engine.set_feature(&mut state, feature::RAIDS, true).is_empty());
+
+        // The opt-in survives a save
+        let mut state = GameState::from_json(&state.to_json().unwrap()).unwrap();
+        assert!(state.has_feature(feature::RAIDS));
+        assert!(!state.has_feature(feature::SEASONS));
+
+        let events = engine.apply_command(
+            &mut state,
+            Command::SetFeature { feature: feature::RAIDS.to_string(), enabled: false },
+        );
+        assert_eq!(events.unwrap().len(), 1);
+        assert!(state.features.is_empty());
+    }
+
+    #[test]
+    fn test_abandon_colony_leaves_legacy() {
+        use crate::types::legacy::AbandonError;
+
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.map.tiles.insert("origin".into(), Tile::new_empty("Origin".to_string(), 0, 0));
+        state.resources.set("fungus", 50.0);
+        let mut elder = Entity::new_worker("elder".to_string(), "origin".to_string());
+        elder.age = 100;
+        state.entities.push(elder);
+        state.entities.push(Entity::new_undertaker("young".to_string(), "origin".to_string()));
+        engine.tick(&mut state);
+
+        let mut twin = state.clone();
+        let events = engine.abandon_colony(&mut state).unwrap();
+        assert!(matches!(events.events()[0].kind, EventKind::ColonyAbandoned { ruins: 1, ghosts: 2, .. }));
+
+        let legacy = state.legacy.clone().unwrap();
+        assert_eq!(legacy.abandoned_at, 1);
+        assert_eq!(legacy.ghosts[0].entity_id, "elder");
+        assert!((legacy.residual[&Key::new("fungus")] - state.resources.get("fungus") * 0.1).abs() < 1e-9);
+
+        // Same colony, same legacy
+        engine.abandon_colony(&mut twin).unwrap();
+        assert_eq!(twin.legacy, state.legacy);
+
+        // Frozen from here on
+        assert!(engine.tick(&mut state).is_empty());
+        assert_eq!(state.tick, 1);
+        assert_eq!(engine.abandon_colony(&mut state).unwrap_err(), AbandonError::AlreadyAbandoned(1));
+    }
+
+    #[test]
+    fn test_ruins_echo_and_excavate() {
+        use crate::types::legacy::RuinError;
+
+        // A past run, abandoned
+        let mut old = GameState::default();
+        old.map.tiles.insert("origin".into(), Tile::new_empty("Origin".to_string(), 0, 0));
+        old.map.tiles.insert("compost".into(), Tile::new_compost("The Heap".to_string(), 1, 0));
+        old.resources.set("fungus", 40.0);
+        old.entities.push(Entity::new_worker("old_timer".to_string(), "origin".to_string()));
+        TickEngine::new(1).abandon_colony(&mut old).unwrap();
+        let legacy = old.legacy.unwrap();
+
+        let mut state = GameState::default();
+        state.map.tiles.insert("origin".into(), Tile::new_empty("Origin".to_string(), 0, 0));
+        let plain = state.clone();
+        let tiles = state.ingest_legacy(&legacy).unwrap();
+        assert_eq!(tiles, vec!["ruin_compost".to_string(), "ruin_origin".to_string()]);
+        assert_eq!(state.map.get_tile("ruin_origin").unwrap().x, 2);
+        assert_eq!(state.ingest_legacy(&legacy), Err(RuinError::AlreadyIngested(legacy.id)));
+
+        let run = |mut state: GameState| {
+            let mut engine = TickEngine::new(42);
+            let mut echoes = Vec::new();
+            for _ in 0..5000 {
+                for event in engine.tick(&mut state).events() {
+                    if let EventKind::EchoOfThePast { ghost_id, .. } = &event.kind {
+                        echoes.push((event.tick, ghost_id.clone()));
+                    }
+                }
+            }
+            (state, echoes)
+        };
+        let (haunted, echoes) = run(state.clone());
+        assert!(!echoes.is_empty());
+        assert!(echoes.iter().all(|(_, id)| id == "old_timer"));
+        assert_eq!(run(state.clone()).1, echoes);
+
+        // Echoes don't disturb the rest of the simulation
+        let (unhaunted, _) = run(plain);
+        assert_eq!(haunted.entities.len(), unhaunted.entities.len());
+        assert_eq!(haunted.resources.get("fungus"), unhaunted.resources.get("fungus"));
+
+        let engine = TickEngine::new(42);
+        engine.excavate_ruin(&mut state, "ruin_origin").unwrap();
+        assert!((state.resources.get("fungus") - 2.0).abs() < 1e-9);
+        assert!(matches!(
+            engine.excavate_ruin(&mut state, "ruin_origin"),
+            Err(RuinError::AlreadyExcavated { .. })
+        ));
+        assert_eq!(engine.excavate_ruin(&mut state, "origin").unwrap_err(), RuinError::NotARuin("origin".to_string()));
+    }
+
+    #[test]
+    fn test_outside_forecast_matches_what_arrives() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        engine.tick(&mut state);
+        assert_eq!(state.outside_seed, 42, "Weather follows the engine seed");
+
+        let forecast = state.outside_conditions_at(5000);
+        for _ in 1..5000 {
+            engine.tick(&mut state);
+        }
+        assert_eq!(state.outside_conditions(), forecast);
+
+        // A host-chosen seed is left alone
+        let mut chosen = GameState { outside_seed: 7, ..Default::default() };
+        engine.tick(&mut chosen);
+        assert_eq!(chosen.outside_seed, 7);
+    }
+
+    #[test]
+    fn test_tiles_keep_activity_counts() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.resources.set("fungus", 1.0);
+        let mut ant = Entity::new_worker("w".to_string(), "heap".to_string());
+        ant.hunger = 40.0;
+        state.entities.push(ant);
+        let mut doomed = Entity::new_worker("d".to_string(), "heap".to_string());
+        doomed.hunger = 0.05;
+        state.entities.push(doomed);
+
+        engine.tick(&mut state);
+        engine.tick(&mut state);
+        let heap = state.activity_on("heap");
+        assert_eq!(heap, crate::types::tile::TileActivity { visits: 3, deaths: 1, meals: 1, mourned: 0 });
+        assert_eq!(state.activity_on("nowhere"), Default::default());
+
+        let restored = GameState::from_json(&state.to_json().unwrap()).unwrap();
+        assert_eq!(restored.activity_on("heap"), heap);
+    }
+
+    #[test]
+    fn test_silent_receiver_slides_toward_derelict() {
+        use crate::types::silence::SilenceStage;
+        use crate::types::summary::Disaster;
+        use crate::types::system::System;
+
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.features.insert(feature::SILENCE_STAGES.to_string());
+        state.config.tuning.receiver_dormant_after = 20;
+        state.config.tuning.receiver_derelict_after = 50;
+        state.systems.insert("receiver".to_string(), System {
+            system_type: SystemType::Antenna,
+            ..System::new_generator("The Receiver".to_string(), HashMap::new())
+        });
+        state.meta.goals.insert(
+            "receiver_maintenance".to_string(),
+            serde_json::json!({ "last_maintained": 0, "maintenance_interval_ticks": 10 }),
+        );
+
+        let mut stages = Vec::new();
+        for _ in 0..60 {
+            for event in engine.tick(&mut state).events() {
+                match event.kind {
+                    EventKind::ReceiverSilent => stages.push((event.tick, SilenceStage::Quiet)),
+                    EventKind::ReceiverSilenceDeepened { stage, silent_for } => {
+                        assert_eq!(event.tick, 10 + silent_for);
+                        stages.push((event.tick, stage));
+                    }
+                    _ => {}
+                }
+            }
+            if state.tick == 25 {
+                let silence = state.receiver_silence().unwrap();
+                assert_eq!((silence.since, silence.silent_for, silence.stage), (10, 15, SilenceStage::Quiet));
+                assert_eq!((silence.next_stage, silence.next_stage_in), (Some(SilenceStage::Dormant), Some(5)));
+            }
+        }
+        assert_eq!(stages, [(10, SilenceStage::Quiet), (30, SilenceStage::Dormant), (60, SilenceStage::Derelict)]);
+        assert!(matches!(state.summary().disasters[..], [Disaster::ReceiverSilent { stage: Some(SilenceStage::Derelict), .. }]));
+
+        // One payment no longer does it
+        let restore_cost = constants::MAINTENANCE_COST_STRANGE_MATTER * constants::RECEIVER_DERELICT_COST_SCALE;
+        assert_eq!(state.receiver_silence().unwrap().restore_cost, restore_cost);
+        state.resources.set("strange_matter", restore_cost - 1.0);
+        engine.tick(&mut state);
+        assert!(state.meta.receiver_silent);
+        state.resources.set("strange_matter", restore_cost);
+        let events = engine.tick(&mut state);
+        assert!(events.events().iter().any(|e| matches!(e.kind, EventKind::ReceiverRestored)));
+        assert!(state.receiver_silence().is_none());
+        assert_eq!(state.meta.receiver_stage, None);
+        assert_eq!(state.resources.get("strange_matter"), 0.0);
+    }
+
+    #[test]
+    fn test_summons_are_logged_in_state() {
+        use crate::types::system::System;
+
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.resources.set("influence", 50.0);
+        state.systems.insert("receiver".to_string(), System {
+            system_type: SystemType::Antenna,
+            ..System::new_generator("The Receiver".to_string(), HashMap::new())
+        });
+
+        let mut arrivals = Vec::new();
+        for _ in 0..6000 {
+            for event in engine.tick(&mut state).events() {
+                if let EventKind::VisitorArrived { visitor_id, .. } = &event.kind {
+                    arrivals.push(visitor_id.clone());
+                }
+            }
+        }
+
+        assert_eq!(state.transmissions.len(), 10);
+        let logged: Vec<_> = state.transmissions.iter().filter_map(|t| t.visitor_id.clone()).collect();
+        assert_eq!(logged, arrivals);
+        assert!(state.transmissions.iter().all(|t| t.success == t.visitor_type.is_some()));
+
+        // A fresh engine picks the cooldown up from the log
+        let restored = GameState::from_json(&state.to_json().unwrap()).unwrap();
+        let mut fresh = TickEngine::new(42);
+        fresh.init_from_state(&restored);
+        assert_eq!(fresh.last_summon_tick(), engine.last_summon_tick());
+    }
+
+    #[test]
+    fn test_zen_entities_go_dormant_and_wake() {
+        use crate::types::config::SimConfig;
+
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState { config: SimConfig::zen(), ..Default::default() };
+        let mut ant = Entity::new_worker("sleepy".to_string(), "origin".to_string());
+        ant.hunger = 0.05;
+        state.entities.push(ant);
+
+        let events = engine.tick(&mut state);
+        assert!(events.events().iter().any(|e| matches!(e.kind, EventKind::EntityDormant { .. })));
+        assert!(state.entities[0].is_dormant());
+        assert_eq!(state.entities[0].hunger, 0.0);
+
+        // Still asleep a while later, and still alive
+        for _ in 0..10 {
+            engine.tick(&mut state);
+        }
+        assert_eq!(state.entities.len(), 1);
+
+        state.resources.set("fungus", 5.0);
+        let events = engine.tick(&mut state);
+        assert!(events.events().iter().any(|e| matches!(e.kind, EventKind::EntityAwoke { .. })));
+        assert!(!state.entities[0].is_dormant());
+    }
+
+    #[test]
+    fn test_zen_compost_never_blights() {
+        use crate::types::config::SimConfig;
+
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState { config: SimConfig::zen(), ..Default::default() };
+        state.map.tiles.insert("compost".into(), Tile::new_compost("The Heap".to_string(), 1, 0));
+        state.map.get_tile_mut("compost").unwrap().add_contamination(1.0);
+
+        for _ in 0..50 {
+            let events = engine.tick(&mut state);
+            assert!(!events.events().iter().any(|e| matches!(e.kind, EventKind::BlightStruck { .. })));
+        }
+    }
+
+    #[test]
+    fn test_ants_remember_what_they_live_through() {
+        use crate::types::memory::{Experience, Memory};
+
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.resources.set("fungus", 10.0);
+        state.map.tiles.insert("origin".into(), Tile::new_empty("Origin".to_string(), 0, 0));
+        state.map.tiles.insert("compost".into(), Tile::new_compost("The Heap".to_string(), 1, 0));
+        let mut starving = Entity::new_worker("w1".to_string(), "origin".to_string());
+        starving.hunger = 5.0;
+        state.entities.push(starving);
+        state.entities.push(Entity::new_worker("w2".to_string(), "origin".to_string()));
+        state.entities.push(Entity::new_undertaker("u".to_string(), "compost".to_string()));
+
+        engine.tick(&mut state);
+        assert_eq!(state.get_entity("w1").unwrap().memories, [Memory { tick: 1, experience: Experience::SurvivedFamine }]);
+        assert!(state.get_entity("w2").unwrap().memories.is_empty());
+
+        // Everyone left standing remembers the blight; the dead take theirs along
+        state.map.get_tile_mut("compost").unwrap().add_contamination(1.0);
+        let events = engine.tick(&mut state);
+        let Some(EventKind::BlightKill { snapshot: Some(snapshot), .. }) = events.events().iter()
+            .map(|e| &e.kind)
+            .find(|kind| matches!(kind, EventKind::BlightKill { .. }))
+        else {
+            panic!("no blight");
+        };
+        assert!(snapshot.memories.is_empty());
+        let blight = Experience::WitnessedBlight { tile: Key::new("compost") };
+        assert_eq!(state.get_entity("w1").unwrap().memories[1].experience, blight);
+        assert_eq!(state.get_entity("w2").unwrap().memories[0].experience, blight);
+        assert_eq!(state.get_entity("w2").unwrap().snapshot().memories.len(), 1);
+
+        // Memories are saved, and only the latest are kept
+        let w1 = state.get_entity_mut("w1").unwrap();
+        for tick in 10..20 {
+            w1.remember(tick, Experience::SurvivedFamine);
+        }
+        assert_eq!(w1.memories.len(), constants::MEMORY_CAPACITY);
+        assert_eq!(w1.memories[0].tick, 12);
+        let restored = GameState::from_json(&state.to_json().unwrap()).unwrap();
+        assert_eq!(restored.get_entity("w1").unwrap().memories, state.get_entity("w1").unwrap().memories);
+    }
+
+    #[test]
+    fn test_preset_scales_hunger_and_is_saved() {
+        use crate::types::config::SimConfig;
+
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState { config: SimConfig::cruel(), ..Default::default() };
+        state.entities.push(Entity::new_worker("test".to_string(), "origin".to_string()));
+        let rate = state.entities[0].hunger_rate;
+
+        engine.tick(&mut state);
+        assert!((state.entities[0].hunger - (100.0 - rate * 1.5)).abs() < 1e-9);
+        assert_eq!(state.default_maintenance_interval(), 1800);
+
+        let restored = GameState::from_json(&state.to_json().unwrap()).unwrap();
+        assert_eq!(restored.config, SimConfig::cruel());
+
+        // Classic saves don't mention a config at all
+        assert!(!GameState::default().to_json().unwrap().contains("\"config\""));
+    }
+
+    #[test]
+    fn test_for_each_entity_matches_serial() {
+        let mut rng = SeededRng::new(9);
+        let colony: Vec<Entity> = (0..2000)
+            .map(|i| {
+                let mut e = Entity::new_worker(format!("w{}", i), "origin".to_string());
+                e.hunger_rate = 0.01 + rng.random() * 0.5;
+                e
+            })
+            .collect();
+
+        let step = |e: &mut Entity| {
+            e.age += 1;
+            e.hunger -= e.hunger_rate;
+        };
+        let mut split = colony.clone();
+        let mut serial = colony;
+        for_each_entity(&mut split, 0, step);
+        for_each_entity(&mut serial, usize::MAX, step);
+
+        for (a, b) in split.iter().zip(&serial) {
+            assert_eq!((&a.id, a.age, a.hunger.to_bits()), (&b.id, b.age, b.hunger.to_bits()));
+        }
+    }
+
+    #[test]
+    fn test_entity_eating() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+
+        let mut entity = Entity::new_worker("test".to_string(), "origin".to_string());
+        entity.hunger = 40.0; // Below threshold
+        state.entities.push(entity);
+        state.resources.set("fungus", 10.0);
+
+        let events = engine.tick(&mut state);
+
+        // Entity should have eaten
+        assert!(state.entities[0].hunger > 40.0);
+        assert!(state.resources.get("fungus") < 10.0);
+        assert!(events.events().iter().any(|e| matches!(e.kind, EventKind::EntityAte { .. })));
+    }
+
+    #[test]
+    fn test_entity_starvation() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+
+        let mut entity = Entity::new_worker("test".to_string(), "origin".to_string());
+        entity.hunger = 0.05; // About to starve
+        state.entities.push(entity);
+
+        let events = engine.tick(&mut state);
+
+        // Entity should have died
+        assert!(state.entities.is_empty());
+        assert!(!state.graveyard.corpses.is_empty());
+        assert!(events.events().iter().any(|e| matches!(e.kind, EventKind::EntityDied { .. })));
+    }
+
+    #[test]
+    fn test_death_event_carries_snapshot() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+
+        let mut entity = Entity::new_undertaker("u1".to_string(), "origin".to_string());
+        entity.age = entity.max_age - 1;
+        state.entities.push(entity);
+
+        let events = engine.tick(&mut state);
+        let snapshot = events.events().iter().find_map(|e| match &e.kind {
+            EventKind::EntityDied { snapshot, .. } => snapshot.clone(),
+            _ => None,
+        });
+
+        // The entity is gone from state but its stats survive on the event
+        let snapshot = snapshot.expect("death should carry a snapshot");
+        assert_eq!(snapshot.role, Some(AntRole::Undertaker));
+        assert_eq!(snapshot.age, snapshot.max_age);
+        assert_eq!(snapshot.tile, "origin");
+
+        // Older event JSON without snapshots still loads
+        let old: EventKind = serde_json::from_str(
+            r#"{"type": "entity_died", "entity_id": "u1", "entity_type": "ant", "cause": "old_age", "tile": "origin"}"#,
+        ).unwrap();
+        assert!(matches!(old, EventKind::EntityDied { snapshot: None, .. }));
+    }
+
+    #[test]
+    fn test_offline_progress() {
+        use crate::timeline::TimeContext;
+
+        let mut state = GameState {
+            last_save_timestamp: Some(1000.0),
+            ..Default::default()
+        };
+        state.resources.set("fungus", 100.0);
+        state.entities.push(Entity::new_worker("fed".to_string(), "origin".to_string()));
+        let mut starving = Entity::new_worker("starving".to_string(), "origin".to_string());
+        starving.hunger = 0.5;
+        starving.food = Some("nectar".to_string());
+        state.entities.push(starving);
+        let mut system_gen = HashMap::new();
+        system_gen.insert("fungus".to_string(), 1.0);
+        let system = crate::types::system::System::new_generator("fungus_farm".to_string(), system_gen);
+        state.systems.insert("fungus_farm".to_string(), system);
+        let kiln = crate::types::system::System::new_converter(
+            "Kiln".to_string(),
+            HashMap::from([("ore".to_string(), 1.0)]),
+            HashMap::from([("crystals".to_string(), 1.0)]),
+        );
+        state.systems.insert("kiln".to_string(), kiln);
+        let mut live = state.clone();
+
+        // 100 seconds away is 100 real ticks
+        let mut engine = TickEngine::new(42);
+        let offline = engine.process_offline_progress(&mut state, 1100.0);
+        assert_eq!(state.tick, 100);
+
+        let mut live_engine = TickEngine::new(42);
+        let mut online = TickEvents::new();
+        for _ in 0..100 {
+            online.extend(live_engine.tick(&mut live));
+        }
+        assert_eq!(serde_json::to_value(&state).unwrap(), serde_json::to_value(&live).unwrap());
+        assert_eq!(offline.events.len(), online.len());
+
+        // Deaths away are recorded like any other, and reported
+        assert!(offline.events.iter().any(|e| matches!(&e.kind, EventKind::EntityDied { entity_id, .. } if entity_id == "starving")));
+        assert!(state.graveyard.corpses.iter().any(|c| c.entity_id == "starving"));
+        assert_eq!(offline.ticks, 100);
+        assert_eq!(offline.elapsed_seconds, 100.0);
+        assert_eq!(offline.deaths.len(), 1);
+        assert_eq!(offline.deaths[0].entity_id, "starving");
+        assert_eq!(offline.deaths[0].cause, DeathCause::Starvation);
+        assert_eq!(offline.resource_deltas["fungus"], state.resources.get("fungus") - 100.0);
+        assert_eq!(offline.warnings, online.warnings());
+        assert_eq!(offline.stalled_systems, BTreeMap::from([("kiln".to_string(), 100)]));
+
+        // Quieter catch-ups leave the chatter out, not the simulation
+        let mut quiet = live.clone();
+        quiet.last_save_timestamp = Some(0.0);
+        live.last_save_timestamp = Some(0.0);
+        let notable = engine.catch_up(&mut quiet, TimeContext { verbosity: Verbosity::Notable, ..TimeContext::at(100.0) });
+        let all = TickEngine::new(42).process_offline_progress(&mut live, 100.0);
+        assert_eq!(quiet.hash(), live.hash());
+        assert!(notable.events.len() < all.events.len());
+        assert_eq!(notable.resource_deltas, all.resource_deltas);
+        assert!(notable.events.iter().all(|e| e.kind.verbosity() == Verbosity::Notable));
+    }
+
+    #[test]
+    fn test_tick_report_bundles_a_tick() {
+        let mut state = GameState::default();
+        state.resources.set("fungus", 10.0);
+        let mut starving = Entity::new_worker("w".to_string(), "origin".to_string());
+        starving.hunger = 0.05;
+        starving.food = Some("nectar".to_string());
+        state.entities.push(starving);
+        let mut live = state.clone();
+
+        let mut engine = TickEngine::new(42);
+        let report = engine.tick_report(&mut state, true);
+        let events = TickEngine::new(42).tick(&mut live);
+        assert_eq!(state.hash(), live.hash());
+
+        assert_eq!(report.tick, 1);
+        assert_eq!(report.events.len(), events.len());
+        assert_eq!(report.warnings, events.warnings());
+        assert_eq!(report.counters.deaths, 1);
+        assert_eq!(report.counters.population_delta, -1);
+        let metrics = report.metrics.as_ref().unwrap();
+        assert_eq!((metrics.population, metrics.corpses), (0, 1));
+        assert_eq!(metrics.state_hash, state.hash());
+
+        // Metrics are opt-in and stay out of the JSON otherwise
+        let report = engine.tick_report(&mut state, false);
+        let json = serde_json::to_value(&report).unwrap();
+        assert!(json.get("metrics").is_none());
+        assert_eq!(json["tick"], 2);
+    }
+
+    #[test]
+    fn test_reports_count_deaths_the_filter_drops() {
+        use crate::timeline::TimeContext;
+
+        let mut state = GameState::default();
+        let mut starving = Entity::new_worker("w".to_string(), "origin".to_string());
+        starving.hunger = 0.05;
+        state.entities.push(starving);
+        let mut away = state.clone();
+        away.last_save_timestamp = Some(0.0);
+
+        let mut engine = TickEngine::new(42);
+        engine.set_event_filter(EventFilter::except([EventKindTag::EntityDied]));
+        let report = engine.tick_report(&mut state, false);
+        assert!(report.events.iter().all(|e| e.kind.tag() != EventKindTag::EntityDied));
+        assert_eq!(report.counters.deaths, 1);
+
+        let mut engine = TickEngine::new(42);
+        engine.set_event_filter(EventFilter::only([EventKindTag::SystemBuilt]));
+        let report = engine.catch_up(&mut away, TimeContext { verbosity: Verbosity::Notable, ..TimeContext::at(100.0) });
+        assert!(report.events.is_empty());
+        assert_eq!(report.deaths.len(), 1);
+        assert_eq!((report.deaths[0].entity_id.as_str(), report.deaths[0].tick), ("w", 1));
+    }
+
+    #[test]
+    fn test_offline_clock_anomalies() {
+        use crate::timeline::{TimeAnomaly, TimeContext};
+
+        let anomaly = |report: &OfflineReport| {
+            let event = report.events.iter().find_map(|e| match e.kind {
+                EventKind::TimeAnomaly { anomaly, .. } => Some(anomaly),
+                _ => None,
+            });
+            assert_eq!(event, report.anomaly);
+            event
+        };
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState {
+            last_save_timestamp: Some(1000.0),
+            ..Default::default()
+        };
+
+        // Jitter within the tolerance is ignored
+        let events = engine.process_offline_progress(&mut state, 998.0);
+        assert!(events.is_empty());
+
+        // Clock set back an hour: nothing simulated, re-anchored at the new reading
+        let events = engine.process_offline_progress(&mut state, -2600.0);
+        assert_eq!(anomaly(&events), Some(TimeAnomaly::Backwards));
+        assert_eq!(state.tick, 0);
+        assert_eq!(state.last_save_timestamp, Some(-2600.0));
+
+        let events = engine.process_offline_progress(&mut state, f64::NAN);
+        assert_eq!(anomaly(&events), Some(TimeAnomaly::NotFinite));
+
+        // A year away is flagged, and still only catches up the cap
+        let events = engine.catch_up(&mut state, TimeContext {
+            max_catchup: 600,
+            ..TimeContext::at(365.0 * 86400.0)
+        });
+        assert_eq!(anomaly(&events), Some(TimeAnomaly::Jump));
+        assert_eq!(state.tick, 600);
+        assert_eq!(events.ticks, 600);
+
+        // So is a reading past anything a Duration can hold
+        let events = engine.catch_up(&mut state, TimeContext {
+            max_catchup: 600,
+            ..TimeContext::at(1e20)
+        });
+        assert_eq!(anomaly(&events), Some(TimeAnomaly::Jump));
+        assert_eq!(events.ticks, 600);
+        assert_eq!(state.tick, 1200);
+    }
+
+    #[test]
+    fn test_tick_rate_scales_durations() {
+        let mut state = GameState {
+            ticks_per_second: 4.0,
+            last_save_timestamp: Some(1000.0),
+            ..Default::default()
+        };
+        assert_eq!(state.ticks_for(constants::SPAWN_INTERVAL_TICKS), 7200);
+        assert_eq!(state.ticks_for(0), 0);
+
+        // 100 seconds away at 4 Hz is 400 ticks
+        let mut engine = TickEngine::new(42);
+        engine.process_offline_progress(&mut state, 1100.0);
+        assert_eq!(state.tick, 400);
+
+        // The offline cap is an hour of wall-clock, not 3600 ticks
+        state.last_save_timestamp = Some(0.0);
+        state.tick = 0;
+        engine.process_offline_progress(&mut state, 1e6);
+        assert_eq!(state.tick, 4 * constants::MAX_OFFLINE_TICKS);
+
+        // Slow hosts still get at least one tick per duration
+        let slow = GameState {
+            ticks_per_second: 0.001,
+            ..Default::default()
+        };
+        assert_eq!(slow.ticks_for(constants::BLIGHT_DURATION), 1);
+    }
+
+    #[test]
+    fn test_rates_follow_wall_time_not_ticks() {
+        use crate::types::system::System;
+
+        // The same hundred seconds at 1 Hz and at 2 Hz
+        let run = |ticks_per_second: f64| {
+            let mut engine = TickEngine::new(42);
+            let mut state = GameState { ticks_per_second, ..Default::default() };
+            state.entities.push(Entity { food: None, ..Entity::new_worker("w".to_string(), "origin".to_string()) });
+            state.systems.insert("farm".to_string(), System::new_generator("Farm".to_string(), HashMap::from([("nutrients".to_string(), 0.5)])));
+            state.systems.insert("still".to_string(), System::new_converter(
+                "Still".to_string(),
+                HashMap::from([("nutrients".to_string(), 0.2)]),
+                HashMap::from([("moonshine".to_string(), 0.1)]),
+            ));
+            state.resources.set("nutrients", 10.0);
+            for _ in 0..(100.0 * ticks_per_second) as u64 {
+                engine.tick(&mut state);
+            }
+            (state.entities[0].hunger, state.resources.get("nutrients"), state.resources.get("moonshine"))
+        };
+        let (slow, fast) = (run(1.0), run(2.0));
+        assert!((slow.0 - fast.0).abs() < 1e-9, "{slow:?} vs {fast:?}");
+        assert!((slow.1 - fast.1).abs() < 1e-9, "{slow:?} vs {fast:?}");
+        assert!((slow.2 - fast.2).abs() < 1e-9 && slow.2 > 0.0, "{slow:?} vs {fast:?}");
+
+        // And lifespans are seconds until an ant is born
+        let worker = Entity::new_worker("w".to_string(), "origin".to_string());
+        let fast = GameState { ticks_per_second: 2.0, ..Default::default() };
+        assert_eq!(fast.spawned(worker.clone()).max_age, 2 * worker.max_age);
+    }
+
+    #[test]
+    fn test_spawn_interval_follows_tick_rate() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState {
+            ticks_per_second: 2.0,
+            ..Default::default()
+        };
+        state.systems.insert(
+            "queen_chamber".to_string(),
+            crate::types::system::System::new_generator("Queen".to_string(), HashMap::new()),
+        );
+        state.resources.set("nutrients", 1000.0);
+        state.resources.set("fungus", 1000.0);
+        state.entities.push(Entity::new_worker("w".to_string(), "origin".to_string()));
+
+        let spawn_ticks: Vec<u64> = (0..8000)
+            .flat_map(|_| engine.tick(&mut state).into_events())
+            .filter(|e| matches!(e.kind, EventKind::AntsSpawned { .. }))
+            .map(|e| e.tick)
+            .collect();
+        let interval = 2 * constants::SPAWN_INTERVAL_TICKS;
+        assert_eq!(spawn_ticks, vec![1 + interval, 1 + 2 * interval]);
+    }
+
+    #[test]
+    fn test_brood_eat_while_developing() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.systems.insert(
+            "queen_chamber".to_string(),
+            crate::types::system::System::new_generator("Queen".to_string(), HashMap::new()),
+        );
+        state.resources.set("nutrients", 16.0);
+        state.resources.set("fungus", 100.0);
+        state.entities.push(Entity::new_worker("w".to_string(), "origin".to_string()));
+        engine.last_spawn_tick = 1;
+
+        let mut events = Vec::new();
+        for _ in 0..=constants::SPAWN_INTERVAL_TICKS {
+            events.extend(engine.tick(&mut state).into_events());
+        }
+        let laid = events.iter().find_map(|e| match &e.kind {
+            EventKind::AntsSpawned { costs, .. } => Some(costs.clone()),
+            _ => None,
+        });
+        let laid = laid.unwrap();
+        assert_eq!(laid[&Key::new("nutrients")], 10.0);
+        assert!(!laid.contains_key(&Key::new("ore")));
+        assert_eq!(state.brood.len(), 2);
+
+        // Soldiers are armoured: they take ore as well
+        let soldier = state.config.spawn_cost(&[AntRole::Soldier]);
+        assert_eq!(soldier[&Key::new("ore")], constants::SOLDIER_ORE_COST);
+        assert_eq!(soldier[&Key::new("fungus")], 5.0);
+        assert_eq!(state.entities.len(), 1, "Brood aren't ants yet");
+
+        // Out of fungus: development stalls
+        state.resources.set("fungus", 0.0);
+        for _ in 0..2 * constants::BROOD_DEVELOPMENT_TICKS {
+            engine.tick(&mut state);
+        }
+        assert_eq!(state.brood.len(), 2);
+
+        state.resources.set("fungus", 100.0);
+        let mut hatched = 0;
+        for _ in 0..constants::BROOD_DEVELOPMENT_TICKS {
+            hatched += engine.tick(&mut state).events().iter()
+                .filter(|e| matches!(e.kind, EventKind::BroodHatched { .. }))
+                .count();
+        }
+        assert_eq!(hatched, 2);
+        assert!(state.brood.is_empty());
+        assert_eq!(state.entities.len(), 3);
+    }
+
+    #[test]
+    fn test_work_policy_assigns_idle_workers() {
+        use crate::types::policy::{Task, WorkPolicy};
+
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.resources.set("fungus", 100.0);
+        for id in ["w3", "w1", "w2"] {
+            state.entities.push(Entity::new_worker(id.to_string(), "origin".to_string()));
+        }
+        state.entities.push(Entity::new_undertaker("u".to_string(), "origin".to_string()));
+
+        let events = engine.set_work_policy(
+            &mut state,
+            WorkPolicy::prioritize([Task::Forage, Task::Haul]).with_limit(Task::Forage, 2),
+        );
+        assert!(matches!(
+            &events.events()[0].kind,
+            EventKind::PolicyChanged { priorities } if priorities == &[Task::Forage, Task::Haul]
+        ));
+
+        engine.tick(&mut state);
+        let task = |id: &str| state.entities.iter().find(|e| e.id == id).unwrap().task;
+        assert_eq!(task("w1"), Some(Task::Forage));
+        assert_eq!(task("w2"), Some(Task::Forage));
+        assert_eq!(task("w3"), Some(Task::Haul), "Forage is full; next priority");
+        assert_eq!(task("u"), None, "Only workers take tasks");
+
+        // Same policy again is a no-op; a new one releases everyone
+        let same = state.policy.clone();
+        assert!(engine.set_work_policy(&mut state, same).is_empty());
+        engine.set_work_policy(&mut state, WorkPolicy::prioritize([Task::Dig]));
+        assert!(state.entities.iter().all(|e| e.task.is_none()));
+        engine.tick(&mut state);
+        assert!(state.entities.iter().filter(|e| e.role == Some(AntRole::Worker))
+            .all(|e| e.task == Some(Task::Dig)));
+    }
+
+    #[test]
+    fn test_groups_carry_out_intents() {
+        use crate::types::group::{GroupError, Intent};
+        use crate::types::policy::Task;
+
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.resources.set("fungus", 100.0);
+        state.map.tiles.insert("origin".into(), Tile::new_empty("Origin".to_string(), 0, 0));
+        state.map.tiles.insert("field".into(), Tile::new_resource("Field".to_string(), 1, 0, "fungus", 10.0));
+        for id in ["w1", "w2", "w3"] {
+            state.entities.push(Entity::new_worker(id.to_string(), "origin".to_string()));
+        }
+        state.entities.push(Entity::new_observer("v".to_string()));
+        let crew = ["w1".to_string(), "w2".to_string()];
+
+        assert_eq!(
+            engine.form_group(&mut state, "crew", &["w1".to_string(), "v".to_string()]).unwrap_err(),
+            GroupError::NotAnAnt("v".to_string())
+        );
+        let events = engine.form_group(&mut state, "crew", &crew).unwrap();
+        assert!(matches!(&events.events()[0].kind, EventKind::GroupFormed { members, .. } if members == &crew));
+        assert!(matches!(
+            engine.form_group(&mut state, "other", &["w2".to_string()]),
+            Err(GroupError::AlreadyGrouped { .. })
+        ));
+
+        // Off to the field together
+        let go = Command::SetGroupIntent { group: "crew".to_string(), intent: Intent::GoTo { tile: "field".to_string() } };
+        assert_eq!(engine.apply_command(&mut state, go).unwrap().len(), 1);
+        let events = engine.tick(&mut state);
+        assert!(events.events().iter().any(|e| matches!(&e.kind, EventKind::GroupArrived { group, .. } if group == "crew")));
+        let tile = |state: &GameState, id: &str| state.get_entity(id).unwrap().tile.clone();
+        assert_eq!(tile(&state, "w1"), "field");
+        assert_eq!(tile(&state, "w2"), "field");
+        assert_eq!(tile(&state, "w3"), "origin");
+        assert_eq!(state.groups["crew"].intent, Intent::Hold);
+
+        // Put to work with no policy in place
+        let mut idle = state.clone();
+        engine.set_group_intent(&mut state, "crew", Intent::Work { task: Task::Forage }).unwrap();
+        engine.tick(&mut state);
+        TickEngine::new(42).tick(&mut idle);
+        assert_eq!(state.get_entity("w1").unwrap().task, Some(Task::Forage));
+        assert_eq!(state.get_entity("w3").unwrap().task, None);
+        assert!(state.resources.get("fungus") > idle.resources.get("fungus"));
+
+        // Disbanding hands them back to the (empty) policy
+        engine.disband_group(&mut state, "crew").unwrap();
+        assert!(state.entities.iter().all(|e| e.task.is_none()));
+
+        // A group whose members all die goes with them
+        engine.form_group(&mut state, "pair", &crew).unwrap();
+        state.entities.retain(|e| !crew.contains(&e.id));
+        let events = engine.tick(&mut state);
+        assert!(events.events().iter().any(|e| matches!(e.kind, EventKind::GroupDisbanded { .. })));
+        assert!(state.groups.is_empty());
+    }
+
+    #[test]
+    fn test_groups_explore_and_stand_guard() {
+        use crate::types::group::{GroupError, Intent};
+
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        for id in ["w1", "w2", "w3"] {
+            state.entities.push(Entity::new_worker(id.to_string(), ORIGIN.to_string()));
+        }
+        let party = ["w1".to_string(), "w2".to_string()];
+        engine.form_group(&mut state, "party", &party).unwrap();
+        assert_eq!(
+            engine.set_group_intent(&mut state, "party", Intent::Explore { duration_ticks: 0 }).unwrap_err(),
+            GroupError::ZeroDuration
+        );
+
+        // The party sets off together and stays a group while away
+        engine.set_group_intent(&mut state, "party", Intent::Explore { duration_ticks: 50 }).unwrap();
+        let events = engine.tick(&mut state);
+        let departed = events.events().iter().find_map(|e| match &e.kind {
+            EventKind::ExpeditionDeparted { members, .. } => Some(members.clone()),
+            _ => None,
+        });
+        assert_eq!(departed.as_deref(), Some(&party[..]));
+        assert_eq!(state.entities.len(), 1);
+        assert_eq!(state.groups["party"].intent, Intent::Hold);
+        engine.tick(&mut state);
+        assert_eq!(state.groups["party"].members.len(), 2);
+
+        // A worker on guard meets a raid with a soldier's strength
+        state.features.insert(feature::RAIDS.to_string());
+        state.config.tuning.raid_chance = 1.0;
+        state.config.tuning.soldier_strength = 100.0;
+        state.resources.set("crystals", 500.0);
+        engine.form_group(&mut state, "guard", &["w3".to_string()]).unwrap();
+        engine.set_group_intent(&mut state, "guard", Intent::Defend).unwrap();
+        let defense = engine.tick(&mut state).events().iter().find_map(|e| match &e.kind {
+            EventKind::DefenseResolved { defense, repelled, .. } => Some((*defense, *repelled)),
+            _ => None,
+        });
+        assert_eq!(defense, Some((100.0, true)));
+    }
+
+    #[test]
+    fn test_foragers_harvest_resource_tiles() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.map.tiles.insert("origin".into(), Tile::new_empty("Origin".to_string(), 0, 0));
+        state.map.tiles.insert("patch".into(), Tile::new_resource("Patch".to_string(), 1, 0, "seeds", 0.05));
+        state.map.tiles.insert("grove".into(), Tile::new_resource("Grove".to_string(), 4, 0, "seeds", 10.0));
+        state.entities.push(Entity::new_forager("f".to_string(), "origin".to_string()));
+
+        // Nearest first, until it runs dry
+        let mut harvested = Vec::new();
+        for _ in 0..3 {
+            for event in engine.tick(&mut state).events() {
+                match &event.kind {
+                    EventKind::ResourceHarvested { tile, amount, .. } => harvested.push((tile.to_string(), *amount)),
+                    EventKind::ResourceDepleted { tile, resource } => harvested.push((format!("{tile} ran out of {resource}"), 0.0)),
+                    _ => {}
+                }
+            }
+        }
+        let rate = constants::HARVEST_YIELD;
+        assert_eq!(harvested.len(), 4);
+        assert_eq!(harvested[3], ("patch ran out of seeds".to_string(), 0.0));
+        assert!((harvested[2].1 - (0.05 - 2.0 * rate)).abs() < 1e-12);
+        assert!((state.resources.get("seeds") - 0.05).abs() < 1e-12);
+        assert!(state.map.get_tile("patch").unwrap().is_depleted());
+        engine.tick(&mut state);
+        assert!(state.map.get_tile("grove").unwrap().stock.unwrap() < 10.0);
+
+        // Walking, a forager only harvests once it's there
+        state.features.insert(feature::MOVEMENT.to_string());
+        state.map.connections.push((Key::new("origin"), Key::new("grove")));
+        state.map.changed();
+        let seeds = state.resources.get("seeds");
+        for _ in 0..constants::STEP_SECONDS {
+            engine.tick(&mut state);
+        }
+        assert_eq!(state.resources.get("seeds"), seeds);
+        assert_eq!(state.get_entity("f").unwrap().tile, "grove");
+        engine.tick(&mut state);
+        assert!(state.resources.get("seeds") > seeds);
+    }
+
+    #[test]
+    fn test_ants_walk_to_where_they_are_needed() {
+        use crate::types::group::Intent;
+        use crate::types::policy::Task;
+
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.features.insert(feature::MOVEMENT.to_string());
+        state.map.tiles.insert("origin".into(), Tile::new_empty("Origin".to_string(), 0, 0));
+        state.map.add_tile("compost", Tile::new_compost("Heap".to_string(), -1, 0), "origin").unwrap();
+        state.map.add_tile("lane", Tile::new_empty("Lane".to_string(), 1, 0), "origin").unwrap();
+        let field = Tile::new_resource("Field".to_string(), 2, 0, "fungus", constants::MAP_FUNGUS_STOCK);
+        state.map.add_tile("field", field, "lane").unwrap();
+
+        let mut forager = Entity::new_worker("w".to_string(), "origin".to_string());
+        forager.task = Some(Task::Forage);
+        state.entities.push(forager);
+        state.entities.push(Entity::new_undertaker("u".to_string(), "origin".to_string()));
+        state.graveyard.add_corpse(Corpse {
+            entity_id: "dead".to_string(),
+            entity_type: "ant".to_string(),
+            death_tick: 0,
+            cause: DeathCause::OldAge,
+            tile: "lane".to_string(),
+        });
+
+        // One connection every STEP_SECONDS: the forager to the field, the
+        // undertaker to the corpse and back towards the compost
+        let mut moves = Vec::new();
+        for _ in 0..2 * constants::STEP_SECONDS {
+            for event in engine.tick(&mut state).events() {
+                if let EventKind::EntityMoved { entity_id, from, to } = &event.kind {
+                    moves.push(format!("{entity_id}: {from} -> {to}"));
+                }
+            }
+        }
+        assert_eq!(moves, ["w: origin -> lane", "u: origin -> lane", "w: lane -> field", "u: lane -> origin"]);
+        assert!(!state.graveyard.has_corpses());
+        assert_eq!(state.resources.get("fungus"), 0.0, "Nothing foraged on the way");
+        engine.tick(&mut state);
+        assert!(state.resources.get("fungus") > 0.0);
+
+        // The corpse is processed at the compost, not where it was picked up
+        for _ in 0..constants::STEP_SECONDS + constants::CORPSE_PROCESSING_TICKS {
+            engine.tick(&mut state);
+        }
+        assert_eq!(state.get_entity("u").unwrap().tile, "compost");
+        assert_eq!(state.graveyard.total_processed, 1);
+
+        // A hungry ant walks home to eat
+        state.resources.set("fungus", 10.0);
+        state.get_entity_mut("w").unwrap().hunger = 40.0;
+        engine.tick(&mut state);
+        assert!(state.resources.get("fungus") > 10.0, "Still harvesting before setting off");
+        for _ in 0..2 * constants::STEP_SECONDS {
+            engine.tick(&mut state);
+        }
+        let w = state.get_entity("w").unwrap();
+        assert_eq!(w.tile, "origin");
+        assert!(w.hunger > 50.0);
+
+        // A group arrives once its members have all walked there (noticed
+        // the tick after the last step)
+        engine.form_group(&mut state, "crew", &["w".to_string()]).unwrap();
+        engine.set_group_intent(&mut state, "crew", Intent::GoTo { tile: "lane".to_string() }).unwrap();
+        let arrived = |events: &TickEvents| events.events().iter().any(|e| matches!(e.kind, EventKind::GroupArrived { .. }));
+        for _ in 0..constants::STEP_SECONDS {
+            assert!(!arrived(&engine.tick(&mut state)));
+        }
+        assert!(arrived(&engine.tick(&mut state)));
+        assert_eq!(state.get_entity("w").unwrap().tile, "lane");
+    }
+
+    #[test]
+    fn test_engine_config_drives_tick_phases() {
+        let mut config = SimConfig::classic();
+        config.tuning.hunger_threshold_eat = 90.0;
+        config.tuning.hunger_gain_from_eating = 5.0;
+        let mut engine = TickEngine::new_with_config(42, config.clone());
+        assert_ne!(engine.config_digest(), TickEngine::new(42).config_digest());
+
+        let mut state = GameState::default();
+        state.resources.set("fungus", 100.0);
+        state.entities.push(Entity::new_worker("w".to_string(), "origin".to_string()));
+        let mut meals = 0;
+        for _ in 0..200 {
+            meals += engine.tick(&mut state).events().iter()
+                .filter(|e| matches!(e.kind, EventKind::EntityAte { .. }))
+                .count();
+        }
+        // Eats at 90 instead of 50, 5 hunger a meal: around ticks 101 and 151
+        // (classic tuning wouldn't eat until tick 500)
+        assert_eq!(meals, 2);
+        assert_eq!(state.config, config, "The engine's config is saved with the state");
+
+        let json = state.to_json().unwrap();
+        let restored = GameState::from_json(&json).unwrap();
+        assert_eq!(restored.config.tuning.hunger_threshold_eat, 90.0);
+    }
+
+    #[test]
+    fn test_corpse_boosts_reach_any_organic_system() {
+        use crate::types::system::System;
+
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.systems.insert(
+            "compost_heap".to_string(),
+            System::new_generator("Compost Heap".to_string(), HashMap::new()).with_organic_boost("nutrients"),
+        );
+        state.systems.insert(
+            "mushroom_farm".to_string(),
+            System::new_generator("Mushroom Farm".to_string(), HashMap::new()).with_organic_boost("fungus"),
+        );
+        state.systems.insert("kiln".to_string(), System::new_generator("Kiln".to_string(), HashMap::new()));
+        for id in ["u1", "u2"] {
+            state.entities.push(Entity::new_undertaker(id.to_string(), "origin".to_string()));
+        }
+        for id in ["d1", "d2"] {
+            state.graveyard.add_corpse(Corpse {
+                entity_id: id.to_string(),
+                entity_type: "ant".to_string(),
+                death_tick: 0,
+                cause: DeathCause::OldAge,
+                tile: "origin".to_string(),
+            });
+        }
+
+        for _ in 0..=constants::CORPSE_PROCESSING_TICKS {
+            engine.tick(&mut state);
+        }
+        // One corpse each: the second goes to the heap with fewer boosts
+        let boosts = |id: &str| state.systems[id].corpse_boosts.len();
+        assert_eq!((boosts("compost_heap"), boosts("mushroom_farm"), boosts("kiln")), (1, 1, 0));
+
+        engine.tick(&mut state);
+        assert!(state.resources.get("nutrients") > 0.0);
+        assert!(state.resources.get("fungus") > 0.0);
+    }
+
+    #[test]
+    fn test_start_action_follows_blueprint() {
+        use crate::types::command::Command;
+
+        let engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.map.tiles.insert("origin".into(), Tile::new_empty("Origin".to_string(), 0, 0));
+        state.map.tiles.insert("heap".into(), Tile::new_compost("Heap".to_string(), 1, 0));
+        state.map.get_tile_mut("heap").unwrap().add_contamination(0.4);
+        state.resources.set("nutrients", 3.0);
+
+        let start = |state: &mut GameState, id: &str, kind, target: Option<&str>| {
+            engine.start_action(state, id, kind, target)
+        };
+        assert_eq!(start(&mut state, "c", ActionKind::Clean, None).unwrap_err(), BlueprintError::NeedsTarget(ActionKind::Clean));
+        assert_eq!(start(&mut state, "c", ActionKind::Clean, Some("void")).unwrap_err(), BlueprintError::UnknownTile("void".to_string()));
+        assert!(matches!(
+            start(&mut state, "c", ActionKind::Clean, Some("origin")).unwrap_err(),
+            BlueprintError::WrongContext { tile_type: TileType::Empty, .. }
+        ));
+
+        // Paid up front, aimed at the heap
+        let events = start(&mut state, "c", ActionKind::Clean, Some("heap")).unwrap();
+        assert!(matches!(&events.events()[0].kind, EventKind::ActionQueued { ticks: 30, .. }));
+        assert_eq!(state.resources.get("nutrients"), 1.0);
+        assert_eq!(state.queues.actions[0].kind(), Some(ActionKind::Clean));
+        assert_eq!(start(&mut state, "c", ActionKind::Dig, Some("origin")).unwrap_err(), BlueprintError::ActionExists("c".to_string()));
+        assert!(matches!(
+            start(&mut state, "c2", ActionKind::Clean, Some("heap")).unwrap_err(),
+            BlueprintError::InsufficientResources { needed: 2.0, available: 1.0, .. }
+        ));
+
+        // Through the command API, as hosts send it
+        let command: Command = serde_json::from_str(
+            r#"{"type": "start_action", "id": "d", "kind": "dig", "target": "origin"}"#,
+        ).unwrap();
+        engine.apply_command(&mut state, command).unwrap();
+
+        for _ in 0..60 {
+            engine.process_actions(&mut state, &mut TickEvents::new(), &mut SeededRng::new(1));
+        }
+        assert!(state.queues.actions.is_empty());
+        assert_eq!(state.map.get_tile("heap").unwrap().contamination, Some(0.0));
+        assert_eq!(state.resources.get("dirt"), 10.0);
+
+        // Saves can retune their blueprints
+        state.config.blueprints.0.remove(&ActionKind::Ritual);
+        assert_eq!(start(&mut state, "r", ActionKind::Ritual, None).unwrap_err(), BlueprintError::NoBlueprint(ActionKind::Ritual));
+    }
+
+    #[test]
+    fn test_map_edits_never_strand_tiles() {
+        use crate::types::command::{Command, CommandError};
+        use crate::types::tile::MapError;
+
+        let engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        let add = |id: &str, to: &str| Command::AddTile {
+            id: id.to_string(),
+            tile: Box::new(Tile::new_empty(id.to_string(), 0, 0)),
+            connect_to: to.to_string(),
+        };
+
+        // origin - hall - den, and a hall - den shortcut to cut later
+        let events = engine.apply_command(&mut state, add("hall", "origin")).unwrap();
+        assert!(matches!(&events[0].kind, EventKind::TileAdded { tile, connected_to } if tile == "hall" && connected_to == "origin"));
+        engine.apply_command(&mut state, add("den", "hall")).unwrap();
+        assert_eq!(engine.apply_command(&mut state, add("den", "hall")).unwrap_err(), MapError::TileExists("den".to_string()).into());
+
+        // Nothing can hang off a tile the origin can't reach
+        state.map.tiles.insert("island".into(), Tile::new_empty("Island".to_string(), 9, 9));
+        assert_eq!(engine.apply_command(&mut state, add("pier", "island")).unwrap_err(), MapError::Unreachable("island".to_string()).into());
+        engine.apply_command(&mut state, Command::ConnectTiles { a: "island".to_string(), b: "den".to_string() }).unwrap();
+        assert_eq!(state.map.reachable_from("origin").len(), 4);
+
+        // The only way to the island and the den can't be cut...
+        let cut = |a: &str, b: &str| Command::DisconnectTiles { a: a.to_string(), b: b.to_string() };
+        assert_eq!(
+            engine.apply_command(&mut state, cut("hall", "den")).unwrap_err(),
+            CommandError::Map(MapError::WouldCutOff("den".to_string())),
+        );
+        // ...until there's another
+        engine.apply_command(&mut state, Command::ConnectTiles { a: "origin".to_string(), b: "island".to_string() }).unwrap();
+        let events = engine.apply_command(&mut state, cut("den", "hall")).unwrap();
+        assert!(matches!(&events[0].kind, EventKind::TilesDisconnected { .. }));
+        assert!(!state.map.are_connected("hall", "den"));
+
+        // Demolition leaves the origin, occupied tiles and bridges alone
+        let demolish = |tile: &str| Command::DemolishTile { tile: tile.to_string() };
+        assert_eq!(engine.apply_command(&mut state, demolish("origin")).unwrap_err(), MapError::Origin.into());
+        assert_eq!(engine.apply_command(&mut state, demolish("island")).unwrap_err(), MapError::WouldCutOff("den".to_string()).into());
+        state.entities.push(Entity::new_worker("w".to_string(), "hall".to_string()));
+        assert_eq!(
+            engine.apply_command(&mut state, demolish("hall")).unwrap_err(),
+            MapError::TileInUse { tile: "hall".to_string(), what: "entities" }.into(),
+        );
+        state.entities.clear();
+        let events = engine.apply_command(&mut state, demolish("hall")).unwrap();
+        assert!(matches!(&events[0].kind, EventKind::TileDemolished { tile } if tile == "hall"));
+        assert!(state.map.get_tile("hall").is_none());
+        assert!(state.map.neighbors("origin").iter().all(|&t| t == "island"));
+        assert_eq!(state.map.reachable_from("origin").len(), 3);
+    }
+
+    #[test]
+    fn test_commands_validate_and_emit_events() {
+        use crate::types::action::ReservedAction;
+        use crate::types::command::{Command, CommandError};
+        use crate::types::outpost::OutpostError;
+
+        let engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.map.tiles.insert("origin".into(), Tile::new_empty("Origin".to_string(), 0, 0));
+        state.map.tiles.insert("tunnel".into(), Tile::new_empty("Tunnel".to_string(), 1, 0));
+        state.entities.push(Entity::new_worker("w".to_string(), "origin".to_string()));
+
+        let mut farm = System::new_generator("Farm".to_string(), HashMap::new());
+        farm.tile = Some("tunnel".to_string());
+        let events = engine.apply_command(&mut state, Command::BuildSystem { id: "farm".to_string(), system: Box::new(farm.clone()) });
+        assert!(matches!(&events.unwrap()[0].kind, EventKind::SystemBuilt { system_id, .. } if system_id == "farm"));
+        assert_eq!(
+            engine.apply_command(&mut state, Command::BuildSystem { id: "farm".to_string(), system: Box::new(farm) }).unwrap_err(),
+            CommandError::SystemExists("farm".to_string()),
+        );
+
+        let events = engine.apply_command(&mut state, Command::AssignRole {
+            entity_id: "w".to_string(),
+            role: AntRole::Undertaker,
+        }).unwrap();
+        assert!(matches!(&events[0].kind, EventKind::RoleAssigned { from: Some(AntRole::Worker), .. }));
+        assert_eq!(state.entities[0].processing_corpse, Some(false));
+
+        let events = engine.apply_command(&mut state, Command::MoveEntity {
+            entity_id: "w".to_string(),
+            tile: "tunnel".to_string(),
+        }).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(state.entities[0].tile, "tunnel");
+        assert_eq!(
+            engine.apply_command(&mut state, Command::MoveEntity {
+                entity_id: "w".to_string(),
+                tile: "void".to_string(),
+            }).unwrap_err(),
+            CommandError::UnknownTile("void".to_string()),
+        );
+
+        // Commands arrive as JSON from hosts
+        let command: Command = serde_json::from_str(
+            r#"{"type": "enqueue_action", "action": {"id": "a1", "type": "survey", "ticks_remaining": 5}}"#,
+        ).unwrap();
+        assert!(engine.apply_command(&mut state, command.clone()).is_ok());
+        assert_eq!(engine.apply_command(&mut state, command).unwrap_err(), CommandError::ActionExists("a1".to_string()));
+
+        // Kinds with a blueprint can't be queued free-form
+        let command: Command = serde_json::from_str(
+            r#"{"type": "enqueue_action", "action": {"id": "a2", "type": "dig", "ticks_remaining": 5}}"#,
+        ).unwrap();
+        assert_eq!(
+            engine.apply_command(&mut state, command).unwrap_err(),
+            CommandError::Reserved(ReservedAction::Typed(ActionKind::Dig)),
+        );
+
+        // Nor can the kinds the engine drives
+        let command: Command = serde_json::from_str(
+            r#"{"type": "enqueue_action", "action": {"id": "a3", "type": "expedition", "ticks_remaining": 5}}"#,
+        ).unwrap();
+        assert_eq!(
+            engine.apply_command(&mut state, command).unwrap_err(),
+            CommandError::Reserved(ReservedAction::Engine("expedition".to_string())),
+        );
+
+        // Engine methods' own errors come through unchanged
+        assert_eq!(
+            engine.apply_command(&mut state, Command::EstablishOutpost { tile: "void".to_string() }).unwrap_err(),
+            CommandError::Outpost(OutpostError::UnknownTile("void".to_string())),
+        );
+    }
+
+    #[test]
+    fn test_meal_reports() {
+        use crate::types::meals::MealReports;
+
+        let run = |reports: MealReports| {
+            let mut engine = TickEngine::new(42);
+            let mut state = GameState { meal_reports: reports, ..Default::default() };
+            state.resources.set("fungus", 20.0);
+            state.entities.push(Entity::new_worker("w1".to_string(), "origin".to_string()));
+            state.entities.push(Entity::new_worker("w2".to_string(), "origin".to_string()));
+            let mut events = Vec::new();
+            for _ in 0..3000 {
+                events.extend(engine.tick(&mut state).into_events());
+            }
+            let ate = events.iter().filter(|e| matches!(e.kind, EventKind::EntityAte { .. })).count();
+            let batched: u64 = events.iter().filter_map(|e| match e.kind {
+                EventKind::MealsEaten { meals, .. } => Some(meals),
+                _ => None,
+            }).sum();
+            (state.resources.get("fungus"), ate, batched)
+        };
+
+        let (fungus, every, _) = run(MealReports::Every);
+        assert!(every > 10);
+
+        // Same meals, summarized every 10 minutes
+        let (batched_fungus, ate, batched) = run(MealReports::Batched { window: 600 });
+        assert_eq!(batched_fungus, fungus);
+        assert_eq!(ate, 0);
+        assert_eq!(batched as usize, every);
+
+        // The first 10 of the 20 fungus are eaten unreported
+        let (shortage_fungus, shortages, _) = run(MealReports::Shortages);
+        assert_eq!(shortage_fungus, fungus);
+        assert_eq!(shortages, every - 10);
+    }
+
+    #[test]
+    fn test_shrine_diminishing_returns() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.resources.set("insight", 100.0);
+
+        let mut consumes = HashMap::new();
+        consumes.insert("insight".to_string(), 1.0);
+        let mut generates = HashMap::new();
+        generates.insert("influence".to_string(), 1.0);
+        state.systems.insert(
+            "shrine".to_string(),
+            crate::types::system::System::new_shrine("Shrine".to_string(), consumes, generates),
+        );
+
+        engine.tick(&mut state);
+        let first = state.resources.get("influence");
+        assert!((first - 1.0).abs() < 1e-9, "Fresh window converts at full efficiency");
+
+        for _ in 0..9 {
+            engine.tick(&mut state);
+        }
+        let gained_last = {
+            let before = state.resources.get("influence");
+            engine.tick(&mut state);
+            state.resources.get("influence") - before
+        };
+        assert!(gained_last < 0.5, "Efficiency should drop with volume, got {}", gained_last);
+        assert!((state.resources.get("insight") - 89.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_thresholds_crossed_together_reflect_in_key_order() {
+        // Each state built from scratch: a clone would share the HashMap's
+        // hasher and walk it in the same order whatever the code does
+        let colony = || {
+            let mut state = GameState::default();
+            let mut rates = HashMap::new();
+            for n in 0..12 {
+                let resource = format!("mineral_{n:02}");
+                state.resources.set(&resource, 99.99);
+                rates.insert(resource, 6.0);
+            }
+            state.systems.insert("mine".to_string(), crate::types::system::System::new_generator("Mine".to_string(), rates));
+            state
+        };
+        let (mut a, mut b) = (colony(), colony());
+        TickEngine::new(42).tick(&mut a);
+        TickEngine::new(42).tick(&mut b);
+
+        let refs = &a.meta.reflection_triggers.pending_refs;
+        // The latest refs are kept, so the first few minerals drop out
+        assert_eq!(refs.len(), constants::MAX_REFLECTION_REFS);
+        assert_eq!(refs[0], ReflectionRef::Resource { name: "mineral_04".to_string(), threshold: 100.0 });
+        assert_eq!(refs, &b.meta.reflection_triggers.pending_refs);
+        assert_eq!(a.hash(), b.hash());
+    }
+
+    #[test]
+    fn test_tick_phases_walk_hash_maps_in_key_order() {
+        // HashMaps iterate in a per-instance order, so a raw walk over one
+        // shows up only between separately built states. Every walk in the
+        // engine goes through `det::` or says why its order can't matter.
+        let source = include_str!("engine.rs");
+        let source = &source[..source.find("#[cfg(test)]\nmod tests {").unwrap()];
+        let fields = ["state.resources.amounts", "state.map.tiles", "state.systems"];
+        let walks = [".iter()", ".iter_mut()", ".keys()", ".values()", ".values_mut()", ".drain()"];
+        let lines: Vec<&str> = source.lines().collect();
+        let raw: Vec<String> = lines.iter().enumerate()
+            .filter(|(_, line)| fields.iter().any(|field| {
+                walks.iter().any(|walk| line.contains(&format!("{field}{walk}")))
+                    || line.contains(&format!("in &{field} "))
+                    || line.contains(&format!("in &mut {field} "))
+            }))
+            .filter(|(i, line)| {
+                let above = i.checked_sub(1).map_or("", |j| lines[j]);
+                ![*line, above].iter().any(|l| l.contains("det::") || l.to_lowercase().contains("order-free"))
+            })
+            .map(|(i, line)| format!("{}: {}", i + 1, line.trim()))
+            .collect();
+        assert!(raw.is_empty(), "raw HashMap walks in the engine:\n{}", raw.join("\n"));
+    }
+
+    #[test]
+    fn test_reflection_aftermath_after_death() {
+        use crate::types::reflection::ReflectionTrigger;
+
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+
+        let mut entity = Entity::new_worker("doomed".to_string(), "origin".to_string());
+        entity.hunger = 0.05;
+        state.entities.push(entity);
+
+        // Let the stillness window pass so only the aftermath can fire
+        state.tick = constants::REFLECTION_STILLNESS_TICKS;
+        state.meta.reflection_triggers.last_significant_tick = state.tick;
+
+        let mut due = Vec::new();
+        for _ in 0..constants::REFLECTION_AFTERMATH_DELAY + 1 {
+            for event in engine.tick(&mut state).into_events() {
+                if let EventKind::ReflectionDue { trigger, refs } = event.kind {
+                    due.push((event.tick, trigger, refs));
+                }
+            }
+        }
+
+        assert_eq!(due.len(), 1);
+        let (tick, trigger, refs) = &due[0];
+        assert_eq!(*tick, constants::REFLECTION_STILLNESS_TICKS + 1 + constants::REFLECTION_AFTERMATH_DELAY);
+        assert_eq!(*trigger, ReflectionTrigger::Aftermath);
+        assert_eq!(refs, &vec![ReflectionRef::Entity { id: "doomed".to_string() }]);
+
+        // The prompt is stored unanswered until the layer above writes it
+        assert_eq!(state.meta.reflections.len(), 1);
+        assert!(state.meta.answer_reflection("It went quietly.".to_string()));
+        assert!(!state.meta.answer_reflection("Again?".to_string()));
+    }
+
+    fn expedition_state() -> GameState {
+        let mut state = GameState::default();
+        for i in 0..4 {
+            state.entities.push(Entity::new_worker(format!("w{}", i), "origin".to_string()));
+        }
+        state
+    }
+
+    fn run_expedition(seed: u64) -> (GameState, Vec<crate::events::Event>) {
+        let mut engine = TickEngine::new(seed);
+        let mut state = expedition_state();
+        let members = vec!["w0".to_string(), "w1".to_string(), "w2".to_string()];
+
+        let mut all = engine.launch_expedition(&mut state, &members, 50).unwrap().into_events();
+        assert_eq!(state.entities.len(), 1, "Members leave entity processing");
+        assert_eq!(state.expeditions.len(), 1);
+
+        for _ in 0..50 {
+            all.extend(engine.tick(&mut state).into_events());
+        }
+        (state, all)
+    }
+
+    #[test]
+    fn test_expedition_returns() {
+        let (state, events) = run_expedition(42);
+
+        let returned = events.iter().find_map(|e| match &e.kind {
+            EventKind::ExpeditionReturned { survivors, lost, .. } => Some((e.tick, survivors.len(), lost.len())),
+            _ => None,
+        });
+        let (tick, survivors, lost) = returned.expect("Expedition should return");
+        assert_eq!(tick, 50);
+        assert_eq!(survivors + lost, 3);
+        assert_eq!(state.entities.len(), 1 + survivors);
+        assert!(state.expeditions.is_empty());
+
+        // Away members don't age; they're processed again from the return tick
+        for member in state.entities.iter().filter(|e| e.id != "w3") {
+            assert_eq!(member.age, 1);
+        }
+    }
+
+    #[test]
+    fn test_expedition_determinism() {
+        let summary = |(state, events): (GameState, Vec<crate::events::Event>)| {
+            let returned: Vec<_> = events.into_iter()
+                .filter_map(|e| match e.kind {
+                    EventKind::ExpeditionReturned { survivors, lost, discovered_tile, .. } => {
+                        Some((survivors, lost, discovered_tile))
+                    }
+                    _ => None,
+                })
+                .collect();
+            let resources: std::collections::BTreeMap<_, _> = state.resources.amounts.into_iter()
+                .map(|(k, v)| (k, v.to_bits()))
+                .collect();
+            (returned, resources)
+        };
+
+        for seed in [1, 2, 3, 42, 99] {
+            assert_eq!(summary(run_expedition(seed)), summary(run_expedition(seed)));
+        }
+    }
+
+    #[test]
+    fn test_expedition_validation() {
+        let engine = TickEngine::new(42);
+        let mut state = expedition_state();
+        state.entities.push(Entity::new_wanderer("v_1".to_string()));
+
+        let err = |ids: &[&str], ticks| {
+            let ids: Vec<String> = ids.iter().map(|s| s.to_string()).collect();
+            engine.launch_expedition(&mut state.clone(), &ids, ticks).unwrap_err()
+        };
+
+        assert_eq!(err(&[], 10), ExpeditionError::NoMembers);
+        assert_eq!(err(&["w0"], 0), ExpeditionError::ZeroDuration);
+        assert_eq!(err(&["nope"], 10), ExpeditionError::UnknownEntity("nope".to_string()));
+        assert_eq!(err(&["v_1"], 10), ExpeditionError::NotAnAnt("v_1".to_string()));
+        assert_eq!(err(&["w0", "w0"], 10), ExpeditionError::DuplicateMember("w0".to_string()));
+        assert!(engine.launch_expedition(&mut state, &["w0".to_string()], 10).is_ok());
+    }
+
+    fn outpost_state() -> GameState {
+        let mut state = GameState::default();
+        state.resources.set("fungus", 100.0);
+        for (id, x) in [("near", 2), ("far", 6)] {
+            state.map.tiles.insert(Key::new(id), Tile::new_resource(id.to_string(), x, 0, "ore", constants::ORE_VEIN_STOCK));
+        }
+        state
+    }
+
+    #[test]
+    fn test_outpost_validation() {
+        let engine = TickEngine::new(42);
+        let mut state = outpost_state();
+
+        assert!(matches!(
+            engine.establish_outpost(&mut state, "near"),
+            Err(OutpostError::TooClose { distance: 2, .. })
+        ));
+        assert_eq!(
+            engine.establish_outpost(&mut state, "origin").unwrap_err(),
+            OutpostError::NotAResourceTile("origin".to_string())
+        );
+        assert_eq!(
+            engine.dispatch_supplies(&mut state, "far").unwrap_err(),
+            OutpostError::NoOutpost("far".to_string())
+        );
+
+        engine.establish_outpost(&mut state, "far").unwrap();
+        assert_eq!(state.resources.get("fungus"), 100.0 - constants::OUTPOST_ESTABLISH_COST_FUNGUS);
+        assert_eq!(
+            engine.establish_outpost(&mut state, "far").unwrap_err(),
+            OutpostError::AlreadyEstablished("far".to_string())
+        );
+    }
+
+    #[test]
+    fn test_outpost_supply_and_loss() {
+        let mut engine = TickEngine::new(42);
+        let mut state = outpost_state();
+        engine.establish_outpost(&mut state, "far").unwrap();
+        state.outposts.get_mut("far").unwrap().supply_ticks = 10;
+
+        for _ in 0..10 {
+            engine.tick(&mut state);
+        }
+        assert!((state.resources.get("ore") - 10.0 * constants::OUTPOST_PRODUCTION_RATE).abs() < 1e-9);
+        assert!(!state.outposts["far"].is_supplied());
+
+        // A supply run restores it before it's lost
+        engine.dispatch_supplies(&mut state, "far").unwrap();
+        let travel = 6 * constants::OUTPOST_HAUL_TICKS_PER_DISTANCE;
+        let mut supplied_at = None;
+        for _ in 0..travel {
+            for event in engine.tick(&mut state).into_events() {
+                if let EventKind::OutpostSupplied { .. } = event.kind {
+                    supplied_at = Some(event.tick);
+                }
+            }
+        }
+        assert_eq!(supplied_at, Some(10 + travel));
+        assert_eq!(state.outposts["far"].condition, 1.0);
+
+        // Left unsupplied, it degrades and is lost
+        state.outposts.get_mut("far").unwrap().supply_ticks = 0;
+        let mut lost = false;
+        for _ in 0..700 {
+            lost |= engine.tick(&mut state).events().iter()
+                .any(|e| matches!(e.kind, EventKind::OutpostLost { .. }));
+        }
+        assert!(lost);
+        assert!(state.outposts.is_empty());
+    }
+
+    fn logistics_state() -> GameState {
+        use crate::types::logistics::LogisticsMode;
+        use crate::types::system::System;
+
+        let mut state = GameState {
+            logistics: LogisticsMode::Local,
+            ..Default::default()
+        };
+
+        let mut dig = System::new_generator("Dig Site".to_string(), HashMap::from([("dirt".to_string(), 0.5)]));
+        dig.tile = Some("dig".to_string());
+        let mut heap = System::new_converter(
+            "Compost Heap".to_string(),
+            HashMap::from([("dirt".to_string(), 0.1)]),
+            HashMap::from([("nutrients".to_string(), 0.2)]),
+        );
+        heap.tile = Some("compost".to_string());
+        state.systems.insert("dig_site".to_string(), dig);
+        state.systems.insert("compost_heap".to_string(), heap);
+        state
+    }
+
+    #[test]
+    fn test_local_logistics_needs_haulers() {
+        let mut engine = TickEngine::new(42);
+        let mut state = logistics_state();
+
+        for _ in 0..100 {
+            engine.tick(&mut state);
+        }
+
+        // Dirt piles up where it's dug; the heap never gets any
+        assert!((state.stockpiles["dig"].get("dirt") - 50.0).abs() < 1e-9);
+        assert_eq!(state.resources.get("dirt"), 0.0);
+        assert_eq!(state.resources.get("nutrients"), 0.0);
+    }
+
+    #[test]
+    fn test_haulers_move_goods() {
+        let mut engine = TickEngine::new(42);
+        let mut state = logistics_state();
+        state.resources.set("fungus", 1000.0);
+        state.entities.push(Entity::new_hauler("h1".to_string(), "origin".to_string()));
+
+        let mut hauled = 0;
+        for _ in 0..200 {
+            hauled += engine.tick(&mut state).events().iter()
+                .filter(|e| matches!(e.kind, EventKind::GoodsHauled { .. }))
+                .count();
+        }
+
+        assert!(hauled > 0);
+        assert!(state.resources.get("nutrients") > 0.0, "Nutrients should reach the colony store");
+        assert!(state.stockpiles["compost"].get("dirt") > 0.0, "The heap should be supplied");
+    }
+
+    #[test]
+    fn test_system_produced_names_stockpile() {
+        let mut engine = TickEngine::new(42);
+        let mut state = logistics_state();
+
+        let events = engine.tick(&mut state);
+        let dig = events.events().iter().find_map(|e| match &e.kind {
+            EventKind::SystemProduced { system_id, stockpile, .. } if system_id == "dig_site" => {
+                Some(*stockpile)
+            }
+            _ => None,
+        });
+        assert_eq!(dig, Some(Some(Key::new("dig"))));
+    }
+
+    #[test]
+    fn test_perception_glitches_never_touch_state() {
+        let mut engine = TickEngine::new(7);
+        let mut state = GameState::default();
+        state.meta.sanity = 0.0;
+        state.resources.set("crystals", 40.0);
+
+        let mut glitches = 0;
+        for _ in 0..2000 {
+            let events = engine.tick(&mut state);
+            for event in events.events() {
+                if let EventKind::PerceptionGlitch { actual, .. } = &event.kind {
+                    assert_eq!(*actual, 40.0);
+                    glitches += 1;
+                }
+            }
+        }
+
+        assert!(glitches > 0, "Expected glitches at zero sanity");
+        assert_eq!(state.resources.get("crystals"), 40.0);
+    }
+
+    #[test]
+    fn test_no_perception_glitches_when_sane() {
+        let mut engine = TickEngine::new(7);
+        let mut state = GameState::default();
+        state.resources.set("crystals", 40.0);
+
+        for _ in 0..2000 {
+            let events = engine.tick(&mut state);
+            assert!(!events.events().iter().any(|e| matches!(
+                e.kind,
+                EventKind::PerceptionGlitch { .. } | EventKind::PhantomVisitorSignal { .. }
+            )));
+        }
+    }
+
+    #[test]
+    fn test_soldiers_stand_against_raids() {
+        use crate::types::threat::Threat;
+
+        type Resolved = (Threat, bool, Vec<EntityId>, BTreeMap<Key, f64>);
+        fn incursions(engine: &mut TickEngine, state: &mut GameState, ticks: u64) -> Vec<Resolved> {
+            let mut resolved = Vec::new();
+            for _ in 0..ticks {
+                for event in engine.tick(state).events() {
+                    if let EventKind::DefenseResolved { threat, repelled, fallen, stolen, .. } = &event.kind {
+                        resolved.push((*threat, *repelled, fallen.clone(), stolen.clone()));
+                    }
+                }
+            }
+            resolved
+        }
+
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.config.tuning.raid_chance = 1.0;
+        state.config.tuning.raid_cooldown = 10;
+        state.config.tuning.soldier_strength = 100.0;
+        state.resources.set("crystals", 500.0);
+        state.entities.push(Entity::new_soldier("s".to_string(), ORIGIN.to_string()));
+        state.entities.push(Entity::new_worker("w".to_string(), ORIGIN.to_string()));
+
+        // Older saves never see a raid
+        assert!(incursions(&mut engine, &mut state, 5).is_empty());
+
+        // A strong guard holds every time; only soldiers are at risk
+        state.features.insert(feature::RAIDS.to_string());
+        let resolved = incursions(&mut engine, &mut state, 30);
+        assert_eq!(resolved.len(), 3, "one incursion per cooldown");
+        for (_, repelled, fallen, stolen) in &resolved {
+            assert!(repelled);
+            assert!(stolen.is_empty());
+            assert!(fallen.iter().all(|id| id == "s"));
+        }
+        assert_eq!(state.resources.get("crystals"), 500.0);
+        let worker = state.get_entity("w").unwrap();
+        assert!(worker.memories.iter().any(|m| matches!(m.experience, Experience::Defended { repelled: true, .. })));
+
+        // Undefended, raiders carry off their share and predators find no one
+        state.entities = Vec::new().into();
+        state.config.tuning.raid_cooldown = 0;
+        let resolved = incursions(&mut engine, &mut state, 10);
+        assert!(resolved.iter().any(|(threat, ..)| *threat == Threat::Raiders));
+        assert!(resolved.iter().any(|(threat, ..)| *threat == Threat::Predators));
+        let mut crystals = 500.0;
+        for (threat, repelled, fallen, stolen) in resolved {
+            assert!(!repelled);
+            assert!(fallen.is_empty());
+            if threat == Threat::Raiders {
+                assert!((stolen[&Key::new("crystals")] - crystals * constants::RAID_PLUNDER).abs() < 1e-9);
+                crystals *= 1.0 - constants::RAID_PLUNDER;
+            } else {
+                assert!(stolen.is_empty());
+            }
+        }
+        assert!((state.resources.get("crystals") - crystals).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_influence_drains_and_whispers() {
+        use crate::types::schedule::ScheduledKind;
+        use crate::types::whisper::Whisper;
+
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.features.insert(feature::WHISPERS.to_string());
+        state.resources.set("influence", 200.0);
+        state.config.tuning.whisper_chance = 0.0;
+
+        // Past the soft cap, a share of the excess drains each second
+        engine.tick(&mut state);
+        let drained = 200.0 - 100.0 * constants::INFLUENCE_DECAY;
+        assert!((state.resources.get("influence") - drained).abs() < 1e-9);
+        state.resources.set("influence", 50.0);
+        engine.tick(&mut state);
+        assert_eq!(state.resources.get("influence"), 50.0);
+
+        // Every whisper costs a pinch; promises come due after the delay
+        state.config.tuning.whisper_chance = 1.0;
+        state.config.tuning.whisper_promise_delay = 5;
+        let (mut insight, mut promised, mut kept) = (0.0, Vec::new(), Vec::new());
+        for _ in 0..10 {
+            for event in engine.tick(&mut state).events() {
+                match &event.kind {
+                    EventKind::Whispered { whisper: Whisper::Insight { amount }, .. } => insight += amount,
+                    EventKind::Whispered { whisper: Whisper::Promise(promise), .. } => promised.push(promise.clone()),
+                    EventKind::PromiseKept { resource, .. } => kept.push((event.tick, *resource)),
+                    _ => {}
+                }
+            }
+        }
+        assert!(insight > 0.0 && !promised.is_empty() && !kept.is_empty());
+        assert_eq!(state.resources.get("influence"), 40.0);
+        assert_eq!(state.resources.get("insight"), insight);
+        for (tick, resource) in &kept {
+            assert!(promised.iter().any(|p| p.due == *tick && p.resource == *resource));
+        }
+
+        // Promises not yet kept are saved and scheduled
+        assert_eq!(state.promises.len(), promised.len() - kept.len());
+        let due = state.upcoming(&engine).into_iter()
+            .filter(|item| matches!(item.kind, ScheduledKind::PromiseDue { .. }))
+            .count();
+        assert_eq!(due, state.promises.len());
+    }
+
+    #[test]
+    fn test_unread_plugin_events_dont_stave_off_boredom() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.queues.events.enqueue(&"nobody reads this").unwrap();
+
+        engine.tick(&mut state);
+        engine.tick(&mut state);
+        assert_eq!(state.meta.boredom, 2);
+        assert_eq!(state.queues.events.len(), 1);
+    }
+
+    #[test]
+    fn test_queen_lays_a_nurse_when_there_is_none() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.features.insert(feature::NURSES.to_string());
+        state.systems.insert(
+            "queen_chamber".to_string(),
+            crate::types::system::System::new_generator("Queen".to_string(), HashMap::new()),
+        );
+        state.resources.set("nutrients", 1000.0);
+        state.resources.set("fungus", 1000.0);
+        state.entities.push(Entity::new_worker("w".to_string(), "origin".to_string()));
+        engine.last_spawn_tick = 1;
+
+        let mut nurses = Vec::new();
+        while nurses.len() < 2 {
+            for event in engine.tick(&mut state).into_events() {
+                if let EventKind::AntsSpawned { nurse_id, costs, .. } = event.kind {
+                    nurses.push(nurse_id.is_some());
+                    let roles = if nurse_id.is_some() {
+                        vec![AntRole::Worker, AntRole::Undertaker, AntRole::Nurse]
+                    } else {
+                        vec![AntRole::Worker, AntRole::Undertaker]
+                    };
+                    assert_eq!(costs, state.config.spawn_cost(&roles));
+                }
+            }
+        }
+        // One nurse growing is enough; the next clutch is just the pair
+        assert_eq!(nurses, [true, false]);
+        let grown = state.entities.iter().filter(|e| e.role == Some(AntRole::Nurse)).count();
+        let growing = state.brood.iter().filter(|b| b.role == AntRole::Nurse).count();
+        assert_eq!(grown + growing, 1);
+    }
+
+    #[test]
+    fn test_nurses_tend_the_brood() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.features.insert(feature::NURSES.to_string());
+        state.resources.set("fungus", 100.0);
+        state.entities.push(Entity::new_nurse("n".to_string(), ORIGIN.to_string()));
+        for i in 0..6 {
+            state.brood.push(Brood {
+                id: format!("b{i}"),
+                role: AntRole::Worker,
+                tile: ORIGIN.to_string(),
+                laid_at: 0,
+                stage: LifeStage::Larva,
+                ticks_remaining: 10,
+                neglected: 0,
+                starving: 0,
+                traits: Vec::new(),
+            });
+        }
+        let interval = state.spawn_interval();
+
+        // One nurse feeds four larvae a tick; the rest grow at a quarter pace
+        let mut tended = 0;
+        for _ in 0..8 {
+            for event in engine.tick(&mut state).events() {
+                if let EventKind::BroodTended { nurse_id, larvae } = &event.kind {
+                    assert_eq!((nurse_id.as_str(), *larvae), ("n", 4));
+                    tended += 1;
+                }
+            }
+        }
+        assert_eq!(tended, 8);
+        let remaining: Vec<u64> = state.brood.iter().map(|b| b.ticks_remaining).collect();
+        assert_eq!(remaining, [2, 2, 2, 2, 8, 8]);
+
+        // Without a nurse the queen lays slower, and long-neglected larvae die
+        state.entities = Vec::new().into();
+        assert_eq!(state.spawn_interval(), interval * constants::UNTENDED_QUEEN_SLOWDOWN);
+        state.config.tuning.brood_neglect_limit = 10;
+        let mut died = Vec::new();
+        for _ in 0..2 {
+            for event in engine.tick(&mut state).events() {
+                if let EventKind::BroodDied { entity_id, .. } = &event.kind {
+                    died.push(entity_id.clone());
+                }
+            }
+        }
+        assert_eq!(died, ["b4", "b5"]);
+        assert_eq!(state.brood.len(), 4);
+    }
+
+    #[test]
+    fn test_brood_grow_through_their_stages() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.features.insert(feature::LIFECYCLE.to_string());
+        state.config.tuning.egg_ticks = 2;
+        state.config.tuning.brood_development_ticks = 3;
+        state.config.tuning.pupa_ticks = 2;
+        state.config.tuning.egg_failure_chance = 0.0;
+        state.resources.set("fungus", 10.0);
+        let egg = |id: &str| Brood {
+            id: id.to_string(),
+            role: AntRole::Worker,
+            tile: ORIGIN.to_string(),
+            laid_at: 0,
+            stage: LifeStage::Egg,
+            ticks_remaining: 2,
+            neglected: 0,
+            starving: 0,
+            traits: Vec::new(),
+        };
+        state.brood.push(egg("b"));
+
+        // Egg, larva, pupa, adult; only the larva eats
+        let mut story = Vec::new();
+        for _ in 0..7 {
+            for event in engine.tick(&mut state).events() {
+                match &event.kind {
+                    EventKind::BroodStageChanged { stage, .. } => story.push((event.tick, format!("{stage:?}"))),
+                    EventKind::BroodHatched { .. } => story.push((event.tick, "Adult".to_string())),
+                    _ => {}
+                }
+            }
+        }
+        let ticks: Vec<u64> = story.iter().map(|(tick, _)| tick - story[0].0).collect();
+        let stages: Vec<&str> = story.iter().map(|(_, stage)| stage.as_str()).collect();
+        assert_eq!(stages, ["Larva", "Pupa", "Adult"]);
+        assert_eq!(ticks, [0, 3, 5]);
+        let ration = constants::BROOD_FOOD_PER_TICK;
+        assert!((state.resources.get("fungus") - (10.0 - 3.0 * ration)).abs() < 1e-12);
+        assert!(state.get_entity("b").is_some());
+
+        // Larvae starve unfed; some eggs never hatch
+        let losses = |engine: &mut TickEngine, state: &mut GameState, ticks: u64| {
+            let mut lost = Vec::new();
+            for _ in 0..ticks {
+                for event in engine.tick(state).events() {
+                    if let EventKind::BroodDied { stage, cause, .. } = &event.kind {
+                        lost.push((*stage, *cause));
+                    }
+                }
+            }
+            lost
+        };
+        state.resources.set("fungus", 0.0);
+        state.config.tuning.larva_starvation_limit = 2;
+        state.brood.push(Brood { stage: LifeStage::Larva, ticks_remaining: 3, ..egg("starved") });
+        assert_eq!(losses(&mut engine, &mut state, 2), [(LifeStage::Larva, BroodLoss::Starved)]);
+
+        state.config.tuning.egg_failure_chance = 1.0;
+        state.brood.push(egg("addled"));
+        assert_eq!(losses(&mut engine, &mut state, 2), [(LifeStage::Egg, BroodLoss::Addled)]);
+        assert!(state.brood.is_empty());
+    }
+
+    #[test]
+    fn test_scenario_ends_and_freezes_the_run() {
+        use crate::types::scenario::{EndCondition, Outcome, Scenario};
+
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        engine.tick(&mut state);
+        let scenario = Scenario {
+            name: "Short Summer".to_string(),
+            win: vec![EndCondition::SurviveTicks { ticks: 5 }],
+            lose: vec![EndCondition::ReachResource { resource: Key::new("dirt"), amount: 1.0 }],
+            started_at: 0,
+        };
+        let events = engine.apply_command(&mut state, Command::StartScenario { scenario: Box::new(scenario) }).unwrap();
+        assert!(matches!(&events[0].kind, EventKind::ScenarioStarted { name } if name == "Short Summer"));
+        let started = state.tick;
+
+        let mut ended = None;
+        for _ in 0..10 {
+            for event in engine.tick(&mut state).events() {
+                if let EventKind::ScenarioCompleted { condition, .. } = &event.kind {
+                    ended = Some((event.tick, condition.clone()));
+                }
+            }
+        }
+        assert_eq!(ended, Some((started + 5, EndCondition::SurviveTicks { ticks: 5 })));
+        assert_eq!(state.ended.as_ref().map(|e| e.outcome), Some(Outcome::Completed));
+
+        // Frozen: ticks do nothing, commands are refused
+        let frozen = state.tick;
+        assert!(engine.tick(&mut state).events().is_empty());
+        assert_eq!(state.tick, frozen);
+        let refused = engine.apply_command(&mut state, Command::SetFeature { feature: "raids".to_string(), enabled: true });
+        assert_eq!(refused.unwrap_err(), CommandError::RunEnded(frozen));
+    }
+
+    #[test]
+    fn test_queen_ages_and_is_succeeded() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.features.insert(feature::SUCCESSION.to_string());
+        state.systems.insert(
+            "queen_chamber".to_string(),
+            crate::types::system::System::new_generator("Queen".to_string(), HashMap::new()),
+        );
+        state.resources.set("nutrients", 200.0);
+        state.resources.set("fungus", 200.0);
+        state.config.tuning.queen_lifespan = 20;
+        state.config.tuning.succession_window = 10;
+        state.config.tuning.brood_development_ticks = 3;
+
+        let mut crowned = Vec::new();
+        let mut lost = Vec::new();
+        let mut laid = Vec::new();
+        let mut run = |engine: &mut TickEngine, state: &mut GameState, ticks: u64| {
+            for _ in 0..ticks {
+                for event in engine.tick(state).events() {
+                    match &event.kind {
+                        EventKind::QueenCrowned { queen_id, reign } => crowned.push((queen_id.clone(), *reign)),
+                        EventKind::QueenLost { queen_id, heir } => lost.push((queen_id.clone(), heir.clone())),
+                        EventKind::PrincessLaid { princess_id, .. } => laid.push(princess_id.clone()),
+                        _ => {}
+                    }
+                }
+            }
+            (crowned.clone(), lost.clone(), laid.clone())
+        };
+
+        // The founder is crowned, and lays a princess as her end nears
+        let (_, _, laid) = run(&mut engine, &mut state, 15);
+        let founder = state.queen.clone().unwrap();
+        assert_eq!(state.get_entity(&founder).unwrap().role, Some(AntRole::Queen));
+        let princess = state.entities.iter().find(|e| e.role == Some(AntRole::Princess)).unwrap().id.clone();
+        assert_eq!(laid, std::slice::from_ref(&princess));
+        assert_eq!(
+            engine.apply_command(&mut state, Command::AssignRole { entity_id: princess.clone(), role: AntRole::Worker }).unwrap_err(),
+            CommandError::Royal(princess.clone()),
+        );
+
+        // She dies of age, and the princess succeeds her
+        let (crowned, lost, _) = run(&mut engine, &mut state, 6);
+        assert!(state.get_entity(&founder).is_none());
+        assert_eq!(lost, [(founder.clone(), Some(princess.clone()))]);
+        assert_eq!(crowned, [(founder, 1), (princess.clone(), 2)]);
+        let queen = state.get_entity(&princess).unwrap();
+        assert_eq!((queen.role.clone(), queen.max_age - queen.age), (Some(AntRole::Queen), 20));
+        assert_eq!(queen.name, Some(crate::rng::names::ant(&princess, &AntRole::Queen)));
+
+        // Even a princess who came without a name takes one with the title
+        let mut nameless = Entity { name: None, ..Entity::new_princess("p".to_string(), ORIGIN.to_string()) };
+        nameless.crown(20);
+        assert_eq!(nameless.name, Some(crate::rng::names::ant("p", &AntRole::Queen)));
+
+        // A queen who dies without an heir leaves a colony that lays nothing
+        state.config.tuning.succession_window = 0;
+        state.entities = Vec::new().into();
+        state.entities.push(Entity { age: 19, max_age: 20, ..Entity::new_queen(princess.clone(), ORIGIN.to_string()) });
+        let (crowned, lost, _) = run(&mut engine, &mut state, 5);
+        assert_eq!(lost.last(), Some(&(princess, None)));
+        assert_eq!(state.queen, None);
+        assert!(state.entities.is_empty() && state.brood.is_empty());
+        assert_eq!(crowned.len(), 2);
     }
 
-    /// Process receiver and summoning
-    fn process_receiver(&mut self, state: &mut GameState, events: &mut TickEvents, rng: &mut SeededRng) {
-        let tick = state.tick;
+    #[test]
+    fn test_ants_inherit_the_queens_traits() {
+        use crate::types::heredity::Trait;
 
-        // Only operate if receiver exists
-        if !state.has_system("receiver") {
-            return;
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        for flag in [feature::SUCCESSION, feature::TRAITS] {
+            state.features.insert(flag.to_string());
+        }
+        state.systems.insert(
+            "queen_chamber".to_string(),
+            crate::types::system::System::new_generator("Queen".to_string(), HashMap::new()),
+        );
+        state.resources.set("nutrients", 200.0);
+        state.resources.set("fungus", 200.0);
+        let queen = Entity::new_queen("q".to_string(), ORIGIN.to_string()).with_traits(vec![Trait::Hardy, Trait::Industrious]);
+        state.entities.push(queen);
+        state.entities.push(Entity::new_worker("w".to_string(), ORIGIN.to_string()));
+        state.queen = Some("q".to_string());
+        state.reign = 1;
+        state.config.tuning.trait_inherit_chance = 1.0;
+        state.config.tuning.trait_mutation_chance = 0.0;
+        state.config.tuning.spawn_interval_ticks = 2;
+        state.config.tuning.brood_development_ticks = 1;
+
+        for _ in 0..4 {
+            engine.tick(&mut state);
         }
+        let born: Vec<&Entity> = state.entities.iter().filter(|e| e.role.as_ref().is_some_and(|r| !r.is_royal()) && e.id != "w").collect();
+        assert_eq!(born.len(), 2);
+        assert!(born.iter().all(|e| e.traits == [Trait::Hardy, Trait::Industrious] && e.max_age == 7200));
 
-        // Check maintenance
-        self.check_receiver_maintenance(state, events);
+        // Hardy ants get hungry slower; traits are saved with the ant
+        let (id, hunger) = (born[0].id.clone(), born[0].hunger);
+        engine.tick(&mut state);
+        let ant = state.get_entity(&id).unwrap();
+        assert!((hunger - ant.hunger - ant.hunger_rate * 0.75).abs() < 1e-9);
+        let json = serde_json::to_value(ant).unwrap();
+        assert_eq!(json["traits"], serde_json::json!(["hardy", "industrious"]));
+
+        // An enduring newborn lives longer
+        let elder = Entity::new_worker("e".to_string(), ORIGIN.to_string()).with_traits(vec![Trait::Enduring]);
+        assert_eq!(elder.max_age, 9000);
+    }
 
-        // If receiver is silent, it doesn't work
-        if state.meta.receiver_silent {
-            return;
-        }
+    #[test]
+    fn test_hosting_extends_a_stay_with_diminishing_returns() {
+        use crate::types::hosting::{HostingEnd, HostingError};
 
-        // Passive listening drain
-        if state.resources.get("influence") > constants::LISTENING_DRAIN {
-            state.resources.add("influence", -constants::LISTENING_DRAIN);
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.entities.push(Entity::new_observer("o".to_string()));
+        state.entities.push(Entity::new_worker("w".to_string(), ORIGIN.to_string()));
+        state.resources.set("fungus", 100.0);
+        state.resources.set("crystals", 10.0);
+        let tuning = &mut state.config.tuning;
+        (tuning.host_interval, tuning.host_extension, tuning.host_max_extension) = (2, 10, 15);
+        (tuning.host_cost_fungus, tuning.host_cost_crystals) = (1.0, 1.0);
+        let max_age = state.entities[0].max_age;
+
+        let host = |engine: &TickEngine, state: &mut GameState, id: &str| {
+            engine.apply_command(state, Command::HostVisitor { visitor_id: id.to_string() })
+        };
+        host(&engine, &mut state, "o").unwrap();
+        assert_eq!(host(&engine, &mut state, "o").unwrap_err(), CommandError::Hosting(HostingError::AlreadyHosted("o".to_string())));
+        assert_eq!(host(&engine, &mut state, "w").unwrap_err(), CommandError::Hosting(HostingError::NotAVisitor("w".to_string())));
+
+        // Each round adds less, until the cap is reached
+        let mut extensions = Vec::new();
+        let mut ended = None;
+        for _ in 0..12 {
+            for event in engine.tick(&mut state).events() {
+                match &event.kind {
+                    EventKind::StayExtended { extended_by, .. } => extensions.push(*extended_by),
+                    EventKind::HostingEnded { reason, .. } => ended = Some(*reason),
+                    _ => {}
+                }
+            }
         }
+        assert_eq!(extensions, [10, 4, 1]);
+        assert_eq!(ended, Some(HostingEnd::Capped));
+        assert_eq!(state.get_entity("o").unwrap().max_age, max_age + 15);
+        assert_eq!(state.resources.get("crystals"), 7.0);
+        assert_eq!(host(&engine, &mut state, "o").unwrap_err(), CommandError::Hosting(HostingError::Capped("o".to_string())));
+
+        // A colony that can't pay stops hosting
+        state.entities.push(Entity::new_observer("p".to_string()));
+        state.resources.set("crystals", 0.0);
+        host(&engine, &mut state, "p").unwrap();
+        let reasons: Vec<HostingEnd> = (0..2).flat_map(|_| engine.tick(&mut state).into_events())
+            .filter_map(|event| match event.kind {
+                EventKind::HostingEnded { reason, .. } => Some(reason),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(reasons, [HostingEnd::Unaffordable]);
+    }
 
-        // Attempt summoning
-        let influence = state.resources.get("influence");
-        if influence < constants::SUMMON_COST {
-            return;
-        }
+    #[test]
+    fn test_injuries_wear_ants_down_before_they_die() {
+        use crate::types::entity::Harm;
 
-        // Check cooldown
-        if self.last_summon_tick > 0 && (tick - self.last_summon_tick) < constants::SUMMON_COOLDOWN {
-            return;
-        }
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.features.insert(feature::INJURIES.to_string());
+        state.config.tuning.starvation_damage = 50.0;
+        state.entities.push(Entity { hunger: 0.05, ..Entity::new_worker("starving".to_string(), ORIGIN.to_string()) });
+        state.entities.push(Entity { health: 99.0, ..Entity::new_worker("mending".to_string(), ORIGIN.to_string()) });
+        state.entities.push(Entity::new_worker("sickly".to_string(), "compost".to_string()));
+        state.resources.set("fungus", 0.0);
+        state.map.tiles.insert(Key::new("compost"), Tile::new_compost("compost".to_string(), 0, 1));
+        state.map.get_tile_mut("compost").unwrap().contamination = Some(0.5);
+        state.config.blight = false;
+
+        let kinds = |engine: &mut TickEngine, state: &mut GameState| -> Vec<EventKind> {
+            engine.tick(state).into_events().into_iter().map(|e| e.kind).collect()
+        };
 
-        // Spend influence
-        state.resources.add("influence", -constants::SUMMON_COST);
-        self.last_summon_tick = tick;
+        // An empty stomach hurts rather than kills, and contamination sickens
+        let events = kinds(&mut engine, &mut state);
+        assert!(events.iter().any(|e| matches!(e,
+            EventKind::EntityInjured { entity_id, harm: Harm::Starvation, health } if entity_id == "starving" && *health == 50.0)));
+        assert!(events.iter().any(|e| matches!(e,
+            EventKind::EntityInjured { entity_id, harm: Harm::Sickness, health } if entity_id == "sickly" && *health == 95.0)));
+        assert_eq!(state.get_entity("starving").unwrap().hunger, 0.0);
+        assert_eq!(state.get_entity("mending").unwrap().health, 99.5);
+
+        // Until it runs out; the well-fed heal back to full
+        let events = kinds(&mut engine, &mut state);
+        assert!(events.iter().any(|e| matches!(e,
+            EventKind::EntityDied { entity_id, cause: DeathCause::Starvation, .. } if entity_id == "starving")));
+        assert!(events.iter().any(|e| matches!(e, EventKind::EntityRecovered { entity_id } if entity_id == "mending")));
+        assert_eq!(state.get_entity("sickly").unwrap().health, 90.0);
+
+        // Health is only saved while it's short of full
+        let saved = serde_json::to_value(&state.entities[..]).unwrap();
+        assert!(saved[0].get("health").is_none());
+        assert_eq!(saved[1]["health"], 90.0);
+    }
 
-        // Roll for success
-        let success = rng.chance(constants::SUMMON_CHANCE);
+    #[test]
+    fn test_renaming_tiles_and_entities() {
+        use crate::types::naming::{NameError, Named};
 
-        events.push(tick, EventKind::InfluenceSpent {
-            amount: constants::SUMMON_COST,
-            success,
+        let engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.entities.push(Entity::new_princess("p".to_string(), ORIGIN.to_string()));
+        state.map.tiles.insert(Key::new("compost"), Tile::new_compost("Compost".to_string(), 0, 1));
+
+        let events = engine.apply_command(&mut state, Command::RenameTile {
+            tile: "compost".to_string(),
+            name: "  The Midden ".to_string(),
+        }).unwrap();
+        assert!(matches!(&events[0].kind, EventKind::Renamed { subject: Named::Tile { tile }, from: Some(from), to }
+            if tile.as_str() == "compost" && from == "Compost" && to == "The Midden"));
+        assert_eq!(state.map.get_tile("compost").unwrap().name, "The Midden");
+
+        let rename = |state: &mut GameState, entity_id: &str, name: &str| engine.apply_command(state, Command::RenameEntity {
+            entity_id: entity_id.to_string(),
+            name: name.to_string(),
         });
+        assert!(matches!(&rename(&mut state, "p", "Beatrix").unwrap()[0].kind,
+            EventKind::Renamed { subject: Named::Entity { entity_id }, to, .. } if entity_id == "p" && to == "Beatrix"));
+        assert!(rename(&mut state, "p", "Beatrix").unwrap().is_empty());
+        assert_eq!(rename(&mut state, "p", "").unwrap_err(), CommandError::Name(NameError::Blank));
+        assert_eq!(rename(&mut state, "nobody", "Beatrix").unwrap_err(), CommandError::UnknownEntity("nobody".to_string()));
+
+        // A player's name outlasts a crowning, and the save
+        state.entities[0].crown(100);
+        let state = GameState::from_json(&state.to_json().unwrap()).unwrap();
+        assert_eq!(state.entities[0].name.as_deref(), Some("Beatrix"));
+        assert_eq!(state.map.get_tile("compost").unwrap().name, "The Midden");
+    }
 
-        if success {
-            // Something answers - choose a visitor type
-            let visitor_type_idx = rng.range(0, 2);
-            let (visitor, visitor_type) = match visitor_type_idx {
-                0 => (Entity::new_wanderer(rng.visitor_id()), VisitorType::Wanderer),
-                1 => (Entity::new_observer(rng.visitor_id()), VisitorType::Observer),
-                _ => (Entity::new_hungry(rng.visitor_id()), VisitorType::Hungry),
-            };
-
-            let name = visitor.name.clone().unwrap_or_default();
-            let id = visitor.id.clone();
+    #[test]
+    fn test_morale_follows_deaths_famine_and_sanity() {
+        use crate::types::morale::Mood;
 
-            state.entities.push(visitor);
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.features.insert(feature::MORALE.to_string());
+        state.config.blight = false;
+        state.entities.push(Entity::new_worker("mourner".to_string(), ORIGIN.to_string()));
+        state.entities.push(Entity { hunger: 5.0, ..Entity::new_worker("famished".to_string(), "compost".to_string()) });
+        state.resources.set("fungus", 0.0);
+        state.map.tiles.insert(Key::new("compost"), Tile::new_compost("compost".to_string(), 0, 1));
+        state.activity.entry(Key::new(ORIGIN)).or_default().deaths = 5;
+
+        let kinds = |engine: &mut TickEngine, state: &mut GameState| -> Vec<EventKind> {
+            engine.tick(state).into_events().into_iter().map(|e| e.kind).collect()
+        };
 
-            events.push(tick, EventKind::VisitorArrived {
-                visitor_id: id,
-                visitor_type,
-                name,
-            });
-        } else {
-            events.push(tick, EventKind::SummoningFailed);
+        // Deaths nearby are mourned once; famine wears on an ant
+        let events = kinds(&mut engine, &mut state);
+        assert!(events.iter().any(|e| matches!(e,
+            EventKind::MoraleChanged { entity_id, mood: Mood::Uneasy, morale } if entity_id == "mourner" && *morale == 50.0)));
+        assert_eq!(state.get_entity("famished").unwrap().morale, 98.0);
+        kinds(&mut engine, &mut state);
+        assert_eq!(state.get_entity("mourner").unwrap().morale, 50.5);
+
+        // Despair may drive ants off, leaving nothing behind, but never the queen
+        state.meta.sanity = 0.0;
+        state.config.tuning.desertion_chance = 1e9;
+        for entity in state.entities.iter_mut() {
+            entity.morale = 10.0;
         }
+        state.entities.push(Entity { morale: 10.0, ..Entity::new_queen("queen".to_string(), ORIGIN.to_string()) });
+        let events = kinds(&mut engine, &mut state);
+        let deserted: Vec<_> = events.iter().filter_map(|e| match e {
+            EventKind::EntityDied { entity_id, cause: DeathCause::Desertion, .. } => Some(entity_id.as_str()),
+            _ => None,
+        }).collect();
+        assert_eq!(deserted, ["mourner", "famished"]);
+        assert_eq!(state.entities.len(), 1);
+        assert!(!state.graveyard.has_corpses());
+
+        // Morale is only saved while it's short of full
+        let saved = serde_json::to_value(&state.entities[..]).unwrap();
+        assert_eq!(saved[0]["morale"], 9.9);
+        assert!(serde_json::to_value(Entity::new_worker("w".to_string(), ORIGIN.to_string())).unwrap().get("morale").is_none());
     }
 
-    /// Check receiver maintenance status
-    fn check_receiver_maintenance(&self, state: &mut GameState, events: &mut TickEvents) {
-        let tick = state.tick;
-
-        // Get maintenance goal if it exists
-        let maint_goal = state.meta.goals.get("receiver_maintenance").cloned();
-        if maint_goal.is_none() {
-            return;
-        }
-
-        let maint_goal = maint_goal.unwrap();
-        let last_maintained = maint_goal.get("last_maintained")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(tick);
-        let interval = maint_goal.get("maintenance_interval_ticks")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(constants::MAINTENANCE_INTERVAL);
-
-        let ticks_since_maint = tick.saturating_sub(last_maintained);
+    #[test]
+    fn test_morale_switched_on_mourns_only_new_deaths() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.config.blight = false;
+        state.config.tuning.famine_hunger = 1.0;
+        state.entities.push(Entity::new_worker("old_hand".to_string(), ORIGIN.to_string()));
+        state.entities.push(Entity { hunger: 5.0, ..Entity::new_worker("peckish".to_string(), ORIGIN.to_string()) });
+        state.activity.entry(Key::new(ORIGIN)).or_default().deaths = 5;
+
+        // A save that ran without morale has history, not fresh grief
+        engine.set_feature(&mut state, feature::MORALE, true);
+        assert_eq!(state.activity[ORIGIN].mourned, 5);
+        engine.tick(&mut state);
+        assert_eq!(state.get_entity("old_hand").unwrap().morale, 100.0);
 
-        // Auto-maintain if we have strange_matter and need maintenance
-        if ticks_since_maint >= interval {
-            let strange_matter = state.resources.get("strange_matter");
+        // And famine starts where the tuning says
+        assert_eq!(state.get_entity("peckish").unwrap().morale, 100.0);
+        state.config.tuning.famine_hunger = constants::FAMINE_HUNGER;
+        engine.tick(&mut state);
+        assert!(state.get_entity("peckish").unwrap().morale < 100.0);
+    }
 
-            if strange_matter >= constants::MAINTENANCE_COST_STRANGE_MATTER {
-                // Consume strange_matter
-                state.resources.add("strange_matter", -constants::MAINTENANCE_COST_STRANGE_MATTER);
+    #[test]
+    fn test_carriers_walk_their_goods_home() {
+        use crate::types::logistics::{Depot, LogisticsMode};
 
-                // Update maintenance timestamp
-                if let Some(goal) = state.meta.goals.get_mut("receiver_maintenance") {
-                    goal["last_maintained"] = serde_json::json!(tick);
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.features.insert(feature::MOVEMENT.to_string());
+        state.features.insert(feature::CARRYING.to_string());
+        state.config.tuning.carry_capacity = 0.05;
+        state.map.tiles.insert("origin".into(), Tile::new_empty("Origin".to_string(), 0, 0));
+        state.map.add_tile("grove", Tile::new_resource("Grove".to_string(), 1, 0, "seeds", 10.0), "origin").unwrap();
+        state.entities.push(Entity::new_forager("f".to_string(), "grove".to_string()));
+
+        // A forager fills up before anything reaches the stores...
+        for _ in 0..3 {
+            engine.tick(&mut state);
+        }
+        let forager = state.get_entity("f").unwrap();
+        assert!((forager.carried() - 0.05).abs() < 1e-12);
+        assert_eq!(state.resources.get("seeds"), 0.0);
+        let saved = serde_json::to_value(forager).unwrap();
+        assert_eq!(saved["route"]["to"]["type"], "colony");
+
+        // ...then walks it home and hands it over
+        let mut delivered = Vec::new();
+        for _ in 0..=constants::STEP_SECONDS {
+            for event in engine.tick(&mut state).into_events() {
+                if let EventKind::GoodsHauled { hauler_id, from, to, .. } = event.kind {
+                    delivered.push((hauler_id, from, to));
                 }
-            } else if !state.meta.receiver_silent {
-                // No fuel - receiver goes silent
-                state.meta.receiver_silent = true;
-                state.meta.receiver_failed_tick = Some(tick);
-                events.push(tick, EventKind::ReceiverSilent);
             }
         }
+        assert_eq!(delivered, [("f".to_string(), Depot::Tile("grove".to_string()), Depot::Colony)]);
+        assert!((state.resources.get("seeds") - 0.05).abs() < 1e-12);
+        assert!(state.get_entity("f").unwrap().inventory.is_empty());
+
+        // Haulers carry under local logistics too, and a fallen one's load
+        // stays where it fell
+        let mut state = logistics_state();
+        state.features.insert(feature::CARRYING.to_string());
+        state.resources.set("fungus", 1000.0);
+        state.entities.push(Entity::new_hauler("h1".to_string(), "origin".to_string()));
+        for _ in 0..200 {
+            engine.tick(&mut state);
+        }
+        assert!(state.resources.get("nutrients") > 0.0);
+        assert!(state.stockpiles["compost"].get("dirt") > 0.0);
+
+        state.entities.clear();
+        state.map.tiles.insert("lane".into(), Tile::new_empty("Lane".to_string(), 5, 5));
+        let mut doomed = Entity { hunger: 0.0, ..Entity::new_hauler("h2".to_string(), "lane".to_string()) };
+        doomed.load("dirt", 3.0, Route { from: Depot::Tile("dig".to_string()), to: Depot::Colony });
+        state.entities.push(doomed);
+        state.resources.set("fungus", 0.0);
+        assert_eq!(state.logistics, LogisticsMode::Local);
+        engine.tick(&mut state);
+        assert!(state.get_entity("h2").is_none());
+        assert_eq!(state.stockpiles["lane"].get("dirt"), 3.0);
+    }
 
-        // If silent and we now have strange_matter, restore
-        if state.meta.receiver_silent && state.resources.get("strange_matter") >= constants::MAINTENANCE_COST_STRANGE_MATTER {
-            state.resources.add("strange_matter", -constants::MAINTENANCE_COST_STRANGE_MATTER);
-            state.meta.receiver_silent = false;
+    #[test]
+    fn test_convergences_bring_several_visitors() {
+        use crate::types::outside::OutsideConditions;
+        use crate::types::system::System;
+
+        let mut state = GameState { outside_seed: 7, ..Default::default() };
+        state.features.insert(feature::CONVERGENCES.to_string());
+        state.config.tuning.summon_chance = 2.0;
+        state.systems.insert("receiver".to_string(), System {
+            system_type: SystemType::Antenna,
+            ..System::new_generator("The Receiver".to_string(), HashMap::new())
+        });
+        let antenna = Tile { tile_type: TileType::Antenna, ..Tile::new_empty("Antenna".to_string(), 3, 3) };
+        state.map.tiles.insert("antenna_1".into(), antenna.clone());
+        let conjunction = (1..).map(|minute| minute * 60)
+            .find(|tick| state.outside_conditions_at(*tick).is_conjunction())
+            .unwrap();
+
+        let summon = |state: &GameState| {
+            let mut state = state.clone();
+            state.tick = conjunction - 1;
+            state.resources.set("influence", 50.0);
+            let events = TickEngine::new(42).tick(&mut state).into_events();
+            let arrived = events.iter().filter(|e| matches!(e.kind, EventKind::VisitorArrived { .. })).count();
+            let convergence = events.into_iter().find_map(|e| match e.kind {
+                EventKind::Convergence { visitor_ids, .. } => Some(visitor_ids),
+                _ => None,
+            });
+            (arrived, convergence)
+        };
 
-            if let Some(goal) = state.meta.goals.get_mut("receiver_maintenance") {
-                goal["last_maintained"] = serde_json::json!(tick);
+        // One antenna hears one voice, conjunction or not
+        assert_eq!(summon(&state), (1, None));
+
+        // With a second, the conjunction brings a wave
+        state.map.tiles.insert("antenna_2".into(), antenna);
+        let (arrived, convergence) = summon(&state);
+        assert!((2..=3).contains(&arrived));
+        assert_eq!(convergence.map(|ids| ids.len()), Some(arrived));
+
+        // Off the conjunction, it's one at a time again
+        let mut quiet = state.clone();
+        quiet.outside_seed = (8..).find(|seed| {
+            !OutsideConditions::at(*seed, conjunction as f64).is_conjunction()
+        }).unwrap();
+        assert_eq!(summon(&quiet), (1, None));
+    }
+
+    #[test]
+    fn test_walkers_follow_the_strongest_trail() {
+        use crate::types::policy::Task;
+
+        // Two equally short ways to the field: the map's path goes by the
+        // hall, the den has the stronger trail
+        let walk = |trails: bool| {
+            let mut engine = TickEngine::new(42);
+            let mut state = GameState::default();
+            state.features.insert(feature::MOVEMENT.to_string());
+            if trails {
+                state.features.insert(feature::TRAILS.to_string());
             }
+            state.map.add_tile("hall", Tile::new_empty("Hall".to_string(), 1, 0), "origin").unwrap();
+            state.map.add_tile("den", Tile::new_empty("Den".to_string(), 0, 1), "origin").unwrap();
+            state.map.add_tile("field", Tile::new_resource("Field".to_string(), 1, 1, "seeds", 100.0), "hall").unwrap();
+            state.map.connect("den", "field").unwrap();
+            assert_eq!(state.map.path("origin", "field").unwrap()[0], "hall");
+            state.trails.lay(Key::new("den"), 5.0);
+
+            let mut forager = Entity::new_worker("w".to_string(), "origin".to_string());
+            forager.task = Some(Task::Forage);
+            state.entities.push(forager);
+            for _ in 0..constants::STEP_SECONDS {
+                engine.tick(&mut state);
+            }
+            state
+        };
 
-            events.push(tick, EventKind::ReceiverRestored);
+        assert_eq!(walk(false).get_entity("w").unwrap().tile, "hall");
+        let mut state = walk(true);
+        assert_eq!(state.get_entity("w").unwrap().tile, "den");
+
+        // The step strengthened the trail it took; every trail fades
+        let den = state.trails.on("den");
+        assert!(den > 5.0 * (1.0 - constants::TRAIL_DECAY).powi(constants::STEP_SECONDS as i32));
+        assert!(den < 5.0 + constants::TRAIL_DEPOSIT);
+        state.entities.clear();
+        let mut engine = TickEngine::new(42);
+        for _ in 0..60 {
+            engine.tick(&mut state);
         }
+        assert!(state.trails.on("den") < den / 2.0);
     }
 
-    /// Process visitor-specific behaviors
-    fn process_visitors(&self, state: &mut GameState, events: &mut TickEvents) {
-        let tick = state.tick;
-
-        // Find visitors that generate resources
-        for entity in &state.entities {
-            if entity.entity_type != EntityType::Visitor {
-                continue;
-            }
+    #[test]
+    fn test_spawns_take_the_configured_baselines() {
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.systems.insert(
+            "queen_chamber".to_string(),
+            crate::types::system::System::new_generator("Queen".to_string(), HashMap::new()),
+        );
+        state.resources.set("nutrients", 200.0);
+        state.resources.set("fungus", 200.0);
+        state.entities.push(Entity::new_queen("q".to_string(), "origin".to_string()));
+
+        // Rebalancing applies to ants born after it, not those already about
+        let worker = state.config.ant_baselines.get_mut(&AntRole::Worker).unwrap();
+        worker.hunger_rate = 0.3;
+        worker.max_age = 600;
+        state.config.ant_baselines.get_mut(&AntRole::Queen).unwrap().hunger_rate = 0.5;
+        let spawned = engine.tick(&mut state).events().iter().find_map(|e| match &e.kind {
+            EventKind::EmergencySpawn { worker_id, .. } => Some(worker_id.clone()),
+            _ => None,
+        });
+        let worker = state.get_entity(&spawned.expect("an emergency spawn")).unwrap();
+        assert_eq!((worker.hunger_rate, worker.max_age), (0.3, 600));
+        assert_eq!(state.get_entity("q").unwrap().hunger_rate, 0.05);
 
-            if let Some(generates) = &entity.generates {
-                for (resource, rate) in generates {
-                    state.resources.add(resource, *rate);
-                    events.push(tick, EventKind::PassiveGeneration {
-                        entity_id: entity.id.clone(),
-                        resource: resource.clone(),
-                        amount: *rate,
-                    });
-                }
-            }
+        for baseline in state.config.visitor_baselines.values_mut() {
+            baseline.max_age = 60;
         }
+        assert_eq!(TickEngine::answer_summons(&state, &mut SeededRng::new(1)).max_age, 60);
+
+        // A wanderer leaves the set gift, however much the colony hoards
+        state.resources.set("strange_matter", 1000.0);
+        let wanderer = (0..100)
+            .map(|seed| TickEngine::answer_summons(&state, &mut SeededRng::new(seed)))
+            .find(|v| v.subtype == Some(VisitorType::Wanderer))
+            .expect("a wanderer answers some summons");
+        assert_eq!(wanderer.gift_on_death.unwrap()["strange_matter"], constants::WANDERER_GIFT);
     }
 
-    /// Check resource thresholds
-    fn check_thresholds(&self, state: &GameState, prev_resources: &HashMap<String, f64>, events: &mut TickEvents) {
-        let tick = state.tick;
-
-        for (resource, &current) in &state.resources.amounts {
-            let prev = prev_resources.get(resource).copied().unwrap_or(0.0);
+    #[test]
+    fn test_weather_turns_with_the_seasons() {
+        use crate::types::weather::{Season, Sky};
 
-            for &threshold in &constants::RESOURCE_THRESHOLDS {
-                if prev < threshold && current >= threshold {
-                    events.push(tick, EventKind::ThresholdCrossed {
-                        resource: resource.clone(),
-                        threshold,
-                        current,
-                    });
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.features.insert(feature::SEASONS.to_string());
+        state.config.tuning.season_length = 10;
+        state.config.tuning.weather_spell = 5;
+        state.systems.insert("farm".to_string(), System::new_generator(
+            "Farm".to_string(),
+            HashMap::from([("fungus".to_string(), 1.0)]),
+        ));
+        state.entities.push(Entity::new_worker("w".to_string(), "origin".to_string()));
+
+        let mut seasons = Vec::new();
+        let mut skies = Vec::new();
+        for _ in 0..40 {
+            let fungus = state.resources.get("fungus");
+            let hunger = state.get_entity("w").unwrap().hunger;
+            let weather = state.current_weather();
+            for event in engine.tick(&mut state).events() {
+                match &event.kind {
+                    EventKind::SeasonChanged { season, year } => seasons.push((event.tick, *season, *year)),
+                    EventKind::WeatherEvent { sky, .. } => skies.push(*sky),
+                    _ => {}
                 }
             }
+
+            // Output and hunger follow the weather the tick began under
+            let (output, appetite) = weather.map_or((1.0, 1.0), |w| (w.production_factor(), w.hunger_factor()));
+            assert!((state.resources.get("fungus") - fungus - output).abs() < 1e-9);
+            assert!((hunger - state.get_entity("w").unwrap().hunger - 0.1 * appetite).abs() < 1e-9);
         }
+        assert_eq!(seasons, [(10, Season::Summer, 0), (20, Season::Autumn, 0), (30, Season::Winter, 0), (40, Season::Spring, 1)]);
+        assert_eq!(state.weather, Some(state.weather_at(40)));
+        let forecast: Vec<Sky> = (1..=40).map(|tick| state.weather_at(tick).sky).collect();
+        let changes: Vec<Sky> = forecast.windows(2).filter(|w| w[0] != w[1]).map(|w| w[1]).collect();
+        assert_eq!(skies, changes);
+
+        // Without the feature the weather is ignored
+        state.features.remove(feature::SEASONS);
+        assert_eq!(state.current_weather(), None);
     }
 
-    /// Process boredom tracking
-    fn process_boredom(&self, state: &mut GameState, events: &mut TickEvents) {
-        let tick = state.tick;
+    #[test]
+    fn test_day_and_night_turn() {
+        use crate::types::policy::Task;
 
-        // Increase boredom if nothing's happening
-        if !state.queues.has_actions() && state.queues.events.is_empty() {
-            state.meta.boredom += 1;
-        } else {
-            state.meta.boredom = state.meta.boredom.saturating_sub(1);
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.features.insert(feature::DAY_NIGHT.to_string());
+        state.config.tuning.day_length = 10;
+        state.config.tuning.night_share = 0.5;
+        let mut forager = Entity::new_worker("w".to_string(), "origin".to_string());
+        forager.task = Some(Task::Forage);
+        state.entities.push(forager);
+
+        let mut turns = Vec::new();
+        let mut foraged = Vec::new();
+        for _ in 0..20 {
+            let fungus = state.resources.get("fungus");
+            for event in engine.tick(&mut state).events() {
+                match &event.kind {
+                    EventKind::DayBreak { day } => turns.push((event.tick, "dawn", *day)),
+                    EventKind::NightFall { day } => turns.push((event.tick, "dusk", *day)),
+                    _ => {}
+                }
+            }
+            foraged.push((state.time_of_day().is_night(), state.resources.get("fungus") - fungus));
         }
-
-        // Emit if boredom is high
-        if state.meta.boredom >= constants::BOREDOM_THRESHOLD {
-            events.push(tick, EventKind::BoredomHigh {
-                level: state.meta.boredom,
-            });
-            state.meta.boredom = 0; // Reset after emitting
+        assert_eq!(turns, [(5, "dusk", 0), (10, "dawn", 1), (15, "dusk", 1), (20, "dawn", 2)]);
+
+        // Half the work in the dark, and no observers by day
+        let yield_by = |night: bool| foraged.iter().find(|(dark, _)| *dark == night).unwrap().1;
+        assert!((yield_by(true) - yield_by(false) * constants::NIGHT_WORK_FACTOR).abs() < 1e-12);
+        assert!(!state.time_of_day().is_night());
+        assert_eq!(state.visitor_weights().observer, 0.0);
+        state.features.remove(feature::DAY_NIGHT);
+        assert!(state.visitor_weights().observer > 0.0);
+
+        // Overnight wonders wait for the dark
+        state.features.insert(feature::DAY_NIGHT.to_string());
+        state.features.insert(feature::WONDERS.to_string());
+        state.config.tuning.wonder_chance = 1.0;
+        for _ in 0..4 {
+            engine.tick(&mut state);
         }
-    }
-
-    /// Initialize from an existing game state (for resuming)
-    pub fn init_from_state(&mut self, state: &GameState) {
-        // Try to infer last spawn tick from entity ages
-        if !state.entities.is_empty() {
-            let youngest_age = state.entities.iter()
-                .filter(|e| e.entity_type == EntityType::Ant)
-                .map(|e| e.age)
-                .min()
-                .unwrap_or(0);
-            self.last_spawn_tick = state.tick.saturating_sub(youngest_age);
+        assert_eq!(state.wonders.len(), 2);
+        assert!(!state.wonders.keys().any(|wonder| daynight::is_overnight(*wonder)));
+        for _ in 0..2 {
+            engine.tick(&mut state);
         }
+        assert_eq!(state.wonders.len(), 4);
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
-    fn test_basic_tick() {
-        let mut engine = TickEngine::new(42);
-        let mut state = GameState::default();
-
-        let events = engine.tick(&mut state);
-        assert_eq!(state.tick, 1);
-        assert!(events.is_empty() || !events.is_empty()); // Just checking it runs
-    }
+    fn test_advance_refuses_ticks_that_would_go_wrong() {
+        use crate::types::scenario::{EndCondition, Ending, Outcome};
 
-    #[test]
-    fn test_entity_aging() {
         let mut engine = TickEngine::new(42);
         let mut state = GameState::default();
+        assert!(engine.tick_n(&mut state, 0).ticks.is_empty());
+        assert_eq!((state.tick, state.bookkeeping), (0, None), "Zero ticks touch nothing");
 
-        state.entities.push(Entity::new_worker("test".to_string(), "origin".to_string()));
+        // A second advance from the same tick is refused
+        assert!(engine.advance(&mut state, 0).is_ok());
+        assert_eq!(engine.advance(&mut state, 0).unwrap_err(), TickRefused::Stale { expected: 0, actual: 1 });
+        assert_eq!(state.tick, 1);
+        assert!(engine.advance(&mut state, 1).is_ok());
 
+        // So is one on a clock moved since, until it's ticked through
+        state.tick = 10;
+        assert_eq!(engine.advance(&mut state, 10).unwrap_err(), TickRefused::ClockMoved { tick: 10, last: 2 });
         engine.tick(&mut state);
+        assert!(engine.advance(&mut state, 11).is_ok());
+
+        // A clock wound back behind the queen's last laying restarts her timer
+        state.systems.insert(
+            "queen_chamber".to_string(),
+            crate::types::system::System::new_generator("Queen".to_string(), HashMap::new()),
+        );
+        state.entities.push(Entity::new_worker("w".to_string(), "origin".to_string()));
+        state.bookkeeping.as_mut().unwrap().last_spawn_tick = 500;
+        engine.tick(&mut state);
+        assert_eq!(engine.last_spawn_tick(), 12);
 
-        assert_eq!(state.entities[0].age, 1);
-        assert!(state.entities[0].hunger < 100.0);
+        // Nothing runs once the run is over
+        state.ended = Some(Ending { tick: 13, outcome: Outcome::Completed, condition: EndCondition::SurviveTicks { ticks: 13 } });
+        let before = state.hash();
+        assert_eq!(engine.advance(&mut state, 13).unwrap_err(), TickRefused::Ended { tick: 13 });
+        assert_eq!(state.hash(), before);
     }
 
     #[test]
-    fn test_entity_eating() {
+    fn test_impossible_systems_are_set_aside_once() {
+        use crate::types::system::System;
+        use crate::types::warning::Warning;
+
         let mut engine = TickEngine::new(42);
         let mut state = GameState::default();
-
-        let mut entity = Entity::new_worker("test".to_string(), "origin".to_string());
-        entity.hunger = 40.0; // Below threshold
-        state.entities.push(entity);
         state.resources.set("fungus", 10.0);
-
-        let events = engine.tick(&mut state);
-
-        // Entity should have eaten
-        assert!(state.entities[0].hunger > 40.0);
-        assert!(state.resources.get("fungus") < 10.0);
-        assert!(events.events().iter().any(|e| matches!(e.kind, EventKind::EntityAte { .. })));
+        state.systems.insert(
+            "leaky_vat".to_string(),
+            System::new_converter(
+                "Leaky vat".to_string(),
+                HashMap::from([("fungus".to_string(), 2.0)]),
+                HashMap::from([("fungus".to_string(), 1.0)]),
+            ),
+        );
+        state.systems.insert(
+            "ghost_mill".to_string(),
+            System::new_converter(
+                "Ghost mill".to_string(),
+                HashMap::from([("ectoplasm".to_string(), 1.0)]),
+                HashMap::from([("ore".to_string(), 1.0)]),
+            ),
+        );
+
+        let mut suspects = Vec::new();
+        for _ in 0..3 {
+            suspects.extend(engine.tick(&mut state).events().iter().filter_map(|event| match &event.kind {
+                EventKind::ConfigurationSuspect { system_id, suspicion } => Some((system_id.clone(), suspicion.clone())),
+                _ => None,
+            }));
+        }
+        assert_eq!(suspects, [
+            ("ghost_mill".to_string(), Suspicion::UnknownResource { resource: "ectoplasm".to_string() }),
+            ("leaky_vat".to_string(), Suspicion::NetDrain { resource: "fungus".to_string(), consumes: 2.0, generates: 1.0 }),
+        ], "Each is reported once, in ID order");
+        assert_eq!(state.resources.get("fungus"), 10.0, "Nothing was drained");
+        let set_aside = state.warnings().into_iter().filter(|w| matches!(w, Warning::SystemSetAside { .. })).count();
+        assert_eq!(set_aside, 2);
+
+        // A fixed system goes back to work
+        state.systems.get_mut("leaky_vat").unwrap().generates = Some(HashMap::from([("ore".to_string(), 1.0)]));
+        engine.tick(&mut state);
+        assert!(state.systems["leaky_vat"].suspect.is_none());
+        assert_eq!(state.resources.get("fungus"), 8.0);
+        assert!(state.systems["ghost_mill"].suspect.is_some());
     }
 
     #[test]
-    fn test_entity_starvation() {
+    fn test_floods_and_cave_ins_strike_and_pass() {
+        use crate::types::hazard::Hazard;
+        use crate::types::memory::Experience;
+        use crate::types::schedule::ScheduledKind;
+        use crate::types::tile::Tile;
+
         let mut engine = TickEngine::new(42);
         let mut state = GameState::default();
-
-        let mut entity = Entity::new_worker("test".to_string(), "origin".to_string());
-        entity.hunger = 0.05; // About to starve
-        state.entities.push(entity);
-
+        state.features.insert(feature::SEASONS.to_string());
+        state.features.insert(feature::HAZARDS.to_string());
+        let tuning = &mut state.config.tuning;
+        tuning.rain_chance = 10.0;
+        tuning.flood_chance = state.ticks_per_second;
+        tuning.flood_duration = 2;
+        tuning.hazard_lethality = 1.0;
+        state.map.tiles.insert(Key::new("origin"), Tile::origin());
+        let mut cellar = Tile::new_empty("Cellar".to_string(), 0, 1);
+        cellar.elevation = Some(-1);
+        state.map.add_tile("cellar", cellar, "origin").unwrap();
+        let mut farm = System::new_generator("Farm".to_string(), HashMap::from([("fungus".to_string(), 1.0)]));
+        farm.tile = Some("cellar".to_string());
+        state.systems.insert("farm".to_string(), farm);
+        state.entities.push(Entity::new_worker("down".to_string(), "cellar".to_string()));
+        state.entities.push(Entity::new_worker("up".to_string(), "origin".to_string()));
+
+        // Rain floods the cellar at once: its farm stops and its ant drowns
+        let events = engine.tick(&mut state);
+        assert!(events.events().iter().any(|e| matches!(e.kind, EventKind::HazardStruck { hazard: Hazard::Flood, .. })));
+        assert!(events.events().iter().any(|e| matches!(
+            &e.kind,
+            EventKind::EntityDied { entity_id, cause: DeathCause::Drowned, .. } if entity_id == "down"
+        )));
+        assert!(state.systems["farm"].is_disabled());
+        assert_eq!(state.graveyard.corpses[0].cause, DeathCause::Drowned);
+        let up = state.get_entity("up").unwrap();
+        assert_eq!(up.memories[0].experience, Experience::WitnessedHazard { hazard: Hazard::Flood, tile: Key::new("cellar") });
+        let passes = ScheduledKind::HazardPasses { hazard: Hazard::Flood, tile: Key::new("cellar") };
+        assert!(state.upcoming(&engine).iter().any(|item| item.kind == passes));
+
+        // Then it passes, and the farm starts up again
+        let mut cleared = false;
+        for _ in 0..state.ticks_for(2) {
+            cleared |= engine.tick(&mut state).events().iter().any(|e| matches!(e.kind, EventKind::HazardCleared { .. }));
+        }
+        assert!(cleared);
+        assert!(!state.systems["farm"].is_disabled());
+
+        // A tile dug through the engine may cave in while it's fresh
+        state.config.tuning.rain_chance = 0.0;
+        state.config.tuning.cave_in_chance = state.ticks_per_second;
+        state.config.tuning.hazard_lethality = 0.0;
+        engine.add_tile(&mut state, "tunnel", Tile::new_empty("Tunnel".to_string(), 1, 0), "origin").unwrap();
+        assert_eq!(state.map.get_tile("tunnel").unwrap().dug_at, Some(state.tick));
+        state.entities.push(Entity::new_worker("digger".to_string(), "tunnel".to_string()));
         let events = engine.tick(&mut state);
+        assert!(events.events().iter().any(|e| matches!(e.kind, EventKind::HazardStruck { hazard: Hazard::CaveIn, .. })));
+        assert!(state.get_entity("digger").is_some(), "Nobody dies at no lethality");
 
-        // Entity should have died
-        assert!(state.entities.is_empty());
-        assert!(!state.graveyard.corpses.is_empty());
-        assert!(events.events().iter().any(|e| matches!(e.kind, EventKind::EntityDied { .. })));
+        // Once it's dug out and settled, it holds
+        state.config.tuning.cave_in_window = 0;
+        for _ in 0..state.ticks_for(constants::CAVE_IN_DURATION) + 5 {
+            engine.tick(&mut state);
+        }
+        assert!(state.map.get_tile("tunnel").unwrap().hazard.is_none());
     }
 
     #[test]
-    fn test_offline_progress() {
+    fn test_hazards_restart_only_what_they_stopped() {
+        use crate::types::tile::Tile;
+
         let mut engine = TickEngine::new(42);
         let mut state = GameState::default();
+        state.features.insert(feature::SEASONS.to_string());
+        state.features.insert(feature::HAZARDS.to_string());
+        let tuning = &mut state.config.tuning;
+        tuning.rain_chance = 10.0;
+        tuning.flood_chance = state.ticks_per_second;
+        tuning.flood_duration = 2;
+        state.map.tiles.insert(Key::new("origin"), Tile::origin());
+
+        // A sunken compost tile, blighted for a while yet, with a shed
+        // working beside the heap and a system the guard set aside
+        let mut compost = Tile::new_compost("The Heap".to_string(), 0, 1);
+        compost.elevation = Some(-1);
+        compost.start_blight(100);
+        compost.blight_stopped = Some(vec!["compost_heap".to_string()]);
+        state.map.add_tile("compost", compost, "origin").unwrap();
+        let rates = HashMap::from([("nutrients".to_string(), 1.0)]);
+        for id in ["compost_heap", "shed", "broken"] {
+            let mut system = System::new_generator(id.to_string(), rates.clone());
+            system.tile = Some("compost".to_string());
+            if id != "shed" {
+                system.disable();
+            }
+            state.systems.insert(id.to_string(), system);
+        }
 
-        // Setup state
-        state.last_save_timestamp = Some(1000.0);
-        state.resources.set("fungus", 100.0);
+        // The flood stops the shed, and passes while the blight goes on
+        let events = engine.tick(&mut state);
+        assert!(events.events().iter().any(|e| matches!(e.kind, EventKind::HazardStruck { .. })));
+        assert!(state.systems["shed"].is_disabled());
+        state.config.tuning.rain_chance = 0.0;
+        for _ in 0..state.ticks_for(2) {
+            engine.tick(&mut state);
+        }
+        assert!(state.map.get_tile("compost").unwrap().hazard.is_none());
+        assert!(!state.systems["shed"].is_disabled());
+        assert!(state.systems["compost_heap"].is_disabled(), "Still blighted");
+        assert!(state.systems["broken"].is_disabled(), "Still quarantined");
+
+        // The blight clearing restarts the heap alone
+        while state.map.get_tile("compost").unwrap().is_blighted() {
+            engine.tick(&mut state);
+        }
+        assert!(!state.systems["compost_heap"].is_disabled());
+        assert!(state.systems["broken"].is_disabled());
+    }
 
-        // Add an entity
-        let mut entity = Entity::new_worker("test_offline".to_string(), "origin".to_string());
-        entity.hunger = 80.0;
-        state.entities.push(entity);
+    #[test]
+    fn test_diggers_open_excavated_tiles() {
+        use crate::types::excavation::{ExcavationError, Vein};
+        use crate::types::tile::Tile;
+        use crate::types::warning::Warning;
 
-        // Add a system that generates resources
-        let mut system_gen = HashMap::new();
-        system_gen.insert("fungus".to_string(), 1.0);
-        let system = crate::types::system::System::new_generator("fungus_farm".to_string(), system_gen);
-        state.systems.insert("fungus_farm".to_string(), system);
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState { outside_seed: 7, ..GameState::default() };
+        state.config.tuning.excavation_work = 0.2;
+        state.map.tiles.insert(Key::new("origin"), Tile::origin());
+        for id in ["a", "b"] {
+            let mut digger = Entity::new_worker(id.to_string(), "origin".to_string());
+            digger.task = Some(Task::Dig);
+            state.entities.push(digger);
+        }
 
-        // 100 seconds elapsed ( > 10 ticks, < 3600)
-        let current_time = 1100.0;
+        // The first two go east and south of the origin
+        engine.dig_tile(&mut state, "east", "origin").unwrap();
+        engine.dig_tile(&mut state, "south", "origin").unwrap();
+        assert_eq!((state.excavations["east"].x, state.excavations["east"].y), (1, 0));
+        assert_eq!((state.excavations["south"].x, state.excavations["south"].y), (0, 1));
+        assert_eq!(engine.dig_tile(&mut state, "east", "origin").unwrap_err(), ExcavationError::AlreadyDigging("east".to_string()));
+        assert_eq!(engine.dig_tile(&mut state, "x", "nowhere").unwrap_err(), ExcavationError::UnknownTile("nowhere".to_string()));
+        assert_eq!(state.tile_in_use("origin"), Some("entities"));
+
+        // Diggers finish the oldest first, still bringing up dirt
+        let mut opened = Vec::new();
+        for _ in 0..state.ticks_for(60) {
+            for event in engine.tick(&mut state).into_events() {
+                if let EventKind::TileExcavated { tile, from, vein } = event.kind {
+                    assert_eq!(from, Key::new("origin"));
+                    assert_eq!(vein, Vein::at(7, state.map.get_tile(&tile).unwrap().x, state.map.get_tile(&tile).unwrap().y, &state.config.tuning));
+                    opened.push(tile);
+                }
+            }
+            if state.excavations.is_empty() {
+                break;
+            }
+        }
+        assert_eq!(opened, [Key::new("east"), Key::new("south")]);
+        assert!(state.resources.get("dirt") > 0.0);
+        let east = state.map.get_tile("east").unwrap();
+        assert!(east.dug_at.is_some());
+        assert!(state.map.are_connected("origin", "east"));
+
+        // The next dig skips the squares now taken
+        engine.dig_tile(&mut state, "west", "origin").unwrap();
+        assert_eq!((state.excavations["west"].x, state.excavations["west"].y), (-1, 0));
+
+        // A tile added by hand under its ID holds it up, with a warning
+        state.map.add_tile("west", Tile::new_empty("Shed".to_string(), 5, 5), "origin").unwrap();
+        for _ in 0..state.ticks_for(60) {
+            engine.tick(&mut state);
+        }
+        assert!(state.excavations["west"].is_done());
+        assert!(state.warnings().contains(&Warning::ExcavationBlocked { tile: Key::new("west") }));
+        state.map.demolish("west").unwrap();
+        engine.tick(&mut state);
+        assert!(state.excavations.is_empty());
+        assert_eq!(state.map.get_tile("west").unwrap().x, -1);
+
+        // A dig from a tile that's since gone is given up
+        engine.dig_tile(&mut state, "deep", "east").unwrap();
+        state.map.demolish("east").unwrap();
+        let cancelled = engine.tick(&mut state).into_events().into_iter().any(|e| matches!(
+            e.kind,
+            EventKind::ExcavationCancelled { ref tile, ref from, .. } if *tile == "deep" && *from == "east"
+        ));
+        assert!(cancelled);
+        assert!(state.excavations.is_empty());
+    }
 
-        engine.process_offline_progress(&mut state, current_time);
+    #[test]
+    fn test_spawn_hooks_shape_newcomers() {
+        use crate::types::heredity::Trait;
+        use crate::types::spawn::{ScheduledHook, SpawnHooks};
 
-        // Check ticks advanced
-        assert_eq!(state.tick, 100);
+        let mut engine = TickEngine::new(42);
+        let mut state = GameState::default();
+        state.systems.insert(
+            "queen_chamber".to_string(),
+            crate::types::system::System::new_generator("Queen".to_string(), HashMap::new()),
+        );
+        state.resources.set("nutrients", 200.0);
+        state.resources.set("fungus", 200.0);
+        state.entities.push(Entity::new_queen("q".to_string(), "origin".to_string()));
+        state.config.ant_baselines.get_mut(&AntRole::Worker).unwrap().on_spawn = SpawnHooks {
+            traits: vec![Trait::Industrious],
+            bundled: BTreeMap::from([(Key::new("crystals"), 3.0)]),
+            scheduled: Some(ScheduledHook {
+                action_type: "first_molt".to_string(),
+                delay: 2,
+                resources: BTreeMap::from([("ore".to_string(), 1.0)]),
+            }),
+        };
 
-        // Check resources generated: 100 ticks * 1.0 fungus = 100 + 100 start = 200
-        // BUT entity eats fungus.
-        // Entity hunger decreases by 0.1 * 0.5 = 0.05 per tick.
-        // 100 ticks -> 5.0 hunger loss.
-        // 80.0 -> 75.0. No eating should happen (threshold 50.0).
+        // The worker comes with its trait, its crystals and its molt; the
+        // undertaker beside it has no hooks
+        let events = engine.tick(&mut state);
+        let worker_id = events.events().iter().find_map(|e| match &e.kind {
+            EventKind::EmergencySpawn { worker_id, .. } => Some(worker_id.clone()),
+            _ => None,
+        }).expect("an emergency spawn");
+        let applied: Vec<_> = events.events().iter().filter_map(|e| match &e.kind {
+            EventKind::SpawnHooksApplied { entity_id, action_id, .. } => Some((entity_id.clone(), action_id.clone())),
+            _ => None,
+        }).collect();
+        assert_eq!(applied, [(worker_id.clone(), Some(format!("{worker_id}/first_molt")))]);
+        assert!(state.get_entity(&worker_id).unwrap().traits.contains(&Trait::Industrious));
+        assert_eq!(state.resources.get("crystals"), 3.0);
+
+        let mut molted = false;
+        for _ in 0..state.ticks_for(2) + 1 {
+            molted |= engine.tick(&mut state).events().iter()
+                .any(|e| matches!(&e.kind, EventKind::ActionComplete { action_type, .. } if action_type == "first_molt"));
+        }
+        assert!(molted);
+        assert_eq!(state.resources.get("ore"), 1.0);
 
-        assert_eq!(state.resources.get("fungus"), 200.0);
-        assert_eq!(state.entities[0].age, 100);
-        // 80 - (0.1 * 0.5 * 100) = 80 - 5 = 75
-        assert!((state.entities[0].hunger - 75.0).abs() < 0.001);
+        // Hooks can't start the engine's own kinds of action
+        state.config.ant_baselines.get_mut(&AntRole::Worker).unwrap().on_spawn.scheduled.as_mut().unwrap().action_type = "dig".to_string();
+        let mut events = TickEvents::new();
+        TickEngine::welcome(&mut state, &mut events, Entity::new_worker("w".to_string(), "origin".to_string()));
+        assert!(events.events().iter().any(|e| matches!(&e.kind, EventKind::SpawnHooksApplied { action_id: None, .. })));
+        assert!(state.queues.actions.is_empty());
     }
 }
@@ -4,9 +4,32 @@
 //! It emits events. The layer above interprets them.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
-use crate::types::entity::{DeathCause, EntityId, VisitorType};
+use crate::guard::Correction;
+use crate::timeline::TimeAnomaly;
+use crate::types::brood::BroodLoss;
+use crate::types::entity::{AntRole, DeathCause, EntityId, EntitySnapshot, Harm, LifeStage, VisitorType};
+use crate::types::key::Key;
+use crate::types::logistics::Depot;
+use crate::types::pause::Subsystem;
+use crate::types::group::Intent;
+use crate::types::silence::SilenceStage;
+use crate::types::threat::Threat;
+use crate::types::scenario::EndCondition;
+use crate::types::hosting::HostingEnd;
+use crate::types::naming::Named;
+use crate::types::whisper::Whisper;
+use crate::types::hazard::Hazard;
+use crate::types::excavation::Vein;
+use crate::types::heredity::Trait;
+use crate::types::system::Suspicion;
+use crate::types::weather::{Season, Sky};
+use crate::types::morale::Mood;
+use crate::types::wonder::Wonder;
+use crate::types::policy::Task;
+use crate::types::warning::Warning;
+use crate::types::reflection::{ReflectionRef, ReflectionTrigger};
 
 /// A single event emitted by the tick engine
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +47,19 @@ impl Event {
     }
 }
 
+/// How much a host is likely to care about an event
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verbosity {
+    /// Per-tick chatter: production, meals, hauling
+    #[default]
+    Debug,
+    /// Routine happenings worth a log line
+    Info,
+    /// Things a player would want to hear about
+    Notable,
+}
+
 /// All possible event kinds
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -33,19 +69,41 @@ pub enum EventKind {
         entity_id: EntityId,
         entity_type: String,
         cause: DeathCause,
-        tile: String,
+        tile: Key,
+        /// The entity as it died
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        snapshot: Option<EntitySnapshot>,
+    },
+
+    /// An entity ran out of food and went dormant instead of starving
+    EntityDormant {
+        entity_id: EntityId,
+        tile: Key,
+    },
+
+    /// A dormant entity was fed and woke up
+    EntityAwoke {
+        entity_id: EntityId,
     },
 
     /// An entity ate food
     EntityAte {
         entity_id: EntityId,
-        food: String,
+        food: Key,
+        hunger_after: f64,
+    },
+
+    /// An entity's meals over the last batch window (`MealReports::Batched`)
+    MealsEaten {
+        entity_id: EntityId,
+        food: Key,
+        meals: u64,
         hunger_after: f64,
     },
 
     /// A resource threshold was crossed (going up)
     ThresholdCrossed {
-        resource: String,
+        resource: Key,
         threshold: f64,
         current: f64,
     },
@@ -61,6 +119,9 @@ pub enum EventKind {
         system_id: String,
         produced: HashMap<String, f64>,
         consumed: HashMap<String, f64>,
+        /// Tile stockpile affected (None = colony store)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stockpile: Option<Key>,
     },
 
     /// A corpse was processed by an undertaker
@@ -72,28 +133,44 @@ pub enum EventKind {
 
     /// Blight struck a tile
     BlightStruck {
-        tile: String,
+        tile: Key,
         contamination: f64,
         duration_ticks: u64,
     },
 
     /// Blight cleared from a tile
     BlightCleared {
-        tile: String,
+        tile: Key,
     },
 
     /// Entity killed by blight
     BlightKill {
         entity_id: EntityId,
-        tile: String,
+        tile: Key,
+        /// The entity as it died
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        snapshot: Option<EntitySnapshot>,
     },
 
-    /// New ants were spawned
+    /// The queen laid a worker and an undertaker as brood (and a nurse,
+    /// when the colony needs one); they join the colony when
+    /// `BroodHatched` fires for their IDs
     AntsSpawned {
         worker_id: EntityId,
         undertaker_id: EntityId,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        nurse_id: Option<EntityId>,
         nutrients_consumed: f64,
         fungus_consumed: f64,
+        /// Everything the clutch cost
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        costs: BTreeMap<Key, f64>,
+    },
+
+    /// A larva finished developing and joined the colony
+    BroodHatched {
+        entity_id: EntityId,
+        role: AntRole,
     },
 
     /// Emergency spawn (colony was empty)
@@ -107,6 +184,9 @@ pub enum EventKind {
         visitor_id: EntityId,
         visitor_type: VisitorType,
         name: String,
+        /// The visitor as it arrived
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        snapshot: Option<EntitySnapshot>,
     },
 
     /// A visitor departed (died)
@@ -115,6 +195,9 @@ pub enum EventKind {
         visitor_type: VisitorType,
         name: String,
         gift: Option<HashMap<String, f64>>,
+        /// The visitor as it left
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        snapshot: Option<EntitySnapshot>,
     },
 
     /// Influence was spent on summoning
@@ -123,6 +206,11 @@ pub enum EventKind {
         success: bool,
     },
 
+    /// Interference in the Outside rose past the surge level
+    StaticSurge {
+        level: f64,
+    },
+
     /// Summoning failed (no visitor arrived)
     SummoningFailed,
 
@@ -135,7 +223,7 @@ pub enum EventKind {
     /// Resource was generated passively (by visitor)
     PassiveGeneration {
         entity_id: EntityId,
-        resource: String,
+        resource: Key,
         amount: f64,
     },
 
@@ -157,22 +245,1045 @@ pub enum EventKind {
         new_value: f64,
         reason: String,
     },
+
+    /// The colony thinks it heard a visitor. Nothing arrived.
+    /// Unreliable: emitted only at low sanity.
+    PhantomVisitorSignal {
+        visitor_type: VisitorType,
+        sanity: f64,
+    },
+
+    /// The colony miscounted a resource. `actual` is the true amount;
+    /// state is unchanged. Unreliable: emitted only at low sanity.
+    PerceptionGlitch {
+        resource: Key,
+        perceived: f64,
+        actual: f64,
+        sanity: f64,
+    },
+
+    /// A group of ants left for the Outside
+    ExpeditionDeparted {
+        expedition_id: String,
+        members: Vec<EntityId>,
+        return_tick: u64,
+    },
+
+    /// An expedition came home
+    ExpeditionReturned {
+        expedition_id: String,
+        survivors: Vec<EntityId>,
+        lost: Vec<EntityId>,
+        loot: HashMap<String, f64>,
+        discovered_tile: Option<String>,
+    },
+
+    /// An outpost was established on a remote tile
+    OutpostEstablished {
+        tile: Key,
+        resource: Key,
+    },
+
+    /// A supply run left for an outpost
+    SupplyDispatched {
+        tile: Key,
+        arrives_tick: u64,
+    },
+
+    /// Supplies reached an outpost
+    OutpostSupplied {
+        tile: Key,
+        supply_ticks: u64,
+    },
+
+    /// An outpost ran out of supplies and stopped producing
+    OutpostUnsupplied {
+        tile: Key,
+    },
+
+    /// An outpost degraded past saving
+    OutpostLost {
+        tile: Key,
+    },
+
+    /// A hauler (or, with `feature::CARRYING`, a forager) moved goods
+    /// between depots
+    GoodsHauled {
+        hauler_id: EntityId,
+        resource: Key,
+        amount: f64,
+        from: Depot,
+        to: Depot,
+    },
+
+    /// Another colony was merged into this one
+    ColonyAbsorbed {
+        /// IDs of the entities that joined (after any renaming)
+        entities: Vec<EntityId>,
+        /// Incoming IDs that were renamed, old -> new
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        renamed: BTreeMap<EntityId, EntityId>,
+        /// Incoming entities left behind because their ID was taken
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        skipped: Vec<EntityId>,
+        /// Resources added to the colony store
+        resources: BTreeMap<Key, f64>,
+        /// Corpses added to the graveyard
+        corpses: usize,
+    },
+
+    /// The colony was abandoned; its legacy seed is in `state.legacy`
+    ColonyAbandoned {
+        legacy_id: u64,
+        ruins: usize,
+        ghosts: usize,
+        /// Resources left in the ruins
+        residual: BTreeMap<Key, f64>,
+    },
+
+    /// A ghost of a past run stirred in its ruin
+    EchoOfThePast {
+        tile: Key,
+        legacy_id: u64,
+        ghost_id: EntityId,
+        cohort: String,
+    },
+
+    /// A ruin was dug up and its loot added to the stores
+    RuinExcavated {
+        tile: Key,
+        legacy_id: u64,
+        loot: BTreeMap<Key, f64>,
+    },
+
+    /// A reflection is due; an unanswered one has been stored in meta
+    ReflectionDue {
+        trigger: ReflectionTrigger,
+        refs: Vec<ReflectionRef>,
+    },
+
+    /// A subsystem was paused; its tick phase is skipped until resumed
+    SubsystemPaused {
+        subsystem: Subsystem,
+    },
+
+    /// The colony's work policy changed; every worker was released to be
+    /// reassigned
+    PolicyChanged {
+        priorities: Vec<Task>,
+    },
+
+    /// A paused subsystem was resumed
+    SubsystemResumed {
+        subsystem: Subsystem,
+    },
+
+    /// The save opted into or out of a feature flag
+    FeatureChanged {
+        feature: String,
+        enabled: bool,
+    },
+
+    /// Ants were formed into a group
+    GroupFormed {
+        group: String,
+        members: Vec<EntityId>,
+    },
+
+    /// A group was given a new intent
+    GroupIntentChanged {
+        group: String,
+        intent: Intent,
+    },
+
+    /// Every member of a group reached the tile it was sent to
+    GroupArrived {
+        group: String,
+        tile: Key,
+    },
+
+    /// A group was disbanded, or lost its last member
+    GroupDisbanded {
+        group: String,
+    },
+
+    /// A system was built by command
+    SystemBuilt {
+        system_id: String,
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tile: Option<Key>,
+    },
+
+    /// An ant was given a new role by command
+    RoleAssigned {
+        entity_id: EntityId,
+        from: Option<AntRole>,
+        to: AntRole,
+    },
+
+    /// An action was queued by command
+    ActionQueued {
+        action_id: String,
+        action_type: String,
+        ticks: u64,
+    },
+
+    /// An entity was moved by command
+    EntityMoved {
+        entity_id: EntityId,
+        from: Key,
+        to: Key,
+    },
+
+    /// The host's clock looked wrong when catching up on offline time
+    TimeAnomaly {
+        anomaly: TimeAnomaly,
+        last_save: f64,
+        now: f64,
+    },
+
+    /// A wonder happened, paying out its one-time rewards
+    WonderOccurred {
+        wonder: Wonder,
+        rewards: BTreeMap<Key, f64>,
+    },
+
+    /// A tick phase drew from the RNG more or fewer times than its budget
+    RngBudgetExceeded {
+        phase: String,
+        expected: u64,
+        actual: u64,
+    },
+
+    /// A tile was added to the map by command
+    TileAdded {
+        tile: Key,
+        connected_to: Key,
+    },
+
+    /// Two tiles were connected by command
+    TilesConnected {
+        a: Key,
+        b: Key,
+    },
+
+    /// The connection between two tiles was removed by command
+    TilesDisconnected {
+        a: Key,
+        b: Key,
+    },
+
+    /// A tile was removed from the map by command
+    TileDemolished {
+        tile: Key,
+    },
+
+    /// A forager harvested a resource tile
+    ResourceHarvested {
+        forager_id: EntityId,
+        tile: Key,
+        resource: Key,
+        amount: f64,
+    },
+
+    /// A resource tile's stock ran out
+    ResourceDepleted {
+        tile: Key,
+        resource: Key,
+    },
+
+    /// Soft-fail mode fixed an invalid edit made between ticks
+    ExternalEditCorrected {
+        correction: Correction,
+    },
+
+    /// A silent receiver slid to a worse stage
+    ReceiverSilenceDeepened {
+        stage: SilenceStage,
+        silent_for: u64,
+    },
+
+    /// An incursion met the colony's defenders
+    DefenseResolved {
+        threat: Threat,
+        /// The incursion's strength against the defenders'
+        strength: f64,
+        defense: f64,
+        repelled: bool,
+        /// Ants killed, defenders or (when predators won) otherwise
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        fallen: Vec<EntityId>,
+        /// Carried off by raiders that won
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        stolen: BTreeMap<Key, f64>,
+    },
+
+    /// Influence was spent on a whisper
+    Whispered {
+        influence_spent: f64,
+        whisper: Whisper,
+    },
+
+    /// A gift a whisper promised arrived
+    PromiseKept {
+        resource: Key,
+        amount: f64,
+    },
+
+    /// A nurse fed larvae this tick
+    BroodTended {
+        nurse_id: EntityId,
+        larvae: u64,
+    },
+
+    /// A developing ant was lost before it hatched
+    BroodDied {
+        entity_id: EntityId,
+        role: AntRole,
+        stage: LifeStage,
+        cause: BroodLoss,
+    },
+
+    /// A developing ant moved on to its next stage
+    BroodStageChanged {
+        entity_id: EntityId,
+        role: AntRole,
+        stage: LifeStage,
+    },
+
+    /// A scenario began
+    ScenarioStarted {
+        name: String,
+    },
+
+    /// A scenario's win condition was met; the run is over
+    ScenarioCompleted {
+        name: String,
+        condition: EndCondition,
+    },
+
+    /// A scenario's lose condition was met; the run is over
+    ScenarioFailed {
+        name: String,
+        condition: EndCondition,
+    },
+
+    /// The colony's health score moved far enough to report (see `health`)
+    HealthChanged {
+        score: f64,
+        previous: Option<f64>,
+    },
+
+    /// A queen took the chamber: the founder, or a princess succeeding
+    QueenCrowned {
+        queen_id: EntityId,
+        reign: u64,
+    },
+
+    /// The reigning queen died; `heir` is the princess who'll succeed her (grown or still brood), if the colony raised one
+    QueenLost {
+        queen_id: EntityId,
+        heir: Option<EntityId>,
+    },
+
+    /// The aging queen laid a princess to succeed her
+    PrincessLaid {
+        princess_id: EntityId,
+        costs: BTreeMap<Key, f64>,
+    },
+
+    /// A hosting round paid for more of a visitor's stay
+    StayExtended {
+        visitor_id: EntityId,
+        extended_by: u64,
+        ticks_left: u64,
+        costs: BTreeMap<Key, f64>,
+    },
+
+    /// The colony stopped hosting a visitor
+    HostingEnded {
+        visitor_id: EntityId,
+        reason: HostingEnd,
+    },
+
+    /// An ant at full health started losing it
+    EntityInjured {
+        entity_id: EntityId,
+        harm: Harm,
+        health: f64,
+    },
+
+    /// A hurt ant healed back to full health
+    EntityRecovered {
+        entity_id: EntityId,
+    },
+
+    /// A player named a tile or entity
+    Renamed {
+        subject: Named,
+        from: Option<String>,
+        to: String,
+    },
+
+    /// An ant's mood changed (with `feature::MORALE`)
+    MoraleChanged {
+        entity_id: EntityId,
+        mood: Mood,
+        morale: f64,
+    },
+
+    /// Several visitors answered one summons during a conjunction (with `feature::CONVERGENCES`)
+    Convergence {
+        visitor_ids: Vec<EntityId>,
+        alignment: f64,
+    },
+
+    /// The colony's year turned to a new season (with `feature::SEASONS`)
+    SeasonChanged {
+        season: Season,
+        year: u64,
+    },
+
+    /// The sky changed: rain, drought, or clearing (with `feature::SEASONS`)
+    WeatherEvent {
+        sky: Sky,
+        season: Season,
+    },
+
+    /// Dawn over the colony (with `feature::DAY_NIGHT`)
+    DayBreak {
+        day: u64,
+    },
+
+    /// Dusk over the colony (with `feature::DAY_NIGHT`)
+    NightFall {
+        day: u64,
+    },
+
+    /// A system's rates can't be right; it sits out until they're fixed
+    ConfigurationSuspect {
+        system_id: String,
+        suspicion: Suspicion,
+    },
+
+    /// A flood or cave-in struck a tile, stopping its systems for `duration_ticks`
+    HazardStruck {
+        hazard: Hazard,
+        tile: Key,
+        duration_ticks: u64,
+    },
+
+    /// A flood or cave-in passed and the tile's systems started up again
+    HazardCleared {
+        hazard: Hazard,
+        tile: Key,
+    },
+
+    /// Diggers started opening a new tile beside another
+    ExcavationStarted {
+        tile: Key,
+        from: Key,
+        x: i32,
+        y: i32,
+        work_needed: f64,
+    },
+
+    /// A dug tile broke through and joined the map (`TileAdded` follows)
+    TileExcavated {
+        tile: Key,
+        from: Key,
+        vein: Vein,
+    },
+
+    /// A dig was given up because the tile it was dug from is gone or
+    /// can no longer be reached from the origin
+    ExcavationCancelled {
+        tile: Key,
+        from: Key,
+        work_done: f64,
+    },
+
+    /// A newcomer's template hooks were carried out as it spawned
+    SpawnHooksApplied {
+        entity_id: EntityId,
+        traits: Vec<Trait>,
+        bundled: BTreeMap<Key, f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action_id: Option<String>,
+    },
+}
+
+impl EventKind {
+    /// Which kind of event this is
+    pub fn tag(&self) -> EventKindTag {
+        use EventKind::*;
+        match self {
+            EntityDied { .. } => EventKindTag::EntityDied,
+            EntityDormant { .. } => EventKindTag::EntityDormant,
+            EntityAwoke { .. } => EventKindTag::EntityAwoke,
+            EntityAte { .. } => EventKindTag::EntityAte,
+            MealsEaten { .. } => EventKindTag::MealsEaten,
+            ThresholdCrossed { .. } => EventKindTag::ThresholdCrossed,
+            ActionComplete { .. } => EventKindTag::ActionComplete,
+            SystemProduced { .. } => EventKindTag::SystemProduced,
+            CorpseProcessed { .. } => EventKindTag::CorpseProcessed,
+            BlightStruck { .. } => EventKindTag::BlightStruck,
+            BlightCleared { .. } => EventKindTag::BlightCleared,
+            BlightKill { .. } => EventKindTag::BlightKill,
+            AntsSpawned { .. } => EventKindTag::AntsSpawned,
+            BroodHatched { .. } => EventKindTag::BroodHatched,
+            EmergencySpawn { .. } => EventKindTag::EmergencySpawn,
+            VisitorArrived { .. } => EventKindTag::VisitorArrived,
+            VisitorDeparted { .. } => EventKindTag::VisitorDeparted,
+            InfluenceSpent { .. } => EventKindTag::InfluenceSpent,
+            StaticSurge { .. } => EventKindTag::StaticSurge,
+            SummoningFailed => EventKindTag::SummoningFailed,
+            ReceiverSilent => EventKindTag::ReceiverSilent,
+            ReceiverRestored => EventKindTag::ReceiverRestored,
+            PassiveGeneration { .. } => EventKindTag::PassiveGeneration,
+            InfluenceTransformed { .. } => EventKindTag::InfluenceTransformed,
+            BoredomHigh { .. } => EventKindTag::BoredomHigh,
+            SanityChanged { .. } => EventKindTag::SanityChanged,
+            PhantomVisitorSignal { .. } => EventKindTag::PhantomVisitorSignal,
+            PerceptionGlitch { .. } => EventKindTag::PerceptionGlitch,
+            ExpeditionDeparted { .. } => EventKindTag::ExpeditionDeparted,
+            ExpeditionReturned { .. } => EventKindTag::ExpeditionReturned,
+            OutpostEstablished { .. } => EventKindTag::OutpostEstablished,
+            SupplyDispatched { .. } => EventKindTag::SupplyDispatched,
+            OutpostSupplied { .. } => EventKindTag::OutpostSupplied,
+            OutpostUnsupplied { .. } => EventKindTag::OutpostUnsupplied,
+            OutpostLost { .. } => EventKindTag::OutpostLost,
+            GoodsHauled { .. } => EventKindTag::GoodsHauled,
+            ColonyAbsorbed { .. } => EventKindTag::ColonyAbsorbed,
+            ColonyAbandoned { .. } => EventKindTag::ColonyAbandoned,
+            EchoOfThePast { .. } => EventKindTag::EchoOfThePast,
+            RuinExcavated { .. } => EventKindTag::RuinExcavated,
+            ReflectionDue { .. } => EventKindTag::ReflectionDue,
+            SubsystemPaused { .. } => EventKindTag::SubsystemPaused,
+            PolicyChanged { .. } => EventKindTag::PolicyChanged,
+            SubsystemResumed { .. } => EventKindTag::SubsystemResumed,
+            FeatureChanged { .. } => EventKindTag::FeatureChanged,
+            GroupFormed { .. } => EventKindTag::GroupFormed,
+            GroupIntentChanged { .. } => EventKindTag::GroupIntentChanged,
+            GroupArrived { .. } => EventKindTag::GroupArrived,
+            GroupDisbanded { .. } => EventKindTag::GroupDisbanded,
+            SystemBuilt { .. } => EventKindTag::SystemBuilt,
+            RoleAssigned { .. } => EventKindTag::RoleAssigned,
+            ActionQueued { .. } => EventKindTag::ActionQueued,
+            EntityMoved { .. } => EventKindTag::EntityMoved,
+            TimeAnomaly { .. } => EventKindTag::TimeAnomaly,
+            WonderOccurred { .. } => EventKindTag::WonderOccurred,
+            RngBudgetExceeded { .. } => EventKindTag::RngBudgetExceeded,
+            TileAdded { .. } => EventKindTag::TileAdded,
+            TilesConnected { .. } => EventKindTag::TilesConnected,
+            TilesDisconnected { .. } => EventKindTag::TilesDisconnected,
+            TileDemolished { .. } => EventKindTag::TileDemolished,
+            ResourceHarvested { .. } => EventKindTag::ResourceHarvested,
+            ResourceDepleted { .. } => EventKindTag::ResourceDepleted,
+            ExternalEditCorrected { .. } => EventKindTag::ExternalEditCorrected,
+            ReceiverSilenceDeepened { .. } => EventKindTag::ReceiverSilenceDeepened,
+            DefenseResolved { .. } => EventKindTag::DefenseResolved,
+            Whispered { .. } => EventKindTag::Whispered,
+            PromiseKept { .. } => EventKindTag::PromiseKept,
+            BroodTended { .. } => EventKindTag::BroodTended,
+            BroodDied { .. } => EventKindTag::BroodDied,
+            BroodStageChanged { .. } => EventKindTag::BroodStageChanged,
+            ScenarioStarted { .. } => EventKindTag::ScenarioStarted,
+            ScenarioCompleted { .. } => EventKindTag::ScenarioCompleted,
+            ScenarioFailed { .. } => EventKindTag::ScenarioFailed,
+            HealthChanged { .. } => EventKindTag::HealthChanged,
+            QueenCrowned { .. } => EventKindTag::QueenCrowned,
+            QueenLost { .. } => EventKindTag::QueenLost,
+            PrincessLaid { .. } => EventKindTag::PrincessLaid,
+            StayExtended { .. } => EventKindTag::StayExtended,
+            HostingEnded { .. } => EventKindTag::HostingEnded,
+            EntityInjured { .. } => EventKindTag::EntityInjured,
+            EntityRecovered { .. } => EventKindTag::EntityRecovered,
+            Renamed { .. } => EventKindTag::Renamed,
+            MoraleChanged { .. } => EventKindTag::MoraleChanged,
+            Convergence { .. } => EventKindTag::Convergence,
+            SeasonChanged { .. } => EventKindTag::SeasonChanged,
+            WeatherEvent { .. } => EventKindTag::WeatherEvent,
+            DayBreak { .. } => EventKindTag::DayBreak,
+            NightFall { .. } => EventKindTag::NightFall,
+            ConfigurationSuspect { .. } => EventKindTag::ConfigurationSuspect,
+            HazardStruck { .. } => EventKindTag::HazardStruck,
+            HazardCleared { .. } => EventKindTag::HazardCleared,
+            ExcavationStarted { .. } => EventKindTag::ExcavationStarted,
+            TileExcavated { .. } => EventKindTag::TileExcavated,
+            ExcavationCancelled { .. } => EventKindTag::ExcavationCancelled,
+            SpawnHooksApplied { .. } => EventKindTag::SpawnHooksApplied,
+        }
+    }
+
+    /// How much a host is likely to care about this event
+    pub fn verbosity(&self) -> Verbosity {
+        self.tag().verbosity()
+    }
+}
+
+/// Which kind an event is, without its data: `EventKind::tag()`, and the
+/// unit `EventFilter`s work in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKindTag {
+    EntityDied,
+    EntityDormant,
+    EntityAwoke,
+    EntityAte,
+    MealsEaten,
+    ThresholdCrossed,
+    ActionComplete,
+    SystemProduced,
+    CorpseProcessed,
+    BlightStruck,
+    BlightCleared,
+    BlightKill,
+    AntsSpawned,
+    BroodHatched,
+    EmergencySpawn,
+    VisitorArrived,
+    VisitorDeparted,
+    InfluenceSpent,
+    StaticSurge,
+    SummoningFailed,
+    ReceiverSilent,
+    ReceiverRestored,
+    PassiveGeneration,
+    InfluenceTransformed,
+    BoredomHigh,
+    SanityChanged,
+    PhantomVisitorSignal,
+    PerceptionGlitch,
+    ExpeditionDeparted,
+    ExpeditionReturned,
+    OutpostEstablished,
+    SupplyDispatched,
+    OutpostSupplied,
+    OutpostUnsupplied,
+    OutpostLost,
+    GoodsHauled,
+    ColonyAbsorbed,
+    ColonyAbandoned,
+    EchoOfThePast,
+    RuinExcavated,
+    ReflectionDue,
+    SubsystemPaused,
+    PolicyChanged,
+    SubsystemResumed,
+    FeatureChanged,
+    GroupFormed,
+    GroupIntentChanged,
+    GroupArrived,
+    GroupDisbanded,
+    SystemBuilt,
+    RoleAssigned,
+    ActionQueued,
+    EntityMoved,
+    TimeAnomaly,
+    WonderOccurred,
+    RngBudgetExceeded,
+    TileAdded,
+    TilesConnected,
+    TilesDisconnected,
+    TileDemolished,
+    ResourceHarvested,
+    ResourceDepleted,
+    ExternalEditCorrected,
+    ReceiverSilenceDeepened,
+    DefenseResolved,
+    Whispered,
+    PromiseKept,
+    BroodTended,
+    BroodDied,
+    BroodStageChanged,
+    ScenarioStarted,
+    ScenarioCompleted,
+    ScenarioFailed,
+    HealthChanged,
+    QueenCrowned,
+    QueenLost,
+    PrincessLaid,
+    StayExtended,
+    HostingEnded,
+    EntityInjured,
+    EntityRecovered,
+    Renamed,
+    MoraleChanged,
+    Convergence,
+    SeasonChanged,
+    WeatherEvent,
+    DayBreak,
+    NightFall,
+    ConfigurationSuspect,
+    HazardStruck,
+    HazardCleared,
+    ExcavationStarted,
+    TileExcavated,
+    ExcavationCancelled,
+    SpawnHooksApplied,
+}
+
+impl EventKindTag {
+    /// How much a host is likely to care about this kind of event
+    pub fn verbosity(self) -> Verbosity {
+        use EventKindTag::*;
+        match self {
+            EntityAte
+            | MealsEaten
+            | SystemProduced
+            | PassiveGeneration
+            | InfluenceTransformed
+            | BoredomHigh
+            | GoodsHauled
+            | ResourceHarvested
+            | BroodTended => Verbosity::Debug,
+
+            EntityAwoke
+            | ActionComplete
+            | CorpseProcessed
+            | BlightCleared
+            | InfluenceSpent
+            | SummoningFailed
+            | SanityChanged
+            | PerceptionGlitch
+            | SupplyDispatched
+            | OutpostSupplied
+            | OutpostUnsupplied
+            | SubsystemPaused
+            | PolicyChanged
+            | SubsystemResumed
+            | FeatureChanged
+            | GroupFormed
+            | GroupIntentChanged
+            | GroupArrived
+            | GroupDisbanded
+            | RoleAssigned
+            | ActionQueued
+            | EntityMoved
+            | TileAdded
+            | TilesConnected
+            | TilesDisconnected
+            | TileDemolished
+            | BroodStageChanged
+            | PrincessLaid
+            | StayExtended
+            | HostingEnded
+            | EntityInjured
+            | EntityRecovered
+            | Renamed
+            | MoraleChanged
+            | WeatherEvent
+            | DayBreak
+            | NightFall
+            | HazardCleared
+            | ExcavationStarted
+            | SpawnHooksApplied => Verbosity::Info,
+
+            EntityDied
+            | EntityDormant
+            | ThresholdCrossed
+            | BlightStruck
+            | BlightKill
+            | AntsSpawned
+            | BroodHatched
+            | EmergencySpawn
+            | VisitorArrived
+            | VisitorDeparted
+            | StaticSurge
+            | ReceiverSilent
+            | ReceiverRestored
+            | PhantomVisitorSignal
+            | ExpeditionDeparted
+            | ExpeditionReturned
+            | OutpostEstablished
+            | OutpostLost
+            | ColonyAbsorbed
+            | ColonyAbandoned
+            | EchoOfThePast
+            | RuinExcavated
+            | ReflectionDue
+            | SystemBuilt
+            | TimeAnomaly
+            | WonderOccurred
+            | RngBudgetExceeded
+            | ResourceDepleted
+            | ExternalEditCorrected
+            | ReceiverSilenceDeepened
+            | DefenseResolved
+            | Whispered
+            | PromiseKept
+            | BroodDied
+            | ScenarioStarted
+            | ScenarioCompleted
+            | ScenarioFailed
+            | HealthChanged
+            | QueenCrowned
+            | QueenLost
+            | Convergence
+            | SeasonChanged
+            | ConfigurationSuspect
+            | HazardStruck
+            | TileExcavated
+            | ExcavationCancelled => Verbosity::Notable,
+        }
+    }
+}
+
+/// Which events a `TickEvents` keeps. Everything else is dropped on push
+/// (or by `TickEvents::retain`), though it still counts as raised.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventFilter {
+    /// Drop events below this level
+    pub min_verbosity: Verbosity,
+
+    /// Keep only these kinds (every kind when empty)
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub only: BTreeSet<EventKindTag>,
+
+    /// Never keep these kinds
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub except: BTreeSet<EventKindTag>,
+}
+
+impl EventFilter {
+    /// Keep everything
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Keep events at `min` or above
+    pub fn at_verbosity(min: Verbosity) -> Self {
+        Self { min_verbosity: min, ..Self::default() }
+    }
+
+    /// Keep only `kinds`
+    pub fn only(kinds: impl IntoIterator<Item = EventKindTag>) -> Self {
+        Self { only: kinds.into_iter().collect(), ..Self::default() }
+    }
+
+    /// Keep everything but `kinds`
+    pub fn except(kinds: impl IntoIterator<Item = EventKindTag>) -> Self {
+        Self { except: kinds.into_iter().collect(), ..Self::default() }
+    }
+
+    /// This filter, but also dropping anything below `min`
+    pub fn at_least(mut self, min: Verbosity) -> Self {
+        self.min_verbosity = self.min_verbosity.max(min);
+        self
+    }
+
+    /// Is an event of kind `tag` kept?
+    pub fn allows(&self, tag: EventKindTag) -> bool {
+        tag.verbosity() >= self.min_verbosity
+            && (self.only.is_empty() || self.only.contains(&tag))
+            && !self.except.contains(&tag)
+    }
+}
+
+/// What a frontend needs to know about one kind of event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct EventInfo {
+    /// The event's `type` tag
+    pub kind: &'static str,
+    pub verbosity: Verbosity,
+    pub summary: &'static str,
 }
 
+/// Every event kind, in declaration order. A new `EventKind` variant needs a
+/// row here too (`test_event_catalog_matches_event_kinds` checks).
+pub const EVENT_CATALOG: &[EventInfo] = &[
+    EventInfo { kind: "entity_died", verbosity: Verbosity::Notable, summary: "An entity died" },
+    EventInfo { kind: "entity_dormant", verbosity: Verbosity::Notable, summary: "An entity ran out of food and went dormant instead of starving" },
+    EventInfo { kind: "entity_awoke", verbosity: Verbosity::Info, summary: "A dormant entity was fed and woke up" },
+    EventInfo { kind: "entity_ate", verbosity: Verbosity::Debug, summary: "An entity ate food" },
+    EventInfo { kind: "meals_eaten", verbosity: Verbosity::Debug, summary: "An entity's meals over the last batch window" },
+    EventInfo { kind: "threshold_crossed", verbosity: Verbosity::Notable, summary: "A resource threshold was crossed" },
+    EventInfo { kind: "action_complete", verbosity: Verbosity::Info, summary: "An action in the queue completed" },
+    EventInfo { kind: "system_produced", verbosity: Verbosity::Debug, summary: "A system produced resources" },
+    EventInfo { kind: "corpse_processed", verbosity: Verbosity::Info, summary: "A corpse was processed by an undertaker" },
+    EventInfo { kind: "blight_struck", verbosity: Verbosity::Notable, summary: "Blight struck a tile" },
+    EventInfo { kind: "blight_cleared", verbosity: Verbosity::Info, summary: "Blight cleared from a tile" },
+    EventInfo { kind: "blight_kill", verbosity: Verbosity::Notable, summary: "Entity killed by blight" },
+    EventInfo { kind: "ants_spawned", verbosity: Verbosity::Notable, summary: "The queen laid a worker and an undertaker as brood" },
+    EventInfo { kind: "brood_hatched", verbosity: Verbosity::Notable, summary: "A larva finished developing and joined the colony" },
+    EventInfo { kind: "emergency_spawn", verbosity: Verbosity::Notable, summary: "Emergency spawn (colony was empty)" },
+    EventInfo { kind: "visitor_arrived", verbosity: Verbosity::Notable, summary: "A visitor arrived from outside" },
+    EventInfo { kind: "visitor_departed", verbosity: Verbosity::Notable, summary: "A visitor departed (died)" },
+    EventInfo { kind: "influence_spent", verbosity: Verbosity::Info, summary: "Influence was spent on summoning" },
+    EventInfo { kind: "static_surge", verbosity: Verbosity::Notable, summary: "Interference in the Outside rose past the surge level" },
+    EventInfo { kind: "summoning_failed", verbosity: Verbosity::Info, summary: "Summoning failed (no visitor arrived)" },
+    EventInfo { kind: "receiver_silent", verbosity: Verbosity::Notable, summary: "Receiver went silent (no maintenance)" },
+    EventInfo { kind: "receiver_restored", verbosity: Verbosity::Notable, summary: "Receiver restored to operation" },
+    EventInfo { kind: "passive_generation", verbosity: Verbosity::Debug, summary: "Resource was generated passively (by visitor)" },
+    EventInfo { kind: "influence_transformed", verbosity: Verbosity::Debug, summary: "A hungry visitor transformed influence" },
+    EventInfo { kind: "boredom_high", verbosity: Verbosity::Debug, summary: "Boredom threshold reached" },
+    EventInfo { kind: "sanity_changed", verbosity: Verbosity::Info, summary: "Sanity changed" },
+    EventInfo { kind: "phantom_visitor_signal", verbosity: Verbosity::Notable, summary: "The colony thinks it heard a visitor (low sanity only)" },
+    EventInfo { kind: "perception_glitch", verbosity: Verbosity::Info, summary: "The colony miscounted a resource (low sanity only)" },
+    EventInfo { kind: "expedition_departed", verbosity: Verbosity::Notable, summary: "A group of ants left for the Outside" },
+    EventInfo { kind: "expedition_returned", verbosity: Verbosity::Notable, summary: "An expedition came home" },
+    EventInfo { kind: "outpost_established", verbosity: Verbosity::Notable, summary: "An outpost was established on a remote tile" },
+    EventInfo { kind: "supply_dispatched", verbosity: Verbosity::Info, summary: "A supply run left for an outpost" },
+    EventInfo { kind: "outpost_supplied", verbosity: Verbosity::Info, summary: "Supplies reached an outpost" },
+    EventInfo { kind: "outpost_unsupplied", verbosity: Verbosity::Info, summary: "An outpost ran out of supplies and stopped producing" },
+    EventInfo { kind: "outpost_lost", verbosity: Verbosity::Notable, summary: "An outpost degraded past saving" },
+    EventInfo { kind: "goods_hauled", verbosity: Verbosity::Debug, summary: "A hauler moved goods between depots" },
+    EventInfo { kind: "colony_absorbed", verbosity: Verbosity::Notable, summary: "Another colony was merged into this one" },
+    EventInfo { kind: "colony_abandoned", verbosity: Verbosity::Notable, summary: "The colony was abandoned" },
+    EventInfo { kind: "echo_of_the_past", verbosity: Verbosity::Notable, summary: "A ghost of a past run stirred in its ruin" },
+    EventInfo { kind: "ruin_excavated", verbosity: Verbosity::Notable, summary: "A ruin was dug up and its loot added to the stores" },
+    EventInfo { kind: "reflection_due", verbosity: Verbosity::Notable, summary: "A reflection is due" },
+    EventInfo { kind: "subsystem_paused", verbosity: Verbosity::Info, summary: "A subsystem was paused" },
+    EventInfo { kind: "policy_changed", verbosity: Verbosity::Info, summary: "The colony's work policy changed" },
+    EventInfo { kind: "subsystem_resumed", verbosity: Verbosity::Info, summary: "A paused subsystem was resumed" },
+    EventInfo { kind: "feature_changed", verbosity: Verbosity::Info, summary: "The save opted into or out of a feature flag" },
+    EventInfo { kind: "group_formed", verbosity: Verbosity::Info, summary: "Ants were formed into a group" },
+    EventInfo { kind: "group_intent_changed", verbosity: Verbosity::Info, summary: "A group was given a new intent" },
+    EventInfo { kind: "group_arrived", verbosity: Verbosity::Info, summary: "Every member of a group reached the tile it was sent to" },
+    EventInfo { kind: "group_disbanded", verbosity: Verbosity::Info, summary: "A group was disbanded, or lost its last member" },
+    EventInfo { kind: "system_built", verbosity: Verbosity::Notable, summary: "A system was built by command" },
+    EventInfo { kind: "role_assigned", verbosity: Verbosity::Info, summary: "An ant was given a new role by command" },
+    EventInfo { kind: "action_queued", verbosity: Verbosity::Info, summary: "An action was queued by command" },
+    EventInfo { kind: "entity_moved", verbosity: Verbosity::Info, summary: "An entity was moved by command" },
+    EventInfo { kind: "time_anomaly", verbosity: Verbosity::Notable, summary: "The host's clock looked wrong when catching up on offline time" },
+    EventInfo { kind: "wonder_occurred", verbosity: Verbosity::Notable, summary: "A once-in-a-lifetime wonder happened" },
+    EventInfo { kind: "rng_budget_exceeded", verbosity: Verbosity::Notable, summary: "A phase's RNG draws drifted from its recorded budget" },
+    EventInfo { kind: "tile_added", verbosity: Verbosity::Info, summary: "A tile was added to the map" },
+    EventInfo { kind: "tiles_connected", verbosity: Verbosity::Info, summary: "Two tiles were connected" },
+    EventInfo { kind: "tiles_disconnected", verbosity: Verbosity::Info, summary: "Two tiles were disconnected" },
+    EventInfo { kind: "tile_demolished", verbosity: Verbosity::Info, summary: "A tile was demolished" },
+    EventInfo { kind: "resource_harvested", verbosity: Verbosity::Debug, summary: "A forager harvested a resource tile" },
+    EventInfo { kind: "resource_depleted", verbosity: Verbosity::Notable, summary: "A resource tile's stock ran out" },
+    EventInfo { kind: "external_edit_corrected", verbosity: Verbosity::Notable, summary: "Soft-fail mode fixed an invalid edit made between ticks" },
+    EventInfo { kind: "receiver_silence_deepened", verbosity: Verbosity::Notable, summary: "A silent receiver slid to a worse stage" },
+    EventInfo { kind: "defense_resolved", verbosity: Verbosity::Notable, summary: "An incursion met the colony's defenders" },
+    EventInfo { kind: "whispered", verbosity: Verbosity::Notable, summary: "Influence was spent on a whisper" },
+    EventInfo { kind: "promise_kept", verbosity: Verbosity::Notable, summary: "A gift a whisper promised arrived" },
+    EventInfo { kind: "brood_tended", verbosity: Verbosity::Debug, summary: "A nurse fed larvae this tick" },
+    EventInfo { kind: "brood_died", verbosity: Verbosity::Notable, summary: "A developing ant was lost before it hatched" },
+    EventInfo { kind: "brood_stage_changed", verbosity: Verbosity::Info, summary: "A developing ant moved on to its next stage" },
+    EventInfo { kind: "scenario_started", verbosity: Verbosity::Notable, summary: "A scenario began" },
+    EventInfo { kind: "scenario_completed", verbosity: Verbosity::Notable, summary: "A scenario's win condition was met; the run is over" },
+    EventInfo { kind: "scenario_failed", verbosity: Verbosity::Notable, summary: "A scenario's lose condition was met; the run is over" },
+    EventInfo { kind: "health_changed", verbosity: Verbosity::Notable, summary: "The colony's health score changed" },
+    EventInfo { kind: "queen_crowned", verbosity: Verbosity::Notable, summary: "A queen was crowned" },
+    EventInfo { kind: "queen_lost", verbosity: Verbosity::Notable, summary: "The queen died" },
+    EventInfo { kind: "princess_laid", verbosity: Verbosity::Info, summary: "A princess was laid" },
+    EventInfo { kind: "stay_extended", verbosity: Verbosity::Info, summary: "A visitor's stay was extended" },
+    EventInfo { kind: "hosting_ended", verbosity: Verbosity::Info, summary: "Hosting a visitor ended" },
+    EventInfo { kind: "entity_injured", verbosity: Verbosity::Info, summary: "An ant was hurt" },
+    EventInfo { kind: "entity_recovered", verbosity: Verbosity::Info, summary: "An ant recovered" },
+    EventInfo { kind: "renamed", verbosity: Verbosity::Info, summary: "Something was renamed" },
+    EventInfo { kind: "morale_changed", verbosity: Verbosity::Info, summary: "An ant's mood changed" },
+    EventInfo { kind: "convergence", verbosity: Verbosity::Notable, summary: "Several visitors arrived at once" },
+    EventInfo { kind: "season_changed", verbosity: Verbosity::Notable, summary: "The colony's year turned to a new season" },
+    EventInfo { kind: "weather_event", verbosity: Verbosity::Info, summary: "The sky changed: rain, drought, or clearing" },
+    EventInfo { kind: "day_break", verbosity: Verbosity::Info, summary: "Dawn over the colony" },
+    EventInfo { kind: "night_fall", verbosity: Verbosity::Info, summary: "Dusk over the colony" },
+    EventInfo { kind: "configuration_suspect", verbosity: Verbosity::Notable, summary: "A system with impossible rates was set aside" },
+    EventInfo { kind: "hazard_struck", verbosity: Verbosity::Notable, summary: "A flood or cave-in struck a tile" },
+    EventInfo { kind: "hazard_cleared", verbosity: Verbosity::Info, summary: "A flood or cave-in passed" },
+    EventInfo { kind: "excavation_started", verbosity: Verbosity::Info, summary: "A new tile started being dug" },
+    EventInfo { kind: "tile_excavated", verbosity: Verbosity::Notable, summary: "A dug tile opened up, maybe onto ore or crystals" },
+    EventInfo { kind: "excavation_cancelled", verbosity: Verbosity::Notable, summary: "A dig was given up, its tile cut off from the colony" },
+    EventInfo { kind: "spawn_hooks_applied", verbosity: Verbosity::Info, summary: "A newcomer spawned with its content-defined effects" },
+];
+
 /// Collection of events from a single tick
 #[derive(Debug, Clone, Default)]
 pub struct TickEvents {
     events: Vec<Event>,
+
+    /// Events it rejects are dropped on push
+    filter: EventFilter,
+
+    /// Every event pushed, by kind, whether it was kept or not
+    raised: BTreeMap<EventKindTag, u64>,
+
+    /// Standing warnings after the tick (never filtered by verbosity)
+    warnings: Vec<Warning>,
 }
 
 impl TickEvents {
     pub fn new() -> Self {
-        Self { events: Vec::new() }
+        Self::default()
+    }
+
+    /// A collection that keeps only events at `min` or above
+    pub fn at_verbosity(min: Verbosity) -> Self {
+        Self::with_filter(EventFilter::at_verbosity(min))
+    }
+
+    /// A collection that keeps only what `filter` allows
+    pub fn with_filter(filter: EventFilter) -> Self {
+        Self { filter, ..Self::default() }
+    }
+
+    /// Would an event at `level` be kept? Lets hot paths skip building
+    /// events nobody asked for.
+    pub fn wants(&self, level: Verbosity) -> bool {
+        level >= self.filter.min_verbosity
+    }
+
+    /// Would an event of kind `tag` be kept?
+    pub fn wants_kind(&self, tag: EventKindTag) -> bool {
+        self.filter.allows(tag)
     }
 
     /// Add an event
     pub fn push(&mut self, tick: u64, kind: EventKind) {
-        self.events.push(Event::new(tick, kind));
+        *self.raised.entry(kind.tag()).or_default() += 1;
+        if self.wants_kind(kind.tag()) {
+            self.events.push(Event::new(tick, kind));
+        }
+    }
+
+    /// Events of kind `tag` raised, including any the filter dropped
+    pub fn raised(&self, tag: EventKindTag) -> u64 {
+        self.raised.get(&tag).copied().unwrap_or(0)
+    }
+
+    /// Was any event at `min` or above raised, kept or not?
+    pub fn raised_any(&self, min: Verbosity) -> bool {
+        self.raised.keys().any(|tag| tag.verbosity() >= min)
+    }
+
+    /// Drop the events `filter` rejects (they still count as raised)
+    pub fn retain(&mut self, filter: &EventFilter) {
+        self.events.retain(|event| filter.allows(event.kind.tag()));
+    }
+
+    /// Events of any of `kinds`, in order
+    pub fn filter_kinds<'a>(&'a self, kinds: &'a [EventKindTag]) -> impl Iterator<Item = &'a Event> + 'a {
+        self.events.iter().filter(move |event| kinds.contains(&event.kind.tag()))
+    }
+
+    /// Append another batch's events. Warnings describe the latest state,
+    /// so `other`'s replace these.
+    pub fn extend(&mut self, other: TickEvents) {
+        self.events.extend(other.events);
+        for (tag, count) in other.raised {
+            *self.raised.entry(tag).or_default() += count;
+        }
+        self.warnings = other.warnings;
+    }
+
+    /// Warnings standing at the end of the tick
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Take the standing warnings, leaving none
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    /// Replace the standing warnings
+    pub fn set_warnings(&mut self, warnings: Vec<Warning>) {
+        self.warnings = warnings;
     }
 
     /// Get all events
@@ -195,3 +1306,122 @@ impl TickEvents {
         self.events.len()
     }
 }
+
+/// The events of one tick in a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TickBatch {
+    pub tick: u64,
+    pub events: Vec<Event>,
+}
+
+/// What happened over a whole batch, for hosts that only want the gist
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchSummary {
+    /// Ticks run
+    pub ticks: u64,
+
+    /// Entities that died, whatever the engine's filter kept
+    pub deaths: u64,
+
+    /// Events raised, by kind, whether the filter kept them or not
+    pub event_counts: BTreeMap<EventKindTag, u64>,
+
+    /// Net change in each colony resource that moved
+    pub resource_deltas: BTreeMap<Key, f64>,
+}
+
+/// Events from `TickEngine::tick_n`: grouped by tick, plus a summary
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchEvents {
+    /// Ticks that had events, in order (quiet ticks are left out)
+    pub ticks: Vec<TickBatch>,
+
+    pub summary: BatchSummary,
+
+    /// Warnings standing after the last tick
+    pub warnings: Vec<Warning>,
+}
+
+impl BatchEvents {
+    /// Add the events of tick `tick`
+    pub fn push(&mut self, tick: u64, mut events: TickEvents) {
+        self.summary.ticks += 1;
+        self.warnings = events.take_warnings();
+        self.push_between(tick, events);
+    }
+
+    /// Add events raised between ticks, at `tick` (a scenario started, a
+    /// command applied), without counting a tick run
+    pub fn push_between(&mut self, tick: u64, events: TickEvents) {
+        self.summary.deaths += events.raised(EventKindTag::EntityDied);
+        for (tag, count) in events.raised {
+            *self.summary.event_counts.entry(tag).or_default() += count;
+        }
+        if !events.events.is_empty() {
+            self.ticks.push(TickBatch { tick, events: events.events });
+        }
+    }
+
+    /// Record the net resource change from `before` to `after`
+    pub fn set_resource_deltas(&mut self, before: &HashMap<Key, f64>, after: &HashMap<Key, f64>) {
+        self.summary.resource_deltas = resource_deltas(before, after);
+    }
+
+    /// Every event in the batch, in order
+    pub fn events(&self) -> impl Iterator<Item = &Event> {
+        self.ticks.iter().flat_map(|batch| &batch.events)
+    }
+}
+
+/// How the colony's counters moved over one tick
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TickCounters {
+    /// Net change in each colony resource that moved
+    pub resource_deltas: BTreeMap<Key, f64>,
+
+    /// Entities gained (or lost, if negative)
+    pub population_delta: i64,
+
+    /// Entities that died, whatever the engine's filter kept
+    pub deaths: u64,
+}
+
+/// A snapshot of colony-wide numbers after a tick, for dashboards
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TickMetrics {
+    pub population: usize,
+    pub ants: usize,
+    pub visitors: usize,
+    /// Corpses waiting in the graveyard
+    pub corpses: usize,
+    /// `GameState::hash` after the tick
+    pub state_hash: u64,
+}
+
+/// Everything a host needs from one tick, as one serializable unit
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TickReport {
+    pub tick: u64,
+    pub events: Vec<Event>,
+
+    /// Warnings standing after the tick
+    pub warnings: Vec<Warning>,
+
+    pub counters: TickCounters,
+
+    /// Only filled in when asked for (hashing the state isn't free)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<TickMetrics>,
+}
+
+/// Net change in each resource from `before` to `after`, leaving out the
+/// ones that didn't move
+pub fn resource_deltas(before: &HashMap<Key, f64>, after: &HashMap<Key, f64>) -> BTreeMap<Key, f64> {
+    let names: BTreeSet<&Key> = before.keys().chain(after.keys()).collect();
+    names.into_iter()
+        .filter_map(|name| {
+            let delta = after.get(name).copied().unwrap_or(0.0) - before.get(name).copied().unwrap_or(0.0);
+            (delta != 0.0).then_some((*name, delta))
+        })
+        .collect()
+}
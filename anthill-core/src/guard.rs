@@ -0,0 +1,190 @@
+//! Soft-fail mode: repairs for invalid edits made between ticks.
+//!
+//! Plugins and hosts edit the state directly between ticks, and a buggy
+//! edit (a NaN stock, an ant with negative hunger, an ant on a tile that
+//! was never built) would otherwise ride along in the save forever. An
+//! engine in soft-fail mode (`TickEngine::set_soft_fail`) looks the state
+//! over before each tick's phases, fixes what it finds the same way every
+//! time, and reports each fix as `ExternalEditCorrected`. Systems it can't
+//! fix are quarantined: disabled, with their rates kept for a plugin to
+//! repair and re-enable.
+//!
+//! A sound save never needs a fix, so it plays out the same with the guard
+//! on or off.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::det;
+use crate::types::entity::{EntityId, EntityType};
+use crate::types::key::Key;
+use crate::types::state::GameState;
+use crate::types::tile::ORIGIN;
+
+/// One invalid edit and how it was fixed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Correction {
+    /// Hunger outside `0..=max_hunger` (or not a number), clamped into it
+    Hunger { entity_id: EntityId, was: f64, now: f64 },
+
+    /// An ant on a tile that isn't on the map, moved to `now`
+    UnknownTile { entity_id: EntityId, was: String, now: Key },
+
+    /// A negative or non-finite stock, reset to zero. `stockpile` is None
+    /// for the colony's own stores.
+    Resource {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stockpile: Option<String>,
+        resource: Key,
+        was: f64,
+    },
+
+    /// A system producing or consuming at a non-finite rate, quarantined
+    NonFiniteRate { system_id: String, resource: String },
+
+    /// A system consuming something that exists nowhere (no stock, no
+    /// producer, no resource tile), quarantined
+    UnknownConsumption { system_id: String, resource: String },
+}
+
+/// Fix every invalid edit in `state`, in a fixed order, and say what was
+/// done
+pub fn repair(state: &mut GameState) -> Vec<Correction> {
+    let mut corrections = Vec::new();
+    repair_entities(state, &mut corrections);
+    repair_resources(state, &mut corrections);
+    repair_systems(state, &mut corrections);
+    corrections
+}
+
+/// Tiles that are never on the map but that entities stand on anyway
+const OFF_MAP: [&str; 1] = ["receiver"];
+
+fn repair_entities(state: &mut GameState, corrections: &mut Vec<Correction>) {
+    let max_hunger = state.config.tuning.max_hunger;
+    // Strays go home, or to the first tile there is
+    let home = if state.map.get_tile(ORIGIN).is_some() {
+        Some(Key::new(ORIGIN))
+    } else {
+        det::sorted(state.map.tiles.keys().copied()).first().copied()
+    };
+
+    for entity in state.entities.iter_mut() {
+        let hunger = entity.hunger;
+        if !(0.0..=max_hunger).contains(&hunger) {
+            entity.hunger = if hunger.is_nan() { 0.0 } else { hunger.clamp(0.0, max_hunger) };
+            corrections.push(Correction::Hunger { entity_id: entity.id.clone(), was: hunger, now: entity.hunger });
+        }
+        // Visitors come and go by way of the receiver, not the map
+        let stray = entity.entity_type != EntityType::Visitor
+            && !OFF_MAP.contains(&entity.tile.as_str())
+            && state.map.get_tile(&entity.tile).is_none();
+        if stray {
+            if let Some(home) = home {
+                let was = std::mem::replace(&mut entity.tile, home.to_string());
+                corrections.push(Correction::UnknownTile { entity_id: entity.id.clone(), was, now: home });
+            }
+        }
+    }
+}
+
+fn repair_resources(state: &mut GameState, corrections: &mut Vec<Correction>) {
+    let pools = std::iter::once((None, &mut state.resources))
+        .chain(state.stockpiles.iter_mut().map(|(tile, pool)| (Some(tile), pool)));
+    for (stockpile, pool) in pools {
+        let invalid: Vec<(Key, f64)> = det::entries(pool.amounts.iter())
+            .into_iter()
+            .filter(|(_, amount)| !amount.is_finite() || **amount < 0.0)
+            .map(|(resource, amount)| (*resource, *amount))
+            .collect();
+        for (resource, was) in invalid {
+            pool.set(&resource, 0.0);
+            corrections.push(Correction::Resource { stockpile: stockpile.cloned(), resource, was });
+        }
+    }
+}
+
+/// Everything the colony has, makes, or can gather
+pub(crate) fn known_resources(state: &GameState) -> BTreeSet<&str> {
+    let mut known = BTreeSet::new();
+    known.extend(state.resources.amounts.keys().map(|key| key.as_ref()));
+    known.extend(state.stockpiles.values().flat_map(|pool| pool.amounts.keys().map(|key| key.as_ref())));
+    known.extend(state.systems.values().flat_map(|system| system.generates.iter().flatten().map(|(r, _)| r.as_str())));
+    known.extend(state.map.tiles.values().filter_map(|tile| tile.resource.as_deref()));
+    known
+}
+
+fn repair_systems(state: &mut GameState, corrections: &mut Vec<Correction>) {
+    let known = known_resources(state);
+    let mut quarantined = Vec::new();
+    for (id, system) in det::entries(state.systems.iter()) {
+        let rates = system.generates.iter().flatten().chain(system.consumes.iter().flatten());
+        let correction = if let Some((resource, _)) = det::entries(rates).into_iter().find(|(_, rate)| !rate.is_finite()) {
+            Correction::NonFiniteRate { system_id: id.clone(), resource: resource.clone() }
+        } else if let Some(resource) = det::sorted(system.consumes.iter().flatten().map(|(r, _)| r))
+            .into_iter()
+            .find(|r| !known.contains(r.as_str()))
+        {
+            Correction::UnknownConsumption { system_id: id.clone(), resource: resource.clone() }
+        } else {
+            continue;
+        };
+        quarantined.push(id.clone());
+        corrections.push(correction);
+    }
+    for id in quarantined {
+        if let Some(system) = state.systems.get_mut(&id) {
+            system.disable();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::entity::Entity;
+    use crate::types::system::System;
+    use crate::types::tile::Tile;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_repairs_are_deterministic_and_reported() {
+        let mut state = GameState::default();
+        state.map.tiles.insert(Key::new(ORIGIN), Tile::origin());
+        state.resources.set("fungus", f64::INFINITY);
+        let mut starved = Entity::new_worker("w1".to_string(), ORIGIN.to_string());
+        starved.hunger = -3.0;
+        state.entities.push(starved);
+        state.entities.push(Entity::new_worker("w2".to_string(), "nowhere".to_string()));
+        state.systems.insert("mill".to_string(), System::new_converter(
+            "Mill".to_string(),
+            HashMap::from([("moonlight".to_string(), 1.0)]),
+            HashMap::from([("flour".to_string(), 1.0)]),
+        ));
+
+        let corrections = repair(&mut state);
+        assert_eq!(corrections, [
+            Correction::Hunger { entity_id: "w1".to_string(), was: -3.0, now: 0.0 },
+            Correction::UnknownTile { entity_id: "w2".to_string(), was: "nowhere".to_string(), now: Key::new(ORIGIN) },
+            Correction::Resource { stockpile: None, resource: Key::new("fungus"), was: f64::INFINITY },
+            Correction::UnknownConsumption { system_id: "mill".to_string(), resource: "moonlight".to_string() },
+        ]);
+        assert_eq!(state.resources.get("fungus"), 0.0);
+        assert!(state.systems["mill"].consumes.is_none());
+
+        // Once fixed, there's nothing left to report
+        assert!(repair(&mut state).is_empty());
+    }
+
+    #[test]
+    fn test_visitors_stay_at_the_receiver() {
+        let mut state = GameState::default();
+        state.map.tiles.insert(Key::new(ORIGIN), Tile::origin());
+        state.entities.push(Entity::new_wanderer("v1".to_string()));
+        assert_eq!(state.entities[0].tile, "receiver");
+
+        assert!(repair(&mut state).is_empty());
+        assert_eq!(state.entities[0].tile, "receiver");
+    }
+}
@@ -0,0 +1,112 @@
+//! The core as a state machine: state + ordered inputs -> state + outputs.
+//!
+//! Everything a host feeds the core between two ticks (commands, a clock
+//! reading) goes into one `TickInput`, and `TickEngine::step` turns it into a
+//! `TickOutput`. Nothing else reaches the simulation, so an `InputLog` of
+//! the inputs plus the starting state reproduces a session exactly, on any
+//! host. Replays, conformance runs and second frontends all start here.
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::TickEngine;
+use crate::events::Event;
+use crate::types::command::Command;
+use crate::types::config::SimConfig;
+use crate::types::state::GameState;
+use crate::types::warning::Warning;
+
+/// What the host hands the core for one step
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TickInput {
+    /// Host clock reading (seconds). Time since the last one is caught up
+    /// through `TickEngine::catch_up` before anything else, and the state
+    /// is re-anchored at it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<f64>,
+
+    /// Applied in order after the catch-up, before the tick
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<Command>,
+}
+
+impl TickInput {
+    /// Nothing but the tick itself
+    pub fn idle() -> Self {
+        Self::default()
+    }
+
+    pub fn at(timestamp: f64) -> Self {
+        Self { timestamp: Some(timestamp), ..Self::default() }
+    }
+
+    pub fn with_command(mut self, command: Command) -> Self {
+        self.commands.push(command);
+        self
+    }
+}
+
+/// A command the core refused, by its place in `TickInput::commands`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rejected {
+    pub index: usize,
+    pub error: String,
+}
+
+/// What one step produced
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TickOutput {
+    /// Tick the state is at after the step
+    pub tick: u64,
+
+    /// Catch-up, command and tick events, in that order
+    pub events: Vec<Event>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rejected: Vec<Rejected>,
+
+    /// Warnings standing after the tick
+    #[serde(default)]
+    pub warnings: Vec<Warning>,
+}
+
+/// Every input of a session, enough to replay it from its starting state
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InputLog {
+    pub seed: u64,
+
+    /// Config the engine imposed, if it was built with one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<SimConfig>,
+
+    pub inputs: Vec<TickInput>,
+}
+
+impl InputLog {
+    /// An empty log for a session run by `engine`
+    pub fn for_engine(engine: &TickEngine) -> Self {
+        Self {
+            seed: engine.seed(),
+            config: engine.config().cloned(),
+            inputs: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, input: TickInput) {
+        self.inputs.push(input);
+    }
+
+    /// A fresh engine like the one that recorded the log
+    pub fn engine(&self) -> TickEngine {
+        match &self.config {
+            Some(config) => TickEngine::new_with_config(self.seed, config.clone()),
+            None => TickEngine::new(self.seed),
+        }
+    }
+
+    /// Step `state` (the session's starting state) through every input
+    pub fn replay(&self, state: &mut GameState) -> Vec<TickOutput> {
+        let mut engine = self.engine();
+        self.inputs.iter().map(|input| engine.step(state, input)).collect()
+    }
+}
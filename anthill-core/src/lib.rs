@@ -25,15 +25,33 @@ pub mod types;
 pub mod events;
 pub mod engine;
 pub mod rng;
+pub mod timeline;
+pub mod digest;
+pub mod query;
+pub mod trace;
+pub mod contract;
+pub mod registry;
+pub mod input;
+pub mod budget;
+pub mod det;
+pub mod guard;
+pub mod advance;
+pub mod run;
+#[cfg(feature = "soak")]
+pub mod soak;
 
 // Re-export main types for convenience
 pub use types::state::GameState;
 pub use types::entity::{Entity, EntityType, AntRole, VisitorType};
 pub use types::resource::Resources;
+pub use types::key::Key;
 pub use types::tile::{Tile, TileType};
 pub use types::system::{System, SystemType};
-pub use events::{Event, EventKind};
+pub use events::{BatchEvents, Event, EventFilter, EventKind, EventKindTag, TickReport, Verbosity};
 pub use engine::TickEngine;
+pub use input::{InputLog, TickInput, TickOutput};
 pub use rng::SeededRng;
+pub use budget::RngBudget;
+pub use run::{run_simulation, RunDescriptor, RunOutput};
 
 pub mod bindings;
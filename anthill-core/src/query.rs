@@ -0,0 +1,328 @@
+//! Read-only queries over a `GameState`.
+//!
+//! `state.query()` hands out composable filters so hosts and engine phases
+//! ask the same questions the same way instead of re-writing iterator
+//! chains. Results come back in a stable order: entities in state order,
+//! systems sorted by ID.
+//!
+//! Entity filters on type, role and tile narrow through an index of the
+//! state's entities, built the first time one of them is asked for and
+//! shared by every query handed out by the same `Query`; other filters then
+//! test only the entities left. Hold on to one `Query` to ask a state
+//! several questions. Systems are few, so their filters scan them all.
+
+use std::cell::OnceCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use crate::types::entity::{AntRole, Entity, EntityType, VisitorType};
+use crate::types::state::GameState;
+use crate::types::system::{System, SystemType};
+
+type Filter<'a, T> = Box<dyn Fn(&T) -> bool + 'a>;
+
+/// Positions in `GameState::entities`, in state order, by what the indexed
+/// filters look at
+#[derive(Default)]
+struct EntityIndex<'a> {
+    by_type: HashMap<EntityType, Vec<usize>>,
+    by_role: HashMap<AntRole, Vec<usize>>,
+    by_tile: HashMap<&'a str, Vec<usize>>,
+}
+
+impl<'a> EntityIndex<'a> {
+    fn build(entities: &'a [Entity]) -> Self {
+        let mut index = Self::default();
+        for (i, e) in entities.iter().enumerate() {
+            index.by_type.entry(e.entity_type.clone()).or_default().push(i);
+            if let Some(role) = &e.role {
+                index.by_role.entry(role.clone()).or_default().push(i);
+            }
+            index.by_tile.entry(e.tile.as_str()).or_default().push(i);
+        }
+        index
+    }
+}
+
+/// Entry point for queries over one state
+#[derive(Clone)]
+pub struct Query<'a> {
+    state: &'a GameState,
+    index: Rc<OnceCell<EntityIndex<'a>>>,
+}
+
+impl<'a> Query<'a> {
+    pub fn new(state: &'a GameState) -> Self {
+        Self { state, index: Rc::default() }
+    }
+
+    /// All living entities, to be narrowed down
+    pub fn entities(&self) -> EntityQuery<'a> {
+        EntityQuery { state: self.state, index: Rc::clone(&self.index), candidates: None, filters: Vec::new() }
+    }
+
+    /// All systems, to be narrowed down
+    pub fn systems(&self) -> SystemQuery<'a> {
+        SystemQuery { state: self.state, filters: Vec::new() }
+    }
+
+    /// IDs of tiles currently blighted
+    pub fn blighted_tiles(&self) -> HashSet<&'a str> {
+        self.state.map.tiles.iter()
+            .filter(|(_, tile)| tile.is_blighted())
+            .map(|(id, _)| id.as_str())
+            .collect()
+    }
+}
+
+/// A filtered view of the colony's entities
+pub struct EntityQuery<'a> {
+    state: &'a GameState,
+    index: Rc<OnceCell<EntityIndex<'a>>>,
+    /// Positions the indexed filters so far allow, in state order (None:
+    /// every entity)
+    candidates: Option<Vec<usize>>,
+    filters: Vec<Filter<'a, Entity>>,
+}
+
+impl<'a> EntityQuery<'a> {
+    /// Keep entities matching `f`
+    pub fn filter(mut self, f: impl Fn(&Entity) -> bool + 'a) -> Self {
+        self.filters.push(Box::new(f));
+        self
+    }
+
+    /// Keep only the candidates at the positions `pick` reads off the
+    /// index (in state order)
+    fn narrow(mut self, pick: impl FnOnce(&EntityIndex<'a>) -> Vec<usize>) -> Self {
+        let state: &'a GameState = self.state;
+        let positions = pick(self.index.get_or_init(|| EntityIndex::build(&state.entities)));
+        self.candidates = Some(match self.candidates.take() {
+            None => positions,
+            Some(kept) => kept.into_iter().filter(|i| positions.binary_search(i).is_ok()).collect(),
+        });
+        self
+    }
+
+    fn of_type(self, entity_type: EntityType) -> Self {
+        self.narrow(|index| index.by_type.get(&entity_type).cloned().unwrap_or_default())
+    }
+
+    pub fn ants(self) -> Self {
+        self.of_type(EntityType::Ant)
+    }
+
+    pub fn visitors(self) -> Self {
+        self.of_type(EntityType::Visitor)
+    }
+
+    pub fn role(self, role: AntRole) -> Self {
+        self.narrow(|index| index.by_role.get(&role).cloned().unwrap_or_default())
+    }
+
+    pub fn visitor_type(self, visitor_type: VisitorType) -> Self {
+        self.filter(move |e| e.subtype.as_ref() == Some(&visitor_type))
+    }
+
+    pub fn on_tile(self, tile: &'a str) -> Self {
+        self.narrow(|index| index.by_tile.get(tile).cloned().unwrap_or_default())
+    }
+
+    /// Entities standing on a blighted tile
+    pub fn on_blighted_tiles(self) -> Self {
+        let blighted = Query::new(self.state).blighted_tiles();
+        self.narrow(|index| {
+            let mut positions: Vec<usize> = blighted.iter()
+                .filter_map(|tile| index.by_tile.get(tile))
+                .flatten()
+                .copied()
+                .collect();
+            positions.sort_unstable();
+            positions
+        })
+    }
+
+    /// Visitors that reach their max age (and leave) within `ticks`
+    pub fn departing_within(self, ticks: u64) -> Self {
+        self.visitors().filter(move |e| e.max_age.saturating_sub(e.age) <= ticks)
+    }
+
+    /// Entities with hunger below `hunger`
+    pub fn hungrier_than(self, hunger: f64) -> Self {
+        self.filter(move |e| e.hunger < hunger)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a Entity> + '_ {
+        let entities: &'a [Entity] = &self.state.entities;
+        let candidates: Box<dyn Iterator<Item = &'a Entity> + '_> = match &self.candidates {
+            Some(positions) => Box::new(positions.iter().map(move |&i| &entities[i])),
+            None => Box::new(entities.iter()),
+        };
+        candidates.filter(move |e| self.filters.iter().all(|f| f(e)))
+    }
+
+    pub fn collect(&self) -> Vec<&'a Entity> {
+        self.iter().collect()
+    }
+
+    pub fn ids(&self) -> Vec<&'a str> {
+        self.iter().map(|e| e.id.as_str()).collect()
+    }
+
+    pub fn count(&self) -> usize {
+        self.iter().count()
+    }
+}
+
+/// A filtered view of the colony's systems
+pub struct SystemQuery<'a> {
+    state: &'a GameState,
+    filters: Vec<Filter<'a, (&'a String, &'a System)>>,
+}
+
+impl<'a> SystemQuery<'a> {
+    /// Keep systems matching `f` (given ID and system)
+    pub fn filter(mut self, f: impl Fn(&(&'a String, &'a System)) -> bool + 'a) -> Self {
+        self.filters.push(Box::new(f));
+        self
+    }
+
+    pub fn of_type(self, system_type: SystemType) -> Self {
+        self.filter(move |(_, s)| s.system_type == system_type)
+    }
+
+    pub fn disabled(self) -> Self {
+        self.filter(|(_, s)| s.is_disabled())
+    }
+
+    pub fn on_tile(self, tile: &'a str) -> Self {
+        self.filter(move |(_, s)| s.tile.as_deref() == Some(tile))
+    }
+
+    /// Systems that have been unable to run for more than `ticks`
+    pub fn starved_for_more_than(self, ticks: u64) -> Self {
+        let now = self.state.tick;
+        self.filter(move |(_, s)| {
+            s.starved_since.is_some_and(|since| now.saturating_sub(since) > ticks)
+        })
+    }
+
+    /// Producing systems that turned anything out on fewer than `share` of
+    /// the ticks in their history: starved on and off, which
+    /// `starved_for_more_than` misses
+    pub fn producing_less_than(self, share: f64) -> Self {
+        self.filter(move |(_, s)| {
+            s.generates.as_ref().is_some_and(|g| !g.is_empty()) && s.uptime().is_some_and(|uptime| uptime < share)
+        })
+    }
+
+    /// Matching systems, sorted by ID
+    pub fn collect(&self) -> Vec<(&'a String, &'a System)> {
+        let state: &'a GameState = self.state;
+        let mut matches: Vec<_> = state.systems.iter()
+            .filter(|entry| self.filters.iter().all(|f| f(entry)))
+            .collect();
+        matches.sort_by_key(|(id, _)| *id);
+        matches
+    }
+
+    pub fn ids(&self) -> Vec<&'a str> {
+        self.collect().into_iter().map(|(id, _)| id.as_str()).collect()
+    }
+
+    pub fn count(&self) -> usize {
+        self.collect().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tile::Tile;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_entity_filters_compose() {
+        let mut state = GameState::default();
+        let mut compost = Tile::new_compost("Compost".to_string(), 1, 0);
+        compost.start_blight(10);
+        state.map.tiles.insert("compost".into(), compost);
+
+        state.entities.push(Entity::new_worker("w1".to_string(), "compost".to_string()));
+        state.entities.push(Entity::new_undertaker("u1".to_string(), "compost".to_string()));
+        state.entities.push(Entity::new_worker("w2".to_string(), "origin".to_string()));
+        let mut visitor = Entity::new_wanderer("v1".to_string());
+        visitor.age = visitor.max_age - 5;
+        state.entities.push(visitor);
+
+        let query = state.query();
+        assert_eq!(query.entities().on_blighted_tiles().ids(), vec!["w1", "u1"]);
+        assert_eq!(query.entities().on_blighted_tiles().role(AntRole::Worker).ids(), vec!["w1"]);
+        assert_eq!(query.entities().departing_within(5).ids(), vec!["v1"]);
+        assert_eq!(query.entities().departing_within(4).count(), 0);
+        assert_eq!(query.entities().on_tile("origin").ants().ids(), vec!["w2"]);
+        assert_eq!(query.entities().role(AntRole::Soldier).count(), 0);
+    }
+
+    #[test]
+    fn test_indexed_filters_match_a_scan() {
+        let mut state = GameState::default();
+        for n in 0..40 {
+            let tile = ["origin", "compost", "lane"][n % 3];
+            let mut ant = match n % 4 {
+                0 => Entity::new_undertaker(format!("u{n}"), tile.to_string()),
+                1 => Entity::new_hauler(format!("h{n}"), tile.to_string()),
+                _ => Entity::new_worker(format!("w{n}"), tile.to_string()),
+            };
+            ant.hunger = n as f64 * 5.0;
+            state.entities.push(ant);
+        }
+        state.entities.push(Entity::new_wanderer("v".to_string()));
+
+        let query = state.query();
+        let scanned = |f: &dyn Fn(&Entity) -> bool| -> Vec<&str> {
+            state.entities.iter().filter(|e| f(e)).map(|e| e.id.as_str()).collect()
+        };
+        assert_eq!(
+            query.entities().role(AntRole::Worker).on_tile("lane").ids(),
+            scanned(&|e| e.role == Some(AntRole::Worker) && e.tile == "lane"),
+        );
+        assert_eq!(
+            query.entities().on_tile("compost").ants().hungrier_than(100.0).ids(),
+            scanned(&|e| e.tile == "compost" && e.entity_type == EntityType::Ant && e.hunger < 100.0),
+        );
+        assert_eq!(query.entities().visitors().ids(), vec!["v"]);
+        assert_eq!(query.entities().on_tile("nowhere").count(), 0);
+    }
+
+    #[test]
+    fn test_starved_systems() {
+        let mut state = GameState::default();
+        let heap = System::new_converter(
+            "Heap".to_string(),
+            HashMap::from([("dirt".to_string(), 1.0)]),
+            HashMap::from([("nutrients".to_string(), 1.0)]),
+        );
+        state.systems.insert("heap".to_string(), heap);
+
+        let mut engine = crate::engine::TickEngine::new(1);
+        for _ in 0..5 {
+            engine.tick(&mut state);
+        }
+        assert_eq!(state.query().systems().starved_for_more_than(3).ids(), vec!["heap"]);
+        assert_eq!(state.query().systems().starved_for_more_than(4).count(), 0);
+
+        // Running again clears it
+        state.resources.set("dirt", 10.0);
+        engine.tick(&mut state);
+        assert!(state.systems["heap"].starved_since.is_none());
+
+        // ...but its history remembers how little it has run
+        assert_eq!(state.systems["heap"].history, [0.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(state.query().systems().producing_less_than(0.5).ids(), vec!["heap"]);
+        for _ in 0..4 {
+            engine.tick(&mut state);
+        }
+        assert_eq!(state.query().systems().producing_less_than(0.5).count(), 0);
+    }
+}
@@ -0,0 +1,321 @@
+//! Everything the core knows about, for in-game encyclopedias.
+//!
+//! `state.registry()` lists the built-in kinds (system and tile types,
+//! visitor templates, engine-driven actions, event kinds) alongside the
+//! content the save itself defines: its systems, the resources it holds,
+//! prices or produces, and any action types waiting in its queue. Lists come
+//! back in a stable order so frontends can diff them between saves.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::events::{EventInfo, EVENT_CATALOG};
+use crate::rng::names;
+use crate::types::entity::{Entity, VisitorType};
+use crate::types::action::ENGINE_ACTIONS;
+use crate::types::expedition::EXPEDITION_ACTION;
+use crate::types::key::Key;
+use crate::types::outpost::SUPPLY_ACTION;
+use crate::types::hosting::HOST_ACTION;
+use crate::types::state::GameState;
+use crate::types::system::SystemType;
+use crate::types::tile::TileType;
+use crate::types::value::DEFAULT_WORTH;
+use crate::types::wonder::Wonder;
+
+/// A built-in system type
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SystemKindInfo {
+    pub kind: SystemType,
+    pub summary: &'static str,
+}
+
+/// A system defined by the save's content
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SystemInfo {
+    pub id: String,
+    pub name: String,
+    pub kind: SystemType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub generates: BTreeMap<String, f64>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub consumes: BTreeMap<String, f64>,
+}
+
+/// A visitor as it arrives from the Outside
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VisitorInfo {
+    pub subtype: VisitorType,
+    pub name: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub food: Option<String>,
+    pub hunger_rate: f64,
+    /// Seconds before it departs
+    pub max_age: u64,
+    /// Produced per second while it stays
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub generates: BTreeMap<String, f64>,
+    /// Left behind when it departs
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub gift_on_death: BTreeMap<String, f64>,
+}
+
+/// A built-in tile type
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TileKindInfo {
+    pub kind: TileType,
+    pub summary: &'static str,
+}
+
+/// A resource the save knows about
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResourceInfo {
+    pub name: Key,
+    /// Worth of one unit at the reference stock (see `Valuation`)
+    pub worth: f64,
+}
+
+/// An action type the queue can hold
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ActionInfo {
+    pub kind: String,
+    /// Set for the engine's own actions; content-defined ones have none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<&'static str>,
+}
+
+/// A wonder, and whether this save has seen it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WonderInfo {
+    pub wonder: Wonder,
+    pub summary: &'static str,
+    pub rewards: BTreeMap<Key, f64>,
+    /// Tick it happened, if it has
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seen_at: Option<u64>,
+}
+
+/// Everything known to the core and to one save
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Registry {
+    pub system_kinds: Vec<SystemKindInfo>,
+    pub systems: Vec<SystemInfo>,
+    pub visitors: Vec<VisitorInfo>,
+    pub tiles: Vec<TileKindInfo>,
+    pub resources: Vec<ResourceInfo>,
+    pub actions: Vec<ActionInfo>,
+    pub wonders: Vec<WonderInfo>,
+    pub events: &'static [EventInfo],
+}
+
+impl Registry {
+    /// The built-ins, as a fresh colony sees them
+    pub fn builtins() -> Self {
+        Self::of(&GameState::default())
+    }
+
+    /// The built-ins plus the content `state` defines
+    pub fn of(state: &GameState) -> Self {
+        Self {
+            system_kinds: system_kinds(),
+            systems: systems(state),
+            visitors: visitors(state),
+            tiles: tiles(),
+            resources: resources(state),
+            actions: actions(state),
+            wonders: wonders(state),
+            events: EVENT_CATALOG,
+        }
+    }
+}
+
+fn system_kinds() -> Vec<SystemKindInfo> {
+    [
+        (SystemType::Generator, "Produces resources every tick"),
+        (SystemType::Converter, "Consumes resources every tick to produce others"),
+        (SystemType::Spawner, "Content-defined producer that brings new things into the colony"),
+        (SystemType::Crafting, "Content-defined producer that makes goods from materials"),
+        (SystemType::Antenna, "Listens to the Outside"),
+        (SystemType::Shrine, "Converter whose returns diminish with the volume put through each window"),
+    ]
+    .into_iter()
+    .map(|(kind, summary)| SystemKindInfo { kind, summary })
+    .collect()
+}
+
+fn systems(state: &GameState) -> Vec<SystemInfo> {
+    let sorted = |rates: &Option<HashMap<String, f64>>| -> BTreeMap<String, f64> {
+        rates.iter().flatten().map(|(resource, rate)| (resource.clone(), *rate)).collect()
+    };
+    let mut systems: Vec<SystemInfo> = state.systems.iter()
+        .map(|(id, system)| SystemInfo {
+            id: id.clone(),
+            name: system.name.clone(),
+            kind: system.system_type.clone(),
+            description: system.description.clone(),
+            generates: sorted(&system.generates),
+            consumes: sorted(&system.consumes),
+        })
+        .collect();
+    systems.sort_by(|a, b| a.id.cmp(&b.id));
+    systems
+}
+
+fn visitors(state: &GameState) -> Vec<VisitorInfo> {
+    [VisitorType::Wanderer, VisitorType::Observer, VisitorType::Hungry]
+        .into_iter()
+        .map(|subtype| {
+            let visitor = state.config.with_baseline(Entity::new_visitor(subtype, String::new()));
+            let subtype = visitor.subtype.expect("visitor templates have a subtype");
+            VisitorInfo {
+                name: names::visitor_kind(&subtype).to_string(),
+                subtype,
+                description: visitor.description.unwrap_or_default(),
+                food: visitor.food,
+                hunger_rate: visitor.hunger_rate,
+                max_age: visitor.max_age,
+                generates: visitor.generates.into_iter().flatten().collect(),
+                gift_on_death: visitor.gift_on_death.into_iter().flatten().collect(),
+            }
+        })
+        .collect()
+}
+
+fn tiles() -> Vec<TileKindInfo> {
+    [
+        (TileType::Empty, "Open ground"),
+        (TileType::Compost, "Where corpses go; gathers contamination and can blight"),
+        (TileType::Extraction, "Ground worked for raw materials"),
+        (TileType::Production, "Home to the colony's production"),
+        (TileType::Resource, "A source of one resource, found on expeditions"),
+        (TileType::Special, "Somewhere out of the ordinary"),
+        (TileType::Aesthetic, "Kept for its looks"),
+        (TileType::Antenna, "Where the colony listens to the Outside"),
+        (TileType::Ruin, "What's left of a past run's colony"),
+    ]
+    .into_iter()
+    .map(|(kind, summary)| TileKindInfo { kind, summary })
+    .collect()
+}
+
+fn resources(state: &GameState) -> Vec<ResourceInfo> {
+    let mut names: BTreeSet<Key> = state.valuation.base.keys().cloned().collect();
+    names.extend(state.resources.amounts.keys().cloned());
+    for system in state.systems.values() {
+        for rates in system.generates.iter().chain(system.consumes.iter()) {
+            names.extend(rates.keys().map(Key::from));
+        }
+    }
+    names.into_iter()
+        .map(|name| ResourceInfo {
+            worth: state.valuation.base.get(&name).copied().unwrap_or(DEFAULT_WORTH),
+            name,
+        })
+        .collect()
+}
+
+fn actions(state: &GameState) -> Vec<ActionInfo> {
+    let mut actions = vec![
+        ActionInfo { kind: EXPEDITION_ACTION.to_string(), summary: Some("A party away in the Outside") },
+        ActionInfo { kind: SUPPLY_ACTION.to_string(), summary: Some("Supplies on their way to an outpost") },
+        ActionInfo { kind: HOST_ACTION.to_string(), summary: Some("The colony paying to keep a visitor longer") },
+    ];
+    actions.extend(state.config.blueprints.0.keys().map(|kind| ActionInfo {
+        kind: kind.to_string(),
+        summary: Some(kind.summary()),
+    }));
+    let content: BTreeSet<&str> = state.queues.actions.iter()
+        .filter(|action| action.kind().is_none())
+        .map(|action| action.action_type.as_str())
+        .filter(|kind| !ENGINE_ACTIONS.contains(kind))
+        .collect();
+    actions.extend(content.into_iter().map(|kind| ActionInfo { kind: kind.to_string(), summary: None }));
+    actions
+}
+
+fn wonders(state: &GameState) -> Vec<WonderInfo> {
+    Wonder::ALL.into_iter()
+        .map(|wonder| WonderInfo {
+            wonder,
+            summary: wonder.summary(),
+            rewards: wonder.rewards().iter().map(|&(r, a)| (Key::new(r), a)).collect(),
+            seen_at: state.wonders.get(&wonder).copied(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventKindTag;
+    use crate::types::action::Action;
+    use crate::types::system::System;
+
+    #[test]
+    fn test_event_catalog_matches_event_kinds() {
+        let source = include_str!("events.rs");
+        let body = &source[source.find("pub enum EventKind {").unwrap()..];
+        let body = &body[..body.find("\n}\n").unwrap()];
+        let declared: Vec<String> = body.lines()
+            .filter_map(|line| line.strip_prefix("    "))
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_uppercase()))
+            .map(|line| {
+                let name = line.trim_end_matches([' ', '{', ',']);
+                let mut snake = String::new();
+                for (i, c) in name.chars().enumerate() {
+                    if c.is_ascii_uppercase() && i > 0 {
+                        snake.push('_');
+                    }
+                    snake.push(c.to_ascii_lowercase());
+                }
+                snake
+            })
+            .collect();
+        let cataloged: Vec<&str> = EVENT_CATALOG.iter().map(|info| info.kind).collect();
+        assert_eq!(cataloged, declared);
+
+        for info in EVENT_CATALOG {
+            let tag: EventKindTag = serde_json::from_value(info.kind.into()).unwrap();
+            assert_eq!(tag.verbosity(), info.verbosity, "{}", info.kind);
+        }
+    }
+
+    #[test]
+    fn test_registry_lists_builtins_and_content() {
+        let builtins = Registry::builtins();
+        assert_eq!(builtins.visitors.len(), 3);
+        assert_eq!(builtins.visitors[0].gift_on_death["strange_matter"], 1.0);
+        assert!(builtins.resources.iter().any(|r| r.name == "strange_matter"));
+        assert!(builtins.systems.is_empty());
+
+        let mut state = GameState::default();
+        let mut still = System::new_generator(
+            "Still".to_string(),
+            HashMap::from([("moonshine".to_string(), 0.1)]),
+        );
+        still.description = Some("Quietly bubbling".to_string());
+        state.systems.insert("still".to_string(), still);
+        state.queues.enqueue_action(Action {
+            id: "a1".to_string(),
+            action_type: "vigil".to_string(),
+            ticks_remaining: 5,
+            target: None,
+            effects: None,
+        });
+
+        let registry = state.registry();
+        assert_eq!(registry.systems[0].id, "still");
+        assert_eq!(registry.systems[0].generates["moonshine"], 0.1);
+        assert!(registry.resources.iter().any(|r| r.name == "moonshine"));
+        assert_eq!(registry.actions.last().unwrap().kind, "vigil");
+        assert!(registry.actions.iter().any(|a| a.kind == "ritual" && a.summary.is_some()));
+        assert!(registry.actions.last().unwrap().summary.is_none());
+
+        let json = serde_json::to_value(&registry).unwrap();
+        assert_eq!(json["events"][0]["kind"], "entity_died");
+        assert_eq!(json["tiles"][0]["kind"], "empty");
+    }
+}
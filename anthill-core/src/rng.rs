@@ -7,6 +7,8 @@ use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 
+pub mod names;
+
 /// A seeded random number generator for deterministic simulation
 #[derive(Debug, Clone)]
 pub struct SeededRng {
@@ -31,6 +33,19 @@ impl SeededRng {
         Self::new(base_seed.wrapping_add(tick.wrapping_mul(2654435761)))
     }
 
+    /// Pick a stream back up exactly where `state` left it
+    pub fn restore(state: RngState) -> Self {
+        let mut rng = Self::new(state.seed);
+        rng.rng.set_word_pos(u128::from(state.word_pos));
+        rng.calls = state.calls;
+        rng
+    }
+
+    /// Where the stream is now, for `restore`
+    pub fn state(&self) -> RngState {
+        RngState::from(self)
+    }
+
     /// Get the original seed
     pub fn seed(&self) -> u64 {
         self.seed
@@ -82,10 +97,15 @@ impl SeededRng {
 }
 
 /// State that can be serialized to restore RNG position
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RngState {
     pub seed: u64,
     pub calls: u64,
+    /// Position in the ChaCha stream, in 32-bit words. Draws consume
+    /// different amounts of the stream, so this (not `calls`) is what pins
+    /// the position down.
+    #[serde(default)]
+    pub word_pos: u64,
 }
 
 impl From<&SeededRng> for RngState {
@@ -93,6 +113,7 @@ impl From<&SeededRng> for RngState {
         Self {
             seed: rng.seed,
             calls: rng.calls,
+            word_pos: rng.rng.get_word_pos() as u64,
         }
     }
 }
@@ -145,6 +166,23 @@ mod tests {
         assert_ne!(id1, id2);
     }
 
+    #[test]
+    fn test_restore_resumes_the_stream() {
+        let mut rng = SeededRng::from_tick(7, 300);
+        rng.chance(0.5);
+        rng.entity_id();
+        rng.range(1, 1000);
+
+        let saved = serde_json::to_string(&rng.state()).unwrap();
+        let mut restored = SeededRng::restore(serde_json::from_str(&saved).unwrap());
+        assert_eq!(restored.calls(), 3);
+        for _ in 0..50 {
+            assert_eq!(restored.random(), rng.random());
+            assert_eq!(restored.visitor_id(), rng.visitor_id());
+        }
+        assert_eq!(restored.state(), rng.state());
+    }
+
     #[test]
     fn test_chance() {
         let mut rng = SeededRng::new(42);
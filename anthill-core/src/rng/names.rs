@@ -0,0 +1,111 @@
+//! Names for new entities.
+//!
+//! A name is a pure function of the entity's ID and its role or subtype.
+//! IDs come from the seeded RNG, so names are as deterministic as anything
+//! else, but working one out draws nothing from the stream: naming never
+//! shifts another phase's draws, and narration outside the core never has
+//! to invent names of its own.
+
+use crate::digest::fnv1a64;
+use crate::types::entity::{AntRole, VisitorType};
+
+/// A name for an ant with ID `id`
+pub fn ant(id: &str, role: &AntRole) -> String {
+    let (deeds, epithets, titles): (&[&str], &[&str], &[&str]) = match role {
+        AntRole::Worker => (
+            &["Carries", "Digs", "Tends the Fungus", "Counts the Tunnels", "Never Rests", "Hums in the Dark"],
+            &["Patient", "Small", "Dusty", "Tireless", "Quiet", "Crooked"],
+            &["Digger", "Tender", "Mason", "Forager"],
+        ),
+        AntRole::Undertaker => (
+            &["Buries", "Keeps Count", "Remembers", "Walks Behind", "Carries the Quiet", "Knows Their Names"],
+            &["Solemn", "Grey", "Gentle", "Unhurried", "Hollow-Eyed", "Last"],
+            &["Keeper", "Mourner", "Bearer", "Warden"],
+        ),
+        AntRole::Hauler => (
+            &["Carries", "Knows the Way", "Walks Far", "Brings It Back", "Takes the Long Road", "Never Drops It"],
+            &["Steady", "Broad", "Restless", "Sure-Footed", "Burdened", "Swift"],
+            &["Carrier", "Runner", "Porter", "Wayfinder"],
+        ),
+        AntRole::Forager => (
+            &["Finds the Green", "Walks Ahead", "Smells the Rain", "Knows the Fields", "Comes Back Heavy", "Strips the Stalk"],
+            &["Keen", "Wandering", "Sun-Dried", "Hungry", "Nimble", "Far-Ranging"],
+            &["Gatherer", "Scout", "Reaper", "Picker"],
+        ),
+        AntRole::Soldier => (
+            &["Holds the Gate", "Bites First", "Stands Alone", "Does Not Yield", "Watches the Dark", "Bleeds for Us"],
+            &["Scarred", "Grim", "Iron-Jawed", "Watchful", "Unbroken", "Red"],
+            &["Guard", "Sentinel", "Shield", "Jaw"],
+        ),
+        AntRole::Nurse => (
+            &["Feeds the Young", "Sings to the Eggs", "Never Sleeps", "Keeps Them Warm", "Licks the Brood", "Waits on the Queen"],
+            &["Soft", "Careful", "Warm", "Tender", "Watchful", "Sleepless"],
+            &["Nurse", "Cradler", "Feeder", "Attendant"],
+        ),
+        AntRole::Queen => (
+            &["Lays the Many", "Was First", "Holds the Chamber", "Outlives Us All", "Is Fed Before Us", "Dreams the Colony"],
+            &["Great", "Swollen", "Ancient", "Pale", "Enthroned", "Fecund"],
+            &["Queen", "Mother", "Matriarch", "Founder"],
+        ),
+        AntRole::Princess => (
+            &["Waits Her Turn", "Will Be Mother", "Is Kept Close", "Grows in the Dark", "Carries the Line", "Hears the Queen"],
+            &["Young", "Winged", "Bright", "Patient", "Favoured", "Untried"],
+            &["Princess", "Heir", "Daughter", "Gyne"],
+        ),
+    };
+
+    let hash = fnv1a64(id.as_bytes());
+    if hash & 1 == 0 {
+        format!("The One Who {}", pick(deeds, hash, 8))
+    } else {
+        format!("The {} {}", pick(epithets, hash, 16), pick(titles, hash, 24))
+    }
+}
+
+/// A name for a visitor with ID `id`
+pub fn visitor(id: &str, subtype: &VisitorType) -> String {
+    let (descriptions, noun): (&[&str], &str) = match subtype {
+        VisitorType::Wanderer => (&["Pale", "Lost", "Distant", "Faded", "Barefoot", "Humming", "Unhurried"], "Wanderer"),
+        VisitorType::Observer => (&["Unblinking", "Silent", "Patient", "Still", "Many-Eyed", "Attentive", "Tall"], "Observer"),
+        VisitorType::Hungry => (&["Gaping", "Ravenous", "Hollow", "Endless", "Aching", "Open", "Starving"], "Thing"),
+    };
+
+    let description = pick(descriptions, fnv1a64(id.as_bytes()), 8);
+    let article = if description.starts_with(['A', 'E', 'I', 'O', 'U']) { "An" } else { "A" };
+    format!("{article} {description} {noun}")
+}
+
+/// What a kind of visitor is called before it has a name of its own
+pub fn visitor_kind(subtype: &VisitorType) -> &'static str {
+    match subtype {
+        VisitorType::Wanderer => "A Wanderer",
+        VisitorType::Observer => "An Observer",
+        VisitorType::Hungry => "A Hungry Thing",
+    }
+}
+
+/// An entry of `list`, chosen by the bits of `hash` from `shift` up
+fn pick<'a>(list: &[&'a str], hash: u64, shift: u32) -> &'a str {
+    list[((hash >> shift) % list.len() as u64) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_follow_the_id() {
+        assert_eq!(ant("3fa9c201", &AntRole::Worker), ant("3fa9c201", &AntRole::Worker));
+        assert!(ant("3fa9c201", &AntRole::Worker).starts_with("The "));
+
+        let names: std::collections::BTreeSet<_> = (0..64).map(|i| ant(&format!("{i:08x}"), &AntRole::Undertaker)).collect();
+        assert!(names.len() > 10, "{names:?}");
+        assert!(names.iter().any(|n| n.starts_with("The One Who ")));
+
+        for i in 0..64 {
+            let name = visitor(&format!("v_{i:06x}"), &VisitorType::Observer);
+            assert!(name.ends_with(" Observer"), "{name}");
+            assert!(!name.starts_with("A U") && !name.starts_with("A A"), "{name}");
+        }
+    }
+}
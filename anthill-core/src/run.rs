@@ -0,0 +1,175 @@
+//! One-call runs: a whole headless simulation in and out as data.
+//!
+//! A wrapper binary, a WASM worker and the Python bindings each need the
+//! same few steps: load a save (or start a colony), set up an engine with a
+//! seed and an event filter, maybe start a scenario, and tick. Doing those
+//! separately in every host lets them drift apart. `run_simulation` does
+//! them all from one `RunDescriptor`, and its `RunOutput` is the final
+//! state with the run's events grouped by tick. It's a pure function: the
+//! same descriptor always gives the same output.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::engine::TickEngine;
+use crate::events::{BatchEvents, EventFilter, TickEvents};
+use crate::types::mapgen::MapGenConfig;
+use crate::types::scenario::Scenario;
+use crate::types::state::GameState;
+
+/// Everything a run needs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunDescriptor {
+    /// The save to start from, in any layout or schema `GameState::from_json`
+    /// takes (a new colony when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<serde_json::Value>,
+
+    /// The cavern a new colony starts in, grown from `seed` (the single
+    /// origin tile when absent; a save keeps its own map)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map: Option<MapGenConfig>,
+
+    /// A scenario to play for, started before the first tick
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scenario: Option<Scenario>,
+
+    pub seed: u64,
+
+    /// Ticks to run
+    pub ticks: u64,
+
+    /// Events to keep
+    #[serde(default)]
+    pub filter: EventFilter,
+}
+
+/// What a run came to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunOutput {
+    pub state: GameState,
+
+    /// The kept events, grouped by tick with quiet ticks left out, and a
+    /// summary of the run. A scenario's start is filed under the tick it
+    /// started on, before the first tick run.
+    pub events: BatchEvents,
+}
+
+/// Why a run couldn't start
+#[derive(Debug, Error)]
+pub enum RunError {
+    #[error("the starting state can't be loaded: {0}")]
+    InvalidState(#[from] serde_json::Error),
+}
+
+/// Run `descriptor` from start to finish
+pub fn run_simulation(descriptor: RunDescriptor) -> Result<RunOutput, RunError> {
+    let mut state = match descriptor.state {
+        Some(doc) => GameState::from_value(doc)?,
+        None => match &descriptor.map {
+            Some(config) => GameState::generated(descriptor.seed, config),
+            None => GameState::new(),
+        },
+    };
+    let mut engine = TickEngine::new(descriptor.seed);
+    engine.init_from_state(&state);
+
+    let before = state.resources.amounts.clone();
+    let mut events = BatchEvents::default();
+    if let Some(scenario) = descriptor.scenario {
+        let mut opening = TickEvents::new();
+        for event in engine.start_scenario(&mut state, scenario).into_events() {
+            opening.push(event.tick, event.kind);
+        }
+        opening.retain(&descriptor.filter);
+        events.push_between(state.tick, opening);
+    }
+    // The engine ticks unfiltered; the filter only trims what's returned
+    for _ in 0..descriptor.ticks {
+        let mut ticked = engine.tick(&mut state);
+        ticked.retain(&descriptor.filter);
+        events.push(state.tick, ticked);
+    }
+    events.set_resource_deltas(&before, &state.resources.amounts);
+    Ok(RunOutput { state, events })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{EventKindTag, Verbosity};
+    use crate::types::scenario::EndCondition;
+    use crate::types::tile::GameMap;
+
+    #[test]
+    fn test_runs_are_pure_and_match_the_engine() {
+        let descriptor = RunDescriptor {
+            seed: 7,
+            ticks: 200,
+            filter: EventFilter::at_verbosity(Verbosity::Info),
+            ..RunDescriptor::default()
+        };
+        let run = run_simulation(descriptor.clone()).unwrap();
+        let again = run_simulation(descriptor).unwrap();
+        assert_eq!(run.state.hash(), again.state.hash());
+        assert_eq!(run.events.summary, again.events.summary);
+
+        // The same as driving an engine by hand
+        let mut state = GameState::new();
+        let mut engine = TickEngine::new(7);
+        engine.set_event_filter(EventFilter::at_verbosity(Verbosity::Info));
+        let batch = engine.tick_n(&mut state, 200);
+        assert_eq!(run.state.hash(), state.hash());
+        assert_eq!(run.events.summary, batch.summary);
+        assert_eq!(run.state.tick, 200);
+
+        // The filter trims the events, never the run
+        let quiet = run_simulation(RunDescriptor {
+            seed: 7,
+            ticks: 200,
+            filter: EventFilter::only([EventKindTag::SystemBuilt]),
+            ..RunDescriptor::default()
+        })
+        .unwrap();
+        assert_eq!(quiet.state.hash(), run.state.hash());
+        assert_eq!(quiet.events.summary, run.events.summary);
+        assert!(quiet.events.events().all(|e| e.kind.tag() == EventKindTag::SystemBuilt));
+    }
+
+    #[test]
+    fn test_runs_resume_saves_and_play_scenarios() {
+        let first = run_simulation(RunDescriptor { seed: 7, ticks: 50, ..RunDescriptor::default() }).unwrap();
+        let doc = serde_json::to_value(&first.state).unwrap();
+        let scenario = Scenario {
+            name: "Hold on".to_string(),
+            win: vec![EndCondition::SurviveTicks { ticks: 10 }],
+            lose: Vec::new(),
+            started_at: 0,
+        };
+        let run = run_simulation(RunDescriptor {
+            state: Some(doc),
+            scenario: Some(scenario),
+            seed: 7,
+            ticks: 20,
+            filter: EventFilter::only([EventKindTag::ScenarioStarted, EventKindTag::ScenarioCompleted]),
+            ..RunDescriptor::default()
+        })
+        .unwrap();
+
+        let kinds: Vec<(u64, EventKindTag)> = run.events.ticks.iter()
+            .flat_map(|batch| batch.events.iter().map(move |event| (batch.tick, event.kind.tag())))
+            .collect();
+        assert_eq!(kinds, [(50, EventKindTag::ScenarioStarted), (60, EventKindTag::ScenarioCompleted)]);
+        assert_eq!(run.events.summary.ticks, 20);
+        assert!(run.state.is_ended());
+
+        // A new colony can start in a generated cavern
+        let map = MapGenConfig { size: 9, ..MapGenConfig::default() };
+        let run = run_simulation(RunDescriptor { map: Some(map.clone()), seed: 7, ticks: 5, ..RunDescriptor::default() }).unwrap();
+        assert_eq!(run.state.map.tiles.len(), 9);
+        assert_eq!(run.state.map.connections, GameMap::generate_with(7, &map).connections);
+
+        let broken = RunDescriptor { state: Some(serde_json::json!({"tick": "soon"})), ..RunDescriptor::default() };
+        assert!(matches!(run_simulation(broken), Err(RunError::InvalidState(_))));
+    }
+}
@@ -0,0 +1,220 @@
+//! Soak testing: run a colony for a very long time and watch for rot.
+//!
+//! Idle games get left running for weeks. `run` ticks a state for as long
+//! as asked, checking invariants every `check_every` ticks, and returns a
+//! structured report rather than panicking, so long-idle regressions can be
+//! asserted on in tests and CI.
+//!
+//! Built only with the `soak` feature.
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::TickEngine;
+use crate::types::state::GameState;
+
+/// Violations past this many end the run early
+pub const MAX_VIOLATIONS: usize = 16;
+
+/// What to run and what counts as healthy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SoakConfig {
+    pub ticks: u64,
+
+    /// Invariants are checked every this many ticks (and after the last)
+    pub check_every: u64,
+
+    /// Most living entities allowed at once
+    pub max_population: usize,
+
+    /// Most rows allowed across the state's growing collections
+    pub max_footprint: usize,
+}
+
+impl Default for SoakConfig {
+    fn default() -> Self {
+        Self {
+            ticks: 1_000_000,
+            check_every: 1000,
+            max_population: 10_000,
+            max_footprint: 100_000,
+        }
+    }
+}
+
+/// Row counts of everything in a state that could grow without bound
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Footprint {
+    pub entities: usize,
+    pub corpses: usize,
+    pub actions: usize,
+    pub queued_events: usize,
+    pub tiles: usize,
+    pub connections: usize,
+    pub systems: usize,
+    pub corpse_boosts: usize,
+    pub reflections: usize,
+    pub decisions: usize,
+    pub expeditions: usize,
+    pub outposts: usize,
+    pub stockpiles: usize,
+    pub ruins: usize,
+}
+
+impl Footprint {
+    pub fn of(state: &GameState) -> Self {
+        Self {
+            entities: state.entities.len(),
+            corpses: state.graveyard.corpses.len(),
+            actions: state.queues.actions.len(),
+            queued_events: state.queues.events.len(),
+            tiles: state.map.tiles.len(),
+            connections: state.map.connections.len(),
+            systems: state.systems.len(),
+            corpse_boosts: state.systems.values().map(|s| s.corpse_boosts.len()).sum(),
+            reflections: state.meta.reflections.len(),
+            decisions: state.meta.recent_decisions.len(),
+            expeditions: state.expeditions.len(),
+            outposts: state.outposts.len(),
+            stockpiles: state.stockpiles.len(),
+            ruins: state.ruins.len(),
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.entities + self.corpses + self.actions + self.queued_events + self.tiles
+            + self.connections + self.systems + self.corpse_boosts + self.reflections
+            + self.decisions + self.expeditions + self.outposts + self.stockpiles + self.ruins
+    }
+}
+
+/// An invariant that failed, and when
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Violation {
+    /// A number in the state went NaN or infinite
+    NonFinite { tick: u64, field: String },
+    PopulationExceeded { tick: u64, population: usize, max: usize },
+    FootprintExceeded { tick: u64, footprint: usize, max: usize },
+}
+
+/// How a soak run went
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SoakReport {
+    pub ticks_run: u64,
+    pub checks: u64,
+    pub events: u64,
+    pub peak_population: usize,
+    pub peak_footprint: usize,
+    pub final_footprint: Footprint,
+    pub violations: Vec<Violation>,
+}
+
+impl SoakReport {
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Tick `state` for `config.ticks`, checking invariants along the way.
+/// Stops early once `MAX_VIOLATIONS` have been recorded.
+pub fn run(engine: &mut TickEngine, state: &mut GameState, config: &SoakConfig) -> SoakReport {
+    let mut report = SoakReport::default();
+    let every = config.check_every.max(1);
+
+    while report.ticks_run < config.ticks && report.violations.len() < MAX_VIOLATIONS {
+        report.events += engine.tick(state).len() as u64;
+        report.ticks_run += 1;
+        report.peak_population = report.peak_population.max(state.entities.len());
+
+        if report.ticks_run % every == 0 || report.ticks_run == config.ticks {
+            check(state, config, &mut report);
+        }
+    }
+
+    report.final_footprint = Footprint::of(state);
+    report
+}
+
+fn check(state: &GameState, config: &SoakConfig, report: &mut SoakReport) {
+    let tick = state.tick;
+    report.checks += 1;
+
+    for field in non_finite_fields(state) {
+        report.violations.push(Violation::NonFinite { tick, field });
+    }
+
+    let population = state.entities.len();
+    if population > config.max_population {
+        report.violations.push(Violation::PopulationExceeded { tick, population, max: config.max_population });
+    }
+
+    let footprint = Footprint::of(state).total();
+    report.peak_footprint = report.peak_footprint.max(footprint);
+    if footprint > config.max_footprint {
+        report.violations.push(Violation::FootprintExceeded { tick, footprint, max: config.max_footprint });
+    }
+}
+
+/// Names of every float in the state that isn't finite, sorted
+fn non_finite_fields(state: &GameState) -> Vec<String> {
+    let mut bad = Vec::new();
+    let mut check = |name: String, value: f64| {
+        if !value.is_finite() {
+            bad.push(name);
+        }
+    };
+
+    for (resource, amount) in &state.resources.amounts {
+        check(format!("resources.{}", resource), *amount);
+    }
+    for (tile, stockpile) in &state.stockpiles {
+        for (resource, amount) in &stockpile.amounts {
+            check(format!("stockpiles.{}.{}", tile, resource), *amount);
+        }
+    }
+    for entity in state.entities.iter() {
+        check(format!("entities.{}.hunger", entity.id), entity.hunger);
+    }
+    for (id, tile) in &state.map.tiles {
+        check(format!("map.{}.contamination", id), tile.contamination.unwrap_or(0.0));
+    }
+    for (id, outpost) in &state.outposts {
+        check(format!("outposts.{}.condition", id), outpost.condition);
+    }
+    check("meta.sanity".to_string(), state.meta.sanity);
+
+    bad.sort();
+    bad
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::entity::Entity;
+
+    #[test]
+    fn test_idle_colony_soaks_clean() {
+        let mut state = GameState::default();
+        state.resources.set("fungus", 500.0);
+        state.resources.set("nutrients", 500.0);
+        state.entities.push(Entity::new_worker("w".to_string(), "origin".to_string()));
+        state.entities.push(Entity::new_undertaker("u".to_string(), "origin".to_string()));
+
+        let config = SoakConfig { ticks: 50_000, ..Default::default() };
+        let report = run(&mut TickEngine::new(42), &mut state, &config);
+        assert!(report.passed(), "{:?}", report.violations);
+        assert_eq!(report.ticks_run, 50_000);
+        assert_eq!(report.checks, 50);
+    }
+
+    #[test]
+    fn test_nan_is_reported() {
+        let mut state = GameState::default();
+        state.resources.set("dirt", f64::NAN);
+
+        let config = SoakConfig { ticks: 10, check_every: 5, ..Default::default() };
+        let report = run(&mut TickEngine::new(42), &mut state, &config);
+        assert_eq!(report.violations[0], Violation::NonFinite { tick: 5, field: "resources.dirt".to_string() });
+        assert_eq!(report.violations.len(), 2);
+    }
+}
@@ -0,0 +1,240 @@
+//! Converting between ticks and wall-clock time.
+//!
+//! The host decides how fast ticks run (`GameState::ticks_per_second`);
+//! everything that shows "30 minutes from now" should go through here
+//! rather than assuming a tick is a second.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::engine::constants;
+use crate::events::{Event, Verbosity};
+use crate::types::entity::{DeathCause, EntityId};
+use crate::types::key::Key;
+use crate::types::warning::Warning;
+use crate::types::schedule::ScheduledItem;
+use crate::types::state::GameState;
+
+/// A tick rate pinned to a moment: `anchor_tick` happened at
+/// `anchor_timestamp` (seconds, host clock)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timeline {
+    ticks_per_second: f64,
+    anchor_tick: u64,
+    anchor_timestamp: f64,
+}
+
+impl Timeline {
+    /// A timeline at `ticks_per_second`, anchored at tick 0 = timestamp 0.
+    /// Non-positive or non-finite rates fall back to 1 tick per second.
+    pub fn new(ticks_per_second: f64) -> Self {
+        let ticks_per_second = if ticks_per_second.is_finite() && ticks_per_second > 0.0 {
+            ticks_per_second
+        } else {
+            1.0
+        };
+        Self {
+            ticks_per_second,
+            anchor_tick: 0,
+            anchor_timestamp: 0.0,
+        }
+    }
+
+    /// The state's timeline, with its current tick happening at `now`
+    pub fn of(state: &GameState, now: f64) -> Self {
+        Self::new(state.ticks_per_second).anchored(state.tick, now)
+    }
+
+    /// The same rate, with `tick` happening at `timestamp`
+    pub fn anchored(self, tick: u64, timestamp: f64) -> Self {
+        Self {
+            anchor_tick: tick,
+            anchor_timestamp: timestamp,
+            ..self
+        }
+    }
+
+    pub fn ticks_per_second(&self) -> f64 {
+        self.ticks_per_second
+    }
+
+    /// Wall-clock length of `ticks`
+    pub fn duration(&self, ticks: u64) -> Duration {
+        Duration::from_secs_f64(ticks as f64 / self.ticks_per_second)
+    }
+
+    /// Whole ticks that fit in `duration` (rounded down)
+    pub fn ticks(&self, duration: Duration) -> u64 {
+        (duration.as_secs_f64() * self.ticks_per_second).floor() as u64
+    }
+
+    /// The tick that is current at wall-clock `timestamp` (never before
+    /// the anchor)
+    pub fn at_tick(&self, timestamp: f64) -> u64 {
+        let elapsed = (timestamp - self.anchor_timestamp).max(0.0);
+        self.anchor_tick + (elapsed * self.ticks_per_second).floor() as u64
+    }
+
+    /// The wall-clock timestamp at which `tick` happens
+    pub fn timestamp_of(&self, tick: u64) -> f64 {
+        let ticks = tick as f64 - self.anchor_tick as f64;
+        self.anchor_timestamp + ticks / self.ticks_per_second
+    }
+
+    /// Wall-clock time from the anchor until `item` happens
+    pub fn until(&self, item: &ScheduledItem) -> Duration {
+        self.duration(item.ticks_until(self.anchor_tick))
+    }
+}
+
+/// The host's clock reading for offline progress, with how far to trust it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeContext {
+    /// Current timestamp (seconds, host clock)
+    pub now: f64,
+
+    /// Backwards drift (seconds) treated as clock jitter rather than an
+    /// anomaly
+    pub tolerance: f64,
+
+    /// Most offline time to simulate (seconds at 1 Hz, scaled like any
+    /// other duration)
+    pub max_catchup: u64,
+
+    /// Events from the catch-up below this level are dropped
+    pub verbosity: Verbosity,
+}
+
+impl TimeContext {
+    /// `now` with the default tolerance and catch-up cap
+    pub fn at(now: f64) -> Self {
+        Self {
+            now,
+            tolerance: constants::TIME_TOLERANCE,
+            max_catchup: constants::MAX_OFFLINE_TICKS,
+            verbosity: Verbosity::Debug,
+        }
+    }
+}
+
+/// What was wrong with a host timestamp
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeAnomaly {
+    /// Not a number, or infinite
+    NotFinite,
+
+    /// Earlier than the last save by more than the tolerance
+    Backwards,
+
+    /// Later than the last save by more than `TIME_JUMP_LIMIT`
+    Jump,
+}
+
+/// Someone who died while the player was away
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OfflineDeath {
+    pub entity_id: EntityId,
+    pub entity_type: String,
+    pub cause: DeathCause,
+    pub tick: u64,
+}
+
+/// What happened while the player was away, for a "while you were away..."
+/// screen. Returned by `TickEngine::catch_up`; when nothing was simulated
+/// (too short an absence, or a bad clock) only `anomaly` and `events` can
+/// be set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OfflineReport {
+    /// Ticks simulated
+    pub ticks: u64,
+
+    /// Seconds away by the host's clock, before the catch-up cap
+    pub elapsed_seconds: f64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anomaly: Option<TimeAnomaly>,
+
+    /// Net change in each colony resource that moved
+    pub resource_deltas: BTreeMap<Key, f64>,
+
+    /// Everyone who died, in order, whatever the filter kept
+    pub deaths: Vec<OfflineDeath>,
+
+    /// System ID -> ticks it spent unable to run
+    pub stalled_systems: BTreeMap<String, u64>,
+
+    /// Everything that fired, at or above `TimeContext::verbosity`
+    pub events: Vec<Event>,
+
+    /// Warnings standing at the end of the catch-up
+    pub warnings: Vec<Warning>,
+}
+
+impl OfflineReport {
+    /// Nothing simulated and nothing to say
+    pub fn is_empty(&self) -> bool {
+        self.ticks == 0 && self.events.is_empty()
+    }
+}
+
+/// A short human-readable duration: "45s", "30m", "2h 5m", "3d 4h"
+pub fn humanize(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (days, hours, mins, secs) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+
+    match (days, hours, mins) {
+        (0, 0, 0) => format!("{}s", secs),
+        (0, 0, m) if secs == 0 => format!("{}m", m),
+        (0, 0, m) => format!("{}m {}s", m, secs),
+        (0, h, 0) => format!("{}h", h),
+        (0, h, m) => format!("{}h {}m", h, m),
+        (d, 0, _) => format!("{}d", d),
+        (d, h, _) => format!("{}d {}h", d, h),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::schedule::ScheduledKind;
+
+    #[test]
+    fn test_conversions_follow_tick_rate() {
+        let one_hz = Timeline::new(1.0);
+        assert_eq!(one_hz.ticks(Duration::from_secs(1800)), 1800);
+
+        let four_hz = Timeline::new(4.0);
+        assert_eq!(four_hz.ticks(Duration::from_secs(1800)), 7200);
+        assert_eq!(four_hz.duration(7200), Duration::from_secs(1800));
+
+        assert_eq!(Timeline::new(0.0).ticks_per_second(), 1.0);
+    }
+
+    #[test]
+    fn test_anchored_timeline() {
+        let mut state = GameState {
+            ticks_per_second: 2.0,
+            ..Default::default()
+        };
+        state.tick = 100;
+
+        let timeline = Timeline::of(&state, 1000.0);
+        assert_eq!(timeline.at_tick(1010.0), 120);
+        assert_eq!(timeline.at_tick(900.0), 100);
+        assert_eq!(timeline.timestamp_of(120), 1010.0);
+
+        let item = ScheduledItem::new(160, ScheduledKind::SpawnWindow);
+        assert_eq!(humanize(timeline.until(&item)), "30s");
+    }
+
+    #[test]
+    fn test_humanize() {
+        assert_eq!(humanize(Duration::from_secs(45)), "45s");
+        assert_eq!(humanize(Duration::from_secs(1800)), "30m");
+        assert_eq!(humanize(Duration::from_secs(90)), "1m 30s");
+        assert_eq!(humanize(Duration::from_secs(7500)), "2h 5m");
+        assert_eq!(humanize(Duration::from_secs(3 * 86400 + 4 * 3600)), "3d 4h");
+    }
+}
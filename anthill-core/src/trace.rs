@@ -0,0 +1,153 @@
+//! Golden traces: compact, committable fingerprints of a simulation run.
+//!
+//! A `Trace` records a state digest every `interval` ticks plus how many of
+//! each event kind fired. Commit one as a fixture; when a change alters
+//! behavior, `Trace::diff` says where and how in plain lines, so balance
+//! changes can be reviewed rather than just noticed.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::digest::digest;
+use crate::engine::TickEngine;
+use crate::types::envelope::hex;
+use crate::types::state::GameState;
+
+/// Default ticks between checkpoints
+pub const TRACE_INTERVAL: u64 = 100;
+
+/// The state at one checkpoint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub tick: u64,
+    /// Hex digest of the state's canonical JSON
+    pub digest: String,
+    pub population: usize,
+}
+
+/// A compact record of one run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trace {
+    pub ticks: u64,
+    pub interval: u64,
+    pub checkpoints: Vec<Checkpoint>,
+    /// Event type -> times fired
+    pub event_counts: BTreeMap<String, u64>,
+}
+
+impl Trace {
+    /// Run `state` for `ticks`, checkpointing every `interval`
+    pub fn record(engine: &mut TickEngine, state: &mut GameState, ticks: u64, interval: u64) -> Self {
+        let interval = interval.max(1);
+        let mut trace = Self {
+            ticks,
+            interval,
+            checkpoints: Vec::new(),
+            event_counts: BTreeMap::new(),
+        };
+
+        for n in 1..=ticks {
+            for event in engine.tick(state).events() {
+                let kind = serde_json::to_value(&event.kind).ok()
+                    .and_then(|v| v.get("type").and_then(|t| t.as_str()).map(str::to_string))
+                    .unwrap_or_default();
+                *trace.event_counts.entry(kind).or_default() += 1;
+            }
+            if n % interval == 0 {
+                trace.checkpoints.push(Checkpoint {
+                    tick: state.tick,
+                    digest: hex(digest(&*state).unwrap_or(0)),
+                    population: state.entities.len(),
+                });
+            }
+        }
+        trace
+    }
+
+    /// What changed going from `self` (the fixture) to `other`
+    pub fn diff(&self, other: &Trace) -> TraceDiff {
+        let mut lines = Vec::new();
+
+        if (self.ticks, self.interval) != (other.ticks, other.interval) {
+            lines.push(format!(
+                "run shape: {} ticks every {} -> {} ticks every {}",
+                self.ticks, self.interval, other.ticks, other.interval
+            ));
+        }
+
+        let diverged: Vec<_> = self.checkpoints.iter().zip(&other.checkpoints)
+            .filter(|(a, b)| a != b)
+            .collect();
+        if let Some((a, b)) = diverged.first() {
+            lines.push(format!(
+                "state diverges at tick {}: digest {} -> {}, population {} -> {}",
+                a.tick, a.digest, b.digest, a.population, b.population
+            ));
+            if diverged.len() > 1 {
+                lines.push(format!("{} later checkpoints also differ", diverged.len() - 1));
+            }
+        }
+
+        let kinds: std::collections::BTreeSet<&String> =
+            self.event_counts.keys().chain(other.event_counts.keys()).collect();
+        for kind in kinds {
+            let before = self.event_counts.get(kind).copied().unwrap_or(0);
+            let after = other.event_counts.get(kind).copied().unwrap_or(0);
+            if before != after {
+                lines.push(format!("{}: {} -> {}", kind, before, after));
+            }
+        }
+
+        TraceDiff { lines }
+    }
+}
+
+/// Human-readable differences between two traces, one per line
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceDiff {
+    pub lines: Vec<String>,
+}
+
+impl TraceDiff {
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+}
+
+impl fmt::Display for TraceDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::entity::Entity;
+
+    fn colony() -> GameState {
+        let mut state = GameState::default();
+        state.resources.set("fungus", 5.0);
+        state.entities.push(Entity::new_worker("w".to_string(), "origin".to_string()));
+        state
+    }
+
+    #[test]
+    fn test_diff_names_what_changed() {
+        let base = Trace::record(&mut TickEngine::new(42), &mut colony(), 1000, TRACE_INTERVAL);
+        assert_eq!(base.checkpoints.len(), 10);
+        assert!(base.diff(&base.clone()).is_empty());
+
+        let mut hungrier = colony();
+        hungrier.entities[0].hunger_rate = 0.2;
+        let changed = Trace::record(&mut TickEngine::new(42), &mut hungrier, 1000, TRACE_INTERVAL);
+
+        let diff = base.diff(&changed);
+        assert!(diff.lines[0].starts_with("state diverges at tick 100"), "{}", diff);
+        assert!(diff.to_string().contains("entity_ate: 2 -> 5"), "{}", diff);
+    }
+}
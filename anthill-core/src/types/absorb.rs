@@ -0,0 +1,87 @@
+//! Policies for merging another colony's state into this one.
+//!
+//! Used by `GameState::absorb` (e.g. finding an abandoned nest). Merging is
+//! deterministic: the other colony's entities and corpses are taken in their
+//! stored order, and renamed IDs depend only on what's already taken.
+
+use serde::{Deserialize, Serialize};
+
+use super::entity::EntityId;
+
+/// Which of the other colony's entities come along
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "ids", rename_all = "snake_case")]
+pub enum EntitySelection {
+    /// Every living entity
+    #[default]
+    All,
+    /// Ants only (visitors stay behind)
+    Ants,
+    /// Just these IDs
+    Ids(Vec<EntityId>),
+    /// Nobody
+    None,
+}
+
+/// What to do when an incoming ID is already taken
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdConflict {
+    /// Give the newcomer a fresh ID (`<id>~2`, `<id>~3`, ...)
+    #[default]
+    Rename,
+    /// Leave the newcomer behind
+    Skip,
+}
+
+/// How the other colony's resources are merged
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceMerge {
+    /// Add everything they had (store and stockpiles) to our colony store
+    #[default]
+    Sum,
+    /// Leave their resources behind
+    Ignore,
+}
+
+/// How `GameState::absorb` merges another colony
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbsorbPolicy {
+    #[serde(default)]
+    pub entities: EntitySelection,
+
+    #[serde(default)]
+    pub id_conflict: IdConflict,
+
+    #[serde(default)]
+    pub resources: ResourceMerge,
+
+    /// Bring their unprocessed corpses (and processed count) too
+    #[serde(default = "default_true")]
+    pub graveyard: bool,
+
+    /// Where newcomers whose tile we don't have end up
+    #[serde(default = "default_arrival_tile")]
+    pub arrival_tile: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_arrival_tile() -> String {
+    "origin".to_string()
+}
+
+impl Default for AbsorbPolicy {
+    fn default() -> Self {
+        Self {
+            entities: EntitySelection::default(),
+            id_conflict: IdConflict::default(),
+            resources: ResourceMerge::default(),
+            graveyard: default_true(),
+            arrival_tile: default_arrival_tile(),
+        }
+    }
+}
@@ -2,6 +2,39 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use thiserror::Error;
+
+use super::blueprint::ActionKind;
+use super::event_queue::EventQueue;
+use super::expedition::EXPEDITION_ACTION;
+use super::hosting::HOST_ACTION;
+use super::outpost::SUPPLY_ACTION;
+
+/// Action types the engine queues and completes itself
+pub const ENGINE_ACTIONS: [&str; 3] = [EXPEDITION_ACTION, SUPPLY_ACTION, HOST_ACTION];
+
+/// Why an action type can't be queued free-form
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ReservedAction {
+    #[error("{0} actions have a blueprint; queue them with start_action")]
+    Typed(ActionKind),
+
+    #[error("{0} actions are queued by the engine alone")]
+    Engine(String),
+}
+
+/// Check that an action of `action_type` may be queued free-form (by
+/// `EnqueueAction` or a spawn hook): it has no blueprint and isn't one the
+/// engine drives
+pub fn check_free_form(action_type: &str) -> Result<(), ReservedAction> {
+    if let Some(kind) = ActionKind::parse(action_type) {
+        return Err(ReservedAction::Typed(kind));
+    }
+    if ENGINE_ACTIONS.contains(&action_type) {
+        return Err(ReservedAction::Engine(action_type.to_string()));
+    }
+    Ok(())
+}
 
 /// An action in the queue
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,11 +49,22 @@ pub struct Action {
     /// Ticks remaining until completion
     pub ticks_remaining: u64,
 
+    /// What the action is aimed at (a tile, an outpost...), if anything
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+
     /// Effects to apply on completion
     #[serde(skip_serializing_if = "Option::is_none")]
     pub effects: Option<ActionEffects>,
 }
 
+impl Action {
+    /// The typed kind this action was queued as, if it has a blueprint
+    pub fn kind(&self) -> Option<ActionKind> {
+        ActionKind::parse(&self.action_type)
+    }
+}
+
 /// Effects applied when an action completes
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionEffects {
@@ -36,9 +80,10 @@ pub struct Queues {
     #[serde(default)]
     pub actions: Vec<Action>,
 
-    /// Pending events (for plugin layer)
+    /// Pending events for the plugin layer, with each consumer's cursor
+    /// (see `event_queue`)
     #[serde(default)]
-    pub events: Vec<serde_json::Value>,
+    pub events: EventQueue,
 }
 
 impl Queues {
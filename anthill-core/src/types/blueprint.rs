@@ -0,0 +1,172 @@
+//! Action blueprints: the kinds of work the engine knows how to queue.
+//!
+//! A blueprint declares what an action of its kind costs up front, how long
+//! it takes, which tiles it can be aimed at and what it yields on
+//! completion. `TickEngine::start_action` checks all of that before queuing,
+//! so a typed action is affordable and well aimed by construction. The
+//! library lives in `SimConfig::blueprints`, so a save can retune it.
+//!
+//! Free-form actions still exist for content-defined kinds the engine knows
+//! nothing about, but they can't borrow a blueprint's name.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use thiserror::Error;
+
+use super::key::Key;
+use super::tile::TileType;
+
+/// A kind of action with a blueprint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionKind {
+    Dig,
+    Clean,
+    Build,
+    Craft,
+    Ritual,
+}
+
+impl ActionKind {
+    pub const ALL: [ActionKind; 5] = [Self::Dig, Self::Clean, Self::Build, Self::Craft, Self::Ritual];
+
+    /// The `action_type` queued actions of this kind carry
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Dig => "dig",
+            Self::Clean => "clean",
+            Self::Build => "build",
+            Self::Craft => "craft",
+            Self::Ritual => "ritual",
+        }
+    }
+
+    /// The kind named by an `action_type`, if it has a blueprint
+    pub fn parse(action_type: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == action_type)
+    }
+
+    /// One line for encyclopedias
+    pub fn summary(self) -> &'static str {
+        match self {
+            Self::Dig => "Dig out open ground for dirt",
+            Self::Clean => "Scrub a compost tile free of contamination",
+            Self::Build => "Ready ground for a new system",
+            Self::Craft => "Work ore into crystals",
+            Self::Ritual => "Commune with the Outside for insight",
+        }
+    }
+}
+
+impl fmt::Display for ActionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What an action of one kind takes and gives
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Blueprint {
+    /// Paid from the colony's stores when the action is queued
+    #[serde(default)]
+    pub cost: BTreeMap<Key, f64>,
+
+    /// Seconds at 1 Hz, like the tuning durations
+    pub duration: u64,
+
+    /// Tile types the action can be aimed at. Empty means it needs no
+    /// target; otherwise it must have one of these.
+    #[serde(default)]
+    pub contexts: Vec<TileType>,
+
+    /// Added to the colony's stores on completion
+    #[serde(default)]
+    pub yields: BTreeMap<Key, f64>,
+}
+
+impl Blueprint {
+    fn new(cost: &[(&str, f64)], duration: u64, contexts: Vec<TileType>, yields: &[(&str, f64)]) -> Self {
+        let amounts = |pairs: &[(&str, f64)]| pairs.iter().map(|&(r, a)| (Key::new(r), a)).collect();
+        Self { cost: amounts(cost), duration, contexts, yields: amounts(yields) }
+    }
+
+    /// Can an action of this kind be aimed at a tile of type `tile_type`?
+    pub fn allows(&self, tile_type: &TileType) -> bool {
+        self.contexts.is_empty() || self.contexts.contains(tile_type)
+    }
+}
+
+/// Every blueprint a save knows, by kind
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Blueprints(pub BTreeMap<ActionKind, Blueprint>);
+
+impl Default for Blueprints {
+    fn default() -> Self {
+        use TileType::*;
+        Self(BTreeMap::from([
+            (ActionKind::Dig, Blueprint::new(&[], 60, vec![Empty, Extraction], &[("dirt", 10.0)])),
+            (ActionKind::Clean, Blueprint::new(&[("nutrients", 2.0)], 30, vec![Compost], &[])),
+            (ActionKind::Build, Blueprint::new(&[("dirt", 20.0)], 120, vec![Empty, Production], &[])),
+            (ActionKind::Craft, Blueprint::new(&[("ore", 2.0), ("fungus", 2.0)], 90, vec![Production], &[("crystals", 1.0)])),
+            (ActionKind::Ritual, Blueprint::new(&[("influence", 1.0)], 60, vec![Special, Antenna], &[("insight", 1.0)])),
+        ]))
+    }
+}
+
+impl Blueprints {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn get(&self, kind: ActionKind) -> Option<&Blueprint> {
+        self.0.get(&kind)
+    }
+}
+
+/// Why a typed action was refused
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum BlueprintError {
+    #[error("this save has no blueprint for {0}")]
+    NoBlueprint(ActionKind),
+
+    #[error("an action with ID {0} is already queued")]
+    ActionExists(String),
+
+    #[error("{0} actions must be aimed at a tile")]
+    NeedsTarget(ActionKind),
+
+    #[error("tile {0} does not exist")]
+    UnknownTile(String),
+
+    #[error("{kind} actions can't be aimed at {tile}, a {tile_type:?} tile")]
+    WrongContext { kind: ActionKind, tile: String, tile_type: TileType },
+
+    #[error("need {needed} {resource}, have {available}")]
+    InsufficientResources { resource: Key, needed: f64, available: f64 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kinds_round_trip_and_have_blueprints() {
+        let blueprints = Blueprints::default();
+        for kind in ActionKind::ALL {
+            assert_eq!(ActionKind::parse(kind.as_str()), Some(kind));
+            assert_eq!(serde_json::to_value(kind).unwrap(), kind.as_str());
+            assert!(blueprints.get(kind).is_some_and(|b| b.duration > 0), "{kind}");
+        }
+        assert_eq!(ActionKind::parse("haul_supply"), None);
+
+        let dig = blueprints.get(ActionKind::Dig).unwrap();
+        assert!(dig.allows(&TileType::Extraction));
+        assert!(!dig.allows(&TileType::Compost));
+
+        let json = serde_json::to_value(&blueprints).unwrap();
+        assert_eq!(json["craft"]["cost"]["ore"], 2.0);
+        assert_eq!(serde_json::from_value::<Blueprints>(json).unwrap(), blueprints);
+    }
+}
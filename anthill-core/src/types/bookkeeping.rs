@@ -0,0 +1,27 @@
+//! The engine's own memory, saved with the state it ticks.
+//!
+//! A `TickEngine` tracks a few things between ticks (when the queen last
+//! laid, when the receiver last tried). It writes them into
+//! `GameState::bookkeeping` after every tick and reads them back before the
+//! next, so a reloaded save picks up exactly where it left off, even under
+//! a fresh engine. The RNG needs no saving: every tick's stream is made
+//! afresh from the engine's seed and the tick. Saves from before this
+//! existed have none; `TickEngine::init_from_state` infers what it can for
+//! those.
+
+use serde::{Deserialize, Serialize};
+
+/// What the engine remembers between ticks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bookkeeping {
+    /// Tick of the last queen spawn (0 before the queen starts her clock)
+    pub last_spawn_tick: u64,
+
+    /// Tick of the last summon attempt (0 if none yet)
+    pub last_summon_tick: u64,
+
+    /// Tick this was written at, to catch a clock moved since (see
+    /// `advance`); None in saves from before it was kept
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tick: Option<u64>,
+}
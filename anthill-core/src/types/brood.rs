@@ -0,0 +1,85 @@
+//! Brood: ants the queen has laid but who haven't hatched yet.
+//!
+//! Brood eat while they develop. A larva that can't be fed stalls rather
+//! than dies, so a famine delays the next generation instead of wasting it.
+//! With `feature::NURSES`, nurses do the feeding: untended larvae grow
+//! slowly, and die if neglected for too long. The queen adds a nurse to
+//! her clutch whenever the colony has none grown or growing.
+//!
+//! With `feature::LIFECYCLE`, brood pass through three stages instead of
+//! one: eggs (which may never hatch), larvae (which eat, and starve if
+//! they go unfed too long) and pupae, then emerge as adults. Older saves'
+//! brood load as larvae.
+
+use serde::{Deserialize, Serialize};
+
+use super::entity::{AntRole, EntityId, LifeStage};
+use super::heredity::Trait;
+
+/// A developing ant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Brood {
+    /// The ID it will hatch with
+    pub id: EntityId,
+
+    pub role: AntRole,
+
+    /// Where it will hatch
+    pub tile: String,
+
+    pub laid_at: u64,
+
+    /// Egg, larva or pupa
+    #[serde(default = "larva")]
+    pub stage: LifeStage,
+
+    /// Ticks still needed before it moves on from this stage (fed ticks,
+    /// for a larva)
+    pub ticks_remaining: u64,
+
+    /// Ticks in a row no nurse has tended it
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub neglected: u64,
+
+    /// Ticks in a row a larva has gone unfed (with `feature::LIFECYCLE`)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub starving: u64,
+
+    /// What it will be born with (see `heredity`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub traits: Vec<Trait>,
+}
+
+/// How a larva (or egg) was lost
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BroodLoss {
+    /// An egg that was never going to hatch
+    Addled,
+    /// Unfed for `larva_starvation_limit`
+    Starved,
+    /// No nurse for `brood_neglect_limit`
+    Neglected,
+}
+
+fn larva() -> LifeStage {
+    LifeStage::Larva
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_older_brood_load_as_larvae() {
+        let json = r#"{"id":"b1","role":"worker","tile":"origin","laid_at":3,"ticks_remaining":40}"#;
+        let brood: Brood = serde_json::from_str(json).unwrap();
+        assert_eq!(brood.stage, LifeStage::Larva);
+        assert_eq!((brood.neglected, brood.starving), (0, 0));
+        assert_eq!(serde_json::to_string(&brood).unwrap(), json.replace(r#","ticks"#, r#","stage":"larva","ticks"#));
+    }
+}
@@ -0,0 +1,213 @@
+//! Columnar saves: the state's big tables written a field at a time.
+//!
+//! Most of a large colony's save is its entities, brood and corpses, every
+//! row spelling out the same field names, and often the same values. Saved
+//! with `SaveOptions::columnar`, each of those tables is written as one
+//! array per field instead (`{"rows": n, "columns": {"hunger": [...]}}`,
+//! null where a row leaves the field out), a field every row shares is
+//! written once as `{"each": value}`, and the save is marked
+//! `"layout": "columnar"`. Loading expands the tables back into rows before
+//! migrations or anything else see the document, so either layout loads
+//! through the same `from_json`.
+//!
+//! A field a row sets to null reads back as left out, which is the same
+//! thing to every table here.
+//!
+//! It pays only for big tables: a colony of a couple of thousand ants
+//! saves in about a third of the bytes and loads a little quicker, but
+//! writes slower (see `test_columnar_saves_load_like_row_saves`). A save
+//! with a handful of rows comes out about the same size either way, so
+//! row saves stay the default.
+
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+/// Top-level key marking a columnar save
+pub const LAYOUT_KEY: &str = "layout";
+
+/// Value of `LAYOUT_KEY` in a columnar save
+pub const COLUMNAR: &str = "columnar";
+
+/// Key of a column every row shares
+const EACH: &str = "each";
+
+/// Tables written in columns, as paths from the top of the state
+pub const TABLES: [&[&str]; 3] = [&["entities"], &["brood"], &["graveyard", "corpses"]];
+
+/// How to write a save
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SaveOptions {
+    /// Write the big tables in columns
+    pub columnar: bool,
+}
+
+impl SaveOptions {
+    pub fn columnar() -> Self {
+        Self { columnar: true }
+    }
+}
+
+/// Why a columnar save couldn't be expanded
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ColumnarError {
+    #[error("unknown save layout `{0}`")]
+    UnknownLayout(String),
+
+    #[error("table `{table}` is not in columns")]
+    Malformed { table: String },
+
+    #[error("column `{column}` of table `{table}` has {found} values for {rows} rows")]
+    Ragged { table: String, column: String, rows: usize, found: usize },
+}
+
+/// Write `doc`'s tables in columns and mark it columnar
+pub fn compact(doc: &mut Value) {
+    let Some(state) = doc.as_object_mut() else {
+        return;
+    };
+    for path in TABLES {
+        if let Some(table) = table_mut(state, path) {
+            if let Some(columns) = to_columns(table) {
+                *table = columns;
+            }
+        }
+    }
+    state.insert(LAYOUT_KEY.to_string(), Value::from(COLUMNAR));
+}
+
+/// Turn a columnar `doc` back into rows, dropping the mark; other saves are
+/// left as they are
+pub fn expand(doc: &mut Value) -> Result<(), ColumnarError> {
+    let Some(state) = doc.as_object_mut() else {
+        return Ok(());
+    };
+    match state.remove(LAYOUT_KEY) {
+        None => return Ok(()),
+        Some(Value::String(layout)) if layout == COLUMNAR => {}
+        Some(other) => return Err(ColumnarError::UnknownLayout(other.to_string())),
+    }
+    for path in TABLES {
+        if let Some(table) = table_mut(state, path) {
+            *table = to_rows(table, &path.join("."))?;
+        }
+    }
+    Ok(())
+}
+
+fn table_mut<'a>(state: &'a mut Map<String, Value>, path: &[&str]) -> Option<&'a mut Value> {
+    let (last, parents) = path.split_last()?;
+    let mut at = state;
+    for parent in parents {
+        at = at.get_mut(*parent)?.as_object_mut()?;
+    }
+    at.get_mut(*last)
+}
+
+/// A list of objects as columns (None if it isn't one)
+fn to_columns(table: &Value) -> Option<Value> {
+    let rows = table.as_array()?;
+    let rows: Vec<&Map<String, Value>> = rows.iter().map(Value::as_object).collect::<Option<_>>()?;
+
+    // Map keys are sorted, so the columns come out in the same order on
+    // every host
+    let mut columns: Map<String, Value> = Map::new();
+    for field in rows.iter().flat_map(|row| row.keys()) {
+        columns.entry(field.clone()).or_insert(Value::Null);
+    }
+    for (field, column) in columns.iter_mut() {
+        let values: Vec<Value> = rows.iter().map(|row| row.get(field).cloned().unwrap_or(Value::Null)).collect();
+        *column = match values.split_first() {
+            Some((first, rest)) if rest.iter().all(|value| value == first) => {
+                Value::Object(Map::from_iter([(EACH.to_string(), first.clone())]))
+            }
+            _ => Value::Array(values),
+        };
+    }
+
+    let mut table = Map::new();
+    table.insert("rows".to_string(), Value::from(rows.len()));
+    table.insert("columns".to_string(), Value::Object(columns));
+    Some(Value::Object(table))
+}
+
+fn to_rows(table: &Value, name: &str) -> Result<Value, ColumnarError> {
+    let malformed = || ColumnarError::Malformed { table: name.to_string() };
+    let rows = table.get("rows").and_then(Value::as_u64).ok_or_else(malformed)? as usize;
+    let columns = table.get("columns").and_then(Value::as_object).ok_or_else(malformed)?;
+
+    let mut out = vec![Map::new(); rows];
+    for (field, column) in columns {
+        if let Some(value) = column.as_object().and_then(|shared| shared.get(EACH)) {
+            if !value.is_null() {
+                for row in out.iter_mut() {
+                    row.insert(field.clone(), value.clone());
+                }
+            }
+            continue;
+        }
+        let values = column.as_array().ok_or_else(malformed)?;
+        if values.len() != rows {
+            return Err(ColumnarError::Ragged {
+                table: name.to_string(),
+                column: field.clone(),
+                rows,
+                found: values.len(),
+            });
+        }
+        for (row, value) in out.iter_mut().zip(values) {
+            if !value.is_null() {
+                row.insert(field.clone(), value.clone());
+            }
+        }
+    }
+    Ok(Value::Array(out.into_iter().map(Value::Object).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_tables_round_trip_through_columns() {
+        let original = json!({
+            "tick": 3,
+            "entities": [{"id": "a", "hunger": 1.0}, {"id": "b", "hunger": 2.0, "carrying": {"ore": 1.0}}],
+            "brood": [],
+            "graveyard": {"corpses": [{"id": "c"}]},
+        });
+        let mut doc = original.clone();
+        compact(&mut doc);
+        assert_eq!(doc["layout"], "columnar");
+        assert_eq!(doc["entities"], json!({
+            "rows": 2,
+            "columns": {"carrying": [null, {"ore": 1.0}], "hunger": [1.0, 2.0], "id": ["a", "b"]},
+        }));
+        assert_eq!(doc["graveyard"]["corpses"], json!({"rows": 1, "columns": {"id": {"each": "c"}}}));
+        assert_eq!(doc["brood"], json!({"rows": 0, "columns": {}}));
+
+        expand(&mut doc).unwrap();
+        assert_eq!(doc, original);
+
+        // Row saves pass untouched
+        expand(&mut doc).unwrap();
+        assert_eq!(doc, original);
+    }
+
+    #[test]
+    fn test_broken_columns_are_refused() {
+        let mut ragged = json!({"layout": "columnar", "entities": {"rows": 2, "columns": {"id": ["a"]}}});
+        assert_eq!(expand(&mut ragged), Err(ColumnarError::Ragged {
+            table: "entities".to_string(),
+            column: "id".to_string(),
+            rows: 2,
+            found: 1,
+        }));
+
+        let mut rows = json!({"layout": "columnar", "entities": [{"id": "a"}]});
+        assert_eq!(expand(&mut rows), Err(ColumnarError::Malformed { table: "entities".to_string() }));
+
+        let mut unknown = json!({"layout": "parquet"});
+        assert!(matches!(expand(&mut unknown), Err(ColumnarError::UnknownLayout(_))));
+    }
+}
@@ -0,0 +1,177 @@
+//! Commands: validated, event-emitting changes from outside the tick.
+//!
+//! Hosts and plugins hand a `Command` to `TickEngine::apply_command` rather
+//! than poking state fields, so every outside change is checked first and
+//! shows up in the event stream like anything else that happens.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::action::{Action, ReservedAction};
+use super::blueprint::{ActionKind, BlueprintError};
+use super::entity::{AntRole, EntityId};
+use super::excavation::ExcavationError;
+use super::expedition::ExpeditionError;
+use super::group::{GroupError, Intent};
+use super::hosting::HostingError;
+use super::legacy::{AbandonError, RuinError};
+use super::naming::NameError;
+use super::outpost::OutpostError;
+use super::pause::Subsystem;
+use super::policy::WorkPolicy;
+use super::scenario::Scenario;
+use super::system::System;
+use super::tile::{MapError, Tile};
+
+/// A change to the colony requested from outside the tick
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Command {
+    /// Add a new system under `id`
+    BuildSystem { id: String, system: Box<System> },
+
+    /// Give an ant a new role
+    AssignRole { entity_id: EntityId, role: AntRole },
+
+    /// Queue a content-defined action (typed kinds go through
+    /// `StartAction`)
+    EnqueueAction { action: Action },
+
+    /// `TickEngine::start_action`
+    StartAction {
+        id: String,
+        kind: ActionKind,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        target: Option<String>,
+    },
+
+    /// `TickEngine::add_tile`
+    AddTile { id: String, tile: Box<Tile>, connect_to: String },
+
+    /// `TickEngine::connect_tiles`
+    ConnectTiles { a: String, b: String },
+
+    /// `TickEngine::disconnect_tiles`
+    DisconnectTiles { a: String, b: String },
+
+    /// `TickEngine::demolish_tile`
+    DemolishTile { tile: String },
+
+    /// Put an entity on another tile
+    MoveEntity { entity_id: EntityId, tile: String },
+
+    /// Give a tile a player's name (see `naming`)
+    RenameTile { tile: String, name: String },
+
+    /// Give an entity a player's name
+    RenameEntity { entity_id: EntityId, name: String },
+
+    /// Replace the work policy (`TickEngine::set_work_policy`)
+    SetWorkPolicy { policy: WorkPolicy },
+
+    /// Pause or resume a phase (`TickEngine::set_paused`)
+    SetPaused { subsystem: Subsystem, paused: bool },
+
+    /// Opt in to or out of a feature flag (`TickEngine::set_feature`)
+    SetFeature { feature: String, enabled: bool },
+
+    /// `TickEngine::form_group`
+    FormGroup { group: String, members: Vec<EntityId> },
+
+    /// `TickEngine::set_group_intent`
+    SetGroupIntent { group: String, intent: Intent },
+
+    /// `TickEngine::disband_group`
+    DisbandGroup { group: String },
+
+    /// `TickEngine::launch_expedition`
+    LaunchExpedition { members: Vec<EntityId>, duration_ticks: u64 },
+
+    /// `TickEngine::establish_outpost`
+    EstablishOutpost { tile: String },
+
+    /// `TickEngine::dispatch_supplies`
+    DispatchSupplies { tile: String },
+
+    /// `TickEngine::dig_tile`
+    DigTile { id: String, from: String },
+
+    /// `TickEngine::excavate_ruin`
+    ExcavateRuin { tile: String },
+
+    /// `TickEngine::abandon_colony`
+    AbandonColony,
+
+    /// `TickEngine::host_visitor`
+    HostVisitor { visitor_id: EntityId },
+
+    /// `TickEngine::stop_hosting`
+    StopHosting { visitor_id: EntityId },
+
+    /// `TickEngine::start_scenario`
+    StartScenario { scenario: Box<Scenario> },
+}
+
+/// Why a command was refused (state is untouched)
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum CommandError {
+    #[error("a system with ID {0} already exists")]
+    SystemExists(String),
+
+    #[error("tile {0} does not exist")]
+    UnknownTile(String),
+
+    #[error("entity {0} not found in the colony")]
+    UnknownEntity(EntityId),
+
+    #[error("entity {0} is not an ant")]
+    NotAnAnt(EntityId),
+
+    #[error("undertaker {0} is carrying a corpse")]
+    CarryingCorpse(EntityId),
+
+    #[error("ant {0} can't take or leave a royal role; queens are crowned, not assigned")]
+    Royal(EntityId),
+
+    #[error("an action with ID {0} is already queued")]
+    ActionExists(String),
+
+    #[error("action {0} must last at least one tick")]
+    ZeroDurationAction(String),
+
+    #[error(transparent)]
+    Reserved(#[from] ReservedAction),
+
+    #[error(transparent)]
+    Blueprint(#[from] BlueprintError),
+
+    #[error(transparent)]
+    Expedition(#[from] ExpeditionError),
+
+    #[error(transparent)]
+    Outpost(#[from] OutpostError),
+
+    #[error(transparent)]
+    Excavation(#[from] ExcavationError),
+
+    #[error(transparent)]
+    Group(#[from] GroupError),
+
+    #[error(transparent)]
+    Map(#[from] MapError),
+
+    #[error(transparent)]
+    Ruin(#[from] RuinError),
+
+    #[error(transparent)]
+    Abandon(#[from] AbandonError),
+
+    #[error(transparent)]
+    Hosting(#[from] HostingError),
+
+    #[error(transparent)]
+    Name(#[from] NameError),
+
+    #[error("the run ended at tick {0}")]
+    RunEnded(u64),
+}
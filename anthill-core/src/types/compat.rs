@@ -0,0 +1,192 @@
+//! Save compatibility: which build wrote a save, and whether this one
+//! should load it.
+//!
+//! `GameState::to_json` stamps every save with the core's version and a
+//! digest of the state's config. `GameState::from_json_checked` reads the
+//! stamp back and returns a `Compatibility` verdict alongside the state,
+//! so a host can warn or refuse up front instead of finding out from odd
+//! behaviour an hour in. Versions follow semver, with the 0.x convention
+//! that a minor bump is a breaking one. Saves from before stamping are
+//! judged on their schema version alone.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+use crate::digest;
+use super::config::SimConfig;
+use super::envelope;
+use super::migrations::SCHEMA_VERSION;
+
+/// Version of anthill-core this build is
+pub const CORE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// What a save records about the build that wrote it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SaveStamp {
+    /// Version of anthill-core that wrote the save
+    pub core_version: String,
+
+    /// Digest of the state's config when it was written (hex)
+    pub config_digest: String,
+}
+
+impl SaveStamp {
+    /// The stamp this build writes for a state run under `config`
+    pub fn current(config: &SimConfig) -> Self {
+        Self {
+            core_version: CORE_VERSION.to_string(),
+            config_digest: config_digest(config),
+        }
+    }
+}
+
+/// Digest of a config as stamped into saves (hex)
+pub fn config_digest(config: &SimConfig) -> String {
+    envelope::hex(digest::digest(config).unwrap_or(0))
+}
+
+/// A `major.minor.patch` version; pre-release and build suffixes are ignored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    pub fn parse(version: &str) -> Option<Self> {
+        let core = version.split(['-', '+']).next()?;
+        let mut parts = core.split('.').map(|part| part.parse().ok());
+        let version = Self { major: parts.next()??, minor: parts.next()??, patch: parts.next()?? };
+        parts.next().is_none().then_some(version)
+    }
+
+    /// Whether saves from `self` and `other` share a format line: same
+    /// major, or for 0.x, same minor
+    pub fn same_line(self, other: Version) -> bool {
+        match self.major {
+            0 => other.major == 0 && self.minor == other.minor,
+            major => other.major == major,
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// How a save relates to this build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "verdict", rename_all = "snake_case")]
+pub enum Compatibility {
+    /// Written by this version under the config it loads with
+    Same,
+    /// Written by an older build on the same line (or before saves were
+    /// stamped), or its config has picked up new defaults since; it loads
+    /// as is
+    MinorUpgrade { from: Option<String> },
+    /// An older save format; it loads after migrating
+    NeedsMigration { from_schema: u32 },
+    /// This build shouldn't run it
+    Incompatible { reason: Incompatibility },
+}
+
+impl Compatibility {
+    pub fn is_loadable(&self) -> bool {
+        !matches!(self, Compatibility::Incompatible { .. })
+    }
+}
+
+/// Why a save can't be loaded by this build
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Incompatibility {
+    #[error("save schema version {found} is newer than this build supports ({supported})")]
+    SchemaTooNew { found: u32, supported: u32 },
+
+    #[error("save was written by core {found}, which isn't a version")]
+    BadVersion { found: String },
+
+    #[error("save was written by core {found}, a different line from this build ({running})")]
+    OtherLine { found: String, running: String },
+
+    #[error("save was written by core {found}, newer than this build ({running})")]
+    TooNew { found: String, running: String },
+}
+
+/// The verdict on a save at `schema` version, stamped with `stamp` (if
+/// any), whose config now loads as `config`
+pub fn check(schema: u32, stamp: Option<&SaveStamp>, config: &SimConfig) -> Compatibility {
+    let incompatible = |reason| Compatibility::Incompatible { reason };
+    if schema > SCHEMA_VERSION {
+        return incompatible(Incompatibility::SchemaTooNew { found: schema, supported: SCHEMA_VERSION });
+    }
+
+    if let Some(stamp) = stamp {
+        let running = Version::parse(CORE_VERSION).expect("the crate version is semver");
+        let Some(found) = Version::parse(&stamp.core_version) else {
+            return incompatible(Incompatibility::BadVersion { found: stamp.core_version.clone() });
+        };
+        if !running.same_line(found) {
+            return incompatible(Incompatibility::OtherLine { found: found.to_string(), running: running.to_string() });
+        }
+        if found > running {
+            return incompatible(Incompatibility::TooNew { found: found.to_string(), running: running.to_string() });
+        }
+    }
+
+    if schema < SCHEMA_VERSION {
+        return Compatibility::NeedsMigration { from_schema: schema };
+    }
+    match stamp {
+        Some(stamp) if stamp.core_version == CORE_VERSION && stamp.config_digest == config_digest(config) => Compatibility::Same,
+        stamp => Compatibility::MinorUpgrade { from: stamp.map(|stamp| stamp.core_version.clone()) },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_and_lines() {
+        let v = |s| Version::parse(s).unwrap();
+        assert_eq!(v("1.4.2-beta+7"), Version { major: 1, minor: 4, patch: 2 });
+        assert_eq!(Version::parse("1.4"), None);
+        assert_eq!(Version::parse("1.4.2.0"), None);
+        assert!(v("1.2.0").same_line(v("1.9.3")));
+        assert!(!v("0.1.0").same_line(v("0.2.0")));
+        assert!(v("0.1.0").same_line(v("0.1.9")));
+        assert!(v("0.1.10") > v("0.1.9"));
+    }
+
+    #[test]
+    fn test_check() {
+        let config = SimConfig::default();
+        let stamp = |version: &str| SaveStamp { core_version: version.to_string(), ..SaveStamp::current(&config) };
+        let running = Version::parse(CORE_VERSION).unwrap();
+
+        assert_eq!(check(SCHEMA_VERSION, Some(&SaveStamp::current(&config)), &config), Compatibility::Same);
+        assert_eq!(check(SCHEMA_VERSION, None, &config), Compatibility::MinorUpgrade { from: None });
+        assert_eq!(check(0, None, &config), Compatibility::NeedsMigration { from_schema: 0 });
+        assert!(matches!(check(SCHEMA_VERSION + 1, None, &config),
+            Compatibility::Incompatible { reason: Incompatibility::SchemaTooNew { .. } }));
+
+        // A config that reads differently now is no longer the same save
+        assert_eq!(
+            check(SCHEMA_VERSION, Some(&SaveStamp::current(&SimConfig::cruel())), &config),
+            Compatibility::MinorUpgrade { from: Some(CORE_VERSION.to_string()) },
+        );
+
+        let newer = Version { patch: running.patch + 1, ..running }.to_string();
+        assert!(matches!(check(SCHEMA_VERSION, Some(&stamp(&newer)), &config),
+            Compatibility::Incompatible { reason: Incompatibility::TooNew { .. } }));
+        let other = Version { major: running.major + 1, ..running }.to_string();
+        assert!(matches!(check(SCHEMA_VERSION, Some(&stamp(&other)), &config),
+            Compatibility::Incompatible { reason: Incompatibility::OtherLine { .. } }));
+        assert!(!check(SCHEMA_VERSION, Some(&stamp("soon")), &config).is_loadable());
+    }
+}
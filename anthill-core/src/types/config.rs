@@ -0,0 +1,753 @@
+//! Difficulty tuning recorded with the save.
+//!
+//! `engine::constants` holds the reference values. A `SimConfig` carries them
+//! as `Tuning` for downstream layers to adjust, and scales the ones that
+//! decide how hard the colony has it. The classic preset is the identity, so
+//! saves without a config run exactly as before.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::engine::constants;
+
+use super::blueprint::Blueprints;
+use super::entity::{AntRole, Entity, VisitorType};
+use super::key::Key;
+use super::spawn::SpawnHooks;
+
+/// Which bundle a config came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    Gentle,
+    #[default]
+    Classic,
+    Cruel,
+    /// No disasters: see `SimConfig::zen`
+    Zen,
+    /// Hand-tuned; doesn't match any bundle
+    Custom,
+}
+
+/// Difficulty multipliers applied on top of the engine constants
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimConfig {
+    pub preset: Preset,
+
+    /// Scales every entity's hunger_rate
+    pub hunger_rate_scale: f64,
+
+    /// Scales the per-tick blight roll (contamination is the base chance)
+    pub blight_chance_scale: f64,
+
+    /// Scales the default receiver maintenance interval
+    pub maintenance_interval_scale: f64,
+
+    /// Scales the strange matter each maintenance costs
+    pub maintenance_cost_scale: f64,
+
+    /// Scales how often raids arrive
+    pub raid_frequency_scale: f64,
+
+    /// Blight can strike contaminated tiles
+    pub blight: bool,
+
+    /// Raids can arrive
+    pub raids: bool,
+
+    /// Starving entities die; when off they go dormant until fed
+    pub starvation_deaths: bool,
+
+    /// What the queen spends to lay one ant of each role
+    pub spawn_costs: BTreeMap<AntRole, BTreeMap<Key, f64>>,
+
+    /// What an ant of each role is born with (see `with_baseline`)
+    #[serde(skip_serializing_if = "is_default_ant_baselines")]
+    pub ant_baselines: BTreeMap<AntRole, Baseline>,
+
+    /// What a visitor of each type arrives with
+    #[serde(skip_serializing_if = "is_default_visitor_baselines")]
+    pub visitor_baselines: BTreeMap<VisitorType, Baseline>,
+
+    /// Values the tick phases run on (the scales above apply on top)
+    #[serde(skip_serializing_if = "Tuning::is_default")]
+    pub tuning: Tuning,
+
+    /// Milestones that fire `ThresholdCrossed`
+    #[serde(skip_serializing_if = "Thresholds::is_default")]
+    pub thresholds: Thresholds,
+
+    /// What each typed action costs, takes and gives
+    #[serde(skip_serializing_if = "Blueprints::is_default")]
+    pub blueprints: Blueprints,
+}
+
+impl SimConfig {
+    /// Slower hunger, rarer blight, cheap and patient receiver
+    pub fn gentle() -> Self {
+        Self {
+            preset: Preset::Gentle,
+            hunger_rate_scale: 0.75,
+            blight_chance_scale: 0.5,
+            maintenance_interval_scale: 1.5,
+            maintenance_cost_scale: 0.5,
+            raid_frequency_scale: 0.5,
+            ..Self::classic()
+        }
+    }
+
+    /// The reference tuning: the constants as written
+    pub fn classic() -> Self {
+        Self {
+            preset: Preset::Classic,
+            hunger_rate_scale: 1.0,
+            blight_chance_scale: 1.0,
+            maintenance_interval_scale: 1.0,
+            maintenance_cost_scale: 1.0,
+            raid_frequency_scale: 1.0,
+            blight: true,
+            raids: true,
+            starvation_deaths: true,
+            spawn_costs: default_spawn_costs(),
+            ant_baselines: default_ant_baselines(),
+            visitor_baselines: default_visitor_baselines(),
+            tuning: Tuning::default(),
+            thresholds: Thresholds::default(),
+            blueprints: Blueprints::default(),
+        }
+    }
+
+    /// Faster hunger, twice the blight, a demanding receiver
+    pub fn cruel() -> Self {
+        Self {
+            preset: Preset::Cruel,
+            hunger_rate_scale: 1.5,
+            blight_chance_scale: 2.0,
+            maintenance_interval_scale: 0.5,
+            maintenance_cost_scale: 2.0,
+            raid_frequency_scale: 2.0,
+            ..Self::classic()
+        }
+    }
+
+    /// Classic tuning with the disasters switched off: no blight, no raids,
+    /// and starving entities go dormant instead of dying
+    pub fn zen() -> Self {
+        Self {
+            preset: Preset::Zen,
+            blight: false,
+            raids: false,
+            starvation_deaths: false,
+            ..Self::classic()
+        }
+    }
+
+    /// Look up a preset by its serialized name
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "gentle" => Some(Self::gentle()),
+            "classic" => Some(Self::classic()),
+            "cruel" => Some(Self::cruel()),
+            "zen" => Some(Self::zen()),
+            _ => None,
+        }
+    }
+
+    pub fn is_classic(&self) -> bool {
+        *self == Self::classic()
+    }
+
+    /// Total cost of laying one ant of each of `roles`
+    pub fn spawn_cost(&self, roles: &[AntRole]) -> BTreeMap<Key, f64> {
+        let mut total = BTreeMap::new();
+        for role in roles {
+            for (resource, amount) in self.spawn_costs.get(role).into_iter().flatten() {
+                *total.entry(*resource).or_default() += amount;
+            }
+        }
+        total
+    }
+
+    /// `entity` as this config spawns it: with its role's or visitor
+    /// type's baseline stats, if the config has one. Applied once, at
+    /// birth or arrival, so entities already about keep what they have.
+    pub fn with_baseline(&self, mut entity: Entity) -> Entity {
+        if let Some(baseline) = self.baseline(&entity) {
+            entity.hunger_rate = baseline.hunger_rate;
+            entity.max_age = baseline.max_age;
+            entity.food = baseline.food.clone();
+        }
+        entity
+    }
+
+    /// The baseline `entity`'s role or visitor type spawns with
+    pub fn baseline(&self, entity: &Entity) -> Option<&Baseline> {
+        match (&entity.role, &entity.subtype) {
+            (Some(role), _) => self.ant_baselines.get(role),
+            (None, Some(subtype)) => self.visitor_baselines.get(subtype),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Stats an entity starts out with, before traits
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    /// Hunger lost per second, before `hunger_rate_scale`
+    pub hunger_rate: f64,
+
+    /// Seconds it lives (ants) or stays (visitors)
+    pub max_age: u64,
+
+    /// Resource it eats (None: it never does)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub food: Option<String>,
+
+    /// What happens when one spawns (see `spawn`)
+    #[serde(default, skip_serializing_if = "SpawnHooks::is_empty")]
+    pub on_spawn: SpawnHooks,
+}
+
+impl Baseline {
+    /// The stats `entity` has now
+    pub fn of(entity: &Entity) -> Self {
+        Self {
+            hunger_rate: entity.hunger_rate,
+            max_age: entity.max_age,
+            food: entity.food.clone(),
+            on_spawn: SpawnHooks::default(),
+        }
+    }
+}
+
+/// Every role as its constructor makes it
+fn default_ant_baselines() -> BTreeMap<AntRole, Baseline> {
+    [AntRole::Worker, AntRole::Undertaker, AntRole::Hauler, AntRole::Forager, AntRole::Soldier, AntRole::Nurse, AntRole::Queen, AntRole::Princess]
+        .into_iter()
+        .map(|role| (role.clone(), Baseline::of(&Entity::new_ant(role, String::new(), String::new()))))
+        .collect()
+}
+
+fn is_default_ant_baselines(baselines: &BTreeMap<AntRole, Baseline>) -> bool {
+    *baselines == default_ant_baselines()
+}
+
+/// Every visitor type as its constructor makes it
+fn default_visitor_baselines() -> BTreeMap<VisitorType, Baseline> {
+    [VisitorType::Wanderer, VisitorType::Observer, VisitorType::Hungry]
+        .into_iter()
+        .map(|subtype| (subtype.clone(), Baseline::of(&Entity::new_visitor(subtype, String::new()))))
+        .collect()
+}
+
+fn is_default_visitor_baselines(baselines: &BTreeMap<VisitorType, Baseline>) -> bool {
+    *baselines == default_visitor_baselines()
+}
+
+/// Every ant costs nutrients and fungus; a worker + undertaker pair comes
+/// to the original flat 10 + 10, and a princess to five times one ant.
+/// Queens are never laid, only crowned.
+fn default_spawn_costs() -> BTreeMap<AntRole, BTreeMap<Key, f64>> {
+    [AntRole::Worker, AntRole::Undertaker, AntRole::Hauler, AntRole::Forager, AntRole::Soldier, AntRole::Nurse, AntRole::Princess]
+        .into_iter()
+        .map(|role| {
+            let each = if role == AntRole::Princess { 25.0 } else { 5.0 };
+            let mut cost: BTreeMap<Key, f64> = [(Key::new("nutrients"), each), (Key::new("fungus"), each)].into_iter().collect();
+            if role == AntRole::Soldier {
+                cost.insert(Key::new("ore"), constants::SOLDIER_ORE_COST);
+            }
+            (role, cost)
+        })
+        .collect()
+}
+
+/// The engine's tuning values, one field per `engine::constants` entry that
+/// a tick phase reads. Defaults are the constants themselves; durations are
+/// seconds at 1 Hz, like the constants.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tuning {
+    // Entities
+    /// Hunger below which an entity eats
+    pub hunger_threshold_eat: f64,
+    /// Hunger one unit of food restores
+    pub hunger_gain_from_eating: f64,
+    pub max_hunger: f64,
+    /// Health lost per second with an empty stomach
+    pub starvation_damage: f64,
+    /// Health lost per second on a blighted tile
+    pub blight_damage: f64,
+    /// Health lost per second on a tile, times its contamination
+    pub sickness_damage: f64,
+    /// Health regained per second while well fed and unharmed
+    pub health_regen: f64,
+    /// Share of the gap to the colony's sanity morale closes per second
+    pub morale_drift: f64,
+    /// Hunger below which an ant is famished
+    pub famine_hunger: f64,
+    /// Morale lost per second while famished
+    pub famine_morale_drain: f64,
+    /// Morale every ant on a tile loses for each death there
+    pub death_morale_shock: f64,
+    /// Chance per second a despairing ant deserts, at no morale (scaled
+    /// down toward the despair line)
+    pub desertion_chance: f64,
+
+    // Queen
+    pub spawn_interval_ticks: u64,
+    /// Kept back on top of each spawn cost
+    pub spawn_reserve: f64,
+    /// Larval stage, or all of development without `feature::LIFECYCLE`
+    pub brood_development_ticks: u64,
+    pub egg_ticks: u64,
+    pub pupa_ticks: u64,
+    /// Share of eggs that never hatch
+    pub egg_failure_chance: f64,
+    /// Seconds unfed before a larva starves
+    pub larva_starvation_limit: u64,
+    /// Fungus per larva per second
+    pub brood_food_per_tick: f64,
+    /// Larvae one nurse can feed each tick
+    pub nurse_capacity: u64,
+    /// Untended larvae eat and grow one tick in this many
+    pub untended_brood_slowdown: u64,
+    /// Seconds untended before a larva dies
+    pub brood_neglect_limit: u64,
+    /// An untended queen lays this many times slower
+    pub untended_queen_slowdown: u64,
+    /// Seconds a queen lives from her crowning
+    pub queen_lifespan: u64,
+    /// Seconds before her death a queen lays a princess
+    pub succession_window: u64,
+    /// Odds each of the queen's traits passes to an ant she lays
+    pub trait_inherit_chance: f64,
+    /// Odds an ant is born with a trait the queen lacks
+    pub trait_mutation_chance: f64,
+
+    // Work
+    /// Dirt per digger per second
+    pub dig_yield: f64,
+
+    /// Resource per forager per second, while a tile lasts
+    pub harvest_yield: f64,
+
+    // Movement
+    /// Seconds to walk from one tile to a connected one
+    pub step_seconds: u64,
+    /// Trail an ant lays on each tile it steps onto (with `feature::TRAILS`)
+    pub trail_deposit: f64,
+    /// Share of every trail that fades each second
+    pub trail_decay: f64,
+
+    // Weather (with `feature::SEASONS`)
+    /// Seconds each season lasts; four make a year
+    pub season_length: u64,
+    /// Seconds between rolls of the sky
+    pub weather_spell: u64,
+    /// Chance a spell is rainy, before the season's odds
+    pub rain_chance: f64,
+    /// Chance a spell is a drought, before the season's odds
+    pub drought_chance: f64,
+
+    // Day and night (with `feature::DAY_NIGHT`)
+    /// Seconds from one dawn to the next
+    pub day_length: u64,
+    /// Share of each day that's night
+    pub night_share: f64,
+    /// Share of their work ants do at night
+    pub night_work_factor: f64,
+
+    // Floods and cave-ins (with `feature::HAZARDS`)
+    /// Chance per second, while it rains, that a low tile floods
+    pub flood_chance: f64,
+    /// Seconds a flood holds its tile
+    pub flood_duration: u64,
+    /// Chance per second that a freshly dug tile caves in
+    pub cave_in_chance: f64,
+    /// Seconds a dug tile stays fresh enough to cave in
+    pub cave_in_window: u64,
+    /// Seconds a cave-in holds its tile
+    pub cave_in_duration: u64,
+    /// Chance each entity on a flooded or caved-in tile dies
+    pub hazard_lethality: f64,
+
+    // Excavation
+    /// Dig work, in dirt dug, it takes to open a tile
+    pub excavation_work: f64,
+    /// Chance a dug square holds an ore vein
+    pub ore_vein_chance: f64,
+    /// Chance a dug square holds a crystal pocket
+    pub crystal_pocket_chance: f64,
+    pub ore_vein_stock: f64,
+    pub crystal_pocket_stock: f64,
+
+    // Undertakers and blight
+    pub corpse_processing_ticks: u64,
+    pub corpse_nutrient_boost: f64,
+    pub corpse_boost_duration: u64,
+    pub contamination_per_corpse: f64,
+    pub blight_duration: u64,
+
+    // Expeditions and outposts
+    /// Per member
+    pub expedition_loss_chance: f64,
+    pub expedition_discovery_chance: f64,
+    pub outpost_establish_cost_fungus: f64,
+    pub outpost_production_rate: f64,
+    pub outpost_supply_cost_fungus: f64,
+    pub outpost_supply_duration: u64,
+    pub outpost_haul_ticks_per_distance: u64,
+    pub outpost_decay_per_tick: f64,
+
+    // Hosting visitors
+    /// Seconds between hosting rounds
+    pub host_interval: u64,
+    /// Paid each hosting round
+    pub host_cost_fungus: f64,
+    pub host_cost_crystals: f64,
+    /// Seconds the first round adds to a visitor's stay
+    pub host_extension: u64,
+    /// Most seconds hosting can ever add to one visitor's stay
+    pub host_max_extension: u64,
+
+    // Logistics and shrines
+    /// Units moved per hauler per second
+    pub hauler_capacity: f64,
+    /// Ticks of output each system keeps in its `history` (0 keeps none)
+    pub production_history: usize,
+    /// Most goods an ant can carry at once (with `feature::CARRYING`)
+    pub carry_capacity: f64,
+    pub logistics_buffer_ticks: f64,
+    pub shrine_window_ticks: u64,
+    pub shrine_half_efficiency_volume: f64,
+
+    // Receiver and visitors
+    pub summon_cost: f64,
+    pub summon_cooldown: u64,
+    pub summon_chance: f64,
+    /// Influence listening costs each second
+    pub listening_drain: f64,
+    /// Strange matter a wanderer leaves when it goes
+    pub wanderer_gift: f64,
+    /// Antenna tiles a receiver needs to hear a convergence
+    pub convergence_antennas: usize,
+    /// Chance a convergence brings a third visitor rather than two
+    pub convergence_third_chance: f64,
+    /// Before `maintenance_interval_scale`
+    pub maintenance_interval: u64,
+    /// Before `maintenance_cost_scale`
+    pub maintenance_cost_strange_matter: f64,
+    /// Seconds of silence before a receiver goes dormant, then derelict
+    pub receiver_dormant_after: u64,
+    pub receiver_derelict_after: u64,
+    /// Restoring a dormant or derelict receiver, in maintenance payments
+    pub receiver_dormant_cost_scale: f64,
+    pub receiver_derelict_cost_scale: f64,
+    pub hungry_influence_consume: f64,
+    pub hungry_strange_matter_produce: f64,
+    pub hungry_hunger_gain: f64,
+
+    // Mood and memory
+    pub boredom_threshold: u64,
+    pub perception_sanity_threshold: f64,
+    /// At zero sanity
+    pub perception_glitch_chance: f64,
+    pub perception_max_distortion: f64,
+    pub reflection_stillness_ticks: u64,
+    pub reflection_aftermath_delay: u64,
+    pub reflection_major_threshold: f64,
+    /// Per haunted ruin per second
+    pub ruin_echo_chance: f64,
+    /// Per second, while any wonder is still unseen
+    pub wonder_chance: f64,
+
+    // Threats
+    /// Per second at the reference wealth, before `raid_frequency_scale`
+    pub raid_chance: f64,
+    pub raid_cooldown: u64,
+    /// Colony worth at which raids come at the base chance and strength
+    pub raid_wealth_reference: f64,
+    pub raid_strength: f64,
+    pub soldier_strength: f64,
+    /// Any other waking ant
+    pub civilian_strength: f64,
+    /// Per defender at even odds
+    pub raid_casualty_chance: f64,
+    /// Share of each store raiders take from an undefended colony
+    pub raid_plunder: f64,
+
+    // Influence and whispers
+    pub influence_soft_cap: f64,
+    /// Share of the influence past the soft cap lost per second
+    pub influence_decay: f64,
+    /// Per second, while there's influence to spend
+    pub whisper_chance: f64,
+    pub whisper_cost: f64,
+    pub whisper_insight: f64,
+    /// Worth of a promised gift, priced when whispered
+    pub whisper_promise_worth: f64,
+    pub whisper_promise_delay: u64,
+}
+
+impl Tuning {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Resource amounts that count as milestones on the way up
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Thresholds {
+    /// Milestone amounts, ascending
+    pub ladder: Vec<f64>,
+
+    /// Past the top of the ladder, keep going in 1-2.5-5 steps per decade
+    /// (2.5k, 5k, 10k, 25k...) so mature colonies still hit milestones
+    pub log_scale: bool,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            ladder: constants::RESOURCE_THRESHOLDS.to_vec(),
+            log_scale: false,
+        }
+    }
+}
+
+impl Thresholds {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Milestones passed going from `prev` to `current`, lowest first
+    pub fn crossed(&self, prev: f64, current: f64) -> Vec<f64> {
+        let passed = |threshold: f64| prev < threshold && current >= threshold;
+        let mut crossed: Vec<f64> = self.ladder.iter().copied().filter(|&t| passed(t)).collect();
+        if !self.log_scale || !current.is_finite() {
+            return crossed;
+        }
+
+        let top = self.ladder.iter().copied().fold(1.0, f64::max);
+        let mut decade = 10f64.powi(top.max(prev).log10().floor() as i32);
+        while decade <= current {
+            for step in [1.0, 2.5, 5.0] {
+                let threshold = decade * step;
+                if threshold > top && passed(threshold) {
+                    crossed.push(threshold);
+                }
+            }
+            decade *= 10.0;
+        }
+        crossed
+    }
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            // Entities
+            hunger_threshold_eat: constants::HUNGER_THRESHOLD_EAT,
+            hunger_gain_from_eating: constants::HUNGER_GAIN_FROM_EATING,
+            max_hunger: constants::MAX_HUNGER,
+            starvation_damage: constants::STARVATION_DAMAGE,
+            blight_damage: constants::BLIGHT_DAMAGE,
+            sickness_damage: constants::SICKNESS_DAMAGE,
+            health_regen: constants::HEALTH_REGEN,
+            morale_drift: constants::MORALE_DRIFT,
+            famine_hunger: constants::FAMINE_HUNGER,
+            famine_morale_drain: constants::FAMINE_MORALE_DRAIN,
+            death_morale_shock: constants::DEATH_MORALE_SHOCK,
+            desertion_chance: constants::DESERTION_CHANCE,
+            // Queen
+            spawn_interval_ticks: constants::SPAWN_INTERVAL_TICKS,
+            nurse_capacity: constants::NURSE_CAPACITY,
+            untended_brood_slowdown: constants::UNTENDED_BROOD_SLOWDOWN,
+            brood_neglect_limit: constants::BROOD_NEGLECT_LIMIT,
+            untended_queen_slowdown: constants::UNTENDED_QUEEN_SLOWDOWN,
+            queen_lifespan: constants::QUEEN_LIFESPAN,
+            succession_window: constants::SUCCESSION_WINDOW,
+            trait_inherit_chance: constants::TRAIT_INHERIT_CHANCE,
+            trait_mutation_chance: constants::TRAIT_MUTATION_CHANCE,
+            spawn_reserve: constants::SPAWN_RESERVE,
+            brood_development_ticks: constants::BROOD_DEVELOPMENT_TICKS,
+            egg_ticks: constants::EGG_TICKS,
+            pupa_ticks: constants::PUPA_TICKS,
+            egg_failure_chance: constants::EGG_FAILURE_CHANCE,
+            larva_starvation_limit: constants::LARVA_STARVATION_LIMIT,
+            brood_food_per_tick: constants::BROOD_FOOD_PER_TICK,
+            // Work
+            dig_yield: constants::DIG_YIELD,
+            harvest_yield: constants::HARVEST_YIELD,
+            // Movement
+            step_seconds: constants::STEP_SECONDS,
+            trail_deposit: constants::TRAIL_DEPOSIT,
+            trail_decay: constants::TRAIL_DECAY,
+            // Weather
+            season_length: constants::SEASON_LENGTH,
+            weather_spell: constants::WEATHER_SPELL,
+            rain_chance: constants::RAIN_CHANCE,
+            drought_chance: constants::DROUGHT_CHANCE,
+            // Day and night
+            day_length: constants::DAY_LENGTH,
+            night_share: constants::NIGHT_SHARE,
+            night_work_factor: constants::NIGHT_WORK_FACTOR,
+            // Floods and cave-ins
+            flood_chance: constants::FLOOD_CHANCE,
+            flood_duration: constants::FLOOD_DURATION,
+            cave_in_chance: constants::CAVE_IN_CHANCE,
+            cave_in_window: constants::CAVE_IN_WINDOW,
+            cave_in_duration: constants::CAVE_IN_DURATION,
+            hazard_lethality: constants::HAZARD_LETHALITY,
+            excavation_work: constants::EXCAVATION_WORK,
+            ore_vein_chance: constants::ORE_VEIN_CHANCE,
+            crystal_pocket_chance: constants::CRYSTAL_POCKET_CHANCE,
+            ore_vein_stock: constants::ORE_VEIN_STOCK,
+            crystal_pocket_stock: constants::CRYSTAL_POCKET_STOCK,
+            // Undertakers and blight
+            corpse_processing_ticks: constants::CORPSE_PROCESSING_TICKS,
+            corpse_nutrient_boost: constants::CORPSE_NUTRIENT_BOOST,
+            corpse_boost_duration: constants::CORPSE_BOOST_DURATION,
+            contamination_per_corpse: constants::CONTAMINATION_PER_CORPSE,
+            blight_duration: constants::BLIGHT_DURATION,
+            // Expeditions and outposts
+            expedition_loss_chance: constants::EXPEDITION_LOSS_CHANCE,
+            expedition_discovery_chance: constants::EXPEDITION_DISCOVERY_CHANCE,
+            outpost_establish_cost_fungus: constants::OUTPOST_ESTABLISH_COST_FUNGUS,
+            outpost_production_rate: constants::OUTPOST_PRODUCTION_RATE,
+            outpost_supply_cost_fungus: constants::OUTPOST_SUPPLY_COST_FUNGUS,
+            host_interval: constants::HOST_INTERVAL,
+            host_cost_fungus: constants::HOST_COST_FUNGUS,
+            host_cost_crystals: constants::HOST_COST_CRYSTALS,
+            host_extension: constants::HOST_EXTENSION,
+            host_max_extension: constants::HOST_MAX_EXTENSION,
+            outpost_supply_duration: constants::OUTPOST_SUPPLY_DURATION,
+            outpost_haul_ticks_per_distance: constants::OUTPOST_HAUL_TICKS_PER_DISTANCE,
+            outpost_decay_per_tick: constants::OUTPOST_DECAY_PER_TICK,
+            // Logistics and shrines
+            hauler_capacity: constants::HAULER_CAPACITY,
+            carry_capacity: constants::CARRY_CAPACITY,
+            production_history: constants::PRODUCTION_HISTORY,
+            logistics_buffer_ticks: constants::LOGISTICS_BUFFER_TICKS,
+            shrine_window_ticks: constants::SHRINE_WINDOW_TICKS,
+            shrine_half_efficiency_volume: constants::SHRINE_HALF_EFFICIENCY_VOLUME,
+            // Receiver and visitors
+            summon_cost: constants::SUMMON_COST,
+            summon_cooldown: constants::SUMMON_COOLDOWN,
+            summon_chance: constants::SUMMON_CHANCE,
+            listening_drain: constants::LISTENING_DRAIN,
+            wanderer_gift: constants::WANDERER_GIFT,
+            convergence_antennas: constants::CONVERGENCE_ANTENNAS,
+            convergence_third_chance: constants::CONVERGENCE_THIRD_CHANCE,
+            maintenance_interval: constants::MAINTENANCE_INTERVAL,
+            maintenance_cost_strange_matter: constants::MAINTENANCE_COST_STRANGE_MATTER,
+            receiver_dormant_after: constants::RECEIVER_DORMANT_AFTER,
+            receiver_derelict_after: constants::RECEIVER_DERELICT_AFTER,
+            receiver_dormant_cost_scale: constants::RECEIVER_DORMANT_COST_SCALE,
+            receiver_derelict_cost_scale: constants::RECEIVER_DERELICT_COST_SCALE,
+            hungry_influence_consume: constants::HUNGRY_INFLUENCE_CONSUME,
+            hungry_strange_matter_produce: constants::HUNGRY_STRANGE_MATTER_PRODUCE,
+            hungry_hunger_gain: constants::HUNGRY_HUNGER_GAIN,
+            // Mood and memory
+            boredom_threshold: constants::BOREDOM_THRESHOLD,
+            perception_sanity_threshold: constants::PERCEPTION_SANITY_THRESHOLD,
+            perception_glitch_chance: constants::PERCEPTION_GLITCH_CHANCE,
+            perception_max_distortion: constants::PERCEPTION_MAX_DISTORTION,
+            reflection_stillness_ticks: constants::REFLECTION_STILLNESS_TICKS,
+            reflection_aftermath_delay: constants::REFLECTION_AFTERMATH_DELAY,
+            reflection_major_threshold: constants::REFLECTION_MAJOR_THRESHOLD,
+            ruin_echo_chance: constants::RUIN_ECHO_CHANCE,
+            wonder_chance: constants::WONDER_CHANCE,
+            raid_chance: constants::RAID_CHANCE,
+            raid_cooldown: constants::RAID_COOLDOWN,
+            raid_wealth_reference: constants::RAID_WEALTH_REFERENCE,
+            raid_strength: constants::RAID_STRENGTH,
+            soldier_strength: constants::SOLDIER_STRENGTH,
+            civilian_strength: constants::CIVILIAN_STRENGTH,
+            raid_casualty_chance: constants::RAID_CASUALTY_CHANCE,
+            raid_plunder: constants::RAID_PLUNDER,
+            influence_soft_cap: constants::INFLUENCE_SOFT_CAP,
+            influence_decay: constants::INFLUENCE_DECAY,
+            whisper_chance: constants::WHISPER_CHANCE,
+            whisper_cost: constants::WHISPER_COST,
+            whisper_insight: constants::WHISPER_INSIGHT,
+            whisper_promise_worth: constants::WHISPER_PROMISE_WORTH,
+            whisper_promise_delay: constants::WHISPER_PROMISE_DELAY,
+        }
+    }
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thresholds_extend_on_a_log_scale() {
+        let mut thresholds = Thresholds::default();
+        assert_eq!(thresholds.crossed(20.0, 120.0), vec![25.0, 50.0, 100.0]);
+        assert!(thresholds.crossed(900.0, 1_000_000.0) == vec![1000.0], "Fixed ladder stops at the top");
+
+        thresholds.log_scale = true;
+        assert_eq!(thresholds.crossed(900.0, 12_000.0), vec![1000.0, 2500.0, 5000.0, 10_000.0]);
+        assert_eq!(thresholds.crossed(30_000.0, 60_000.0), vec![50_000.0]);
+        assert!(thresholds.crossed(60_000.0, 60_001.0).is_empty());
+
+        let custom = Thresholds { ladder: vec![3.0, 7.0], log_scale: true };
+        assert_eq!(custom.crossed(0.0, 30.0), vec![3.0, 7.0, 10.0, 25.0]);
+    }
+
+    #[test]
+    fn test_presets_roundtrip_by_name() {
+        for name in ["gentle", "classic", "cruel", "zen"] {
+            let config = SimConfig::preset(name).unwrap();
+            let json = serde_json::to_value(&config).unwrap();
+            assert_eq!(json["preset"], name);
+            assert_eq!(serde_json::from_value::<SimConfig>(json).unwrap(), config);
+        }
+        assert!(SimConfig::preset("nightmare").is_none());
+        assert!(SimConfig::default().is_classic());
+    }
+
+    #[test]
+    fn test_tuning_is_omitted_until_changed() {
+        let mut config = SimConfig::cruel();
+        assert!(serde_json::to_value(&config).unwrap().get("tuning").is_none());
+
+        config.tuning.summon_chance = 0.9;
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["tuning"]["summon_chance"], 0.9);
+        assert_eq!(serde_json::from_value::<SimConfig>(json).unwrap(), config);
+    }
+
+    #[test]
+    fn test_baselines_set_stats_at_spawn() {
+        let mut config = SimConfig::classic();
+        assert_eq!(config.ant_baselines[&AntRole::Undertaker].hunger_rate, 0.15);
+        assert_eq!(config.visitor_baselines[&VisitorType::Observer].food.as_deref(), Some("crystals"));
+        assert!(serde_json::to_value(&config).unwrap().get("ant_baselines").is_none());
+
+        let worker = Entity::new_worker("w".to_string(), "origin".to_string());
+        assert_eq!(config.with_baseline(worker.clone()).hunger_rate, worker.hunger_rate);
+        let soldier = config.ant_baselines.get_mut(&AntRole::Soldier).unwrap();
+        soldier.hunger_rate = 0.2;
+        soldier.max_age = 3600;
+        let spawned = config.with_baseline(Entity::new_soldier("s".to_string(), "origin".to_string()));
+        assert_eq!((spawned.hunger_rate, spawned.max_age), (0.2, 3600));
+        assert_eq!(config.with_baseline(worker.clone()).hunger_rate, worker.hunger_rate);
+
+        // A role without a baseline keeps its constructor's
+        config.ant_baselines.remove(&AntRole::Soldier);
+        let json = serde_json::to_value(&config).unwrap();
+        assert!(json["ant_baselines"].get("soldier").is_none());
+        assert_eq!(config.with_baseline(Entity::new_soldier("s".to_string(), "origin".to_string())).hunger_rate, 0.1);
+        assert_eq!(serde_json::from_value::<SimConfig>(json).unwrap(), config);
+    }
+}
@@ -0,0 +1,57 @@
+//! Convergences: rare summons that several visitors answer at once.
+//!
+//! With `feature::CONVERGENCES`, a summons that succeeds while the Outside
+//! is in conjunction (its `alignment` at `CONJUNCTION_LEVEL` or past it, and
+//! no static surge), heard by a receiver with at least `convergence_antennas`
+//! antenna tiles, brings two visitors, or three with
+//! `convergence_third_chance`. Conjunctions come from the Outside's noise,
+//! so like the rest of its weather they can be read ahead of time. Visitors
+//! who arrive together play off each other (see `interplay`), and the
+//! arrival is reported as one `Convergence`.
+
+use super::entity::{Entity, VisitorType};
+
+/// How much longer everyone in a wave stays when an observer is among them
+pub const OBSERVED_STAY_FACTOR: f64 = 1.5;
+
+/// Share a wanderer's gift grows by for each visitor it arrived with
+pub const GIFT_BONUS_PER_COMPANION: f64 = 0.5;
+
+/// Let visitors who arrived together affect each other: an observer among
+/// them keeps the whole wave longer, and each wanderer's parting gift grows
+/// with its company
+pub fn interplay(wave: &mut [Entity]) {
+    let observed = wave.iter().any(|v| v.subtype == Some(VisitorType::Observer));
+    let companions = wave.len().saturating_sub(1) as f64;
+    for visitor in wave.iter_mut() {
+        if observed {
+            visitor.max_age = (visitor.max_age as f64 * OBSERVED_STAY_FACTOR) as u64;
+        }
+        for amount in visitor.gift_on_death.iter_mut().flat_map(|gift| gift.values_mut()) {
+            *amount *= 1.0 + GIFT_BONUS_PER_COMPANION * companions;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_interplay() {
+        let mut wanderer = Entity::new_wanderer("w".to_string());
+        wanderer.gift_on_death = Some(HashMap::from([("strange_matter".to_string(), 2.0)]));
+        let stay = wanderer.max_age;
+
+        let mut alone = [wanderer.clone(), Entity::new_hungry("h".to_string())];
+        interplay(&mut alone);
+        assert_eq!(alone[0].gift_on_death.as_ref().unwrap()["strange_matter"], 3.0);
+        assert_eq!(alone[0].max_age, stay);
+
+        let mut watched = [wanderer, Entity::new_hungry("h".to_string()), Entity::new_observer("o".to_string())];
+        interplay(&mut watched);
+        assert_eq!(watched[0].gift_on_death.as_ref().unwrap()["strange_matter"], 4.0);
+        assert_eq!(watched[0].max_age, (stay as f64 * OBSERVED_STAY_FACTOR) as u64);
+    }
+}
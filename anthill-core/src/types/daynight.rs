@@ -0,0 +1,72 @@
+//! Day and night over the colony.
+//!
+//! Every `day_length` seconds a day begins at dawn, and the last
+//! `night_share` of it is night. The time of day comes from the tick alone
+//! (`GameState::time_of_day`). With `feature::DAY_NIGHT` it matters: ants
+//! work at `night_work_factor` in the dark, observers only come by night,
+//! and the wonders that happen overnight only happen then. The engine
+//! reports the turns as `DayBreak` and `NightFall`.
+
+use serde::{Deserialize, Serialize};
+
+use super::wonder::Wonder;
+
+/// Light or dark
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DayPhase {
+    Day,
+    Night,
+}
+
+/// Where the colony is in its day
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimeOfDay {
+    /// Days gone by (0 for the first)
+    pub day: u64,
+
+    /// How far through the day, from 0 at dawn toward 1
+    pub fraction: f64,
+
+    pub phase: DayPhase,
+}
+
+impl TimeOfDay {
+    /// The time of day `seconds` into a colony's life, with days of
+    /// `day_length` seconds the last `night_share` of which is night
+    pub fn at(seconds: f64, day_length: u64, night_share: f64) -> Self {
+        let days = seconds.max(0.0) / day_length.max(1) as f64;
+        let fraction = days.fract();
+        let phase = if fraction >= 1.0 - night_share.clamp(0.0, 1.0) { DayPhase::Night } else { DayPhase::Day };
+        Self { day: days as u64, fraction, phase }
+    }
+
+    pub fn is_night(&self) -> bool {
+        self.phase == DayPhase::Night
+    }
+}
+
+/// Wonders that only happen overnight
+pub fn is_overnight(wonder: Wonder) -> bool {
+    matches!(wonder, Wonder::CrystallineBloom | Wonder::FallenStar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_turn_to_night() {
+        let at = |seconds: f64| TimeOfDay::at(seconds, 100, 0.25);
+        assert_eq!(at(0.0).phase, DayPhase::Day);
+        assert_eq!(at(74.0).phase, DayPhase::Day);
+        assert_eq!(at(75.0).phase, DayPhase::Night);
+        assert!(at(99.0).is_night());
+        let morning = at(110.0);
+        assert_eq!((morning.day, morning.phase), (1, DayPhase::Day));
+        assert!((morning.fraction - 0.1).abs() < 1e-9);
+
+        assert!(!TimeOfDay::at(99.0, 100, 0.0).is_night());
+        assert!(TimeOfDay::at(0.0, 100, 1.0).is_night());
+    }
+}
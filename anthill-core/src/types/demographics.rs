@@ -0,0 +1,56 @@
+//! Colony demographics: who's alive, how old, how hungry, who's about to go.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::entity::{Entity, EntityType};
+
+/// Entities expected to leave the colony within the report's horizon
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedDeaths {
+    /// Ants reaching their max age
+    pub old_age: u64,
+
+    /// Entities whose hunger runs out while their food store is empty
+    pub starvation: u64,
+
+    /// Visitors reaching their max age (they leave rather than die)
+    pub departures: u64,
+}
+
+/// A snapshot report of the colony's population
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Demographics {
+    /// Width of each age bucket in ticks
+    pub bucket_ticks: u64,
+
+    /// Cohort ("worker", "undertaker", "hauler", "forager", "soldier",
+    /// "nurse", "visitor") -> counts per age bucket, youngest first
+    pub pyramid: BTreeMap<String, Vec<u64>>,
+
+    /// Cohort -> head count
+    pub population: BTreeMap<String, u64>,
+
+    /// Cohort -> mean hunger
+    pub average_hunger: BTreeMap<String, f64>,
+
+    /// Mean hunger across everyone (0 for an empty colony)
+    pub overall_average_hunger: f64,
+
+    /// How far ahead `expected_deaths` looks, in ticks
+    pub horizon: u64,
+
+    pub expected_deaths: ExpectedDeaths,
+}
+
+/// The cohort an entity is counted under
+pub fn cohort(entity: &Entity) -> String {
+    match (&entity.entity_type, &entity.role) {
+        (EntityType::Visitor, _) => "visitor".to_string(),
+        (EntityType::Ant, Some(role)) => serde_json::to_value(role)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_else(|| "ant".to_string()),
+        (EntityType::Ant, None) => "ant".to_string(),
+    }
+}
@@ -0,0 +1,425 @@
+//! State diffs: what changed between two snapshots, field by field.
+//!
+//! `earlier.diff(&later)` lists resource deltas, entities that arrived,
+//! left or changed, tile changes and any other top-level field that moved.
+//! Observers describe "what changed since the last snapshot" from it, and
+//! `StateDiff::apply` replays it onto a copy of the earlier state to get
+//! the later one back. Field values are plain JSON, as in a save; `null`
+//! marks a field that is no longer set.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use thiserror::Error;
+
+use super::entity::{Entity, EntityId};
+use super::key::Key;
+use super::shared::Shared;
+use super::state::GameState;
+use super::tile::{GameMap, Tile};
+
+/// How one resource moved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceChange {
+    /// Change in the amount held (a missing resource counts as 0)
+    pub delta: f64,
+    /// Amount held now; `None` if the resource is gone from the stores
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<f64>,
+}
+
+/// What happened to one entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum EntityChange {
+    /// Arrived, at `index` in the later state's entity list
+    Added { entity: Box<Entity>, index: usize },
+    Removed { id: EntityId },
+    Changed { id: EntityId, fields: BTreeMap<String, Value> },
+}
+
+/// What happened to one tile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum TileChange {
+    Added { id: Key, tile: Box<Tile> },
+    Removed { id: Key },
+    Changed { id: Key, fields: BTreeMap<String, Value> },
+}
+
+/// Everything that changed from one state to another
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StateDiff {
+    pub from_tick: u64,
+    pub to_tick: u64,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub resources: BTreeMap<Key, ResourceChange>,
+
+    /// Removals first, then changes and arrivals in the later state's order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entities: Vec<EntityChange>,
+
+    /// By tile ID
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tiles: Vec<TileChange>,
+
+    /// The map's connections, if they changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connections: Option<Vec<(Key, Key)>>,
+
+    /// The map's edit count (`GameMap::version`), if it moved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map_version: Option<u64>,
+
+    /// Every other top-level field that changed, with its new value
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, Value>,
+}
+
+/// Why a diff couldn't be applied (the state may be partly patched)
+#[derive(Debug, Error)]
+pub enum DiffError {
+    #[error("diff starts at tick {expected}, state is at tick {found}")]
+    WrongTick { expected: u64, found: u64 },
+
+    #[error("entity {0} not found")]
+    UnknownEntity(EntityId),
+
+    #[error("tile {0} not found")]
+    UnknownTile(Key),
+
+    #[error("patched value doesn't fit: {0}")]
+    Malformed(#[from] serde_json::Error),
+}
+
+impl StateDiff {
+    /// What changed going from `old` to `new`
+    pub fn between(old: &GameState, new: &GameState) -> Self {
+        Self {
+            from_tick: old.tick,
+            to_tick: new.tick,
+            resources: resource_changes(old, new),
+            entities: if Shared::ptr_eq(&old.entities, &new.entities) {
+                Vec::new()
+            } else {
+                entity_changes(&old.entities, &new.entities)
+            },
+            tiles: if Shared::ptr_eq(&old.map, &new.map) {
+                Vec::new()
+            } else {
+                tile_changes(&old.map, &new.map)
+            },
+            connections: (old.map.connections != new.map.connections).then(|| new.map.connections.clone()),
+            map_version: (old.map.version != new.map.version).then_some(new.map.version),
+            fields: field_changes(&object(&rest(old)), &object(&rest(new))),
+        }
+    }
+
+    /// Nothing changed but (possibly) the tick
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+            && self.entities.is_empty()
+            && self.tiles.is_empty()
+            && self.connections.is_none()
+            && self.map_version.is_none()
+            && self.fields.is_empty()
+    }
+
+    /// Replay the diff onto `state`, which must be at `from_tick`
+    pub fn apply(&self, state: &mut GameState) -> Result<(), DiffError> {
+        if state.tick != self.from_tick {
+            return Err(DiffError::WrongTick { expected: self.from_tick, found: state.tick });
+        }
+
+        if !self.fields.is_empty() {
+            let entities = std::mem::take(&mut state.entities);
+            let map = std::mem::take(&mut state.map);
+            let resources = std::mem::take(&mut state.resources);
+            let mut doc = object(&*state);
+            patch(&mut doc, &self.fields);
+            *state = serde_json::from_value(Value::Object(doc))?;
+            state.entities = entities;
+            state.map = map;
+            state.resources = resources;
+        }
+
+        for (name, change) in &self.resources {
+            match change.amount {
+                Some(amount) => state.resources.set(name, amount),
+                None => {
+                    state.resources.amounts.remove(name);
+                }
+            }
+        }
+
+        for change in &self.entities {
+            match change {
+                EntityChange::Added { entity, index } => {
+                    // Arrivals come in the later state's order, so each
+                    // lands where it was once those before it are in
+                    let index = (*index).min(state.entities.len());
+                    state.entities.insert(index, (**entity).clone());
+                }
+                EntityChange::Removed { id } => {
+                    let before = state.entities.len();
+                    state.entities.retain(|e| e.id != *id);
+                    if state.entities.len() == before {
+                        return Err(DiffError::UnknownEntity(id.clone()));
+                    }
+                }
+                EntityChange::Changed { id, fields } => {
+                    let entity = state.get_entity_mut(id).ok_or_else(|| DiffError::UnknownEntity(id.clone()))?;
+                    *entity = patched(&*entity, fields)?;
+                }
+            }
+        }
+
+        for change in &self.tiles {
+            match change {
+                TileChange::Added { id, tile } => {
+                    state.map.tiles.insert(*id, (**tile).clone());
+                }
+                TileChange::Removed { id } => {
+                    state.map.tiles.remove(id).ok_or(DiffError::UnknownTile(*id))?;
+                }
+                TileChange::Changed { id, fields } => {
+                    let tile = state.map.tiles.get_mut(id).ok_or(DiffError::UnknownTile(*id))?;
+                    *tile = patched(&*tile, fields)?;
+                }
+            }
+        }
+
+        if let Some(connections) = &self.connections {
+            state.map.connections.clone_from(connections);
+        }
+        // The version keys the path cache, so paths found before are dropped
+        if let Some(version) = self.map_version {
+            state.map.version = version;
+        }
+        state.tick = self.to_tick;
+        // A diff that doesn't carry the engine's bookkeeping still moves
+        // its clock note along with the tick, or `advance` would refuse
+        // the replica as having had its clock moved
+        if !self.fields.contains_key("bookkeeping") {
+            if let Some(bookkeeping) = state.bookkeeping.as_mut().filter(|b| b.tick == Some(self.from_tick)) {
+                bookkeeping.tick = Some(self.to_tick);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn resource_changes(old: &GameState, new: &GameState) -> BTreeMap<Key, ResourceChange> {
+    let names: BTreeSet<&Key> = old.resources.amounts.keys().chain(new.resources.amounts.keys()).collect();
+    names.into_iter()
+        .filter_map(|name| {
+            let was = old.resources.amounts.get(name).copied();
+            let now = new.resources.amounts.get(name).copied();
+            (was != now).then(|| {
+                let delta = now.unwrap_or(0.0) - was.unwrap_or(0.0);
+                (*name, ResourceChange { delta, amount: now })
+            })
+        })
+        .collect()
+}
+
+fn entity_changes(old: &[Entity], new: &[Entity]) -> Vec<EntityChange> {
+    let before: HashMap<&str, &Entity> = old.iter().map(|e| (e.id.as_str(), e)).collect();
+    let after: BTreeSet<&str> = new.iter().map(|e| e.id.as_str()).collect();
+
+    let mut changes: Vec<EntityChange> = old.iter()
+        .filter(|e| !after.contains(e.id.as_str()))
+        .map(|e| EntityChange::Removed { id: e.id.clone() })
+        .collect();
+    for (index, entity) in new.iter().enumerate() {
+        match before.get(entity.id.as_str()) {
+            None => changes.push(EntityChange::Added { entity: Box::new(entity.clone()), index }),
+            Some(was) => {
+                let fields = field_changes(&object(was), &object(entity));
+                if !fields.is_empty() {
+                    changes.push(EntityChange::Changed { id: entity.id.clone(), fields });
+                }
+            }
+        }
+    }
+    changes
+}
+
+fn tile_changes(old: &GameMap, new: &GameMap) -> Vec<TileChange> {
+    let ids: BTreeSet<&Key> = old.tiles.keys().chain(new.tiles.keys()).collect();
+    ids.into_iter()
+        .filter_map(|id| match (old.tiles.get(id), new.tiles.get(id)) {
+            (None, Some(tile)) => Some(TileChange::Added { id: *id, tile: Box::new(tile.clone()) }),
+            (Some(_), None) => Some(TileChange::Removed { id: *id }),
+            (Some(was), Some(tile)) => {
+                let fields = field_changes(&object(was), &object(tile));
+                (!fields.is_empty()).then_some(TileChange::Changed { id: *id, fields })
+            }
+            (None, None) => None,
+        })
+        .collect()
+}
+
+/// `state` without the parts diffed on their own (cheap: they're shared)
+fn rest(state: &GameState) -> GameState {
+    GameState {
+        tick: 0,
+        resources: Default::default(),
+        entities: Default::default(),
+        map: Default::default(),
+        ..state.clone()
+    }
+}
+
+fn object<T: Serialize + ?Sized>(value: &T) -> Map<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Fields that differ going from `old` to `new`; `null` for ones now unset
+fn field_changes(old: &Map<String, Value>, new: &Map<String, Value>) -> BTreeMap<String, Value> {
+    let mut changes: BTreeMap<String, Value> = new.iter()
+        .filter(|(field, value)| old.get(*field) != Some(*value))
+        .map(|(field, value)| (field.clone(), value.clone()))
+        .collect();
+    for field in old.keys().filter(|field| !new.contains_key(*field)) {
+        changes.insert(field.clone(), Value::Null);
+    }
+    changes
+}
+
+fn patch(object: &mut Map<String, Value>, fields: &BTreeMap<String, Value>) {
+    for (field, value) in fields {
+        if value.is_null() {
+            object.remove(field);
+        } else {
+            object.insert(field.clone(), value.clone());
+        }
+    }
+}
+
+fn patched<T: Serialize + serde::de::DeserializeOwned>(value: &T, fields: &BTreeMap<String, Value>) -> Result<T, serde_json::Error> {
+    let mut doc = object(value);
+    patch(&mut doc, fields);
+    serde_json::from_value(Value::Object(doc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::TickEngine;
+
+    fn colony() -> GameState {
+        let mut state = GameState::default();
+        state.resources.set("fungus", 50.0);
+        state.resources.set("nutrients", 100.0);
+        state.resources.set("influence", 20.0);
+        let mut elder = Entity::new_worker("elder".to_string(), "origin".to_string());
+        elder.age = elder.max_age - 50;
+        state.entities.push(elder);
+        let mut hungry = Entity::new_worker("w1".to_string(), "origin".to_string());
+        hungry.hunger = 20.0;
+        state.entities.push(hungry);
+        state.entities.push(Entity::new_undertaker("u1".to_string(), "origin".to_string()));
+        state
+    }
+
+    #[test]
+    fn test_diff_describes_changes_and_applies() {
+        let mut engine = TickEngine::new(3);
+        let mut state = colony();
+        let snapshot = state.clone();
+        assert!(snapshot.diff(&state).is_empty());
+
+        for _ in 0..200 {
+            engine.tick(&mut state);
+        }
+        state.map.tiles.insert(Key::new("field"), Tile::new_empty("Field".to_string(), 1, 0));
+        state.map.connections.push((Key::new("origin"), Key::new("field")));
+        state.resources.amounts.remove("influence");
+
+        let diff = snapshot.diff(&state);
+        assert_eq!((diff.from_tick, diff.to_tick), (0, 200));
+        assert!(diff.resources["fungus"].delta < 0.0);
+        assert_eq!(diff.resources["influence"], ResourceChange { delta: -20.0, amount: None });
+        assert!(diff.entities.iter().any(|c| matches!(c, EntityChange::Removed { id } if id == "elder")));
+        assert!(diff.entities.iter().any(|c| matches!(
+            c,
+            EntityChange::Changed { id, fields } if id == "w1" && fields.contains_key("age")
+        )));
+        assert!(matches!(&diff.tiles[..], [TileChange::Added { id, .. }] if id == "field"));
+        assert!(diff.fields.contains_key("activity"));
+
+        // Survives the wire, and replays to the same state
+        let diff: StateDiff = serde_json::from_str(&serde_json::to_string(&diff).unwrap()).unwrap();
+        let mut replayed = snapshot.clone();
+        diff.apply(&mut replayed).unwrap();
+        assert_eq!(serde_json::to_value(&replayed).unwrap(), serde_json::to_value(&state).unwrap());
+
+        // Only from where it was taken
+        assert!(matches!(diff.apply(&mut replayed), Err(DiffError::WrongTick { expected: 0, found: 200 })));
+    }
+
+    #[test]
+    fn test_arrivals_keep_their_place() {
+        let mut state = colony();
+        let snapshot = state.clone();
+        state.entities.retain(|e| e.id != "elder");
+        state.entities.insert(0, Entity::new_worker("first".to_string(), "origin".to_string()));
+        state.entities.insert(2, Entity::new_worker("between".to_string(), "origin".to_string()));
+        state.entities.push(Entity::new_worker("last".to_string(), "origin".to_string()));
+
+        let mut replayed = snapshot.clone();
+        snapshot.diff(&state).apply(&mut replayed).unwrap();
+        let ids = |state: &GameState| state.entities.iter().map(|e| e.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&replayed), ["first", "w1", "between", "u1", "last"]);
+        assert_eq!(replayed.hash(), state.hash());
+    }
+
+    #[test]
+    fn test_replica_synced_by_diffs_keeps_ticking() {
+        let mut engine = TickEngine::new(3);
+        let mut state = colony();
+        engine.tick(&mut state);
+        let mut replica = state.clone();
+
+        let snapshot = state.clone();
+        for _ in 0..20 {
+            engine.tick(&mut state);
+        }
+        let diff = snapshot.diff(&state);
+        diff.apply(&mut replica).unwrap();
+        let mut twin = TickEngine::new(3);
+        twin.init_from_state(&replica);
+        twin.advance(&mut replica, 21).unwrap();
+        engine.advance(&mut state, 21).unwrap();
+        assert_eq!(replica.hash(), state.hash());
+
+        // Even when the diff leaves the bookkeeping out
+        let snapshot = state.clone();
+        engine.tick(&mut state);
+        let mut diff = snapshot.diff(&state);
+        diff.fields.remove("bookkeeping");
+        diff.apply(&mut replica).unwrap();
+        assert_eq!(replica.bookkeeping.unwrap().tick, Some(23));
+        assert!(twin.advance(&mut replica, 23).is_ok());
+    }
+
+    #[test]
+    fn test_diff_carries_map_edits() {
+        let mut state = colony();
+        let snapshot = state.clone();
+        state.map.add_tile("field", Tile::new_empty("Field".to_string(), 1, 0), "origin").unwrap();
+        assert_ne!(state.map.version, snapshot.map.version);
+
+        let mut replayed = snapshot.clone();
+        let diff = snapshot.diff(&state);
+        assert_eq!(diff.map_version, Some(state.map.version));
+        diff.apply(&mut replayed).unwrap();
+        assert_eq!(replayed.hash(), state.hash());
+        assert_eq!(replayed.map.path("origin", "field").as_deref(), Some(&[Key::new("field")][..]));
+    }
+}
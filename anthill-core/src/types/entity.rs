@@ -1,13 +1,22 @@
 //! Entity types: ants, visitors, and their properties.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::det;
+use crate::engine::constants;
+use crate::rng::names;
+
+use super::heredity::Trait;
+use super::logistics::Route;
+use super::memory::{Experience, Memory};
+use super::policy::Task;
 
 /// Unique identifier for an entity
 pub type EntityId = String;
 
 /// The type of entity
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum EntityType {
     Ant,
@@ -15,15 +24,58 @@ pub enum EntityType {
 }
 
 /// Role of an ant in the colony
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum AntRole {
     Worker,
     Undertaker,
+    Hauler,
+    /// Gathers from resource tiles into the colony's stores
+    Forager,
+    /// Stands against raiders and predators (see `threat`)
+    Soldier,
+    /// Feeds the brood and tends the queen
+    Nurse,
+    /// Lays the colony's brood (see `feature::SUCCESSION`)
+    Queen,
+    /// Raised to succeed the queen when she dies
+    Princess,
+}
+
+impl AntRole {
+    /// Queens and princesses: they neither work, fight nor change roles
+    pub fn is_royal(&self) -> bool {
+        matches!(self, AntRole::Queen | AntRole::Princess)
+    }
+}
+
+/// Where an ant is in its life, from laid to grown. Every entity is grown;
+/// the earlier stages are brood (see `GameState::brood`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LifeStage {
+    /// Needs nothing but time, and may never hatch
+    Egg,
+    /// Eats, and starves if it goes unfed too long
+    Larva,
+    /// Needs nothing but time
+    Pupa,
+    Adult,
+}
+
+impl LifeStage {
+    /// The stage after this one (adults stay adults)
+    pub fn next(self) -> Self {
+        match self {
+            LifeStage::Egg => LifeStage::Larva,
+            LifeStage::Larva => LifeStage::Pupa,
+            LifeStage::Pupa | LifeStage::Adult => LifeStage::Adult,
+        }
+    }
 }
 
 /// Type of visitor from the Outside
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum VisitorType {
     Wanderer,
@@ -64,11 +116,12 @@ pub struct Entity {
     #[serde(default = "default_hunger")]
     pub hunger: f64,
 
-    /// Hunger decrease per tick
+    /// Hunger decrease per second
     #[serde(default = "default_hunger_rate")]
     pub hunger_rate: f64,
 
-    /// Maximum age before death (ticks)
+    /// Maximum age before death (ticks; the constructors give it in
+    /// seconds, which `GameState::spawned` turns into ticks)
     #[serde(default = "default_max_age")]
     pub max_age: u64,
 
@@ -80,10 +133,6 @@ pub struct Entity {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub processing_corpse: Option<bool>,
 
-    /// For undertakers: ticks spent processing current corpse
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub processing_ticks: Option<u64>,
-
     /// Visitor flag: came from outside
     #[serde(skip_serializing_if = "Option::is_none")]
     pub from_outside: Option<bool>,
@@ -96,13 +145,70 @@ pub struct Entity {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gift_on_death: Option<HashMap<String, f64>>,
 
-    /// Resources generated per tick (for observers)
+    /// Resources generated per second (for observers)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub generates: Option<HashMap<String, f64>>,
 
     /// Does this entity transform what it eats? (hungry visitors)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub transforms: Option<bool>,
+
+    /// Out of food with starvation deaths switched off; wakes once fed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dormant: Option<bool>,
+
+    /// What the work policy has this worker doing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<Task>,
+
+    /// Tile the entity is walking to (see the movement phase)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+
+    /// Notable experiences, oldest first (see `memory`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memories: Vec<Memory>,
+
+    /// Per-entity countdowns and counters in ticks, by name (see `timer`).
+    /// New timed behaviours add a name here instead of a field.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub timers: BTreeMap<String, u64>,
+
+    /// What the ant was born with (see `heredity`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub traits: Vec<Trait>,
+
+    /// 0 to `MAX_HEALTH`; with `feature::INJURIES`, hunger, blight and
+    /// sickness wear it down, and an ant dies when it's gone
+    #[serde(default = "default_health", skip_serializing_if = "is_full_health")]
+    pub health: f64,
+
+    /// 0 to `MAX_MORALE`; with `feature::MORALE`, sanity, famine and
+    /// deaths nearby move it (see `morale`)
+    #[serde(default = "default_morale", skip_serializing_if = "is_full_morale")]
+    pub morale: f64,
+
+    /// Goods the ant is carrying, by resource; with `feature::CARRYING`,
+    /// foragers and haulers hold what they move here, up to
+    /// `carry_capacity` in all
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub inventory: HashMap<String, f64>,
+
+    /// Where the carried goods came from and are bound
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub route: Option<Route>,
+}
+
+/// Names of the timers in `Entity::timers`
+pub mod timer {
+    /// Undertakers: ticks spent processing the current corpse
+    pub const PROCESSING: &str = "processing";
+
+    /// Walkers: ticks spent on the current step
+    pub const WALKING: &str = "walking";
+
+    /// Visitors: ticks hosting has added to their stay
+    pub const HOSTED: &str = "hosted";
 }
 
 fn default_hunger() -> f64 {
@@ -117,15 +223,31 @@ fn default_max_age() -> u64 {
     7200 // 2 hours
 }
 
+fn default_health() -> f64 {
+    constants::MAX_HEALTH
+}
+
+fn is_full_health(health: &f64) -> bool {
+    *health >= constants::MAX_HEALTH
+}
+
+fn default_morale() -> f64 {
+    constants::MAX_MORALE
+}
+
+fn is_full_morale(morale: &f64) -> bool {
+    *morale >= constants::MAX_MORALE
+}
+
 impl Entity {
     /// Create a new worker ant
     pub fn new_worker(id: EntityId, tile: String) -> Self {
         Self {
+            name: Some(names::ant(&id, &AntRole::Worker)),
             id,
             entity_type: EntityType::Ant,
             role: Some(AntRole::Worker),
             subtype: None,
-            name: None,
             tile,
             age: 0,
             hunger: 100.0,
@@ -133,23 +255,32 @@ impl Entity {
             max_age: 7200,
             food: Some("fungus".to_string()),
             processing_corpse: None,
-            processing_ticks: None,
             from_outside: None,
             description: None,
             gift_on_death: None,
             generates: None,
             transforms: None,
+            dormant: None,
+            task: None,
+            destination: None,
+            memories: Vec::new(),
+            timers: BTreeMap::new(),
+            traits: Vec::new(),
+            health: constants::MAX_HEALTH,
+            morale: constants::MAX_MORALE,
+            inventory: HashMap::new(),
+            route: None,
         }
     }
 
     /// Create a new undertaker ant
     pub fn new_undertaker(id: EntityId, tile: String) -> Self {
         Self {
+            name: Some(names::ant(&id, &AntRole::Undertaker)),
             id,
             entity_type: EntityType::Ant,
             role: Some(AntRole::Undertaker),
             subtype: None,
-            name: None,
             tile,
             age: 0,
             hunger: 100.0,
@@ -157,12 +288,149 @@ impl Entity {
             max_age: 7200,
             food: Some("fungus".to_string()),
             processing_corpse: Some(false),
-            processing_ticks: Some(0),
             from_outside: None,
             description: None,
             gift_on_death: None,
             generates: None,
             transforms: None,
+            dormant: None,
+            task: None,
+            destination: None,
+            memories: Vec::new(),
+            timers: BTreeMap::new(),
+            traits: Vec::new(),
+            health: constants::MAX_HEALTH,
+            morale: constants::MAX_MORALE,
+            inventory: HashMap::new(),
+            route: None,
+        }
+    }
+
+    /// Create a new hauler ant (moves goods between stockpiles)
+    pub fn new_hauler(id: EntityId, tile: String) -> Self {
+        Self {
+            role: Some(AntRole::Hauler),
+            name: Some(names::ant(&id, &AntRole::Hauler)),
+            ..Self::new_worker(id, tile)
+        }
+    }
+
+    /// Create a new forager ant (harvests resource tiles)
+    pub fn new_forager(id: EntityId, tile: String) -> Self {
+        Self {
+            role: Some(AntRole::Forager),
+            name: Some(names::ant(&id, &AntRole::Forager)),
+            ..Self::new_worker(id, tile)
+        }
+    }
+
+    /// Create a new soldier ant (defends the colony)
+    pub fn new_soldier(id: EntityId, tile: String) -> Self {
+        Self {
+            role: Some(AntRole::Soldier),
+            name: Some(names::ant(&id, &AntRole::Soldier)),
+            ..Self::new_worker(id, tile)
+        }
+    }
+
+    /// Create a new nurse ant (feeds brood, tends the queen)
+    pub fn new_nurse(id: EntityId, tile: String) -> Self {
+        Self {
+            role: Some(AntRole::Nurse),
+            name: Some(names::ant(&id, &AntRole::Nurse)),
+            ..Self::new_worker(id, tile)
+        }
+    }
+
+    /// Create a new queen, who outlives her workers many times over
+    pub fn new_queen(id: EntityId, tile: String) -> Self {
+        Self {
+            role: Some(AntRole::Queen),
+            name: Some(names::ant(&id, &AntRole::Queen)),
+            hunger_rate: 0.05,
+            max_age: constants::QUEEN_LIFESPAN,
+            ..Self::new_worker(id, tile)
+        }
+    }
+
+    /// Create a new princess, a queen in waiting
+    pub fn new_princess(id: EntityId, tile: String) -> Self {
+        Self {
+            role: Some(AntRole::Princess),
+            name: Some(names::ant(&id, &AntRole::Princess)),
+            ..Self::new_queen(id, tile)
+        }
+    }
+
+    /// Make this princess queen, to live `lifespan` ticks from now (longer
+    /// if she's enduring; she takes a queen's name with the title, unless
+    /// a player has named her)
+    pub fn crown(&mut self, lifespan: u64) {
+        self.role = Some(AntRole::Queen);
+        if self.name.is_none() || self.name == Some(names::ant(&self.id, &AntRole::Princess)) {
+            self.name = Some(names::ant(&self.id, &AntRole::Queen));
+        }
+        self.max_age = self.age + (lifespan as f64 * self.trait_factor(Trait::lifespan_factor)) as u64;
+    }
+
+    /// Give a newborn ant its traits, and the longer life an enduring one
+    /// gets
+    pub fn with_traits(mut self, traits: Vec<Trait>) -> Self {
+        self.traits = traits;
+        self.max_age = (self.max_age as f64 * self.trait_factor(Trait::lifespan_factor)) as u64;
+        self
+    }
+
+    /// Does this ant forage: a forager, or a worker put on `Task::Forage`?
+    /// Both harvest resource tiles alike.
+    pub fn forages(&self) -> bool {
+        self.role == Some(AntRole::Forager) || self.task == Some(Task::Forage)
+    }
+
+    /// Total goods carried
+    pub fn carried(&self) -> f64 {
+        det::entries(self.inventory.iter()).into_iter().map(|(_, amount)| amount).sum()
+    }
+
+    /// Pick up `amount` of `resource` bound along `route`
+    pub fn load(&mut self, resource: &str, amount: f64, route: Route) {
+        *self.inventory.entry(resource.to_string()).or_default() += amount;
+        self.route = Some(route);
+    }
+
+    /// Put everything down, returning it by resource (in name order) with
+    /// the route it came along
+    pub fn unload(&mut self) -> (Vec<(String, f64)>, Option<Route>) {
+        let mut goods: Vec<_> = self.inventory.drain().collect();
+        goods.sort_by(|a, b| a.0.cmp(&b.0));
+        (goods, self.route.take())
+    }
+
+    /// `factor` over all the entity's traits, multiplied (1 with none)
+    pub fn trait_factor(&self, factor: fn(Trait) -> f64) -> f64 {
+        self.traits.iter().map(|t| factor(*t)).product()
+    }
+
+    /// Create an adult ant of `role`
+    pub fn new_ant(role: AntRole, id: EntityId, tile: String) -> Self {
+        match role {
+            AntRole::Worker => Self::new_worker(id, tile),
+            AntRole::Undertaker => Self::new_undertaker(id, tile),
+            AntRole::Hauler => Self::new_hauler(id, tile),
+            AntRole::Forager => Self::new_forager(id, tile),
+            AntRole::Soldier => Self::new_soldier(id, tile),
+            AntRole::Nurse => Self::new_nurse(id, tile),
+            AntRole::Queen => Self::new_queen(id, tile),
+            AntRole::Princess => Self::new_princess(id, tile),
+        }
+    }
+
+    /// Create a visitor of `subtype`
+    pub fn new_visitor(subtype: VisitorType, id: EntityId) -> Self {
+        match subtype {
+            VisitorType::Wanderer => Self::new_wanderer(id),
+            VisitorType::Observer => Self::new_observer(id),
+            VisitorType::Hungry => Self::new_hungry(id),
         }
     }
 
@@ -172,11 +440,11 @@ impl Entity {
         gift.insert("strange_matter".to_string(), 1.0);
 
         Self {
+            name: Some(names::visitor(&id, &VisitorType::Wanderer)),
             id,
             entity_type: EntityType::Visitor,
             role: None,
             subtype: Some(VisitorType::Wanderer),
-            name: Some("A Wanderer".to_string()),
             tile: "receiver".to_string(),
             age: 0,
             hunger: 100.0,
@@ -184,12 +452,21 @@ impl Entity {
             max_age: 1800, // 30 minutes
             food: None,
             processing_corpse: None,
-            processing_ticks: None,
             from_outside: Some(true),
             description: Some("Passes through. Leaves something behind.".to_string()),
             gift_on_death: Some(gift),
             generates: None,
             transforms: None,
+            dormant: None,
+            task: None,
+            destination: None,
+            memories: Vec::new(),
+            timers: BTreeMap::new(),
+            traits: Vec::new(),
+            health: constants::MAX_HEALTH,
+            morale: constants::MAX_MORALE,
+            inventory: HashMap::new(),
+            route: None,
         }
     }
 
@@ -199,11 +476,11 @@ impl Entity {
         generates.insert("insight".to_string(), 0.001);
 
         Self {
+            name: Some(names::visitor(&id, &VisitorType::Observer)),
             id,
             entity_type: EntityType::Visitor,
             role: None,
             subtype: Some(VisitorType::Observer),
-            name: Some("An Observer".to_string()),
             tile: "receiver".to_string(),
             age: 0,
             hunger: 100.0,
@@ -211,23 +488,32 @@ impl Entity {
             max_age: 3600, // 1 hour
             food: Some("crystals".to_string()),
             processing_corpse: None,
-            processing_ticks: None,
             from_outside: Some(true),
             description: Some("Watches. Generates insight from the watching.".to_string()),
             gift_on_death: None,
             generates: Some(generates),
             transforms: None,
+            dormant: None,
+            task: None,
+            destination: None,
+            memories: Vec::new(),
+            timers: BTreeMap::new(),
+            traits: Vec::new(),
+            health: constants::MAX_HEALTH,
+            morale: constants::MAX_MORALE,
+            inventory: HashMap::new(),
+            route: None,
         }
     }
 
     /// Create a hungry visitor
     pub fn new_hungry(id: EntityId) -> Self {
         Self {
+            name: Some(names::visitor(&id, &VisitorType::Hungry)),
             id,
             entity_type: EntityType::Visitor,
             role: None,
             subtype: Some(VisitorType::Hungry),
-            name: Some("A Hungry Thing".to_string()),
             tile: "receiver".to_string(),
             age: 0,
             hunger: 100.0,
@@ -235,12 +521,21 @@ impl Entity {
             max_age: 900, // 15 minutes
             food: Some("influence".to_string()),
             processing_corpse: None,
-            processing_ticks: None,
             from_outside: Some(true),
             description: Some("Consumes. Transforms what it consumes.".to_string()),
             gift_on_death: None,
             generates: None,
             transforms: Some(true),
+            dormant: None,
+            task: None,
+            destination: None,
+            memories: Vec::new(),
+            timers: BTreeMap::new(),
+            traits: Vec::new(),
+            health: constants::MAX_HEALTH,
+            morale: constants::MAX_MORALE,
+            inventory: HashMap::new(),
+            route: None,
         }
     }
 
@@ -249,6 +544,57 @@ impl Entity {
         self.hunger <= 0.0 || self.age >= self.max_age
     }
 
+    /// Capture the entity's notable stats for an event
+    pub fn snapshot(&self) -> EntitySnapshot {
+        EntitySnapshot {
+            entity_type: self.entity_type.clone(),
+            role: self.role.clone(),
+            subtype: self.subtype.clone(),
+            name: self.name.clone(),
+            tile: self.tile.clone(),
+            age: self.age,
+            max_age: self.max_age,
+            hunger: self.hunger,
+            memories: self.memories.clone(),
+        }
+    }
+
+    /// Remember `experience` at `tick`, letting the oldest memory fade once
+    /// there are `MEMORY_CAPACITY`
+    pub fn remember(&mut self, tick: u64, experience: Experience) {
+        if self.memories.len() >= constants::MEMORY_CAPACITY {
+            self.memories.remove(0);
+        }
+        self.memories.push(Memory { tick, experience });
+    }
+
+    /// Current value of timer `name` (0 if unset)
+    pub fn timer(&self, name: &str) -> u64 {
+        self.timers.get(name).copied().unwrap_or(0)
+    }
+
+    /// Set timer `name`
+    pub fn set_timer(&mut self, name: &str, ticks: u64) {
+        self.timers.insert(name.to_string(), ticks);
+    }
+
+    /// Add one tick to timer `name`, returning the new value
+    pub fn advance_timer(&mut self, name: &str) -> u64 {
+        let ticks = self.timers.entry(name.to_string()).or_insert(0);
+        *ticks += 1;
+        *ticks
+    }
+
+    /// Remove timer `name`
+    pub fn clear_timer(&mut self, name: &str) {
+        self.timers.remove(name);
+    }
+
+    /// Asleep for want of food (zen colonies only)
+    pub fn is_dormant(&self) -> bool {
+        self.dormant.unwrap_or(false)
+    }
+
     /// Get cause of death if dead
     pub fn cause_of_death(&self) -> Option<DeathCause> {
         if self.hunger <= 0.0 {
@@ -261,6 +607,31 @@ impl Entity {
     }
 }
 
+/// An entity as it was when an event fired, so observers don't need the
+/// entity to still be in state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntitySnapshot {
+    #[serde(rename = "type")]
+    pub entity_type: EntityType,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<AntRole>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtype: Option<VisitorType>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    pub tile: String,
+    pub age: u64,
+    pub max_age: u64,
+    pub hunger: f64,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memories: Vec<Memory>,
+}
+
 /// Cause of entity death
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -268,4 +639,39 @@ pub enum DeathCause {
     Starvation,
     OldAge,
     Blight,
+    /// Lost on an expedition to the Outside (no corpse comes home)
+    LostOutside,
+    /// Killed by raiders or predators (see `threat`)
+    Slain,
+    /// Worn down by a contaminated tile (with `feature::INJURIES`)
+    Sickness,
+    /// Wandered off in despair (with `feature::MORALE`; no corpse is left)
+    Desertion,
+    /// Caught in a flooded tile (with `feature::HAZARDS`)
+    Drowned,
+    /// Caught in a cave-in (with `feature::HAZARDS`)
+    Buried,
+}
+
+/// What's wearing an ant's health down (with `feature::INJURIES`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Harm {
+    /// An empty stomach
+    Starvation,
+    /// Standing on a blighted tile
+    Blight,
+    /// Standing on a contaminated one
+    Sickness,
+}
+
+impl Harm {
+    /// What an ant this kills dies of
+    pub fn cause(self) -> DeathCause {
+        match self {
+            Harm::Starvation => DeathCause::Starvation,
+            Harm::Blight => DeathCause::Blight,
+            Harm::Sickness => DeathCause::Sickness,
+        }
+    }
 }
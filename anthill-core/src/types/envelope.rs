@@ -0,0 +1,57 @@
+//! Save envelope: a state wrapped with what's needed to trust it.
+//!
+//! `GameState::to_json_envelope` records the envelope format, the core
+//! version, a digest of the state and a digest of the engine config;
+//! `GameState::from_json_envelope` checks all of them before loading, so
+//! truncated or hand-edited saves are refused up front.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::migrations::MigrationError;
+
+/// Current envelope format
+pub const ENVELOPE_VERSION: u32 = 1;
+
+/// A state plus integrity metadata, as written to disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveEnvelope {
+    /// Envelope format version
+    pub envelope_version: u32,
+
+    /// Version of anthill-core that wrote the save
+    pub core_version: String,
+
+    /// Digest of `state` (hex)
+    pub state_digest: String,
+
+    /// Digest of the engine config the state was run under (hex)
+    pub config_digest: String,
+
+    /// The state itself, as plain JSON
+    pub state: serde_json::Value,
+}
+
+/// Why an enveloped save was refused
+#[derive(Debug, Error)]
+pub enum EnvelopeError {
+    #[error("save is not a valid envelope: {0}")]
+    Malformed(#[from] serde_json::Error),
+
+    #[error("envelope version {found} is not supported (expected {supported})")]
+    UnsupportedVersion { found: u32, supported: u32 },
+
+    #[error("saved state can't be upgraded: {0}")]
+    Schema(#[from] MigrationError),
+
+    #[error("state digest mismatch: envelope says {expected}, state hashes to {found}")]
+    StateDigestMismatch { expected: String, found: String },
+
+    #[error("engine config mismatch: save was run under {expected}, this engine is {found}")]
+    ConfigDigestMismatch { expected: String, found: String },
+}
+
+/// Digests are written as 16 hex digits
+pub(crate) fn hex(digest: u64) -> String {
+    format!("{:016x}", digest)
+}
@@ -0,0 +1,193 @@
+//! The plugin event queue: events the plugin layer hands itself across
+//! ticks and restarts.
+//!
+//! Every enqueued event gets the next sequence number. Consumers subscribe
+//! by name and each keep a cursor, saved with the state, at the last
+//! sequence number they acknowledged; reading never moves it, so a plugin
+//! that crashes before acking reads the same events again on restart. An
+//! event is dropped once every subscriber has acked it. The queue holds at
+//! most `capacity` events: past that, enqueueing is refused until the
+//! slowest consumer catches up, rather than growing without bound.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+use crate::engine::constants;
+
+/// An event waiting on its consumers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedEvent {
+    pub seq: u64,
+    pub payload: serde_json::Value,
+}
+
+impl QueuedEvent {
+    /// The payload as the type it was enqueued as
+    pub fn decode<T: DeserializeOwned>(&self) -> Result<T, QueueError> {
+        serde_json::from_value(self.payload.clone()).map_err(|e| QueueError::Payload(e.to_string()))
+    }
+}
+
+/// Why the queue refused a call
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum QueueError {
+    #[error("event queue is full ({0} events); the slowest consumer must ack first")]
+    Full(usize),
+
+    #[error("no consumer named {0} has subscribed")]
+    UnknownConsumer(String),
+
+    #[error("can't ack event {seq}; the last one enqueued is {last}")]
+    AckAhead { seq: u64, last: u64 },
+
+    #[error("event payload doesn't fit: {0}")]
+    Payload(String),
+}
+
+/// Pending plugin events and each consumer's place in them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventQueue {
+    /// Events some subscriber hasn't acked yet, oldest first
+    #[serde(default)]
+    pub pending: Vec<QueuedEvent>,
+
+    /// Sequence number of the last event enqueued (0 before the first)
+    #[serde(default)]
+    pub last_seq: u64,
+
+    /// Each consumer's cursor: the last sequence number it acked
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub cursors: BTreeMap<String, u64>,
+
+    /// Most events held at once
+    #[serde(default = "default_capacity")]
+    pub capacity: usize,
+}
+
+fn default_capacity() -> usize {
+    constants::EVENT_QUEUE_CAPACITY
+}
+
+impl Default for EventQueue {
+    fn default() -> Self {
+        Self {
+            pending: Vec::new(),
+            last_seq: 0,
+            cursors: BTreeMap::new(),
+            capacity: default_capacity(),
+        }
+    }
+}
+
+impl EventQueue {
+    /// Add an event, returning its sequence number
+    pub fn enqueue<T: Serialize>(&mut self, event: &T) -> Result<u64, QueueError> {
+        if self.pending.len() >= self.capacity {
+            return Err(QueueError::Full(self.capacity));
+        }
+        let payload = serde_json::to_value(event).map_err(|e| QueueError::Payload(e.to_string()))?;
+        self.last_seq += 1;
+        self.pending.push(QueuedEvent { seq: self.last_seq, payload });
+        Ok(self.last_seq)
+    }
+
+    /// Start tracking `consumer`, from the oldest event still pending
+    /// (subscribing again changes nothing)
+    pub fn subscribe(&mut self, consumer: &str) {
+        let from = self.pending.first().map_or(self.last_seq, |event| event.seq - 1);
+        self.cursors.entry(consumer.to_string()).or_insert(from);
+    }
+
+    /// Stop tracking `consumer`, dropping anything only it was holding
+    pub fn unsubscribe(&mut self, consumer: &str) {
+        if self.cursors.remove(consumer).is_some() {
+            self.compact();
+        }
+    }
+
+    /// Up to `limit` events `consumer` hasn't acked, oldest first
+    pub fn unread(&self, consumer: &str, limit: usize) -> Result<&[QueuedEvent], QueueError> {
+        let cursor = self.cursor(consumer)?;
+        let start = self.pending.partition_point(|event| event.seq <= cursor);
+        let end = self.pending.len().min(start.saturating_add(limit));
+        Ok(&self.pending[start..end])
+    }
+
+    /// Mark everything up to `seq` read by `consumer`, returning how many
+    /// events that dropped from the queue. Acking behind the cursor is a
+    /// no-op, so a retried ack is harmless.
+    pub fn ack(&mut self, consumer: &str, seq: u64) -> Result<usize, QueueError> {
+        if seq > self.last_seq {
+            return Err(QueueError::AckAhead { seq, last: self.last_seq });
+        }
+        let cursor = self.cursors.get_mut(consumer)
+            .ok_or_else(|| QueueError::UnknownConsumer(consumer.to_string()))?;
+        *cursor = (*cursor).max(seq);
+        Ok(self.compact())
+    }
+
+    /// Last sequence number `consumer` acked
+    pub fn cursor(&self, consumer: &str) -> Result<u64, QueueError> {
+        self.cursors.get(consumer).copied().ok_or_else(|| QueueError::UnknownConsumer(consumer.to_string()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Drop events every subscriber has acked; with no subscribers,
+    /// everything waits for the first
+    fn compact(&mut self) -> usize {
+        let Some(&slowest) = self.cursors.values().min() else {
+            return 0;
+        };
+        let read = self.pending.partition_point(|event| event.seq <= slowest);
+        self.pending.drain(..read);
+        read
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consumers_keep_their_own_place() {
+        let mut queue = EventQueue { capacity: 3, ..Default::default() };
+        assert_eq!(queue.enqueue(&"first").unwrap(), 1);
+        queue.subscribe("narrator");
+        queue.subscribe("stats");
+        queue.enqueue(&"second").unwrap();
+        queue.enqueue(&"third").unwrap();
+        assert_eq!(queue.enqueue(&"fourth"), Err(QueueError::Full(3)));
+
+        // Reading doesn't move the cursor; acking does, and only one's own
+        let seqs = |events: &[QueuedEvent]| events.iter().map(|e| e.seq).collect::<Vec<_>>();
+        assert_eq!(seqs(queue.unread("narrator", 2).unwrap()), [1, 2]);
+        assert_eq!(queue.ack("narrator", 2).unwrap(), 0);
+        assert_eq!(seqs(queue.unread("narrator", 10).unwrap()), [3]);
+        assert_eq!(seqs(queue.unread("stats", 10).unwrap()), [1, 2, 3]);
+
+        // Once both have read an event it's gone, making room
+        assert_eq!(queue.ack("stats", 1).unwrap(), 1);
+        assert_eq!(queue.ack("stats", 1).unwrap(), 0);
+        assert_eq!(queue.enqueue(&"fourth").unwrap(), 4);
+        assert_eq!(queue.ack("stats", 9), Err(QueueError::AckAhead { seq: 9, last: 4 }));
+        assert_eq!(queue.unread("plugin", 1), Err(QueueError::UnknownConsumer("plugin".to_string())));
+
+        // Cursors survive a save
+        let queue: EventQueue = serde_json::from_str(&serde_json::to_string(&queue).unwrap()).unwrap();
+        assert_eq!(queue.unread("stats", 1).unwrap()[0].decode::<String>().unwrap(), "second");
+
+        // A consumer leaving releases what only it held
+        let mut queue = queue;
+        queue.unsubscribe("stats");
+        assert_eq!(seqs(&queue.pending), [3, 4]);
+    }
+}
@@ -0,0 +1,140 @@
+//! Excavations: new tiles dug out of the ground next to old ones.
+//!
+//! `TickEngine::dig_tile` opens an excavation beside an existing tile, on
+//! the first free square east, south, west or north of it. From then on
+//! the colony's diggers put their work into it as well as bringing up
+//! dirt: every bit of dirt dug counts toward its `excavation_work`, oldest
+//! excavation first. When it's done the tile joins the map, connected to
+//! the one it was dug from. What the diggers break into is set by the
+//! Outside seed and the square alone, so the same square always holds the
+//! same thing: open ground, an ore vein, or a crystal pocket.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::digest::fnv1a64;
+use crate::engine::constants;
+use crate::rng::SeededRng;
+use super::config::Tuning;
+use super::key::Key;
+use super::tile::{GameMap, Tile};
+
+/// Squares beside a tile, in the order they're tried
+pub const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+/// What a dug square turns out to hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Vein {
+    /// Open ground
+    Empty,
+    Ore,
+    Crystal,
+}
+
+impl Vein {
+    /// What the square at (`x`, `y`) holds under `seed`
+    pub fn at(seed: u64, x: i32, y: i32, tuning: &Tuning) -> Self {
+        let bytes = [seed.to_le_bytes().as_slice(), &x.to_le_bytes(), &y.to_le_bytes()].concat();
+        let roll = SeededRng::new(fnv1a64(&bytes) ^ constants::EXCAVATION_STREAM).random();
+        if roll < tuning.crystal_pocket_chance {
+            Vein::Crystal
+        } else if roll < tuning.crystal_pocket_chance + tuning.ore_vein_chance {
+            Vein::Ore
+        } else {
+            Vein::Empty
+        }
+    }
+
+    /// The tile dug out at (`x`, `y`)
+    pub fn tile(self, x: i32, y: i32, tuning: &Tuning) -> Tile {
+        match self {
+            Vein::Empty => Tile::new_empty("Fresh Tunnel".to_string(), x, y),
+            Vein::Ore => Tile::new_resource("Ore Vein".to_string(), x, y, "ore", tuning.ore_vein_stock),
+            Vein::Crystal => Tile::new_resource("Crystal Pocket".to_string(), x, y, "crystals", tuning.crystal_pocket_stock),
+        }
+    }
+}
+
+/// A tile being dug, keyed by the ID it will have in
+/// `GameState::excavations`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Excavation {
+    /// The tile it's dug from, which it will connect to
+    pub from: Key,
+
+    pub x: i32,
+    pub y: i32,
+
+    /// Tick it was started
+    pub started_at: u64,
+
+    /// Dig work put in so far, in dirt dug
+    pub work_done: f64,
+
+    /// Dig work it takes
+    pub work_needed: f64,
+}
+
+impl Excavation {
+    pub fn is_done(&self) -> bool {
+        self.work_done >= self.work_needed
+    }
+
+    /// Share of the work done, from 0 to 1
+    pub fn progress(&self) -> f64 {
+        if self.work_needed > 0.0 { (self.work_done / self.work_needed).min(1.0) } else { 1.0 }
+    }
+}
+
+/// The first free square beside `from`, not on the map and not already
+/// being dug (`taken`). The map has no lookup by square, so the tiles
+/// beside `from` are gathered in one pass.
+pub fn free_square(map: &GameMap, from: &Tile, taken: impl Fn(i32, i32) -> bool) -> Option<(i32, i32)> {
+    let beside: Vec<(i32, i32)> = map.tiles.values()
+        .map(|tile| (tile.x, tile.y))
+        .filter(|&(x, y)| (x - from.x).abs() + (y - from.y).abs() == 1)
+        .collect();
+    DIRECTIONS.into_iter()
+        .map(|(dx, dy)| (from.x + dx, from.y + dy))
+        .find(|&(x, y)| !taken(x, y) && !beside.contains(&(x, y)))
+}
+
+/// Why a dig was refused
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ExcavationError {
+    #[error("tile {0} does not exist")]
+    UnknownTile(String),
+
+    #[error("tile {0} can't be reached from the origin")]
+    Unreachable(String),
+
+    #[error("a tile with ID {0} already exists")]
+    TileExists(String),
+
+    #[error("tile {0} is already being dug")]
+    AlreadyDigging(String),
+
+    #[error("there's no free ground beside tile {0}")]
+    NoRoom(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_squares_hold_the_same_vein_every_time() {
+        let tuning = Tuning::default();
+        let veins: Vec<Vein> = (0..200).map(|x| Vein::at(7, x, 3, &tuning)).collect();
+        assert_eq!(veins, (0..200).map(|x| Vein::at(7, x, 3, &tuning)).collect::<Vec<_>>());
+        for vein in [Vein::Empty, Vein::Ore, Vein::Crystal] {
+            assert!(veins.contains(&vein), "{vein:?} turns up somewhere");
+        }
+        assert!(veins.iter().filter(|v| **v == Vein::Empty).count() > veins.len() / 2);
+        assert_ne!(veins, (0..200).map(|x| Vein::at(8, x, 3, &tuning)).collect::<Vec<_>>());
+
+        let pocket = Vein::Crystal.tile(1, 2, &tuning);
+        assert_eq!((pocket.resource.as_deref(), pocket.stock), (Some("crystals"), Some(tuning.crystal_pocket_stock)));
+    }
+}
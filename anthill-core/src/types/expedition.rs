@@ -0,0 +1,57 @@
+//! Expeditions: groups of ants sent to the Outside.
+//!
+//! Members are removed from `GameState::entities` while away, so no tick
+//! phase touches them. They're held here until the expedition returns.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::entity::{Entity, EntityId};
+
+/// The action type used for expedition timers in the action queue
+pub const EXPEDITION_ACTION: &str = "expedition";
+
+/// A group of ants away from the colony
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Expedition {
+    /// Unique identifier (shared with its queued action)
+    pub id: String,
+
+    /// The ants on the expedition, frozen as they left
+    pub members: Vec<Entity>,
+
+    /// Tile the expedition left from (and returns to)
+    pub from_tile: String,
+
+    /// Tick the expedition departed
+    pub departed_tick: u64,
+
+    /// Tick the expedition is due back
+    pub return_tick: u64,
+}
+
+impl Expedition {
+    /// IDs of the ants on this expedition
+    pub fn member_ids(&self) -> Vec<EntityId> {
+        self.members.iter().map(|e| e.id.clone()).collect()
+    }
+}
+
+/// Why an expedition couldn't be launched
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ExpeditionError {
+    #[error("an expedition needs at least one member")]
+    NoMembers,
+
+    #[error("an expedition must last at least one tick")]
+    ZeroDuration,
+
+    #[error("entity {0} not found in the colony")]
+    UnknownEntity(EntityId),
+
+    #[error("entity {0} is not an ant")]
+    NotAnAnt(EntityId),
+
+    #[error("entity {0} is listed more than once")]
+    DuplicateMember(EntityId),
+}
@@ -0,0 +1,74 @@
+//! Per-save feature flags for staged rollouts.
+//!
+//! Mechanics that would change how an existing colony plays out check
+//! `GameState::has_feature` before running. Saves written before a mechanic
+//! existed carry no flag for it and keep their legacy behavior tick for
+//! tick; `GameState::new` starts fresh colonies with every flag in
+//! `CURRENT`. Toggle through `TickEngine::set_feature` to get events.
+
+/// Raids on the colony (the threat subsystem)
+pub const RAIDS: &str = "raids";
+
+/// Seasons turning over the colony's year
+pub const SEASONS: &str = "seasons";
+
+/// Once-in-a-lifetime wonders (see `wonder`)
+pub const WONDERS: &str = "wonders";
+
+/// Ants walk the map's connections to where they eat and work (the
+/// movement phase); without it they act wherever they stand
+pub const MOVEMENT: &str = "movement";
+
+/// A silent receiver goes dormant, then derelict, each costlier to restore
+/// (see `silence`)
+pub const SILENCE_STAGES: &str = "silence_stages";
+
+/// Influence past a soft cap drains away, and some is spent on whispers
+/// (see `whisper`)
+pub const WHISPERS: &str = "whispers";
+
+/// Brood need nurses to feed them, and the queen lays slower untended
+pub const NURSES: &str = "nurses";
+
+/// Brood develop egg, larva, pupa, each stage with its own needs and
+/// losses (see `brood`)
+pub const LIFECYCLE: &str = "lifecycle";
+
+/// The queen is an ant who ages and dies; a princess raised before she
+/// does succeeds her, and with no living queen nothing is laid
+pub const SUCCESSION: &str = "succession";
+
+/// Ants are born with traits passed down from the queen (see `heredity`)
+pub const TRAITS: &str = "traits";
+
+/// Ants have health apart from hunger: starving, blight and contamination
+/// wear it down, rest restores it, and they die when it's gone rather than
+/// the moment they go hungry or blight strikes
+pub const INJURIES: &str = "injuries";
+
+/// Ants have morale, moved by sanity, famine and deaths nearby; low
+/// morale slows their work and may drive them off (see `morale`)
+pub const MORALE: &str = "morale";
+
+/// Foragers and haulers carry goods in their inventories and walk them
+/// where they're going, rather than moving them the moment they're taken
+pub const CARRYING: &str = "carrying";
+
+/// A summons during an Outside conjunction, heard by a receiver with
+/// enough antennas, can bring several visitors at once (see `convergence`)
+pub const CONVERGENCES: &str = "convergences";
+
+/// Walking ants lay pheromone trails that fade over time, and walkers
+/// with a choice of ways follow the strongest (see `pheromone`)
+pub const TRAILS: &str = "trails";
+
+/// Ants work less at night, some visitors and wonders only come after
+/// dark, and dawn and dusk are reported (see `daynight`)
+pub const DAY_NIGHT: &str = "day_night";
+
+/// Low tiles flood in the rain and fresh ones cave in, stopping their
+/// systems and killing those caught there (see `hazard`)
+pub const HAZARDS: &str = "hazards";
+
+/// Flags a new game starts with
+pub const CURRENT: &[&str] = &[RAIDS, SEASONS, WONDERS, MOVEMENT, SILENCE_STAGES, WHISPERS, NURSES, LIFECYCLE, SUCCESSION, TRAITS, INJURIES, MORALE, CARRYING, CONVERGENCES, TRAILS, DAY_NIGHT, HAZARDS];
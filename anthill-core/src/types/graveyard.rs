@@ -47,6 +47,12 @@ impl Graveyard {
         }
     }
 
+    /// Take the oldest corpse lying on `tile`
+    pub fn take_corpse_on(&mut self, tile: &str) -> Option<Corpse> {
+        let idx = self.corpses.iter().position(|c| c.tile == tile)?;
+        Some(self.corpses.remove(idx))
+    }
+
     /// Peek at the next corpse without removing
     pub fn peek_corpse(&self) -> Option<&Corpse> {
         self.corpses.first()
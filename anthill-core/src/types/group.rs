@@ -0,0 +1,75 @@
+//! Groups: named sets of ants that act on a shared intent.
+//!
+//! Anything that moves several ants together (an expedition party, a guard
+//! standing against raids, a crew holding a tile, a team put on one task
+//! such as hauling) is a `Group` in `GameState::groups`. Hosts form, retask
+//! and disband groups through commands; the groups phase carries out each
+//! group's intent every tick, and the threat phase calls on guards. An ant
+//! belongs to at most one group, and members away on an expedition stay in
+//! their group until they come home.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use thiserror::Error;
+
+use super::entity::EntityId;
+use super::policy::Task;
+
+/// What a group is trying to do
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Intent {
+    /// Stay where they are
+    #[default]
+    Hold,
+    /// Gather on `tile`; the group holds there once everyone has arrived
+    GoTo { tile: String },
+    /// Every worker in the group works `task`, outside the work policy
+    Work { task: Task },
+    /// Set off together on an expedition lasting `duration_ticks`; the
+    /// group holds once it has left
+    Explore { duration_ticks: u64 },
+    /// Stand guard: members meet every incursion as soldiers do, however
+    /// they were born
+    Defend,
+}
+
+/// A named set of ants with a shared intent
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Group {
+    pub members: BTreeSet<EntityId>,
+
+    #[serde(default)]
+    pub intent: Intent,
+}
+
+impl Group {
+    pub fn new(members: impl IntoIterator<Item = EntityId>) -> Self {
+        Self { members: members.into_iter().collect(), intent: Intent::Hold }
+    }
+}
+
+/// Why a group command was refused
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum GroupError {
+    #[error("a group named {0} already exists")]
+    GroupExists(String),
+
+    #[error("no group named {0}")]
+    UnknownGroup(String),
+
+    #[error("a group needs at least one member")]
+    NoMembers,
+
+    #[error("entity {0} is not an ant in the colony")]
+    NotAnAnt(EntityId),
+
+    #[error("entity {entity_id} already belongs to group {group}")]
+    AlreadyGrouped { entity_id: EntityId, group: String },
+
+    #[error("tile {0} does not exist")]
+    UnknownTile(String),
+
+    #[error("an expedition must last at least one tick")]
+    ZeroDuration,
+}
@@ -0,0 +1,55 @@
+//! Hazards: disasters that strike one tile at a time.
+//!
+//! Blight is the oldest, rolled from the compost heap's contamination. With
+//! `feature::HAZARDS` two more join it: floods, on tiles below the surface
+//! (negative `elevation`) while it rains, and cave-ins, on tiles dug within
+//! the last `cave_in_window` seconds. All of them strike the same way
+//! (`TickEngine` shares one routine): the systems on the tile stop, whoever
+//! is standing there may die of it, and the rest of the colony remembers.
+//! A flood or cave-in holds its tile for a while (`Tile::hazard`), then
+//! passes and the systems it stopped start up again, unless something
+//! else (another hazard, or the guard's quarantine) still holds them.
+
+use serde::{Deserialize, Serialize};
+
+use super::entity::DeathCause;
+
+/// A kind of disaster
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hazard {
+    Blight,
+    Flood,
+    CaveIn,
+}
+
+impl Hazard {
+    /// What those it kills die of
+    pub fn cause(self) -> DeathCause {
+        match self {
+            Hazard::Blight => DeathCause::Blight,
+            Hazard::Flood => DeathCause::Drowned,
+            Hazard::CaveIn => DeathCause::Buried,
+        }
+    }
+}
+
+/// A flood or cave-in holding a tile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Struck {
+    pub hazard: Hazard,
+
+    /// Tick it passes on
+    pub until: u64,
+
+    /// Systems it stopped, the ones to start again when it passes (None in
+    /// older saves: every system on the tile)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stopped: Option<Vec<String>>,
+}
+
+impl Struck {
+    pub fn has_passed(&self, tick: u64) -> bool {
+        tick >= self.until
+    }
+}
@@ -0,0 +1,84 @@
+//! Colony health: one number for how the colony is doing.
+//!
+//! Every frontend shows the same score because the core works it out.
+//! Five parts, each scored 0 (dire) to 1 (fine) from the state alone, are
+//! weighed by `WEIGHTS` into a score out of 100:
+//!
+//! - **Food**: the shortest runway of any food being eaten faster than it's
+//!   made, against `HEALTH_FOOD_HORIZON` seconds (1 when nothing drains).
+//! - **Population**: brood on the way against ants dying or about to (the
+//!   unburied dead, and ants within a demographic bracket of old age), per
+//!   living ant, centred on 0.5; 0 with no ants at all.
+//! - **Contamination**: the compost's contamination, inverted; 0 while
+//!   blighted.
+//! - **Sanity**: the colony's sanity out of 100.
+//! - **Maintenance**: 1 with no receiver or the next maintenance paid for;
+//!   the share of the interval left while it isn't; 0 once it's silent.
+//!
+//! The engine reports the score with `HealthChanged` when it moves by
+//! `HEALTH_CHANGE_THRESHOLD` points or more from the last one reported.
+
+use serde::{Deserialize, Serialize};
+
+/// How much each part counts toward the score (they sum to 1)
+pub const WEIGHTS: HealthParts = HealthParts {
+    food: 0.3,
+    population: 0.2,
+    contamination: 0.15,
+    sanity: 0.2,
+    maintenance: 0.15,
+};
+
+/// The score and what went into it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Health {
+    /// 0 to 100
+    pub score: f64,
+    pub parts: HealthParts,
+}
+
+/// Each part of the score, 0 to 1
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HealthParts {
+    pub food: f64,
+    pub population: f64,
+    pub contamination: f64,
+    pub sanity: f64,
+    pub maintenance: f64,
+}
+
+impl Health {
+    /// Weigh `parts` (clamped to 0..=1) into a score
+    pub fn from_parts(parts: HealthParts) -> Self {
+        let parts = HealthParts {
+            food: parts.food.clamp(0.0, 1.0),
+            population: parts.population.clamp(0.0, 1.0),
+            contamination: parts.contamination.clamp(0.0, 1.0),
+            sanity: parts.sanity.clamp(0.0, 1.0),
+            maintenance: parts.maintenance.clamp(0.0, 1.0),
+        };
+        let score = 100.0 * (WEIGHTS.food * parts.food
+            + WEIGHTS.population * parts.population
+            + WEIGHTS.contamination * parts.contamination
+            + WEIGHTS.sanity * parts.sanity
+            + WEIGHTS.maintenance * parts.maintenance);
+        Self { score, parts }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weights_sum_to_one() {
+        let all = |value| HealthParts { food: value, population: value, contamination: value, sanity: value, maintenance: value };
+        assert!((Health::from_parts(all(1.0)).score - 100.0).abs() < 1e-9);
+        assert_eq!(Health::from_parts(all(0.0)).score, 0.0);
+
+        // Out-of-range parts are clamped before weighing
+        let health = Health::from_parts(HealthParts { food: 2.0, ..all(0.0) });
+        assert_eq!(health.parts.food, 1.0);
+        assert!((health.score - 30.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,91 @@
+//! Heritable traits: what an ant is born with.
+//!
+//! With `feature::TRAITS`, each ant the queen lays gets its traits when
+//! it's laid: each of the reigning queen's traits passes on with
+//! `trait_inherit_chance`, and any trait she lacks turns up with
+//! `trait_mutation_chance`. The rolls hash the newcomer's ID (which comes
+//! from the seeded RNG) rather than drawing from the stream, so the same
+//! seed breeds the same colony and no other phase's draws move. Traits
+//! scale how fast an ant gets hungry, how long it lives, and how much its
+//! work turns up; the entity and work phases read them every tick.
+
+use serde::{Deserialize, Serialize};
+
+use crate::digest::fnv1a64;
+
+/// Something an ant was born with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trait {
+    /// Gets hungry slower
+    Hardy,
+    /// Gets hungry faster
+    Sickly,
+    /// Turns up more from foraging, digging and harvesting
+    Industrious,
+    /// Lives longer
+    Enduring,
+}
+
+impl Trait {
+    pub const ALL: [Trait; 4] = [Trait::Hardy, Trait::Sickly, Trait::Industrious, Trait::Enduring];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Trait::Hardy => "hardy",
+            Trait::Sickly => "sickly",
+            Trait::Industrious => "industrious",
+            Trait::Enduring => "enduring",
+        }
+    }
+
+    /// Multiplier on hunger lost per tick
+    pub fn hunger_factor(self) -> f64 {
+        match self {
+            Trait::Hardy => 0.75,
+            Trait::Sickly => 1.25,
+            _ => 1.0,
+        }
+    }
+
+    /// Multiplier on what the ant's work turns up
+    pub fn work_factor(self) -> f64 {
+        if self == Trait::Industrious { 1.25 } else { 1.0 }
+    }
+
+    /// Multiplier on how long the ant lives
+    pub fn lifespan_factor(self) -> f64 {
+        if self == Trait::Enduring { 1.25 } else { 1.0 }
+    }
+}
+
+/// Traits for the ant with ID `id`, born to a parent with `parent`
+/// traits, in `Trait::ALL` order
+pub fn inherit(id: &str, parent: &[Trait], inherit_chance: f64, mutation_chance: f64) -> Vec<Trait> {
+    Trait::ALL.into_iter()
+        .filter(|t| {
+            let chance = if parent.contains(t) { inherit_chance } else { mutation_chance };
+            let roll = fnv1a64(format!("{id}/trait/{}", t.name()).as_bytes()) as f64 / u64::MAX as f64;
+            roll < chance
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inheritance_is_a_function_of_the_id() {
+        let parent = [Trait::Hardy, Trait::Industrious];
+        assert_eq!(inherit("a1", &parent, 1.0, 0.0), parent);
+        assert!(inherit("a1", &parent, 0.0, 0.0).is_empty());
+        assert_eq!(inherit("a1", &[], 0.0, 1.0), Trait::ALL);
+
+        let ids: Vec<String> = (0..200).map(|i| format!("ant{i}")).collect();
+        let bred = |id: &String| inherit(id, &parent, 0.5, 0.05);
+        assert_eq!(ids.iter().map(bred).collect::<Vec<_>>(), ids.iter().map(bred).collect::<Vec<_>>());
+        let hardy = ids.iter().filter(|id| bred(id).contains(&Trait::Hardy)).count();
+        assert!((60..140).contains(&hardy), "about half inherit: {hardy}");
+    }
+}
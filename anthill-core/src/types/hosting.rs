@@ -0,0 +1,92 @@
+//! Hosting: paying to keep a visitor around longer.
+//!
+//! `HostVisitor` queues a recurring `host_visitor` action aimed at the
+//! visitor. Each time it comes round (every `host_interval`) the colony
+//! pays `host_cost_fungus` and `host_cost_crystals` and the visitor's stay
+//! grows, by less each time: `host_extension` scaled by the share of
+//! `host_max_extension` not yet granted, so the total never passes the cap.
+//! Hosting stops when the visitor leaves, the colony can't pay, the cap is
+//! reached, or `StopHosting` calls it off.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::entity::EntityId;
+
+/// The action type used for hosting in the action queue
+pub const HOST_ACTION: &str = "host_visitor";
+
+/// The queued hosting action's ID for `visitor_id`
+pub fn action_id(visitor_id: &str) -> String {
+    format!("{HOST_ACTION}_{visitor_id}")
+}
+
+/// Ticks the next extension adds to a stay already extended by
+/// `extended`: `base` scaled by how much of `cap` is left, and never past
+/// it. Rounded up, so only a stay at the cap (or a zero `base`) gets 0.
+pub fn stay_extension(extended: u64, base: u64, cap: u64) -> u64 {
+    if cap == 0 {
+        return 0;
+    }
+    let left = cap.saturating_sub(extended);
+    let scaled = (base as f64 * left as f64 / cap as f64).ceil() as u64;
+    scaled.min(left)
+}
+
+/// Why hosting a visitor stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HostingEnd {
+    /// The visitor left (or was never there by the time it came round)
+    Departed,
+    /// The colony couldn't pay for another round
+    Unaffordable,
+    /// The stay can't be extended any further
+    Capped,
+    /// Called off with `StopHosting`
+    Stopped,
+}
+
+/// Why a hosting command was refused
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum HostingError {
+    #[error("entity {0} not found in the colony")]
+    UnknownVisitor(EntityId),
+
+    #[error("entity {0} is not a visitor")]
+    NotAVisitor(EntityId),
+
+    #[error("visitor {0} is already being hosted")]
+    AlreadyHosted(EntityId),
+
+    #[error("visitor {0} isn't being hosted")]
+    NotHosted(EntityId),
+
+    #[error("visitor {0}'s stay can't be extended any further")]
+    Capped(EntityId),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extensions_diminish_to_the_cap() {
+        let mut extended = 0;
+        let mut steps = Vec::new();
+        loop {
+            let step = stay_extension(extended, 600, 1200);
+            if step == 0 {
+                break;
+            }
+            steps.push(step);
+            extended += step;
+        }
+        assert_eq!(&steps[..3], [600, 300, 150]);
+        assert!(steps.windows(2).all(|w| w[1] <= w[0]));
+        assert_eq!(extended, 1200, "Small steps still reach the cap");
+        assert_eq!(stay_extension(1199, 600, 1200), 1);
+        assert_eq!(stay_extension(0, 5000, 1200), 1200);
+        assert_eq!(stay_extension(0, 600, 0), 0);
+    }
+}
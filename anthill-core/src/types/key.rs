@@ -0,0 +1,246 @@
+//! Interned keys for resource names and tile IDs.
+//!
+//! Each distinct name is stored once per process and never freed, so a
+//! `Key` is a `Copy` handle and equality is an address comparison. Keys
+//! hash and order by their text (so keyed maps can still be queried with a
+//! `&str`) and serialize as plain strings, so saves and event JSON are
+//! unchanged.
+//!
+//! The table is shared by every state in the process, so it holds the
+//! union of the names every one of them has used: resource names (a few
+//! dozen at most) and tile IDs (one per tile ever dug, generated or
+//! discovered, including tiles since demolished). A process that runs one
+//! colony keeps a table the size of its map's history; one that loads many
+//! unrelated saves grows by each save's tiles, at roughly the length of
+//! the ID plus two words apiece. Hosts that cycle through saves for a long
+//! time can watch `Key::interned()`, and restart the process if it matters.
+//! Lookups (`Key::lookup`, keyed map queries by `&str`) never add to it.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::{OnceLock, RwLock};
+
+fn table() -> &'static RwLock<HashSet<&'static str>> {
+    static TABLE: OnceLock<RwLock<HashSet<&'static str>>> = OnceLock::new();
+    TABLE.get_or_init(Default::default)
+}
+
+/// An interned resource name or tile ID
+#[derive(Clone, Copy)]
+pub struct Key(&'static str);
+
+impl Key {
+    /// Intern `name`, returning its key
+    pub fn new(name: &str) -> Self {
+        if let Some(key) = Self::lookup(name) {
+            return key;
+        }
+        let mut table = table().write().unwrap_or_else(|e| e.into_inner());
+        // Another thread may have interned it between the locks
+        if let Some(&interned) = table.get(name) {
+            return Self(interned);
+        }
+        let interned: &'static str = Box::leak(name.to_owned().into_boxed_str());
+        table.insert(interned);
+        Self(interned)
+    }
+
+    /// The key for `name` if it has been interned. A name that was never
+    /// interned can't be in any keyed map, so lookups don't grow the table.
+    pub fn lookup(name: &str) -> Option<Self> {
+        let table = table().read().unwrap_or_else(|e| e.into_inner());
+        table.get(name).map(|&interned| Self(interned))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+
+    /// How many names have been interned so far in this process
+    pub fn interned() -> usize {
+        table().read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.0, other.0)
+    }
+}
+
+impl Eq for Key {}
+
+// Must hash like `str` for the `Borrow<str>` impl below
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl Borrow<str> for Key {
+    fn borrow(&self) -> &str {
+        self.0
+    }
+}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(other.0)
+    }
+}
+
+impl PartialEq<str> for Key {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Key {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Key {
+    fn eq(&self, other: &String) -> bool {
+        self.0 == other
+    }
+}
+
+impl Deref for Key {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.0
+    }
+}
+
+impl AsRef<str> for Key {
+    fn as_ref(&self) -> &str {
+        self.0
+    }
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.0, f)
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl From<&str> for Key {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<&String> for Key {
+    fn from(name: &String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for Key {
+    fn from(name: String) -> Self {
+        Self::new(&name)
+    }
+}
+
+impl From<&Key> for Key {
+    fn from(key: &Key) -> Self {
+        *key
+    }
+}
+
+impl From<Key> for String {
+    fn from(key: Key) -> Self {
+        key.0.to_string()
+    }
+}
+
+impl Serialize for Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Ok(Self::new(&name))
+    }
+}
+
+/// Anything that names a key: `str`, `String` or a `Key` itself. Writers
+/// take this so callers already holding a `Key` skip the intern table.
+pub trait AsKey {
+    fn to_key(&self) -> Key;
+}
+
+impl AsKey for str {
+    fn to_key(&self) -> Key {
+        Key::new(self)
+    }
+}
+
+impl AsKey for String {
+    fn to_key(&self) -> Key {
+        Key::new(self)
+    }
+}
+
+impl<T: AsKey + ?Sized> AsKey for &T {
+    fn to_key(&self) -> Key {
+        (**self).to_key()
+    }
+}
+
+impl AsKey for Key {
+    fn to_key(&self) -> Key {
+        *self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interning() {
+        let a = Key::new("key_test_dirt");
+        let b = Key::from("key_test_dirt".to_string());
+        assert_eq!(a, b);
+        assert!(std::ptr::eq(a.as_str(), b.as_str()));
+        assert_eq!(a, "key_test_dirt");
+
+        assert!(Key::lookup("key_test_never_interned").is_none());
+
+        // Keyed maps can be queried by text
+        let map = std::collections::HashMap::from([(a, 1)]);
+        assert_eq!(map.get("key_test_dirt"), Some(&1));
+        assert!(Key::new("key_test_a") < Key::new("key_test_b"));
+    }
+
+    #[test]
+    fn test_serializes_as_string() {
+        let key = Key::new("key_test_fungus");
+        let json = serde_json::to_string(&key).unwrap();
+        assert_eq!(json, r#""key_test_fungus""#);
+        assert_eq!(serde_json::from_str::<Key>(&json).unwrap(), key);
+    }
+}
@@ -0,0 +1,112 @@
+//! Legacy seeds: what an abandoned colony leaves for the next run.
+//!
+//! Abandoning a colony freezes it and distills it into a `LegacySeed`:
+//! the layout that becomes ruins, a share of what was left in the stores,
+//! and the ants still around to haunt the place. A later game's scenario
+//! can be built from it.
+//!
+//! `GameState::ingest_legacy` does that: each ruin becomes a `ruin_*` tile
+//! with a `RuinSite` holding its ghosts and buried loot. Ruins echo now and
+//! then, and can be excavated once.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+use super::entity::EntityId;
+use super::key::Key;
+use super::tile::TileType;
+
+/// A tile of the old colony, as the next run will find it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ruin {
+    pub tile: Key,
+    pub name: String,
+    pub tile_type: TileType,
+    pub x: i32,
+    pub y: i32,
+
+    /// Systems that stood on the tile, sorted by ID
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub systems: Vec<String>,
+}
+
+/// Someone who was still there at the end
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ghost {
+    pub entity_id: EntityId,
+
+    /// Cohort label, as in `Demographics`
+    pub cohort: String,
+
+    pub tile: Key,
+    pub age: u64,
+
+    /// Died before the end but was never laid to rest
+    #[serde(default)]
+    pub unburied: bool,
+}
+
+/// The deterministic summary of an abandoned colony
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LegacySeed {
+    /// Stable identifier, derived from the engine seed and the final state
+    pub id: u64,
+
+    /// Tick the colony was abandoned
+    pub abandoned_at: u64,
+
+    pub ruins: Vec<Ruin>,
+
+    /// Resources left lying in the ruins
+    pub residual: BTreeMap<Key, f64>,
+
+    /// Oldest first; capped at `constants::LEGACY_MAX_GHOSTS`
+    pub ghosts: Vec<Ghost>,
+
+    /// Everyone who died over the colony's life
+    pub total_dead: u64,
+}
+
+/// A past run's tile standing in this one, keyed by its tile ID in
+/// `GameState::ruins`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuinSite {
+    /// The legacy seed it came from
+    pub legacy_id: u64,
+
+    /// The tile's ID in the old colony
+    pub former_tile: Key,
+
+    /// Ghosts of those last seen on the tile
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ghosts: Vec<Ghost>,
+
+    /// What excavating it turns up
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub loot: BTreeMap<Key, f64>,
+
+    /// Tick it was dug up, if it has been
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub excavated_at: Option<u64>,
+}
+
+/// Why a ruin couldn't be excavated
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum RuinError {
+    #[error("legacy {0:016x} is already in this colony")]
+    AlreadyIngested(u64),
+
+    #[error("tile {0} is not a ruin")]
+    NotARuin(String),
+
+    #[error("ruin {tile} was already excavated at tick {tick}")]
+    AlreadyExcavated { tile: String, tick: u64 },
+}
+
+/// Why a colony couldn't be abandoned
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum AbandonError {
+    #[error("colony was already abandoned at tick {0}")]
+    AlreadyAbandoned(u64),
+}
@@ -0,0 +1,67 @@
+//! Internal logistics: per-tile stockpiles and hauling.
+//!
+//! In the default `GlobalPool` mode every system reads and writes the
+//! colony-wide `GameState::resources`, exactly as before. In `Local` mode,
+//! tile-bound systems work out of their tile's stockpile and hauler ants
+//! move goods between stockpiles and the colony store.
+
+use serde::{Deserialize, Serialize};
+
+use super::key::Key;
+use super::tile::ORIGIN;
+
+/// How produced goods reach where they're consumed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogisticsMode {
+    /// Everything lives in one colony-wide pool (legacy behavior)
+    #[default]
+    GlobalPool,
+    /// Tile-bound systems use per-tile stockpiles; haulers move goods
+    Local,
+}
+
+impl LogisticsMode {
+    // Takes a reference for serde's skip_serializing_if
+    pub fn is_global(&self) -> bool {
+        *self == Self::GlobalPool
+    }
+}
+
+/// Where a haul starts or ends
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "tile", rename_all = "snake_case")]
+pub enum Depot {
+    /// The colony-wide store (`GameState::resources`)
+    Colony,
+    /// A tile's stockpile
+    Tile(String),
+}
+
+impl Depot {
+    /// Where a carrier picks up or drops off at this depot: the colony
+    /// store is kept at the origin
+    pub fn tile(&self) -> &str {
+        match self {
+            Depot::Colony => ORIGIN,
+            Depot::Tile(tile) => tile,
+        }
+    }
+}
+
+/// Where an ant's load was picked up and where it's bound (with
+/// `feature::CARRYING`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Route {
+    pub from: Depot,
+    pub to: Depot,
+}
+
+/// A pending transfer, in the order haulers should work it
+#[derive(Debug, Clone, PartialEq)]
+pub struct HaulJob {
+    pub resource: Key,
+    pub amount: f64,
+    pub from: Depot,
+    pub to: Depot,
+}
@@ -0,0 +1,191 @@
+//! Generated maps: seeded starting caverns.
+//!
+//! `GameMap::default()` is the single origin tile every colony used to
+//! start on. `GameMap::generate` grows a cavern instead: tiles are added
+//! one at a time beside ones already placed, each connected to the tile it
+//! grew from, so the whole cavern hangs off the origin. A few neighbouring
+//! tiles that didn't grow from one another are joined as well, giving the
+//! cavern loops. The first tile grown is the compost heap (under the
+//! `compost` ID the engine looks for), and a share of the rest hold a
+//! resource vein. Everything comes from the seed and the `MapGenConfig`,
+//! so the same pair always gives the same cavern on every host.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::engine::constants;
+use crate::rng::SeededRng;
+use super::excavation::DIRECTIONS;
+use super::key::Key;
+use super::tile::{GameMap, Tile, ORIGIN};
+
+/// ID of the generated compost heap
+pub const COMPOST: &str = "compost";
+
+/// How to grow a starting cavern
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MapGenConfig {
+    /// Tiles in the cavern, the origin included (at most `MAP_MAX_SIZE`)
+    pub size: usize,
+
+    /// Give the first tile grown over to a compost heap
+    pub compost: bool,
+
+    /// Chance each of the other tiles holds a resource vein
+    pub resource_share: f64,
+
+    /// What a vein can hold, with its starting stock; each is as likely
+    pub resources: BTreeMap<Key, f64>,
+
+    /// Chance each side-by-side pair of tiles not already joined gets a
+    /// connection of its own
+    pub loop_chance: f64,
+}
+
+impl Default for MapGenConfig {
+    fn default() -> Self {
+        Self {
+            size: constants::MAP_SIZE,
+            compost: true,
+            resource_share: constants::MAP_RESOURCE_SHARE,
+            resources: [
+                (Key::new("fungus"), constants::MAP_FUNGUS_STOCK),
+                (Key::new("ore"), constants::ORE_VEIN_STOCK),
+                (Key::new("crystals"), constants::CRYSTAL_POCKET_STOCK),
+            ]
+            .into_iter()
+            .collect(),
+            loop_chance: constants::MAP_LOOP_CHANCE,
+        }
+    }
+}
+
+impl GameMap {
+    /// A cavern of `size` tiles grown from `seed`, with the default
+    /// parameters otherwise
+    pub fn generate(seed: u64, size: usize) -> Self {
+        Self::generate_with(seed, &MapGenConfig { size, ..MapGenConfig::default() })
+    }
+
+    /// A cavern grown from `seed` as `config` says
+    pub fn generate_with(seed: u64, config: &MapGenConfig) -> Self {
+        let mut rng = SeededRng::new(seed ^ constants::MAPGEN_STREAM);
+
+        // Grow the layout: each step picks one of the free squares beside
+        // a placed tile, listed in placement then direction order. The list
+        // is kept as tiles are placed rather than rebuilt every step.
+        let size = config.size.clamp(1, constants::MAP_MAX_SIZE);
+        let beside = |i: usize, (x, y): (i32, i32)| DIRECTIONS.map(|(dx, dy)| (i, (x + dx, y + dy)));
+        let mut squares: Vec<(i32, i32)> = vec![(0, 0)];
+        let mut placed: HashMap<(i32, i32), usize> = HashMap::from([((0, 0), 0)]);
+        let mut tree: Vec<(usize, usize)> = Vec::new();
+        let mut frontier: Vec<(usize, (i32, i32))> = beside(0, (0, 0)).to_vec();
+        while squares.len() < size {
+            let (parent, square) = frontier[rng.choose_index(frontier.len()).unwrap_or(0)];
+            let i = squares.len();
+            placed.insert(square, i);
+            tree.push((parent, i));
+            squares.push(square);
+            frontier.retain(|(_, free)| *free != square);
+            frontier.extend(beside(i, square).into_iter().filter(|(_, free)| !placed.contains_key(free)));
+        }
+
+        let ids: Vec<Key> = (0..squares.len())
+            .map(|i| match i {
+                0 => Key::new(ORIGIN),
+                1 if config.compost => Key::new(COMPOST),
+                _ => Key::new(&format!("cavern_{i}")),
+            })
+            .collect();
+
+        // Then what each tile holds
+        let veins: Vec<(&Key, &f64)> = config.resources.iter().collect();
+        let mut tiles = HashMap::new();
+        for (i, &(x, y)) in squares.iter().enumerate() {
+            let tile = match i {
+                0 => Tile::origin(),
+                1 if config.compost => Tile::new_compost("The Heap".to_string(), x, y),
+                _ => match rng.chance(config.resource_share).then(|| rng.choose_index(veins.len())).flatten() {
+                    Some(v) => {
+                        let (resource, stock) = veins[v];
+                        Tile::new_resource(format!("A Seam of {resource}"), x, y, resource, *stock)
+                    }
+                    None => Tile::new_empty("A Hollow".to_string(), x, y),
+                },
+            };
+            tiles.insert(ids[i], tile);
+        }
+
+        // And a few loops between side-by-side tiles
+        let mut connections: Vec<(Key, Key)> = tree.iter().map(|&(a, b)| (ids[a], ids[b])).collect();
+        for (i, &(x, y)) in squares.iter().enumerate() {
+            for (dx, dy) in [(1, 0), (0, 1)] {
+                let Some(&j) = placed.get(&(x + dx, y + dy)) else {
+                    continue;
+                };
+                let joined = tree.contains(&(i, j)) || tree.contains(&(j, i));
+                if !joined && rng.chance(config.loop_chance) {
+                    connections.push((ids[i], ids[j]));
+                }
+            }
+        }
+
+        let mut map = GameMap::default();
+        map.tiles = tiles;
+        map.connections = connections;
+        map.changed();
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tile::TileType;
+
+    fn layout(map: &GameMap) -> Vec<(String, i32, i32, Option<String>)> {
+        let mut tiles: Vec<_> = map.tiles.iter()
+            .map(|(id, t)| (id.to_string(), t.x, t.y, t.resource.clone()))
+            .collect();
+        tiles.sort();
+        tiles
+    }
+
+    #[test]
+    fn test_caverns_follow_the_seed() {
+        let map = GameMap::generate(7, 30);
+        assert_eq!(map.tiles.len(), 30);
+        assert_eq!(layout(&map), layout(&GameMap::generate(7, 30)));
+        assert_eq!(map.connections, GameMap::generate(7, 30).connections);
+        assert_ne!(layout(&map), layout(&GameMap::generate(8, 30)));
+
+        // All of it reachable, on distinct squares, joined only side by side
+        assert_eq!(map.reachable_from(ORIGIN).len(), 30);
+        let mut squares: Vec<_> = map.tiles.values().map(|t| (t.x, t.y)).collect();
+        squares.sort();
+        squares.dedup();
+        assert_eq!(squares.len(), 30);
+        assert!(map.connections.iter().all(|(a, b)| map.grid_distance(a, b) == Some(1)));
+        assert!(map.connections.len() >= 29);
+
+        assert_eq!(map.get_tile(COMPOST).unwrap().tile_type, TileType::Compost);
+        assert!(map.tiles.values().any(|t| t.is_harvestable()));
+    }
+
+    #[test]
+    fn test_config_shapes_the_cavern() {
+        assert_eq!(GameMap::generate(7, 0).tiles.len(), 1);
+        assert_eq!(GameMap::generate(7, usize::MAX).tiles.len(), constants::MAP_MAX_SIZE);
+
+        let bare = MapGenConfig { size: 20, compost: false, resource_share: 0.0, loop_chance: 0.0, ..MapGenConfig::default() };
+        let map = GameMap::generate_with(7, &bare);
+        assert!(map.get_tile(COMPOST).is_none());
+        assert!(map.tiles.values().all(|t| t.tile_type == TileType::Empty));
+        assert_eq!(map.connections.len(), 19, "A tree, with no loops");
+
+        let rich = MapGenConfig { resource_share: 1.0, resources: BTreeMap::from([(Key::new("ore"), 5.0)]), ..bare };
+        let map = GameMap::generate_with(7, &rich);
+        assert_eq!(map.tiles.values().filter(|t| t.resource.as_deref() == Some("ore")).count(), 19);
+    }
+}
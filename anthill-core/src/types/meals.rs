@@ -0,0 +1,40 @@
+//! How routine meals show up in event output.
+//!
+//! An ant hovering around the hunger threshold eats every few ticks for its
+//! whole life. `MealReports` trades the per-meal `EntityAte` stream for a
+//! periodic summary, or for silence while food is plentiful. Only the events
+//! change; the ants eat the same either way.
+
+use serde::{Deserialize, Serialize};
+
+use super::key::Key;
+
+/// Reporting mode for meals
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum MealReports {
+    /// An `EntityAte` for every meal
+    #[default]
+    Every,
+
+    /// One `MealsEaten` per entity per `window` seconds
+    Batched { window: u64 },
+
+    /// `EntityAte` only when the meal leaves the store below
+    /// `MEAL_SHORTAGE_STOCK`
+    Shortages,
+}
+
+impl MealReports {
+    pub fn is_every(&self) -> bool {
+        *self == Self::Every
+    }
+}
+
+/// Meals an entity has eaten since the last batch went out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MealTally {
+    pub food: Key,
+    pub meals: u64,
+    pub hunger_after: f64,
+}
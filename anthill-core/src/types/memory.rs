@@ -0,0 +1,43 @@
+//! Entity memories: what an ant has lived through.
+//!
+//! The engine writes a memory when something notable happens to or around
+//! an ant (a blight or other hazard, a visitor, a famine it came through, a raid it fought
+//! off). Memories are saved with the ant and travel in its
+//! `EntitySnapshot`, so a narrator can tell a colony's story from one ant's
+//! point of view. Each ant keeps only its latest
+//! `constants::MEMORY_CAPACITY`; older ones fade.
+
+use serde::{Deserialize, Serialize};
+
+use super::entity::EntityId;
+use super::key::Key;
+use super::hazard::Hazard;
+use super::threat::Threat;
+
+/// One thing an entity remembers, and when it happened
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Memory {
+    pub tick: u64,
+    #[serde(flatten)]
+    pub experience: Experience,
+}
+
+/// What there is to remember
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Experience {
+    /// Was in the colony when blight struck `tile`
+    WitnessedBlight { tile: Key },
+
+    /// Was on the tile where a visitor arrived
+    MetVisitor { visitor_id: EntityId, name: String },
+
+    /// Ate again after going nearly (or entirely) hungry
+    SurvivedFamine,
+
+    /// Stood against an incursion and lived
+    Defended { threat: Threat, repelled: bool },
+
+    /// Was in the colony when a flood or cave-in struck `tile`
+    WitnessedHazard { hazard: Hazard, tile: Key },
+}
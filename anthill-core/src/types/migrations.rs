@@ -0,0 +1,171 @@
+//! Save schema versions and the migrations between them.
+//!
+//! Every serialized state carries `schema_version`. Saves from before the
+//! field existed (including the Python-era ones) are version 0. Loading
+//! runs the document through `MIGRATIONS` one step at a time, on plain
+//! JSON, before serde sees it, so a renamed or reshaped field only needs a
+//! new step here and a bump of `SCHEMA_VERSION`.
+
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::engine::constants;
+
+/// Schema version this build writes
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// Upgrades a document by one version, in place
+type Migration = fn(&mut Map<String, Value>);
+
+/// `MIGRATIONS[n]` takes a version `n` document to version `n + 1`
+const MIGRATIONS: [Migration; SCHEMA_VERSION as usize] = [v0_to_v1, v1_to_v2, v2_to_v3];
+
+/// Why a save couldn't be brought up to date
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error("save is not a JSON object")]
+    NotAnObject,
+
+    #[error("save schema version {found} is newer than this build supports ({supported})")]
+    TooNew { found: u32, supported: u32 },
+}
+
+/// Schema version of a serialized state (0 if it predates versioning)
+pub fn version_of(doc: &Value) -> u32 {
+    doc.get("schema_version")
+        .and_then(Value::as_u64)
+        .map_or(0, |version| version as u32)
+}
+
+/// Upgrade a serialized state to `SCHEMA_VERSION`, returning the version
+/// it started at
+pub fn migrate(doc: &mut Value) -> Result<u32, MigrationError> {
+    let found = version_of(doc);
+    if found > SCHEMA_VERSION {
+        return Err(MigrationError::TooNew { found, supported: SCHEMA_VERSION });
+    }
+    let state = doc.as_object_mut().ok_or(MigrationError::NotAnObject)?;
+    for step in &MIGRATIONS[found as usize..] {
+        step(state);
+    }
+    state.insert("schema_version".to_string(), SCHEMA_VERSION.into());
+    Ok(found)
+}
+
+/// Undertakers kept their processing count in `processing_ticks` before
+/// entity timers, and corpse boosts were hard-wired to the compost heap's
+/// nutrients before systems declared their `organic_boost`
+fn v0_to_v1(state: &mut Map<String, Value>) {
+    let entities = state.get_mut("entities").and_then(Value::as_array_mut);
+    for entity in entities.into_iter().flatten().filter_map(Value::as_object_mut) {
+        if let Some(ticks) = entity.remove("processing_ticks") {
+            if ticks.is_u64() {
+                let timers = entity.entry("timers").or_insert_with(|| Value::Object(Map::new()));
+                if let Some(timers) = timers.as_object_mut() {
+                    timers.entry("processing").or_insert(ticks);
+                }
+            }
+        }
+    }
+
+    let heap = state.get_mut("systems")
+        .and_then(|systems| systems.get_mut("compost_heap"))
+        .and_then(Value::as_object_mut);
+    if let Some(heap) = heap {
+        if heap.get("organic_boost").is_none_or(Value::is_null) {
+            heap.insert("organic_boost".to_string(), "nutrients".into());
+        }
+    }
+}
+
+/// The plugin event queue was a bare list of events before it numbered
+/// them and kept consumers' cursors
+fn v1_to_v2(state: &mut Map<String, Value>) {
+    let queues = state.get_mut("queues").and_then(Value::as_object_mut);
+    let Some(queues) = queues else {
+        return;
+    };
+    if let Some(Value::Array(events)) = queues.remove("events") {
+        let last_seq = events.len();
+        let pending: Vec<Value> = events.into_iter()
+            .enumerate()
+            .map(|(i, payload)| serde_json::json!({ "seq": i + 1, "payload": payload }))
+            .collect();
+        queues.insert("events".to_string(), serde_json::json!({ "pending": pending, "last_seq": last_seq }));
+    }
+}
+
+/// Resource tiles found by expeditions had no stock, and so never ran out
+fn v2_to_v3(state: &mut Map<String, Value>) {
+    let tiles = state.get_mut("map")
+        .and_then(|map| map.get_mut("tiles"))
+        .and_then(Value::as_object_mut);
+    for tile in tiles.into_iter().flat_map(|tiles| tiles.values_mut()).filter_map(Value::as_object_mut) {
+        let is_resource = tile.get("type").and_then(Value::as_str) == Some("resource");
+        if is_resource && tile.get("stock").is_none_or(Value::is_null) {
+            tile.insert("stock".to_string(), constants::REMOTE_TILE_STOCK.into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_v0_to_v1() {
+        let mut doc = json!({
+            "tick": 5,
+            "entities": [{ "id": "u1", "processing_ticks": 7 }, { "id": "w1" }],
+            "systems": { "compost_heap": { "name": "Compost Heap" } }
+        });
+        assert_eq!(migrate(&mut doc).unwrap(), 0);
+        assert_eq!(doc["schema_version"], SCHEMA_VERSION);
+        assert_eq!(doc["entities"][0]["timers"]["processing"], 7);
+        assert!(doc["entities"][0].get("processing_ticks").is_none());
+        assert!(doc["entities"][1].get("timers").is_none());
+        assert_eq!(doc["systems"]["compost_heap"]["organic_boost"], "nutrients");
+
+        // Already current: nothing to do
+        let before = doc.clone();
+        assert_eq!(migrate(&mut doc).unwrap(), SCHEMA_VERSION);
+        assert_eq!(doc, before);
+    }
+
+    #[test]
+    fn test_v1_to_v2() {
+        let mut doc = json!({
+            "schema_version": 1,
+            "queues": { "actions": [], "events": [{ "kind": "hello" }, "bare"] }
+        });
+        assert_eq!(migrate(&mut doc).unwrap(), 1);
+        assert_eq!(doc["queues"]["events"], json!({
+            "pending": [{ "seq": 1, "payload": { "kind": "hello" } }, { "seq": 2, "payload": "bare" }],
+            "last_seq": 2
+        }));
+    }
+
+    #[test]
+    fn test_v2_to_v3() {
+        let mut doc = json!({
+            "schema_version": 2,
+            "map": { "tiles": {
+                "origin": { "type": "empty" },
+                "hollow": { "type": "resource", "resource": "ore" },
+                "seam": { "type": "resource", "resource": "ore", "stock": 3.5 }
+            } }
+        });
+        assert_eq!(migrate(&mut doc).unwrap(), 2);
+        assert!(doc["map"]["tiles"]["origin"].get("stock").is_none());
+        assert_eq!(doc["map"]["tiles"]["hollow"]["stock"], constants::REMOTE_TILE_STOCK);
+        assert_eq!(doc["map"]["tiles"]["seam"]["stock"], 3.5);
+    }
+
+    #[test]
+    fn test_refuses_newer_and_malformed_saves() {
+        let mut doc = json!({ "schema_version": SCHEMA_VERSION + 1 });
+        assert!(matches!(migrate(&mut doc), Err(MigrationError::TooNew { .. })));
+        assert!(matches!(migrate(&mut json!([1, 2])), Err(MigrationError::NotAnObject)));
+    }
+}
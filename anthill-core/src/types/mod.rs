@@ -12,3 +12,53 @@ pub mod system;
 pub mod state;
 pub mod graveyard;
 pub mod action;
+pub mod schedule;
+pub mod reflection;
+pub mod expedition;
+pub mod outpost;
+pub mod logistics;
+pub mod pause;
+pub mod shared;
+pub mod key;
+pub mod envelope;
+pub mod absorb;
+pub mod demographics;
+pub mod config;
+pub mod legacy;
+pub mod outside;
+pub mod value;
+pub mod transmission;
+pub mod brood;
+pub mod policy;
+pub mod warning;
+pub mod meals;
+pub mod command;
+pub mod feature;
+pub mod migrations;
+pub mod group;
+pub mod diff;
+pub mod blueprint;
+pub mod wonder;
+pub mod bookkeeping;
+pub mod summary;
+pub mod memory;
+pub mod silence;
+pub mod threat;
+pub mod whisper;
+pub mod scenario;
+pub mod health;
+pub mod event_queue;
+pub mod heredity;
+pub mod hosting;
+pub mod naming;
+pub mod compat;
+pub mod morale;
+pub mod convergence;
+pub mod pheromone;
+pub mod weather;
+pub mod daynight;
+pub mod columnar;
+pub mod hazard;
+pub mod excavation;
+pub mod spawn;
+pub mod mapgen;
@@ -0,0 +1,79 @@
+//! Morale: how an ant feels about the colony.
+//!
+//! With `feature::MORALE`, each ant's `morale` drifts toward the colony's
+//! sanity, drains while it's famished, and takes a blow for every death on
+//! its tile. It's told in moods: a content ant works as hard as it can, an
+//! uneasy or despairing one less, and a despairing one may desert (see
+//! `DeathCause::Desertion`). Desertion rolls seed a throwaway generator
+//! from the ant's ID and the tick, so they draw nothing from the stream.
+//! (Hashing alone, as for addled eggs, is too lumpy across consecutive
+//! ticks.)
+
+use serde::{Deserialize, Serialize};
+
+use crate::digest::fnv1a64;
+use crate::rng::SeededRng;
+
+/// Morale at or above which an ant is content
+pub const CONTENT: f64 = 60.0;
+
+/// Morale below which an ant despairs
+pub const DESPAIR: f64 = 25.0;
+
+/// Share of its work a wholly demoralized ant still does
+pub const LEAST_WORK: f64 = 0.5;
+
+/// How an ant's morale reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mood {
+    Despairing,
+    Uneasy,
+    Content,
+}
+
+impl Mood {
+    pub fn of(morale: f64) -> Self {
+        if morale >= CONTENT {
+            Mood::Content
+        } else if morale >= DESPAIR {
+            Mood::Uneasy
+        } else {
+            Mood::Despairing
+        }
+    }
+}
+
+/// Multiplier on what an ant with `morale` turns up: 1 while content,
+/// falling to `LEAST_WORK` at none
+pub fn work_factor(morale: f64) -> f64 {
+    if morale >= CONTENT {
+        1.0
+    } else {
+        LEAST_WORK + (1.0 - LEAST_WORK) * morale.max(0.0) / CONTENT
+    }
+}
+
+/// Whether the ant `id` deserts at `tick`, given its chance this tick
+pub fn deserts(id: &str, tick: u64, chance: f64) -> bool {
+    SeededRng::new(fnv1a64(format!("{id}/desert/{tick}").as_bytes())).chance(chance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moods_and_work() {
+        assert_eq!(Mood::of(100.0), Mood::Content);
+        assert_eq!(Mood::of(CONTENT - 1.0), Mood::Uneasy);
+        assert_eq!(Mood::of(DESPAIR - 1.0), Mood::Despairing);
+        assert_eq!(work_factor(CONTENT), 1.0);
+        assert_eq!(work_factor(0.0), LEAST_WORK);
+        assert!(work_factor(DESPAIR) > LEAST_WORK && work_factor(DESPAIR) < 1.0);
+
+        let deserted = (0..1000).filter(|tick| deserts("a1", *tick, 0.1)).count();
+        assert!((60..140).contains(&deserted), "about a tenth desert: {deserted}");
+        assert!(!(0..1000).any(|tick| deserts("a1", tick, 0.0)));
+    }
+}
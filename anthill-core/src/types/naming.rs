@@ -0,0 +1,65 @@
+//! Player-given names for tiles and entities.
+//!
+//! `RenameTile` and `RenameEntity` write the name straight into the tile or
+//! entity, so it lives in the save with everything else and every frontend
+//! shows the same one. Names are trimmed and checked here first.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::entity::EntityId;
+use super::key::Key;
+
+/// Longest name a player can give, in characters
+pub const MAX_NAME_CHARS: usize = 40;
+
+/// What was renamed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Named {
+    Tile { tile: Key },
+    Entity { entity_id: EntityId },
+}
+
+/// Why a name was refused
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum NameError {
+    #[error("names can't be blank")]
+    Blank,
+
+    #[error("names can be at most {MAX_NAME_CHARS} characters; this one has {0}")]
+    TooLong(usize),
+
+    #[error("names can't contain control characters")]
+    ControlCharacter,
+}
+
+/// `name` trimmed, if it's fit to use
+pub fn validate(name: &str) -> Result<String, NameError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(NameError::Blank);
+    }
+    let chars = name.chars().count();
+    if chars > MAX_NAME_CHARS {
+        return Err(NameError::TooLong(chars));
+    }
+    if name.chars().any(char::is_control) {
+        return Err(NameError::ControlCharacter);
+    }
+    Ok(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(validate("  The Long Gallery ").unwrap(), "The Long Gallery");
+        assert_eq!(validate("Ölfänger").unwrap(), "Ölfänger");
+        assert_eq!(validate(" \t "), Err(NameError::Blank));
+        assert_eq!(validate(&"a".repeat(41)), Err(NameError::TooLong(41)));
+        assert_eq!(validate("line\nbreak"), Err(NameError::ControlCharacter));
+    }
+}
@@ -0,0 +1,58 @@
+//! Outposts: remote resource tiles worked on the colony's behalf.
+//!
+//! An outpost produces only while it has supplies. Supplies arrive by
+//! hauling action; without them the outpost degrades and is eventually lost.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The action type used for supply runs in the action queue
+pub const SUPPLY_ACTION: &str = "haul_supply";
+
+/// A remote outpost, keyed by its tile ID in `GameState::outposts`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Outpost {
+    /// Resource the outpost produces
+    pub resource: String,
+
+    /// Amount produced per supplied second
+    pub rate: f64,
+
+    /// Tick the outpost was established
+    pub established_tick: u64,
+
+    /// Supplied ticks remaining
+    pub supply_ticks: u64,
+
+    /// Condition (1.0 = sound, 0.0 = lost); decays while unsupplied
+    pub condition: f64,
+}
+
+impl Outpost {
+    /// Is the outpost currently supplied (and so producing)?
+    pub fn is_supplied(&self) -> bool {
+        self.supply_ticks > 0
+    }
+}
+
+/// Why an outpost operation was refused
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum OutpostError {
+    #[error("tile {0} does not exist")]
+    UnknownTile(String),
+
+    #[error("tile {0} is not a resource tile")]
+    NotAResourceTile(String),
+
+    #[error("tile {tile} is {distance} from origin; outposts need at least {min}")]
+    TooClose { tile: String, distance: u64, min: u64 },
+
+    #[error("tile {0} already has an outpost")]
+    AlreadyEstablished(String),
+
+    #[error("tile {0} has no outpost")]
+    NoOutpost(String),
+
+    #[error("need {needed} {resource}, have {available}")]
+    InsufficientResources { resource: String, needed: f64, available: f64 },
+}
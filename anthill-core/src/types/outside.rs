@@ -0,0 +1,114 @@
+//! Conditions in the Outside: slow, seeded weather the receiver listens
+//! through.
+//!
+//! Each channel is 1D value noise over wall-clock time: a hashed value
+//! every `OUTSIDE_PERIOD` seconds, smoothly interpolated between. It depends
+//! only on `GameState::outside_seed` and the time, so any tick's
+//! conditions, past or future, can be read without running the simulation.
+
+use serde::{Deserialize, Serialize};
+
+use crate::digest::fnv1a64;
+use crate::engine::constants;
+
+/// Relative odds of each visitor type answering a summons
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VisitorWeights {
+    pub wanderer: f64,
+    pub observer: f64,
+    pub hungry: f64,
+}
+
+impl VisitorWeights {
+    pub fn total(&self) -> f64 {
+        self.wanderer + self.observer + self.hungry
+    }
+}
+
+/// The Outside at one moment
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OutsideConditions {
+    /// How well the receiver hears (0-1); scales summon chance from half to
+    /// one and a half times the base
+    pub clarity: f64,
+
+    /// Interference (0-1); a surge past `STATIC_SURGE_LEVEL` is reported
+    pub static_level: f64,
+
+    /// How closely the Outside lines up (0-1); past `CONJUNCTION_LEVEL`
+    /// it's in conjunction (see `convergence`)
+    pub alignment: f64,
+
+    pub visitor_weights: VisitorWeights,
+}
+
+impl OutsideConditions {
+    /// Conditions `seconds` into a colony's life under `seed`
+    pub fn at(seed: u64, seconds: f64) -> Self {
+        Self {
+            clarity: noise(seed, 0, seconds),
+            static_level: noise(seed, 1, seconds),
+            alignment: noise(seed, 5, seconds),
+            visitor_weights: VisitorWeights {
+                wanderer: 0.5 + noise(seed, 2, seconds),
+                observer: 0.5 + noise(seed, 3, seconds),
+                hungry: 0.5 + noise(seed, 4, seconds),
+            },
+        }
+    }
+
+    /// Multiplier on the base summon chance
+    pub fn summon_modifier(&self) -> f64 {
+        0.5 + self.clarity
+    }
+
+    pub fn is_static_surge(&self) -> bool {
+        self.static_level >= constants::STATIC_SURGE_LEVEL
+    }
+
+    /// Lined up, and quiet enough to hear several voices at once
+    pub fn is_conjunction(&self) -> bool {
+        self.alignment >= constants::CONJUNCTION_LEVEL && !self.is_static_surge()
+    }
+}
+
+/// Smoothed value noise in [0, 1) for one channel
+fn noise(seed: u64, channel: u8, seconds: f64) -> f64 {
+    let t = seconds.max(0.0) / constants::OUTSIDE_PERIOD as f64;
+    let cell = t.floor() as u64;
+    let f = t - t.floor();
+    let smooth = f * f * (3.0 - 2.0 * f);
+    let a = lattice(seed, channel, cell);
+    let b = lattice(seed, channel, cell + 1);
+    a + (b - a) * smooth
+}
+
+fn lattice(seed: u64, channel: u8, cell: u64) -> f64 {
+    let bytes = [&seed.to_le_bytes()[..], &[channel], &cell.to_le_bytes()[..]].concat();
+    (fnv1a64(&bytes) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conditions_vary_slowly() {
+        let a = OutsideConditions::at(7, 100.0);
+        let b = OutsideConditions::at(7, 101.0);
+        assert!((a.clarity - b.clarity).abs() < 0.01);
+        assert_eq!(a, OutsideConditions::at(7, 100.0));
+        assert_ne!(a, OutsideConditions::at(8, 100.0));
+
+        for hour in 0..48 {
+            let c = OutsideConditions::at(7, hour as f64 * 3600.0);
+            assert!((0.0..1.0).contains(&c.clarity));
+            assert!((0.0..1.0).contains(&c.static_level));
+            assert!((0.0..1.0).contains(&c.alignment));
+        }
+
+        // Conjunctions come, but rarely
+        let conjunctions = (0..1000).filter(|hour| OutsideConditions::at(7, *hour as f64 * 3600.0).is_conjunction()).count();
+        assert!((1..200).contains(&conjunctions), "{conjunctions}");
+    }
+}
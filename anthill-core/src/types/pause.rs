@@ -0,0 +1,86 @@
+//! Paused subsystems: typed toggles for freezing parts of the simulation.
+//!
+//! A paused subsystem's tick phase is skipped entirely, so hosts can stage
+//! scripted moments (e.g. no visitors during a cutscene) without zeroing
+//! resources. Toggle through `TickEngine::set_paused` to get events.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// A pausable part of the simulation, one per tick phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    Actions,
+    Systems,
+    Entities,
+    Undertakers,
+    Blight,
+    Queen,
+    Receiver,
+    Visitors,
+    Outposts,
+    Hauling,
+    Boredom,
+    Perception,
+    Reflections,
+    Echoes,
+    Wonders,
+    Groups,
+    Work,
+    Harvest,
+    Movement,
+    Threats,
+    Whispers,
+    Morale,
+    Weather,
+    DayNight,
+    Hazards,
+}
+
+/// The set of currently paused subsystems
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Paused(BTreeSet<Subsystem>);
+
+impl Paused {
+    /// Is `subsystem` paused?
+    pub fn contains(&self, subsystem: Subsystem) -> bool {
+        self.0.contains(&subsystem)
+    }
+
+    /// Pause or resume `subsystem`, returning whether anything changed
+    pub fn set(&mut self, subsystem: Subsystem, paused: bool) -> bool {
+        if paused {
+            self.0.insert(subsystem)
+        } else {
+            self.0.remove(&subsystem)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Paused subsystems, in phase order
+    pub fn iter(&self) -> impl Iterator<Item = Subsystem> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paused_serializes_as_list() {
+        let mut paused = Paused::default();
+        assert!(paused.set(Subsystem::Visitors, true));
+        assert!(!paused.set(Subsystem::Visitors, true));
+        paused.set(Subsystem::Actions, true);
+
+        let json = serde_json::to_string(&paused).unwrap();
+        assert_eq!(json, r#"["actions","visitors"]"#);
+        assert_eq!(serde_json::from_str::<Paused>(&json).unwrap(), paused);
+    }
+}
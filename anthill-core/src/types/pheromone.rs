@@ -0,0 +1,99 @@
+//! Pheromone trails: where the colony's ants have been walking.
+//!
+//! With `feature::TRAILS`, every step an ant takes lays `trail_deposit` on
+//! the tile it steps onto (twice that if it's carrying something), and
+//! every trail fades by `trail_decay` a second until it's gone. A walker
+//! with more than one shortest way to go takes the one whose next tile
+//! smells strongest, so well-trodden routes draw the rest of the colony
+//! onto them. Ties keep the map's own path, and nothing is rolled, so the
+//! same walks lay the same trails on every host.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::key::Key;
+
+/// Strength below which a trail has faded away
+pub const FADED: f64 = 0.01;
+
+/// Strongest a trail gets, however busy the tile
+pub const SATURATED: f64 = 100.0;
+
+/// How much more an ant carrying goods lays
+pub const LADEN_FACTOR: f64 = 2.0;
+
+/// Trail strength by tile ID; tiles without a trail are left out
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Trails(BTreeMap<Key, f64>);
+
+impl Trails {
+    /// How strong the trail on `tile` is (0 if there's none)
+    pub fn on(&self, tile: &str) -> f64 {
+        self.0.get(tile).copied().unwrap_or(0.0)
+    }
+
+    /// Lay `amount` on `tile`, up to `SATURATED`
+    pub fn lay(&mut self, tile: Key, amount: f64) {
+        let trail = self.0.entry(tile).or_default();
+        *trail = (*trail + amount).min(SATURATED);
+    }
+
+    /// Keep `kept` of every trail, dropping those that fade away
+    pub fn fade(&mut self, kept: f64) {
+        self.0.retain(|_, trail| {
+            *trail *= kept;
+            *trail >= FADED
+        });
+    }
+
+    /// Of `steps`, in the order given, the one with the strongest trail;
+    /// ties go to the earliest
+    pub fn strongest<'a>(&self, steps: impl IntoIterator<Item = &'a Key>) -> Option<&'a Key> {
+        steps.into_iter().fold(None, |best: Option<&Key>, step| match best {
+            Some(best) if self.on(best) >= self.on(step) => Some(best),
+            _ => Some(step),
+        })
+    }
+
+    /// Tiles with a trail, strongest first (ties by tile ID)
+    pub fn ranked(&self) -> Vec<(&str, f64)> {
+        let mut ranked: Vec<(&str, f64)> = self.0.iter().map(|(tile, trail)| (tile.as_str(), *trail)).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trails_lay_fade_and_lead() {
+        let mut trails = Trails::default();
+        trails.lay(Key::new("lane"), 1.0);
+        trails.lay(Key::new("lane"), 1.0);
+        trails.lay(Key::new("den"), 500.0);
+        assert_eq!(trails.on("lane"), 2.0);
+        assert_eq!(trails.on("den"), SATURATED);
+        assert_eq!(trails.on("hall"), 0.0);
+        assert_eq!(trails.ranked(), [("den", SATURATED), ("lane", 2.0)]);
+
+        let steps = [Key::new("hall"), Key::new("lane"), Key::new("shaft")];
+        assert_eq!(trails.strongest(&steps).map(Key::as_str), Some("lane"));
+        assert_eq!(Trails::default().strongest(&steps).map(Key::as_str), Some("hall"));
+        assert_eq!(trails.strongest(&[]), None);
+
+        trails.fade(0.5);
+        assert_eq!(trails.on("lane"), 1.0);
+        for _ in 0..7 {
+            trails.fade(0.5);
+        }
+        assert_eq!(trails.on("lane"), 0.0);
+        assert_eq!(trails.ranked().len(), 1);
+    }
+}
@@ -0,0 +1,72 @@
+//! Colony work policy: how idle workers get put to work.
+//!
+//! The player sets priorities once ("foraging > hauling > digging") instead
+//! of steering individual ants. Each tick the work phase hands idle workers,
+//! in ID order, the first task in priority order that still has room.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::tile::TileType;
+
+/// Something a worker can be assigned to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Task {
+    /// Gathers fungus
+    Forage,
+    /// Joins the haulers (local logistics only)
+    Haul,
+    /// Digs up dirt
+    Dig,
+}
+
+impl Task {
+    /// The kind of tile the task is done on, if it has one. Hauling happens
+    /// wherever the goods are.
+    pub fn site(self) -> Option<TileType> {
+        match self {
+            Self::Forage => Some(TileType::Resource),
+            Self::Dig => Some(TileType::Extraction),
+            Self::Haul => None,
+        }
+    }
+}
+
+/// The colony's standing orders for idle workers
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkPolicy {
+    /// Highest priority first; tasks not listed are never assigned. Empty
+    /// means workers are left alone.
+    #[serde(default)]
+    pub priorities: Vec<Task>,
+
+    /// Most workers a task may hold; unlisted tasks are uncapped
+    #[serde(default)]
+    pub limits: BTreeMap<Task, usize>,
+}
+
+impl WorkPolicy {
+    /// Priorities only, no caps
+    pub fn prioritize(priorities: impl IntoIterator<Item = Task>) -> Self {
+        Self {
+            priorities: priorities.into_iter().collect(),
+            limits: BTreeMap::new(),
+        }
+    }
+
+    /// Cap `task` at `limit` workers
+    pub fn with_limit(mut self, task: Task, limit: usize) -> Self {
+        self.limits.insert(task, limit);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.priorities.is_empty()
+    }
+
+    /// No priorities and no caps: nothing worth saving
+    pub fn is_unset(&self) -> bool {
+        *self == Self::default()
+    }
+}
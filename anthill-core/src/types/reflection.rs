@@ -0,0 +1,203 @@
+//! Typed reflections and the trigger bookkeeping behind them.
+//!
+//! The engine decides *when* a reflection is due (stillness, or the
+//! aftermath of something significant). What gets written is up to the
+//! layer above.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::entity::EntityId;
+
+/// Why a reflection was prompted
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum ReflectionTrigger {
+    /// Nothing significant happened for a long while
+    Stillness,
+    /// Something significant happened a little while ago
+    Aftermath,
+    /// Written by hand, not prompted by the engine
+    #[default]
+    Manual,
+    /// A trigger this version doesn't know (kept verbatim)
+    Custom(String),
+}
+
+impl From<String> for ReflectionTrigger {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "stillness" => Self::Stillness,
+            "aftermath" => Self::Aftermath,
+            "manual" => Self::Manual,
+            _ => Self::Custom(s),
+        }
+    }
+}
+
+impl From<ReflectionTrigger> for String {
+    fn from(trigger: ReflectionTrigger) -> Self {
+        match trigger {
+            ReflectionTrigger::Stillness => "stillness".to_string(),
+            ReflectionTrigger::Aftermath => "aftermath".to_string(),
+            ReflectionTrigger::Manual => "manual".to_string(),
+            ReflectionTrigger::Custom(s) => s,
+        }
+    }
+}
+
+/// Something a reflection refers to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReflectionRef {
+    Entity { id: EntityId },
+    Tile { id: String },
+    System { id: String },
+    Resource { name: String, threshold: f64 },
+}
+
+/// A single reflection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reflection {
+    /// Tick the reflection was prompted
+    pub tick: u64,
+
+    /// Why it was prompted
+    #[serde(default)]
+    pub trigger: ReflectionTrigger,
+
+    /// The question asked, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+
+    /// The reflection itself (None until answered)
+    #[serde(default, alias = "response")]
+    pub text: Option<String>,
+
+    /// Entities, tiles, etc. the reflection is about
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub refs: Vec<ReflectionRef>,
+
+    /// Fields written by newer or older versions, preserved verbatim
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl Reflection {
+    /// Create an unanswered reflection
+    pub fn new(tick: u64, trigger: ReflectionTrigger, refs: Vec<ReflectionRef>) -> Self {
+        Self {
+            tick,
+            trigger,
+            prompt: None,
+            text: None,
+            refs,
+            extra: HashMap::new(),
+        }
+    }
+
+    /// Has the reflection been written?
+    pub fn is_answered(&self) -> bool {
+        self.text.is_some()
+    }
+}
+
+/// Engine-maintained bookkeeping for when the next reflection is due
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReflectionTriggers {
+    /// Tick of the last significant event
+    #[serde(default)]
+    pub last_significant_tick: u64,
+
+    /// What the last significant events referred to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pending_refs: Vec<ReflectionRef>,
+
+    /// Is an aftermath reflection waiting to be prompted?
+    #[serde(default)]
+    pub aftermath_pending: bool,
+
+    /// Tick the engine last prompted a reflection
+    #[serde(default)]
+    pub last_prompt_tick: u64,
+}
+
+impl ReflectionTriggers {
+    /// Note that something significant happened
+    pub fn note_significant(&mut self, tick: u64, refs: Vec<ReflectionRef>, max_refs: usize) {
+        self.last_significant_tick = tick;
+        self.aftermath_pending = true;
+        self.pending_refs.extend(refs);
+
+        // Keep the most recent refs
+        let excess = self.pending_refs.len().saturating_sub(max_refs);
+        self.pending_refs.drain(..excess);
+    }
+
+    /// Which reflection (if any) is due at `tick`.
+    ///
+    /// `last_reflection_tick` is the newest stored reflection, so saves
+    /// written before the engine tracked prompts don't fire immediately.
+    pub fn due(
+        &self,
+        tick: u64,
+        last_reflection_tick: u64,
+        stillness_ticks: u64,
+        aftermath_delay: u64,
+    ) -> Option<ReflectionTrigger> {
+        let last_prompt = self.last_prompt_tick.max(last_reflection_tick);
+        if tick.saturating_sub(last_prompt) < stillness_ticks {
+            return None;
+        }
+
+        let since_event = tick.saturating_sub(self.last_significant_tick);
+        if since_event >= stillness_ticks {
+            Some(ReflectionTrigger::Stillness)
+        } else if self.aftermath_pending && since_event >= aftermath_delay {
+            Some(ReflectionTrigger::Aftermath)
+        } else {
+            None
+        }
+    }
+
+    /// Record that a prompt went out, returning the refs it covers
+    pub fn mark_prompted(&mut self, tick: u64) -> Vec<ReflectionRef> {
+        self.last_prompt_tick = tick;
+        self.aftermath_pending = false;
+        std::mem::take(&mut self.pending_refs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loads_untyped_reflection() {
+        let json = r#"{
+            "prompt": "What are you waiting for?",
+            "response": "Rain.",
+            "tick": 89792,
+            "trigger": "aftermath",
+            "mood": "wistful"
+        }"#;
+
+        let reflection: Reflection = serde_json::from_str(json).unwrap();
+        assert_eq!(reflection.trigger, ReflectionTrigger::Aftermath);
+        assert_eq!(reflection.text.as_deref(), Some("Rain."));
+        assert!(reflection.extra.contains_key("mood"));
+
+        // Unknown fields survive a roundtrip
+        let out = serde_json::to_value(&reflection).unwrap();
+        assert_eq!(out["mood"], "wistful");
+        assert_eq!(out["trigger"], "aftermath");
+    }
+
+    #[test]
+    fn test_unknown_trigger_preserved() {
+        let reflection: Reflection =
+            serde_json::from_str(r#"{"tick": 1, "trigger": "eclipse", "response": null}"#).unwrap();
+        assert_eq!(reflection.trigger, ReflectionTrigger::Custom("eclipse".to_string()));
+        assert!(!reflection.is_answered());
+    }
+}
@@ -3,11 +3,13 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::key::{AsKey, Key};
+
 /// Collection of all resources in the simulation
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Resources {
     #[serde(flatten)]
-    pub amounts: HashMap<String, f64>,
+    pub amounts: HashMap<Key, f64>,
 }
 
 impl Resources {
@@ -24,21 +26,21 @@ impl Resources {
     }
 
     /// Set the amount of a resource
-    pub fn set(&mut self, name: &str, amount: f64) {
-        self.amounts.insert(name.to_string(), amount);
+    pub fn set<K: AsKey + ?Sized>(&mut self, name: &K, amount: f64) {
+        self.amounts.insert(name.to_key(), amount);
     }
 
     /// Add to a resource (can be negative)
-    pub fn add(&mut self, name: &str, delta: f64) {
-        let current = self.get(name);
-        self.amounts.insert(name.to_string(), current + delta);
+    pub fn add<K: AsKey + ?Sized>(&mut self, name: &K, delta: f64) {
+        *self.amounts.entry(name.to_key()).or_insert(0.0) += delta;
     }
 
     /// Subtract from a resource (returns false if insufficient)
-    pub fn try_consume(&mut self, name: &str, amount: f64) -> bool {
-        let current = self.get(name);
+    pub fn try_consume<K: AsKey + ?Sized>(&mut self, name: &K, amount: f64) -> bool {
+        let key = name.to_key();
+        let current = self.get(&key);
         if current >= amount {
-            self.amounts.insert(name.to_string(), current - amount);
+            self.amounts.insert(key, current - amount);
             true
         } else {
             false
@@ -66,8 +68,8 @@ impl Resources {
         true
     }
 
-    /// Add all resources from a map
-    pub fn add_all(&mut self, additions: &HashMap<String, f64>) {
+    /// Add all resources from a map (or another `Resources`' amounts)
+    pub fn add_all<'a, K: AsKey + 'a>(&mut self, additions: impl IntoIterator<Item = (&'a K, &'a f64)>) {
         for (name, amount) in additions {
             self.add(name, *amount);
         }
@@ -0,0 +1,134 @@
+//! Scenarios: a run with a goal, and the ways it can end.
+//!
+//! A scenario names win and lose conditions; the engine checks them after
+//! every tick's phases. The first one met ends the run (lose conditions
+//! are checked first, so a colony can't win on the tick it dies), with a
+//! `ScenarioCompleted` or `ScenarioFailed` event and `state.ended` set.
+//! An ended run is frozen: ticks do nothing and commands are refused.
+
+use serde::{Deserialize, Serialize};
+
+use super::feature;
+use super::key::Key;
+use super::state::GameState;
+
+/// A goal for a run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+
+    /// Any one of these wins the run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub win: Vec<EndCondition>,
+
+    /// Any one of these loses it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lose: Vec<EndCondition>,
+
+    /// Tick the scenario started on (set when it's started)
+    #[serde(default)]
+    pub started_at: u64,
+}
+
+/// Something that ends a run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EndCondition {
+    /// `ticks` have passed since the scenario started
+    SurviveTicks { ticks: u64 },
+
+    /// The colony's stores hold at least `amount` of `resource`
+    ReachResource { resource: Key, amount: f64 },
+
+    /// The queen's chamber is gone, or (with `feature::SUCCESSION`) the
+    /// queen died with no grown princess to take her place
+    QueenDies,
+}
+
+/// How a run turned out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Completed,
+    Failed,
+}
+
+/// A finished run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ending {
+    pub tick: u64,
+    pub outcome: Outcome,
+
+    /// The condition that ended it
+    pub condition: EndCondition,
+}
+
+impl EndCondition {
+    /// Is this condition met in `state`, for a scenario started at
+    /// `started_at`?
+    pub fn is_met(&self, state: &GameState, started_at: u64) -> bool {
+        match self {
+            EndCondition::SurviveTicks { ticks } => state.tick.saturating_sub(started_at) >= *ticks,
+            EndCondition::ReachResource { resource, amount } => state.resources.get(resource) >= *amount,
+            EndCondition::QueenDies => {
+                !state.has_system("queen_chamber")
+                    || (state.has_feature(feature::SUCCESSION)
+                        && state.reign > 0
+                        && state.queen.as_deref().and_then(|id| state.get_entity(id)).is_none())
+            }
+        }
+    }
+}
+
+impl Scenario {
+    /// How the run ends now, if it does: the first lose condition met,
+    /// else the first win condition
+    pub fn evaluate(&self, state: &GameState) -> Option<Ending> {
+        let met = |conditions: &[EndCondition]| {
+            conditions.iter().find(|condition| condition.is_met(state, self.started_at)).cloned()
+        };
+        let (outcome, condition) = met(&self.lose)
+            .map(|condition| (Outcome::Failed, condition))
+            .or_else(|| met(&self.win).map(|condition| (Outcome::Completed, condition)))?;
+        Some(Ending { tick: state.tick, outcome, condition })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::system::System;
+
+    #[test]
+    fn test_losing_beats_winning() {
+        let mut state = GameState { tick: 50, ..Default::default() };
+        state.resources.set("fungus", 20.0);
+        let scenario = Scenario {
+            name: "Hold Out".to_string(),
+            win: vec![
+                EndCondition::ReachResource { resource: Key::new("fungus"), amount: 100.0 },
+                EndCondition::SurviveTicks { ticks: 40 },
+            ],
+            lose: vec![EndCondition::QueenDies],
+            started_at: 10,
+        };
+
+        // No queen chamber: lost, even with the time survived
+        let ending = scenario.evaluate(&state).unwrap();
+        assert_eq!((ending.tick, ending.outcome, ending.condition), (50, Outcome::Failed, EndCondition::QueenDies));
+
+        state.systems.insert("queen_chamber".to_string(), System::new_converter(
+            "Queen Chamber".to_string(),
+            Default::default(),
+            Default::default(),
+        ));
+        let ending = scenario.evaluate(&state).unwrap();
+        assert_eq!((ending.outcome, ending.condition), (Outcome::Completed, EndCondition::SurviveTicks { ticks: 40 }));
+
+        state.tick = 49;
+        assert_eq!(scenario.evaluate(&state), None);
+
+        let json = serde_json::to_string(&scenario).unwrap();
+        assert_eq!(serde_json::from_str::<Scenario>(&json).unwrap(), scenario);
+    }
+}
@@ -0,0 +1,74 @@
+//! Scheduled happenings: known future beats, for countdowns.
+
+use serde::{Deserialize, Serialize};
+
+use super::entity::EntityId;
+use super::hazard::Hazard;
+use super::key::Key;
+
+/// What is scheduled to happen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduledKind {
+    /// The queen becomes eligible to spawn (resources permitting)
+    SpawnWindow,
+
+    /// Receiver maintenance falls due
+    MaintenanceDue,
+
+    /// A visitor reaches its max age and departs
+    VisitorDeparture {
+        visitor_id: EntityId,
+        name: String,
+    },
+
+    /// A queued action completes
+    ActionComplete {
+        action_id: String,
+        action_type: String,
+    },
+
+    /// An outpost's supplies run out
+    OutpostUnsupplied {
+        tile: String,
+    },
+
+    /// Blight clears from a tile
+    BlightExpiry {
+        tile: String,
+    },
+
+    /// A flood or cave-in passes
+    HazardPasses {
+        hazard: Hazard,
+        tile: Key,
+    },
+
+    /// A gift a whisper promised arrives
+    PromiseDue {
+        resource: Key,
+        amount: f64,
+    },
+}
+
+/// A known future beat
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledItem {
+    /// The tick on which it happens
+    pub tick: u64,
+
+    /// What happens
+    #[serde(flatten)]
+    pub kind: ScheduledKind,
+}
+
+impl ScheduledItem {
+    pub fn new(tick: u64, kind: ScheduledKind) -> Self {
+        Self { tick, kind }
+    }
+
+    /// Ticks from `now` until this happens
+    pub fn ticks_until(&self, now: u64) -> u64 {
+        self.tick.saturating_sub(now)
+    }
+}
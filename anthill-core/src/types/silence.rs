@@ -0,0 +1,52 @@
+//! Receiver silence: how a quiet receiver slides toward ruin.
+//!
+//! A receiver that misses its maintenance goes quiet. Left that way it goes
+//! dormant, then derelict, and each stage costs more strange matter to
+//! bring back (`Tuning::receiver_dormant_after` and friends). The
+//! maintenance check moves the receiver along, emitting
+//! `ReceiverSilenceDeepened`; `GameState::receiver_silence` tells a UI how
+//! long it has been and what comes next. Saves without
+//! `feature::SILENCE_STAGES` stay quiet forever, at the old price.
+
+use serde::{Deserialize, Serialize};
+
+/// How far gone a silent receiver is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SilenceStage {
+    /// Missed its maintenance; one payment brings it back
+    Quiet,
+    /// Cold and dusty; costs `receiver_dormant_cost_scale` times as much
+    Dormant,
+    /// A ruin of wires; costs `receiver_derelict_cost_scale` times as much
+    Derelict,
+}
+
+impl SilenceStage {
+    /// The stage after this one, if any
+    pub fn next(self) -> Option<Self> {
+        match self {
+            Self::Quiet => Some(Self::Dormant),
+            Self::Dormant => Some(Self::Derelict),
+            Self::Derelict => None,
+        }
+    }
+}
+
+/// Where a silent receiver stands, for the UI
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Silence {
+    /// Tick it went silent
+    pub since: u64,
+    pub silent_for: u64,
+    pub stage: SilenceStage,
+
+    /// Strange matter it takes to bring it back now
+    pub restore_cost: f64,
+
+    /// The stage it's sliding toward, and the ticks until it gets there
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_stage: Option<SilenceStage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_stage_in: Option<u64>,
+}
@@ -0,0 +1,135 @@
+//! Spawn hooks: what content gives a newcomer the moment it arrives.
+//!
+//! Each role's and visitor type's `Baseline` can carry `on_spawn` hooks,
+//! carried out whenever the queen lays, hatches or crowns one, or a summons
+//! brings one in: traits it starts with on top of any it inherits, resources
+//! it brings into the stores, and a content-defined action queued in its
+//! name to complete later. None of it draws from the RNG, so a creature
+//! with hooks spawns the same way under every seed, and saves whose
+//! baselines have none run as before.
+//!
+//! Entities have no status effects of their own, so traits stand in for
+//! "starting status effects": they last the creature's life. A scheduled
+//! action's type is checked as the config loads (`check_free_form`), so
+//! a hook can't set going a kind with a blueprint or one the engine drives.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use super::action::{check_free_form, Action, ActionEffects, ReservedAction};
+use super::entity::Entity;
+use super::heredity::Trait;
+use super::key::Key;
+
+/// What happens when an entity of one template spawns
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpawnHooks {
+    /// Traits it starts with, whatever it inherits
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub traits: Vec<Trait>,
+
+    /// Added to the colony's stores when it arrives
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub bundled: BTreeMap<Key, f64>,
+
+    /// An action queued in its name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduled: Option<ScheduledHook>,
+}
+
+/// A content-defined action a newcomer sets going
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedHook")]
+pub struct ScheduledHook {
+    /// The action's `action_type`
+    pub action_type: String,
+
+    /// Seconds until it completes (at least one tick)
+    pub delay: u64,
+
+    /// Resource changes when it does
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub resources: BTreeMap<String, f64>,
+}
+
+/// A `ScheduledHook` as written, before its action type is checked
+#[derive(Deserialize)]
+struct UncheckedHook {
+    action_type: String,
+    delay: u64,
+    #[serde(default)]
+    resources: BTreeMap<String, f64>,
+}
+
+impl TryFrom<UncheckedHook> for ScheduledHook {
+    type Error = ReservedAction;
+
+    fn try_from(hook: UncheckedHook) -> Result<Self, ReservedAction> {
+        check_free_form(&hook.action_type)?;
+        Ok(Self { action_type: hook.action_type, delay: hook.delay, resources: hook.resources })
+    }
+}
+
+impl ScheduledHook {
+    /// The action for the entity with ID `entity_id`, due in `ticks`
+    pub fn action(&self, entity_id: &str, ticks: u64) -> Action {
+        let resources: HashMap<String, f64> = self.resources.clone().into_iter().collect();
+        Action {
+            id: format!("{entity_id}/{}", self.action_type),
+            action_type: self.action_type.clone(),
+            ticks_remaining: ticks.max(1),
+            target: None,
+            effects: (!resources.is_empty()).then_some(ActionEffects { resources: Some(resources) }),
+        }
+    }
+}
+
+impl SpawnHooks {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// `entity` with the hooks' traits added, living longer or shorter
+    /// for each one it didn't already have
+    pub fn with_traits(&self, mut entity: Entity) -> Entity {
+        for &t in &self.traits {
+            if !entity.traits.contains(&t) {
+                entity.traits.push(t);
+                entity.max_age = (entity.max_age as f64 * t.lifespan_factor()) as u64;
+            }
+        }
+        entity.traits.sort();
+        entity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hooks_add_only_missing_traits() {
+        let hooks = SpawnHooks { traits: vec![Trait::Enduring, Trait::Hardy], ..SpawnHooks::default() };
+        let ant = Entity::new_worker("a".to_string(), "origin".to_string()).with_traits(vec![Trait::Hardy]);
+        let lifespan = ant.max_age;
+        let ant = hooks.with_traits(ant);
+        assert_eq!(ant.traits, [Trait::Hardy, Trait::Enduring]);
+        assert_eq!(ant.max_age, (lifespan as f64 * Trait::Enduring.lifespan_factor()) as u64);
+        assert!(SpawnHooks::default().is_empty());
+
+        let hook = ScheduledHook { action_type: "molt".to_string(), delay: 0, resources: BTreeMap::new() };
+        let action = hook.action("a", 0);
+        assert_eq!((action.id.as_str(), action.ticks_remaining), ("a/molt", 1));
+        assert!(action.effects.is_none());
+    }
+
+    #[test]
+    fn test_hooks_cannot_schedule_reserved_actions() {
+        let hook: ScheduledHook = serde_json::from_str(r#"{"action_type": "molt", "delay": 3}"#).unwrap();
+        assert_eq!(hook.delay, 3);
+        for reserved in ["dig", "expedition", "host_visitor"] {
+            let json = format!(r#"{{"action_type": "{reserved}", "delay": 3}}"#);
+            assert!(serde_json::from_str::<SpawnHooks>(&format!(r#"{{"scheduled": {json}}}"#)).is_err(), "{reserved}");
+        }
+    }
+}
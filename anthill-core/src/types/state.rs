@@ -1,17 +1,66 @@
 //! Complete game state.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::time::Duration;
 
-use super::entity::Entity;
+use crate::engine::{constants, TickEngine};
+use crate::det::{self, OrdF64};
+use crate::digest;
+use crate::query::Query;
+use crate::registry::Registry;
+use crate::events::{EventKind, TickEvents};
+use crate::timeline::Timeline;
+
+use super::entity::{AntRole, Entity, EntityId, EntityType, LifeStage};
 use super::resource::Resources;
-use super::tile::GameMap;
+use super::tile::{GameMap, Tile, TileActivity, TileType};
 use super::system::System;
 use super::graveyard::Graveyard;
 use super::action::Queues;
+use super::expedition::Expedition;
+use super::outpost::Outpost;
+use super::logistics::{Depot, LogisticsMode};
+use super::pause::{Paused, Subsystem};
+use super::shared::Shared;
+use super::envelope::{self, EnvelopeError, SaveEnvelope, ENVELOPE_VERSION};
+use super::absorb::{AbsorbPolicy, EntitySelection, IdConflict, ResourceMerge};
+use super::config::SimConfig;
+use super::demographics::{self, Demographics};
+use super::summary::{Disaster, Summary, SummaryCounters, VisitorSummary};
+use super::legacy::{LegacySeed, RuinError, RuinSite};
+use super::outside::{OutsideConditions, VisitorWeights};
+use super::value::Valuation;
+use super::transmission::Transmission;
+use super::brood::Brood;
+use super::policy::WorkPolicy;
+use super::meals::{MealReports, MealTally};
+use super::key::Key;
+use super::schedule::{ScheduledItem, ScheduledKind};
+use super::warning::Warning;
+use super::whisper::Promise;
+use super::health::{Health, HealthParts};
+use super::heredity::{self, Trait};
+use super::scenario::{Ending, Scenario};
+use super::reflection::{Reflection, ReflectionTriggers};
+use super::feature;
+use super::migrations::{self, SCHEMA_VERSION};
+use super::compat::{self, Compatibility, SaveStamp};
+use super::group::{Group, Intent};
+use super::wonder::Wonder;
+use super::bookkeeping::Bookkeeping;
+use super::diff::StateDiff;
+use super::silence::{Silence, SilenceStage};
+use super::pheromone::Trails;
+use super::excavation::Excavation;
+use super::mapgen::MapGenConfig;
+use super::weather::Weather;
+use super::daynight::TimeOfDay;
+use super::columnar::{self, SaveOptions};
 
 /// Metadata about the game (non-simulation state)
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Meta {
     /// Boredom counter (increments when nothing happens)
     #[serde(default)]
@@ -47,7 +96,11 @@ pub struct Meta {
 
     /// Reflections (philosophical musings)
     #[serde(default)]
-    pub reflections: Vec<serde_json::Value>,
+    pub reflections: Vec<Reflection>,
+
+    /// When the next reflection is due (engine-maintained)
+    #[serde(default)]
+    pub reflection_triggers: ReflectionTriggers,
 
     /// Colony sanity level
     #[serde(default = "default_sanity")]
@@ -60,15 +113,78 @@ pub struct Meta {
     /// When did the receiver fail?
     #[serde(skip_serializing_if = "Option::is_none")]
     pub receiver_failed_tick: Option<u64>,
+
+    /// How far a silent receiver has slid (see `silence`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receiver_stage: Option<SilenceStage>,
 }
 
 fn default_sanity() -> f64 {
     100.0
 }
 
+fn default_ticks_per_second() -> f64 {
+    1.0
+}
+
+fn is_default_tick_rate(rate: &f64) -> bool {
+    *rate == default_ticks_per_second()
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+impl Meta {
+    /// Store a reflection, dropping the oldest beyond the retention limit
+    pub fn add_reflection(&mut self, reflection: Reflection) {
+        self.reflections.push(reflection);
+        let excess = self.reflections.len().saturating_sub(constants::MAX_REFLECTIONS);
+        self.reflections.drain(..excess);
+    }
+
+    /// Write the text of the most recent unanswered reflection.
+    /// Returns false if every reflection is already answered.
+    pub fn answer_reflection(&mut self, text: String) -> bool {
+        match self.reflections.iter_mut().rev().find(|r| !r.is_answered()) {
+            Some(reflection) => {
+                reflection.text = Some(text);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Default for Meta {
+    fn default() -> Self {
+        Self {
+            boredom: 0,
+            recent_decisions: Vec::new(),
+            rejected_ideas: Vec::new(),
+            fired_cards: Vec::new(),
+            estate: None,
+            decor: Vec::new(),
+            jewelry: Vec::new(),
+            goals: HashMap::new(),
+            reflections: Vec::new(),
+            reflection_triggers: ReflectionTriggers::default(),
+            sanity: default_sanity(),
+            receiver_silent: false,
+            receiver_failed_tick: None,
+            receiver_stage: None,
+        }
+    }
+}
+
 /// The complete game state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameState {
+    /// Save format version (see `migrations`); 0 for saves from before it
+    /// was recorded
+    #[serde(default)]
+    pub schema_version: u32,
+
     /// Current tick number
     pub tick: u64,
 
@@ -76,13 +192,13 @@ pub struct GameState {
     pub resources: Resources,
 
     /// Production systems
-    pub systems: HashMap<String, System>,
+    pub systems: Shared<HashMap<String, System>>,
 
     /// Living entities
-    pub entities: Vec<Entity>,
+    pub entities: Shared<Vec<Entity>>,
 
     /// The map
-    pub map: GameMap,
+    pub map: Shared<GameMap>,
 
     /// Action and event queues
     pub queues: Queues,
@@ -92,48 +208,380 @@ pub struct GameState {
 
     /// The graveyard
     #[serde(default)]
-    pub graveyard: Graveyard,
+    pub graveyard: Shared<Graveyard>,
 
     /// Last save timestamp (for offline progress)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_save_timestamp: Option<f64>,
+
+    /// Expeditions currently away (members are not in `entities`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expeditions: Vec<Expedition>,
+
+    /// Remote outposts by tile ID
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub outposts: BTreeMap<String, Outpost>,
+
+    /// How goods move around the colony
+    #[serde(default, skip_serializing_if = "LogisticsMode::is_global")]
+    pub logistics: LogisticsMode,
+
+    /// Per-tile stockpiles (local logistics only)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub stockpiles: BTreeMap<String, Resources>,
+
+    /// How many ticks the host runs per wall-clock second
+    #[serde(default = "default_ticks_per_second", skip_serializing_if = "is_default_tick_rate")]
+    pub ticks_per_second: f64,
+
+    /// Fractional tick carried between scaled batches, in
+    /// 1/`SUB_TICKS_PER_TICK` units
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub sub_tick: u64,
+
+    /// Subsystems whose tick phases are skipped
+    #[serde(default, skip_serializing_if = "Paused::is_empty")]
+    pub paused: Paused,
+
+    /// Difficulty tuning; classic when absent
+    #[serde(default, skip_serializing_if = "SimConfig::is_classic")]
+    pub config: SimConfig,
+
+    /// Set once the colony is abandoned; the engine leaves it alone after
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legacy: Option<LegacySeed>,
+
+    /// Ruins of past runs, keyed by tile ID
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub ruins: BTreeMap<String, RuinSite>,
+
+    /// Tiles being dug, keyed by the ID each will have
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub excavations: BTreeMap<String, Excavation>,
+
+    /// Seeds the weather of the Outside; the engine fills in its own seed
+    /// on the first tick if the host hasn't chosen one
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub outside_seed: u64,
+
+    /// What resources are worth in trades, tributes, offerings and gifts
+    #[serde(default, skip_serializing_if = "Valuation::is_default")]
+    pub valuation: Valuation,
+
+    /// Per-tile activity counters, keyed by tile ID
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub activity: BTreeMap<Key, TileActivity>,
+
+    /// Pheromone trails the ants have laid (see `pheromone`)
+    #[serde(default, skip_serializing_if = "Trails::is_empty")]
+    pub trails: Trails,
+
+    /// The weather as of the last tick (see `weather`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weather: Option<Weather>,
+
+    /// Recent summon attempts, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transmissions: Vec<Transmission>,
+
+    /// Laid but not yet hatched, in laying order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub brood: Vec<Brood>,
+
+    /// Standing orders for idle workers
+    #[serde(default, skip_serializing_if = "WorkPolicy::is_unset")]
+    pub policy: WorkPolicy,
+
+    /// How meals are reported in event output
+    #[serde(default, skip_serializing_if = "MealReports::is_every")]
+    pub meal_reports: MealReports,
+
+    /// Meals waiting for the next batch, by entity (batched reporting only)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub meal_tallies: BTreeMap<EntityId, MealTally>,
+
+    /// Groups of ants acting together, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub groups: BTreeMap<String, Group>,
+
+    /// Mechanics this save has opted into (see `feature`)
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub features: BTreeSet<String>,
+
+    /// Wonders the colony has seen, with the tick each happened
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub wonders: BTreeMap<Wonder, u64>,
+
+    /// Tick the last incursion came, if one ever has (see `threat`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_incursion: Option<u64>,
+
+    /// Gifts whispers have promised, soonest first (see `whisper`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub promises: Vec<Promise>,
+
+    /// The reigning queen, while she lives (see `feature::SUCCESSION`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queen: Option<EntityId>,
+
+    /// Queens the colony has crowned, the founder included
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub reign: u64,
+
+    /// The goal this run is playing for, if any (see `scenario`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scenario: Option<Scenario>,
+
+    /// Set once a scenario ends the run; the engine leaves it alone after
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ended: Option<Ending>,
+
+    /// Health score last reported with `HealthChanged` (see `health`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reported_health: Option<f64>,
+
+    /// The engine's memory between ticks (see `bookkeeping`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bookkeeping: Option<Bookkeeping>,
 }
 
 impl Default for GameState {
     fn default() -> Self {
         Self {
+            schema_version: SCHEMA_VERSION,
             tick: 0,
             resources: Resources::new(),
-            systems: HashMap::new(),
-            entities: Vec::new(),
-            map: GameMap::default(),
+            systems: Shared::default(),
+            entities: Shared::default(),
+            map: Shared::default(),
             queues: Queues::default(),
             meta: Meta::default(),
-            graveyard: Graveyard::default(),
+            graveyard: Shared::default(),
             last_save_timestamp: None,
+            expeditions: Vec::new(),
+            outposts: BTreeMap::new(),
+            logistics: LogisticsMode::default(),
+            stockpiles: BTreeMap::new(),
+            ticks_per_second: default_ticks_per_second(),
+            sub_tick: 0,
+            paused: Paused::default(),
+            config: SimConfig::default(),
+            legacy: None,
+            ruins: BTreeMap::new(),
+            excavations: BTreeMap::new(),
+            outside_seed: 0,
+            valuation: Valuation::default(),
+            activity: BTreeMap::new(),
+            trails: Trails::default(),
+            weather: None,
+            transmissions: Vec::new(),
+            brood: Vec::new(),
+            policy: WorkPolicy::default(),
+            meal_reports: MealReports::default(),
+            meal_tallies: BTreeMap::new(),
+            groups: BTreeMap::new(),
+            features: BTreeSet::new(),
+            wonders: BTreeMap::new(),
+            last_incursion: None,
+            promises: Vec::new(),
+            queen: None,
+            reign: 0,
+            scenario: None,
+            ended: None,
+            reported_health: None,
+            bookkeeping: None,
         }
     }
 }
 
 impl GameState {
-    /// Create a fresh game state
+    /// Create a fresh game state, opted into every current feature.
+    /// `default()` is the legacy state older saves load into, with none.
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            features: feature::CURRENT.iter().map(|name| name.to_string()).collect(),
+            ..Self::default()
+        }
     }
 
-    /// Load state from JSON
+    /// Create a fresh game state, like `new()`, in a cavern grown from
+    /// `seed` (see `mapgen`)
+    pub fn generated(seed: u64, config: &MapGenConfig) -> Self {
+        Self {
+            map: GameMap::generate_with(seed, config).into(),
+            ..Self::new()
+        }
+    }
+
+    /// Load state from JSON, upgrading older schema versions and refusing
+    /// saves this build can't run
     pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
-        serde_json::from_str(json)
+        Self::from_json_checked(json).map(|(state, _)| state)
+    }
+
+    /// Load state from JSON like `from_json`, also returning how the save
+    /// relates to this build (see `compat`)
+    pub fn from_json_checked(json: &str) -> Result<(Self, Compatibility), serde_json::Error> {
+        Self::from_value_checked(serde_json::from_str(json)?)
+    }
+
+    /// Load state from a JSON document, upgrading older schema versions
+    pub fn from_value(doc: serde_json::Value) -> Result<Self, serde_json::Error> {
+        Self::from_value_checked(doc).map(|(state, _)| state)
+    }
+
+    fn from_value_checked(mut doc: serde_json::Value) -> Result<(Self, Compatibility), serde_json::Error> {
+        use serde::de::Error;
+
+        let schema = migrations::version_of(&doc);
+        let stamp = doc.as_object_mut()
+            .and_then(|state| state.remove("stamp"))
+            .map(serde_json::from_value::<SaveStamp>)
+            .transpose()?;
+        columnar::expand(&mut doc).map_err(Error::custom)?;
+        migrations::migrate(&mut doc).map_err(Error::custom)?;
+        let state: Self = serde_json::from_value(doc)?;
+        match compat::check(schema, stamp.as_ref(), &state.config) {
+            Compatibility::Incompatible { reason } => Err(Error::custom(reason)),
+            verdict => Ok((state, verdict)),
+        }
+    }
+
+    /// The state as written to disk: its fields plus the stamp of the
+    /// build writing it, laid out as `options` ask
+    fn stamped(&self, options: SaveOptions) -> Result<serde_json::Value, serde_json::Error> {
+        let mut doc = serde_json::to_value(self)?;
+        if options.columnar {
+            columnar::compact(&mut doc);
+        }
+        if let Some(state) = doc.as_object_mut() {
+            state.insert("stamp".to_string(), serde_json::to_value(SaveStamp::current(&self.config))?);
+        }
+        Ok(doc)
     }
 
     /// Serialize state to JSON
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
-        serde_json::to_string(self)
+        self.to_json_with(SaveOptions::default())
+    }
+
+    /// Serialize state to JSON laid out as `options` ask; `from_json` loads
+    /// every layout
+    pub fn to_json_with(&self, options: SaveOptions) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&self.stamped(options)?)
     }
 
     /// Serialize state to pretty JSON
     pub fn to_json_pretty(&self) -> Result<String, serde_json::Error> {
-        serde_json::to_string_pretty(self)
+        serde_json::to_string_pretty(&self.stamped(SaveOptions::default())?)
+    }
+
+    /// Serialize state to MessagePack: the same shape as `to_json`, a
+    /// fraction of the size and much quicker to load
+    #[cfg(feature = "binary")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+        self.to_bytes_with(SaveOptions::default())
+    }
+
+    /// Serialize state to MessagePack laid out as `options` ask
+    #[cfg(feature = "binary")]
+    pub fn to_bytes_with(&self, options: SaveOptions) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+        use serde::ser::Error;
+
+        let doc = self.stamped(options).map_err(rmp_serde::encode::Error::custom)?;
+        rmp_serde::to_vec_named(&doc)
+    }
+
+    /// Load state from `to_bytes` output
+    #[cfg(feature = "binary")]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, rmp_serde::decode::Error> {
+        use serde::de::Error;
+
+        #[derive(Deserialize)]
+        struct Version {
+            #[serde(default)]
+            schema_version: u32,
+            #[serde(default)]
+            stamp: Option<SaveStamp>,
+            #[serde(default)]
+            layout: Option<String>,
+        }
+
+        // Current row saves from this build decode directly; anything else
+        // goes through the checks, expansion and migrations
+        let version = rmp_serde::from_slice::<Version>(bytes)?;
+        let current = version.schema_version == SCHEMA_VERSION
+            && version.stamp.is_some_and(|s| s.core_version == compat::CORE_VERSION);
+        if current && version.layout.is_none() {
+            return rmp_serde::from_slice(bytes);
+        }
+        Self::from_value(rmp_serde::from_slice(bytes)?).map_err(rmp_serde::decode::Error::custom)
+    }
+
+    /// Stable digest of everything the simulation runs on. Host bookkeeping
+    /// (the save timestamp, meal reporting) and the narrative parts of
+    /// `meta` (decisions, ideas, cards, estate, decor, jewelry) are left
+    /// out, so two runs that simulate the same colony hash the same.
+    pub fn hash(&self) -> u64 {
+        let Ok(mut value) = serde_json::to_value(self) else {
+            return 0;
+        };
+        if let Some(state) = value.as_object_mut() {
+            for field in ["schema_version", "last_save_timestamp", "meal_reports", "meal_tallies"] {
+                state.remove(field);
+            }
+            if let Some(meta) = state.get_mut("meta").and_then(|m| m.as_object_mut()) {
+                for field in ["recent_decisions", "rejected_ideas", "fired_cards", "estate", "decor", "jewelry"] {
+                    meta.remove(field);
+                }
+            }
+        }
+        digest::digest(&value).unwrap_or(0)
+    }
+
+    /// Serialize state inside an integrity envelope, stamped with `engine`'s config
+    pub fn to_json_envelope(&self, engine: &TickEngine) -> Result<String, serde_json::Error> {
+        let state = serde_json::to_value(self)?;
+        let envelope = SaveEnvelope {
+            envelope_version: ENVELOPE_VERSION,
+            core_version: env!("CARGO_PKG_VERSION").to_string(),
+            state_digest: envelope::hex(digest::digest(&state)?),
+            config_digest: envelope::hex(engine.config_digest()),
+            state,
+        };
+        serde_json::to_string(&envelope)
+    }
+
+    /// Load state from an envelope, verifying it against `engine`'s config
+    pub fn from_json_envelope(json: &str, engine: &TickEngine) -> Result<Self, EnvelopeError> {
+        let envelope: SaveEnvelope = serde_json::from_str(json)?;
+
+        if envelope.envelope_version != ENVELOPE_VERSION {
+            return Err(EnvelopeError::UnsupportedVersion {
+                found: envelope.envelope_version,
+                supported: ENVELOPE_VERSION,
+            });
+        }
+
+        let state_digest = envelope::hex(digest::digest(&envelope.state)?);
+        if state_digest != envelope.state_digest {
+            return Err(EnvelopeError::StateDigestMismatch {
+                expected: envelope.state_digest,
+                found: state_digest,
+            });
+        }
+
+        let config_digest = envelope::hex(engine.config_digest());
+        if config_digest != envelope.config_digest {
+            return Err(EnvelopeError::ConfigDigestMismatch {
+                expected: envelope.config_digest,
+                found: config_digest,
+            });
+        }
+
+        let mut state = envelope.state;
+        migrations::migrate(&mut state)?;
+        Ok(serde_json::from_value(state)?)
     }
 
     /// Get an entity by ID
@@ -158,15 +606,833 @@ impl GameState {
             .count()
     }
 
+    /// Composable read-only queries over this state
+    pub fn query(&self) -> Query<'_> {
+        Query::new(self)
+    }
+
+    /// Field-level changes from this state to `other`
+    pub fn diff(&self, other: &GameState) -> StateDiff {
+        StateDiff::between(self, other)
+    }
+
+    /// The built-in kinds plus this save's content, for encyclopedias
+    pub fn registry(&self) -> Registry {
+        Registry::of(self)
+    }
+
+    /// Has the colony been abandoned?
+    pub fn is_abandoned(&self) -> bool {
+        self.legacy.is_some()
+    }
+
+    /// Has a scenario ended the run?
+    pub fn is_ended(&self) -> bool {
+        self.ended.is_some()
+    }
+
+    /// Bring a past run's ruins into this colony.
+    ///
+    /// Each ruin becomes a `ruin_<old tile>` tile east of the current map,
+    /// keeping its old layout, with the ghosts last seen there and an even
+    /// share of the residual stores as loot. Returns the new tile IDs.
+    pub fn ingest_legacy(&mut self, legacy: &LegacySeed) -> Result<Vec<String>, RuinError> {
+        if self.ruins.values().any(|r| r.legacy_id == legacy.id) {
+            return Err(RuinError::AlreadyIngested(legacy.id));
+        }
+
+        let east_edge = self.map.tiles.values().map(|t| t.x).max().unwrap_or(0);
+        let west_ruin = legacy.ruins.iter().map(|r| r.x).min().unwrap_or(0);
+        let shift = east_edge + constants::RUIN_GAP - west_ruin;
+        let share = 1.0 / legacy.ruins.len().max(1) as f64;
+
+        let mut added = Vec::new();
+        for ruin in &legacy.ruins {
+            let base = format!("ruin_{}", ruin.tile);
+            let mut tile_id = base.clone();
+            let mut n = 1;
+            while self.map.tiles.contains_key(tile_id.as_str()) {
+                n += 1;
+                tile_id = format!("{}~{}", base, n);
+            }
+
+            let mut tile = Tile::new_empty(format!("Ruins of {}", ruin.name), ruin.x + shift, ruin.y);
+            tile.tile_type = TileType::Ruin;
+            self.map.tiles.insert(Key::new(&tile_id), tile);
+
+            self.ruins.insert(tile_id.clone(), RuinSite {
+                legacy_id: legacy.id,
+                former_tile: ruin.tile,
+                ghosts: legacy.ghosts.iter().filter(|g| g.tile == ruin.tile).cloned().collect(),
+                loot: legacy.residual.iter().map(|(r, amount)| (*r, amount * share)).collect(),
+                excavated_at: None,
+            });
+            added.push(tile_id);
+        }
+        Ok(added)
+    }
+
+    /// Conditions in the Outside right now
+    pub fn outside_conditions(&self) -> OutsideConditions {
+        self.outside_conditions_at(self.tick)
+    }
+
+    /// Conditions in the Outside at `tick`, past or future (a forecast)
+    pub fn outside_conditions_at(&self, tick: u64) -> OutsideConditions {
+        OutsideConditions::at(self.outside_seed, tick as f64 / self.ticks_per_second)
+    }
+
+    /// The colony's weather at `tick`, past or future (a forecast)
+    pub fn weather_at(&self, tick: u64) -> Weather {
+        Weather::at(self.outside_seed, tick as f64 / self.ticks_per_second, &self.config.tuning)
+    }
+
+    /// The weather the colony is under, if it has seasons
+    pub fn current_weather(&self) -> Option<Weather> {
+        self.weather.filter(|_| self.has_feature(feature::SEASONS))
+    }
+
+    /// Where the colony is in its day right now
+    pub fn time_of_day(&self) -> TimeOfDay {
+        self.time_of_day_at(self.tick)
+    }
+
+    /// Where the colony is in its day at `tick`
+    pub fn time_of_day_at(&self, tick: u64) -> TimeOfDay {
+        let tuning = &self.config.tuning;
+        TimeOfDay::at(tick as f64 / self.ticks_per_second, tuning.day_length, tuning.night_share)
+    }
+
+    /// Whether it's dark enough to matter: night, with `feature::DAY_NIGHT`
+    pub fn is_dark(&self) -> bool {
+        self.has_feature(feature::DAY_NIGHT) && self.time_of_day().is_night()
+    }
+
+    /// Share of their work ants do right now (less in the dark)
+    pub fn shift_factor(&self) -> f64 {
+        if self.is_dark() { self.config.tuning.night_work_factor } else { 1.0 }
+    }
+
+    /// Odds of each visitor type answering a summons now: the Outside's
+    /// weights, leaned on by the colony's own scarcity. Famine draws the
+    /// hungry; a crystal surplus draws observers, who only come by night
+    /// with `feature::DAY_NIGHT`.
+    pub fn visitor_weights(&self) -> VisitorWeights {
+        let mut weights = self.outside_conditions().visitor_weights;
+        weights.hungry *= self.valuation.scarcity(self.resources.get("fungus"));
+        weights.observer /= self.valuation.scarcity(self.resources.get("crystals"));
+        if self.has_feature(feature::DAY_NIGHT) && !self.time_of_day().is_night() {
+            weights.observer = 0.0;
+        }
+        weights
+    }
+
+    /// What the colony's stores are worth, as `valuation` prices them
+    pub fn wealth(&self) -> f64 {
+        let stores = det::entries(self.resources.amounts.iter());
+        self.valuation.worth(stores, &self.resources)
+    }
+
+    /// How hard the colony draws threats: its wealth against
+    /// `raid_wealth_reference` (1 there), capped at
+    /// `constants::RAID_MAX_PRESSURE`
+    pub fn raid_pressure(&self) -> f64 {
+        let reference = self.config.tuning.raid_wealth_reference;
+        if reference <= 0.0 {
+            return constants::RAID_MAX_PRESSURE;
+        }
+        (self.wealth() / reference).clamp(0.0, constants::RAID_MAX_PRESSURE)
+    }
+
+    /// Log a summon attempt, dropping the oldest beyond the retention limit
+    pub fn record_transmission(&mut self, transmission: Transmission) {
+        self.transmissions.push(transmission);
+        let excess = self.transmissions.len().saturating_sub(constants::MAX_TRANSMISSIONS);
+        self.transmissions.drain(..excess);
+    }
+
+    /// Activity counters for a tile (all zero if nothing has happened there)
+    pub fn activity_on(&self, tile: &str) -> TileActivity {
+        self.activity.get(tile).copied().unwrap_or_default()
+    }
+
+    /// Strange matter one receiver maintenance costs
+    pub fn maintenance_cost(&self) -> f64 {
+        self.config.tuning.maintenance_cost_strange_matter * self.config.maintenance_cost_scale
+    }
+
+    /// The stage a receiver silent for `silent_for` ticks has reached
+    pub fn silence_stage_after(&self, silent_for: u64) -> SilenceStage {
+        let tuning = &self.config.tuning;
+        if !self.has_feature(feature::SILENCE_STAGES) {
+            SilenceStage::Quiet
+        } else if silent_for >= self.ticks_for(tuning.receiver_derelict_after) {
+            SilenceStage::Derelict
+        } else if silent_for >= self.ticks_for(tuning.receiver_dormant_after) {
+            SilenceStage::Dormant
+        } else {
+            SilenceStage::Quiet
+        }
+    }
+
+    /// Strange matter it takes to bring the silent receiver back
+    pub fn receiver_restore_cost(&self) -> f64 {
+        let scale = match self.meta.receiver_stage.unwrap_or(SilenceStage::Quiet) {
+            SilenceStage::Quiet => 1.0,
+            SilenceStage::Dormant => self.config.tuning.receiver_dormant_cost_scale,
+            SilenceStage::Derelict => self.config.tuning.receiver_derelict_cost_scale,
+        };
+        self.maintenance_cost() * scale
+    }
+
+    /// How long the receiver has been silent, how far gone it is and what
+    /// comes next; None while it's listening
+    pub fn receiver_silence(&self) -> Option<Silence> {
+        if !self.meta.receiver_silent {
+            return None;
+        }
+        let since = self.meta.receiver_failed_tick.unwrap_or(self.tick);
+        let stage = self.meta.receiver_stage.unwrap_or(SilenceStage::Quiet);
+        let next_stage = stage.next().filter(|_| self.has_feature(feature::SILENCE_STAGES));
+        let silent_for = self.tick.saturating_sub(since);
+        let next_stage_in = next_stage.map(|next| {
+            let after = match next {
+                SilenceStage::Dormant => self.config.tuning.receiver_dormant_after,
+                _ => self.config.tuning.receiver_derelict_after,
+            };
+            self.ticks_for(after).saturating_sub(silent_for)
+        });
+        Some(Silence { since, silent_for, stage, restore_cost: self.receiver_restore_cost(), next_stage, next_stage_in })
+    }
+
+    /// Receiver maintenance interval when the goal doesn't set one, in ticks
+    pub fn default_maintenance_interval(&self) -> u64 {
+        let seconds = self.config.tuning.maintenance_interval as f64 * self.config.maintenance_interval_scale;
+        self.ticks_for(seconds.round() as u64)
+    }
+
+    /// Population report: age pyramid and hunger by cohort, plus who is
+    /// expected to die or depart within `horizon` ticks
+    pub fn demographics(&self, horizon: u64) -> Demographics {
+        let bucket_ticks = self.ticks_for(constants::DEMOGRAPHIC_BUCKET_TICKS);
+        let mut report = Demographics {
+            bucket_ticks,
+            horizon,
+            ..Default::default()
+        };
+        let mut hunger_sums: BTreeMap<String, f64> = BTreeMap::new();
+        let mut total_hunger = 0.0;
+
+        for entity in self.entities.iter() {
+            let cohort = demographics::cohort(entity);
+
+            let bucket = (entity.age / bucket_ticks) as usize;
+            let buckets = report.pyramid.entry(cohort.clone()).or_default();
+            if buckets.len() <= bucket {
+                buckets.resize(bucket + 1, 0);
+            }
+            buckets[bucket] += 1;
+
+            *report.population.entry(cohort.clone()).or_default() += 1;
+            *hunger_sums.entry(cohort).or_default() += entity.hunger;
+            total_hunger += entity.hunger;
+
+            // Same order the entity phase checks: starvation, then age
+            let fed = entity.food.as_ref().is_some_and(|food| self.resources.has(food, 1.0));
+            let hunger_rate = self.per_tick(entity.hunger_rate * self.config.hunger_rate_scale);
+            let starves = self.config.starvation_deaths && !fed && hunger_rate > 0.0
+                && entity.hunger <= hunger_rate * horizon as f64;
+            let ages_out = entity.max_age.saturating_sub(entity.age) <= horizon;
+            let deaths = &mut report.expected_deaths;
+            if starves {
+                deaths.starvation += 1;
+            } else if ages_out && entity.entity_type == EntityType::Visitor {
+                deaths.departures += 1;
+            } else if ages_out {
+                deaths.old_age += 1;
+            }
+        }
+
+        for (cohort, sum) in hunger_sums {
+            let count = report.population[&cohort] as f64;
+            report.average_hunger.insert(cohort, sum / count);
+        }
+        if !self.entities.is_empty() {
+            report.overall_average_hunger = total_hunger / self.entities.len() as f64;
+        }
+        report
+    }
+
+    /// The colony at a glance, for a frontend joining mid-run
+    pub fn summary(&self) -> Summary {
+        let mut population = BTreeMap::new();
+        for entity in self.entities.iter() {
+            *population.entry(demographics::cohort(entity)).or_default() += 1;
+        }
+
+        let mut top_resources: Vec<(Key, f64)> = self.resources.amounts.iter().map(|(r, a)| (*r, *a)).collect();
+        det::sort_by_key(&mut top_resources, |(_, amount)| Reverse(OrdF64(*amount)), |(resource, _)| *resource);
+        top_resources.truncate(constants::SUMMARY_TOP_RESOURCES);
+
+        let mut disasters: Vec<Disaster> = det::entries(self.map.tiles.iter())
+            .into_iter()
+            .filter(|(_, tile)| tile.is_blighted())
+            .map(|(id, tile)| Disaster::Blight { tile: *id, ticks_remaining: tile.blight_ticks_remaining.unwrap_or(0) })
+            .collect();
+        if self.meta.receiver_silent {
+            disasters.push(Disaster::ReceiverSilent {
+                since: self.meta.receiver_failed_tick,
+                stage: self.meta.receiver_stage,
+            });
+        }
+
+        let visitors = self.query().entities().visitors().iter()
+            .map(|v| VisitorSummary {
+                id: v.id.clone(),
+                subtype: v.subtype.clone(),
+                name: v.name.clone(),
+                ticks_left: v.max_age.saturating_sub(v.age),
+            })
+            .collect();
+
+        Summary {
+            tick: self.tick,
+            population,
+            top_resources,
+            sanity: self.meta.sanity,
+            disasters,
+            warnings: self.warnings(),
+            visitors,
+            counters: SummaryCounters {
+                corpses: self.graveyard.corpses.len() as u64,
+                processed: self.graveyard.total_processed,
+                brood: self.brood.len() as u64,
+                expeditions_away: self.expeditions.len() as u64,
+                outposts: self.outposts.len() as u64,
+                groups: self.groups.len() as u64,
+                wonders_seen: self.wonders.len() as u64,
+                recent_summons: self.transmissions.len() as u64,
+                recent_answers: self.transmissions.iter().filter(|t| t.success).count() as u64,
+            },
+        }
+    }
+
     /// Get all entities on a tile
     pub fn entities_on_tile(&self, tile: &str) -> Vec<&Entity> {
         self.entities.iter().filter(|e| e.tile == tile).collect()
     }
 
+    /// Merge `other` colony into this one according to `policy`.
+    ///
+    /// Deterministic: incoming entities and corpses are taken in stored
+    /// order. Newcomers on tiles this map doesn't have are moved to
+    /// `policy.arrival_tile`. Emits `ColonyAbsorbed`.
+    pub fn absorb(&mut self, other: GameState, policy: &AbsorbPolicy) -> TickEvents {
+        let tick = self.tick;
+        let mut taken: HashSet<EntityId> = self.entities.iter().map(|e| e.id.clone())
+            .chain(self.expeditions.iter().flat_map(|x| x.member_ids()))
+            .chain(self.graveyard.corpses.iter().map(|c| c.entity_id.clone()))
+            .collect();
+        let mut renamed = BTreeMap::new();
+        let mut skipped = Vec::new();
+
+        // Claim an ID for a newcomer, or None if it has to stay behind
+        let mut claim = |id: &EntityId, taken: &mut HashSet<EntityId>| -> Option<EntityId> {
+            if taken.insert(id.clone()) {
+                return Some(id.clone());
+            }
+            match policy.id_conflict {
+                IdConflict::Skip => None,
+                IdConflict::Rename => {
+                    let fresh = (2..)
+                        .map(|n| format!("{}~{}", id, n))
+                        .find(|candidate| !taken.contains(candidate))?;
+                    taken.insert(fresh.clone());
+                    renamed.insert(id.clone(), fresh.clone());
+                    Some(fresh)
+                }
+            }
+        };
+
+        let resources: BTreeMap<Key, f64> = match policy.resources {
+            ResourceMerge::Sum => other.total_resources().amounts.into_iter().collect(),
+            ResourceMerge::Ignore => BTreeMap::new(),
+        };
+        self.resources.add_all(&resources);
+
+        let GameState { entities, graveyard, .. } = other;
+
+        let mut joined = Vec::new();
+        for mut entity in Shared::unwrap_or_clone(entities) {
+            let selected = match &policy.entities {
+                EntitySelection::All => true,
+                EntitySelection::Ants => entity.entity_type == EntityType::Ant,
+                EntitySelection::Ids(ids) => ids.contains(&entity.id),
+                EntitySelection::None => false,
+            };
+            if !selected {
+                continue;
+            }
+            match claim(&entity.id, &mut taken) {
+                Some(id) => entity.id = id,
+                None => {
+                    skipped.push(entity.id);
+                    continue;
+                }
+            }
+            if self.map.get_tile(&entity.tile).is_none() {
+                entity.tile = policy.arrival_tile.clone();
+            }
+            joined.push(entity.id.clone());
+            self.entities.push(entity);
+        }
+
+        let mut corpses = 0;
+        if policy.graveyard {
+            let graveyard = Shared::unwrap_or_clone(graveyard);
+            for mut corpse in graveyard.corpses {
+                let Some(id) = claim(&corpse.entity_id, &mut taken) else {
+                    continue;
+                };
+                corpse.entity_id = id;
+                if self.map.get_tile(&corpse.tile).is_none() {
+                    corpse.tile = policy.arrival_tile.clone();
+                }
+                self.graveyard.add_corpse(corpse);
+                corpses += 1;
+            }
+            self.graveyard.total_processed += graveyard.total_processed;
+        }
+
+        let mut events = TickEvents::new();
+        events.push(tick, EventKind::ColonyAbsorbed {
+            entities: joined,
+            renamed,
+            skipped,
+            resources,
+            corpses,
+        });
+        events
+    }
+
+    /// Ticks spanning `seconds` at this state's tick rate (at least 1 for
+    /// any nonzero duration). Used to scale the engine's duration constants.
+    pub fn ticks_for(&self, seconds: u64) -> u64 {
+        let ticks = Timeline::new(self.ticks_per_second).ticks(Duration::from_secs(seconds));
+        if seconds > 0 { ticks.max(1) } else { 0 }
+    }
+
+    /// How much of a `per_second` rate falls in one tick at this state's
+    /// tick rate
+    pub fn per_tick(&self, per_second: f64) -> f64 {
+        per_second / Timeline::new(self.ticks_per_second).ticks_per_second()
+    }
+
+    /// `entity` with its role's or visitor type's baseline, its lifespan
+    /// (given in seconds) turned into ticks at this state's tick rate
+    pub fn spawned(&self, entity: Entity) -> Entity {
+        let mut entity = self.config.with_baseline(entity);
+        entity.max_age = self.ticks_for(entity.max_age);
+        entity
+    }
+
+    /// Ticks between the queen's layings: slower while no nurse tends her
+    /// (with `feature::NURSES`)
+    pub fn spawn_interval(&self) -> u64 {
+        let interval = self.ticks_for(self.config.tuning.spawn_interval_ticks);
+        let tended = self.entities.iter().any(|e| e.role == Some(AntRole::Nurse) && !e.is_dormant());
+        if self.has_feature(feature::NURSES) && !tended {
+            interval * self.config.tuning.untended_queen_slowdown.max(1)
+        } else {
+            interval
+        }
+    }
+
+    /// Ticks a brood spends in `stage`. Without `feature::LIFECYCLE`, brood
+    /// are larvae all the way through.
+    pub fn stage_ticks(&self, stage: LifeStage) -> u64 {
+        let tuning = &self.config.tuning;
+        match stage {
+            LifeStage::Egg => self.ticks_for(tuning.egg_ticks),
+            LifeStage::Larva => self.ticks_for(tuning.brood_development_ticks),
+            LifeStage::Pupa => self.ticks_for(tuning.pupa_ticks),
+            LifeStage::Adult => 0,
+        }
+    }
+
+    /// Traits for a newborn ant with ID `id`: passed down from the
+    /// reigning queen, if there is one (see `heredity`). None without
+    /// `feature::TRAITS`.
+    pub fn newborn_traits(&self, id: &str) -> Vec<Trait> {
+        if !self.has_feature(feature::TRAITS) {
+            return Vec::new();
+        }
+        let parent = self.queen.as_deref()
+            .and_then(|queen| self.get_entity(queen))
+            .map_or(&[][..], |queen| queen.traits.as_slice());
+        let tuning = &self.config.tuning;
+        heredity::inherit(id, parent, tuning.trait_inherit_chance, tuning.trait_mutation_chance)
+    }
+
+    /// Ticks an ant takes to walk one connection
+    pub fn step_ticks(&self) -> u64 {
+        self.ticks_for(self.config.tuning.step_seconds).max(1)
+    }
+
+    /// Ticks an ant would take to walk from `from` to `to`, or None if
+    /// there's no way. An estimate: hunger and other errands can send it
+    /// elsewhere on the way.
+    pub fn travel_ticks(&self, from: &str, to: &str) -> Option<u64> {
+        Some(self.map.distance(from, to)? as u64 * self.step_ticks())
+    }
+
+    /// Is `subsystem` running (not paused)?
+    pub fn is_running(&self, subsystem: Subsystem) -> bool {
+        !self.paused.contains(subsystem)
+    }
+
+    /// What still stands on or points at `tile`, if anything: a map edit
+    /// must not pull the ground out from under it
+    pub fn tile_in_use(&self, tile: &str) -> Option<&'static str> {
+        if self.entities.iter().any(|e| e.tile == tile) {
+            Some("entities")
+        } else if self.brood.iter().any(|b| b.tile == tile) {
+            Some("brood")
+        } else if self.graveyard.corpses.iter().any(|c| c.tile == tile) {
+            Some("uncollected corpses")
+        } else if self.systems.values().any(|s| s.tile.as_deref() == Some(tile)) {
+            Some("a system")
+        } else if self.outposts.contains_key(tile) || self.ruins.contains_key(tile) {
+            Some("an outpost or ruin")
+        } else if self.stockpiles.get(tile).is_some_and(|pile| !pile.amounts.is_empty()) {
+            Some("a stockpile")
+        } else if self.excavations.values().any(|dig| dig.from == tile) {
+            Some("a tile being dug from it")
+        } else if self.expeditions.iter().any(|x| x.from_tile == tile) {
+            Some("an expedition's way home")
+        } else if self.queues.actions.iter().any(|a| a.target.as_deref() == Some(tile)) {
+            Some("a queued action")
+        } else if self.groups.values().any(|g| matches!(&g.intent, Intent::GoTo { tile: to } if to == tile)) {
+            Some("a group headed there")
+        } else {
+            None
+        }
+    }
+
+    /// Name of the group `entity_id` belongs to, if any
+    pub fn group_of(&self, entity_id: &str) -> Option<&str> {
+        self.groups.iter()
+            .find(|(_, group)| group.members.contains(entity_id))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Has this save opted into feature `name`?
+    pub fn has_feature(&self, name: &str) -> bool {
+        self.features.contains(name)
+    }
+
+    /// Opt in to or out of feature `name`, returning whether anything changed.
+    /// Switching morale on leaves the colony's past deaths mourned already,
+    /// so ants grieve only those from then on.
+    pub fn set_feature(&mut self, name: &str, enabled: bool) -> bool {
+        if enabled {
+            let added = self.features.insert(name.to_string());
+            if added && name == feature::MORALE {
+                for activity in self.activity.values_mut() {
+                    activity.mourned = activity.deaths;
+                }
+            }
+            added
+        } else {
+            self.features.remove(name)
+        }
+    }
+
+    /// Everything the colony owns: the colony store plus every stockpile
+    pub fn total_resources(&self) -> Resources {
+        let mut total = self.resources.clone();
+        for pile in self.stockpiles.values() {
+            total.add_all(&pile.amounts);
+        }
+        total
+    }
+
+    /// The stockpile a system on `tile` works out of, or None for the
+    /// colony-wide pool
+    pub fn stockpile_for(&self, tile: Option<&str>) -> Option<String> {
+        tile.filter(|_| !self.logistics.is_global()).map(str::to_string)
+    }
+
+    /// The pool a system on `tile` works out of: the tile's stockpile in
+    /// local logistics, otherwise the colony-wide resources
+    pub fn pool_mut(&mut self, tile: Option<&str>) -> &mut Resources {
+        match tile {
+            Some(tile) if !self.logistics.is_global() => {
+                self.stockpiles.entry(tile.to_string()).or_default()
+            }
+            _ => &mut self.resources,
+        }
+    }
+
+    /// The resources held at a depot, if it has any
+    pub fn depot(&self, depot: &Depot) -> Option<&Resources> {
+        match depot {
+            Depot::Colony => Some(&self.resources),
+            Depot::Tile(tile) => self.stockpiles.get(tile),
+        }
+    }
+
+    /// Mutable resources at a depot (creates an empty stockpile if needed)
+    pub fn depot_mut(&mut self, depot: &Depot) -> &mut Resources {
+        match depot {
+            Depot::Colony => &mut self.resources,
+            Depot::Tile(tile) => self.stockpiles.entry(tile.clone()).or_default(),
+        }
+    }
+
     /// Check if a system exists
     pub fn has_system(&self, system_id: &str) -> bool {
         self.systems.contains_key(system_id)
     }
+
+    /// List known future beats, soonest first, without advancing state.
+    ///
+    /// Only deterministic schedules are included: a visitor that might
+    /// starve first is still listed at its max-age departure.
+    pub fn upcoming(&self, engine: &TickEngine) -> Vec<ScheduledItem> {
+        let now = self.tick;
+        let next = now + 1;
+        let mut items = Vec::new();
+
+        // Queen spawn window
+        if self.has_system("queen_chamber") {
+            let tick = match engine.last_spawn_tick() {
+                // The queen starts her clock on the next tick
+                0 => next + self.spawn_interval(),
+                last => (last + self.spawn_interval()).max(next),
+            };
+            items.push(ScheduledItem::new(tick, ScheduledKind::SpawnWindow));
+        }
+
+        // Receiver maintenance
+        if self.has_system("receiver") {
+            if let Some(goal) = self.meta.goals.get("receiver_maintenance") {
+                let last = goal.get("last_maintained").and_then(|v| v.as_u64());
+                let interval = goal.get("maintenance_interval_ticks")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or_else(|| self.default_maintenance_interval());
+                if let Some(last) = last {
+                    let tick = (last + interval).max(next);
+                    items.push(ScheduledItem::new(tick, ScheduledKind::MaintenanceDue));
+                }
+            }
+        }
+
+        // Visitor departures (age is checked after incrementing)
+        for entity in self.query().entities().visitors().iter() {
+            let tick = now + entity.max_age.saturating_sub(entity.age).max(1);
+            items.push(ScheduledItem::new(tick, ScheduledKind::VisitorDeparture {
+                visitor_id: entity.id.clone(),
+                name: entity.name.clone().unwrap_or_default(),
+            }));
+        }
+
+        // Action completions
+        for action in &self.queues.actions {
+            let tick = now + action.ticks_remaining.max(1);
+            items.push(ScheduledItem::new(tick, ScheduledKind::ActionComplete {
+                action_id: action.id.clone(),
+                action_type: action.action_type.clone(),
+            }));
+        }
+
+        // Outposts running out of supplies
+        for (tile, outpost) in &self.outposts {
+            if outpost.is_supplied() {
+                items.push(ScheduledItem::new(now + outpost.supply_ticks, ScheduledKind::OutpostUnsupplied {
+                    tile: tile.clone(),
+                }));
+            }
+        }
+
+        // Blight expiry (the engine only ticks the compost tile)
+        if let Some(tile) = self.map.get_tile("compost") {
+            if tile.is_blighted() {
+                let tick = now + tile.blight_ticks_remaining.unwrap_or(0).max(1);
+                items.push(ScheduledItem::new(tick, ScheduledKind::BlightExpiry {
+                    tile: "compost".to_string(),
+                }));
+            }
+        }
+
+        // Floods and cave-ins passing
+        for (id, tile) in det::entries(self.map.tiles.iter()) {
+            if let Some(struck) = &tile.hazard {
+                items.push(ScheduledItem::new(struck.until.max(next), ScheduledKind::HazardPasses {
+                    hazard: struck.hazard,
+                    tile: *id,
+                }));
+            }
+        }
+
+        // Whispered promises
+        for promise in &self.promises {
+            items.push(ScheduledItem::new(promise.due.max(next), ScheduledKind::PromiseDue {
+                resource: promise.resource,
+                amount: promise.amount,
+            }));
+        }
+
+        // Stable sort keeps insertion order for same-tick items
+        items.sort_by_key(|item| item.tick);
+        items
+    }
+
+    /// Conditions the colony should hear about now, judged by the engine's
+    /// thresholds. `tick()` attaches these to its output.
+    pub fn warnings(&self) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+
+        // Food running out
+        for (food, seconds_left) in self.food_runway() {
+            if seconds_left < constants::FOOD_RUNWAY_WARNING {
+                let stock = self.resources.get(food.as_str());
+                warnings.push(Warning::LowFoodRunway { food, stock, seconds_left });
+            }
+        }
+
+        // Maintenance the receiver can't pay for
+        if let Some(ticks_left) = self.maintenance_ticks_left() {
+            let cost = self.maintenance_cost();
+            let strange_matter = self.resources.get("strange_matter");
+            if ticks_left <= self.ticks_for(constants::MAINTENANCE_WARNING) && strange_matter < cost {
+                warnings.push(Warning::MaintenanceDue { ticks_left, cost, strange_matter });
+            }
+        }
+
+        // Blight odds on the compost tile (the only one that rolls)
+        if self.config.blight {
+            if let Some(tile) = self.map.get_tile("compost").filter(|t| !t.is_blighted()) {
+                let contamination = tile.contamination.unwrap_or(0.0);
+                let chance = contamination * self.config.blight_chance_scale;
+                if chance >= constants::BLIGHT_RISK_WARNING {
+                    warnings.push(Warning::BlightRisk { tile: Key::new("compost"), contamination, chance });
+                }
+            }
+        }
+
+        // Ants but no one to bury them
+        let ants = self.count_entities_by_type(&EntityType::Ant);
+        let undertakers = self.query().entities().role(AntRole::Undertaker)
+            .filter(|e| !e.is_dormant())
+            .count();
+        if ants > 0 && undertakers == 0 {
+            warnings.push(Warning::NoUndertakers { corpses: self.graveyard.corpses.len() });
+        }
+
+        // Systems set aside as impossible
+        for (id, system) in det::entries(self.systems.iter()) {
+            if let Some(suspicion) = &system.suspect {
+                warnings.push(Warning::SystemSetAside { system_id: id.clone(), suspicion: suspicion.clone() });
+            }
+        }
+
+        // Digs held up by a tile added under their ID
+        for (id, dig) in det::entries(self.excavations.iter()) {
+            if dig.is_done() && self.map.get_tile(id).is_some() {
+                warnings.push(Warning::ExcavationBlocked { tile: Key::new(id) });
+            }
+        }
+
+        warnings
+    }
+
+    /// Seconds until each food the colony is eating faster than it makes
+    /// runs out, at the current rate: what the ants and brood eat per
+    /// second, less what the running systems make
+    pub fn food_runway(&self) -> BTreeMap<Key, f64> {
+        let hunger_scale = self.config.hunger_rate_scale;
+        let mut drain: BTreeMap<Key, f64> = BTreeMap::new();
+        for ant in self.entities.iter().filter(|e| e.entity_type == EntityType::Ant) {
+            if let Some(food) = &ant.food {
+                *drain.entry(Key::new(food)).or_default() +=
+                    ant.hunger_rate * hunger_scale / self.config.tuning.hunger_gain_from_eating;
+            }
+        }
+        if !self.brood.is_empty() {
+            *drain.entry(Key::new("fungus")).or_default() +=
+                self.brood.len() as f64 * self.config.tuning.brood_food_per_tick;
+        }
+        for (_, system) in det::entries(self.systems.iter()).into_iter().filter(|(_, s)| !s.is_disabled()) {
+            for (food, amount) in drain.iter_mut() {
+                let made = system.generates.as_ref().and_then(|g| g.get(food.as_str())).unwrap_or(&0.0);
+                let used = system.consumes.as_ref().and_then(|c| c.get(food.as_str())).unwrap_or(&0.0);
+                *amount += used - made;
+            }
+        }
+        drain.into_iter()
+            .filter(|(_, per_second)| *per_second > 0.0)
+            .map(|(food, per_second)| (food, self.resources.get(food.as_str()) / per_second))
+            .collect()
+    }
+
+    /// Ticks until the listening receiver's next maintenance falls due
+    /// (None with no receiver, a silent one, or no maintenance on record)
+    pub fn maintenance_ticks_left(&self) -> Option<u64> {
+        if !self.has_system("receiver") || self.meta.receiver_silent {
+            return None;
+        }
+        let goal = self.meta.goals.get("receiver_maintenance")?;
+        let last = goal.get("last_maintained").and_then(|v| v.as_u64())?;
+        let interval = goal.get("maintenance_interval_ticks")
+            .and_then(|v| v.as_u64())
+            .unwrap_or_else(|| self.default_maintenance_interval());
+        Some((last + interval).saturating_sub(self.tick))
+    }
+
+    /// The colony's health score and its parts (see `health`)
+    pub fn health(&self) -> Health {
+        let runway = self.food_runway().into_values().fold(f64::INFINITY, f64::min);
+        let food = (runway / constants::HEALTH_FOOD_HORIZON).min(1.0);
+
+        let ants: Vec<&Entity> = self.entities.iter().filter(|e| e.entity_type == EntityType::Ant).collect();
+        let population = if ants.is_empty() {
+            0.0
+        } else {
+            let aging = ants.iter()
+                .filter(|e| e.max_age.saturating_sub(e.age) <= constants::DEMOGRAPHIC_BUCKET_TICKS)
+                .count();
+            let dying = (self.graveyard.corpses.len() + aging) as f64;
+            0.5 + 0.5 * (self.brood.len() as f64 - dying) / ants.len() as f64
+        };
+
+        let contamination = match self.map.get_tile("compost") {
+            Some(tile) if tile.is_blighted() => 0.0,
+            Some(tile) => 1.0 - tile.contamination.unwrap_or(0.0),
+            None => 1.0,
+        };
+
+        let maintenance = if !self.has_system("receiver") {
+            1.0
+        } else if self.meta.receiver_silent {
+            0.0
+        } else {
+            match self.maintenance_ticks_left() {
+                Some(ticks_left) if self.resources.get("strange_matter") < self.maintenance_cost() => {
+                    ticks_left as f64 / self.default_maintenance_interval().max(1) as f64
+                }
+                _ => 1.0,
+            }
+        };
+
+        Health::from_parts(HealthParts {
+            food,
+            population,
+            contamination,
+            sanity: self.meta.sanity / 100.0,
+            maintenance,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -179,6 +1445,7 @@ mod tests {
         assert_eq!(state.tick, 0);
         assert!(state.entities.is_empty());
         assert!(state.map.tiles.contains_key("origin"));
+        assert_eq!(state.meta.sanity, 100.0);
     }
 
     #[test]
@@ -187,5 +1454,319 @@ mod tests {
         let json = state.to_json().unwrap();
         let restored = GameState::from_json(&json).unwrap();
         assert_eq!(restored.tick, state.tick);
+
+        // Caps set ahead of any priorities are kept
+        let policy = WorkPolicy::default().with_limit(crate::types::policy::Task::Haul, 2);
+        let state = GameState { policy: policy.clone(), ..GameState::default() };
+        assert_eq!(GameState::from_json(&state.to_json().unwrap()).unwrap().policy, policy);
+    }
+
+    #[test]
+    fn test_snapshot_shares_until_written() {
+        use crate::types::entity::Entity;
+
+        let mut state = GameState::default();
+        state.entities.push(Entity::new_worker("w1".to_string(), "origin".to_string()));
+        let snapshot = state.clone();
+        assert!(Shared::ptr_eq(&state.entities, &snapshot.entities));
+
+        TickEngine::new(1).tick(&mut state);
+
+        // Entities were written and copied; the untouched map is still shared
+        assert!(!Shared::ptr_eq(&state.entities, &snapshot.entities));
+        assert!(Shared::ptr_eq(&state.map, &snapshot.map));
+        assert_eq!(snapshot.entities[0].age, 0);
+        assert_eq!(state.entities[0].age, 1);
+    }
+
+    #[test]
+    fn test_absorb_renames_and_sums() {
+        use crate::types::entity::Entity;
+        use crate::types::graveyard::Corpse;
+        use crate::types::entity::DeathCause;
+
+        let mut home = GameState::default();
+        home.resources.set("fungus", 10.0);
+        home.entities.push(Entity::new_worker("a1".to_string(), "origin".to_string()));
+
+        let mut nest = GameState::default();
+        nest.resources.set("fungus", 5.0);
+        nest.resources.set("ore", 2.0);
+        nest.stockpiles.entry("far".to_string()).or_default().set("ore", 1.0);
+        nest.entities.push(Entity::new_worker("a1".to_string(), "far_tunnel".to_string()));
+        nest.entities.push(Entity::new_wanderer("v1".to_string()));
+        nest.graveyard.add_corpse(Corpse {
+            entity_id: "a0".to_string(),
+            entity_type: "ant".to_string(),
+            death_tick: 3,
+            cause: DeathCause::OldAge,
+            tile: "origin".to_string(),
+        });
+
+        let policy = AbsorbPolicy { entities: EntitySelection::Ants, ..Default::default() };
+        let events = home.absorb(nest.clone(), &policy);
+
+        assert_eq!(home.resources.get("fungus"), 15.0);
+        assert_eq!(home.resources.get("ore"), 3.0);
+        assert_eq!(home.entities.len(), 2, "The visitor stays behind");
+        assert_eq!(home.entities[1].id, "a1~2");
+        assert_eq!(home.entities[1].tile, "origin");
+        assert_eq!(home.graveyard.corpses.len(), 1);
+
+        match &events.events()[0].kind {
+            EventKind::ColonyAbsorbed { entities, renamed, corpses, .. } => {
+                assert_eq!(entities, &vec!["a1~2".to_string()]);
+                assert_eq!(renamed.get("a1").map(String::as_str), Some("a1~2"));
+                assert_eq!(*corpses, 1);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        // Skipping leaves conflicting newcomers behind
+        let mut home2 = GameState::default();
+        home2.entities.push(Entity::new_worker("a1".to_string(), "origin".to_string()));
+        let policy = AbsorbPolicy {
+            id_conflict: IdConflict::Skip,
+            resources: ResourceMerge::Ignore,
+            graveyard: false,
+            ..Default::default()
+        };
+        home2.absorb(nest, &policy);
+        let ids: Vec<&str> = home2.entities.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["a1", "v1"]);
+        assert_eq!(home2.resources.get("ore"), 0.0);
+    }
+
+    #[test]
+    fn test_demographics() {
+        use crate::types::entity::Entity;
+
+        let mut state = GameState::default();
+        state.resources.set("fungus", 10.0);
+
+        let mut elder = Entity::new_worker("w1".to_string(), "origin".to_string());
+        elder.age = elder.max_age - 10;
+        elder.hunger = 60.0;
+        state.entities.push(elder);
+        let mut young = Entity::new_worker("w2".to_string(), "origin".to_string());
+        young.hunger = 80.0;
+        state.entities.push(young);
+        state.entities.push(Entity::new_undertaker("u1".to_string(), "origin".to_string()));
+        let mut visitor = Entity::new_hungry("v1".to_string());
+        visitor.hunger = 1.0;
+        state.entities.push(visitor);
+
+        let report = state.demographics(100);
+        assert_eq!(report.bucket_ticks, constants::DEMOGRAPHIC_BUCKET_TICKS);
+        assert_eq!(report.pyramid["worker"], vec![1, 0, 0, 1]);
+        assert_eq!(report.population["undertaker"], 1);
+        assert_eq!(report.average_hunger["worker"], 70.0);
+        assert_eq!(report.overall_average_hunger, (60.0 + 80.0 + 100.0 + 1.0) / 4.0);
+        assert_eq!(report.expected_deaths, crate::types::demographics::ExpectedDeaths {
+            old_age: 1,
+            starvation: 1,
+            departures: 0,
+        });
+    }
+
+    #[test]
+    fn test_travel_ticks() {
+        let mut state = GameState { ticks_per_second: 2.0, ..Default::default() };
+        state.map.tiles.insert(Key::new("origin"), Tile::new_empty("Origin".to_string(), 0, 0));
+        state.map.add_tile("hall", Tile::new_empty("Hall".to_string(), 1, 0), "origin").unwrap();
+        state.map.add_tile("den", Tile::new_empty("Den".to_string(), 2, 0), "hall").unwrap();
+        state.map.tiles.insert(Key::new("island"), Tile::new_empty("Island".to_string(), 9, 9));
+
+        assert_eq!(state.travel_ticks("origin", "den"), Some(2 * 2 * constants::STEP_SECONDS));
+        assert_eq!(state.travel_ticks("den", "den"), Some(0));
+        assert_eq!(state.travel_ticks("origin", "island"), None);
+    }
+
+    #[test]
+    fn test_summary() {
+        use crate::types::entity::Entity;
+        use crate::types::summary::Disaster;
+
+        let mut state = GameState::default();
+        for (resource, amount) in [("fungus", 50.0), ("dirt", 80.0), ("ore", 50.0), ("insight", 1.0), ("crystals", 3.0), ("nutrients", 2.0)] {
+            state.resources.set(resource, amount);
+        }
+        state.entities.push(Entity::new_worker("w1".to_string(), "origin".to_string()));
+        state.entities.push(Entity::new_worker("w2".to_string(), "origin".to_string()));
+        let mut observer = Entity::new_observer("v_1".to_string());
+        observer.age = 600;
+        state.entities.push(observer);
+        let mut heap = Tile::new_compost("Heap".to_string(), 1, 0);
+        heap.start_blight(40);
+        state.map.tiles.insert(Key::new("heap"), heap);
+        state.meta.receiver_silent = true;
+
+        let summary = state.summary();
+        assert_eq!(summary.population["worker"], 2);
+        assert_eq!(summary.population["visitor"], 1);
+        let top: Vec<_> = summary.top_resources.iter().map(|(r, _)| r.as_str()).collect();
+        assert_eq!(top, ["dirt", "fungus", "ore", "crystals", "nutrients"]);
+        assert_eq!(summary.disasters, vec![
+            Disaster::Blight { tile: Key::new("heap"), ticks_remaining: 40 },
+            Disaster::ReceiverSilent { since: None, stage: None },
+        ]);
+        assert_eq!(summary.visitors[0].ticks_left, 3000);
+        assert_eq!(summary.visitors[0].name, state.entities[2].name);
+
+        let json = serde_json::to_string(&summary).unwrap();
+        assert!(json.len() < state.to_json().unwrap().len());
+    }
+
+    #[test]
+    fn test_visitor_weights_follow_scarcity() {
+        let mut state = GameState::default();
+        state.resources.set("fungus", 100.0);
+        state.resources.set("crystals", 100.0);
+        let neutral = state.visitor_weights();
+        assert_eq!(neutral, state.outside_conditions().visitor_weights);
+
+        state.resources.set("fungus", 0.0);
+        state.resources.set("crystals", 10_000.0);
+        let lean = state.visitor_weights();
+        assert_eq!(lean.hungry, neutral.hungry * 2.0);
+        assert_eq!(lean.observer, neutral.observer * 2.0);
+        assert_eq!(lean.wanderer, neutral.wanderer);
+    }
+
+    #[test]
+    fn test_total_resources() {
+        let mut state = GameState::default();
+        state.resources.set("dirt", 5.0);
+        state.stockpiles.entry("dig".to_string()).or_default().set("dirt", 2.5);
+        state.stockpiles.entry("heap".to_string()).or_default().set("nutrients", 1.0);
+
+        let total = state.total_resources();
+        assert_eq!(total.get("dirt"), 7.5);
+        assert_eq!(total.get("nutrients"), 1.0);
+        assert_eq!(state.resources.get("nutrients"), 0.0);
+    }
+
+    #[test]
+    fn test_missing_stockpiles_mean_global_pool() {
+        let json = GameState::default().to_json().unwrap();
+        assert!(!json.contains("stockpiles"));
+        assert!(!json.contains("logistics"));
+
+        let restored = GameState::from_json(&json).unwrap();
+        assert!(restored.logistics.is_global());
+        assert!(restored.stockpiles.is_empty());
+    }
+
+    #[test]
+    fn test_upcoming_matches_actual_ticks() {
+        use crate::events::EventKind;
+        use crate::types::action::Action;
+
+        let mut engine = TickEngine::new(1);
+        let mut state = GameState::default();
+
+        let mut visitor = Entity::new_wanderer("v_1".to_string());
+        visitor.age = visitor.max_age - 4;
+        state.entities.push(visitor);
+        state.queues.enqueue_action(Action {
+            id: "a1".to_string(),
+            action_type: "dig".to_string(),
+            ticks_remaining: 7,
+            target: None,
+            effects: None,
+        });
+
+        let upcoming = state.upcoming(&engine);
+        assert_eq!(upcoming.len(), 2);
+        assert!(matches!(upcoming[0].kind, ScheduledKind::VisitorDeparture { .. }));
+        assert_eq!(upcoming[0].tick, 4);
+        assert_eq!(upcoming[1].tick, 7);
+
+        let mut departed_at = None;
+        let mut completed_at = None;
+        for _ in 0..10 {
+            for event in engine.tick(&mut state).into_events() {
+                match event.kind {
+                    EventKind::VisitorDeparted { .. } => departed_at = Some(event.tick),
+                    EventKind::ActionComplete { .. } => completed_at = Some(event.tick),
+                    _ => {}
+                }
+            }
+        }
+
+        assert_eq!(departed_at, Some(4));
+        assert_eq!(completed_at, Some(7));
+    }
+
+    #[test]
+    fn test_hash_ignores_host_bookkeeping() {
+        let mut state = GameState::default();
+        state.resources.set("fungus", 10.0);
+        let hash = state.hash();
+
+        let mut noisy = state.clone();
+        noisy.last_save_timestamp = Some(99.0);
+        noisy.meta.decor.push(serde_json::json!({"name": "pebble"}));
+        assert_eq!(noisy.hash(), hash);
+
+        state.resources.set("fungus", 10.5);
+        assert_ne!(state.hash(), hash);
+    }
+
+    #[test]
+    fn test_warnings() {
+        let mut engine = TickEngine::new(1);
+        let mut state = GameState::default();
+        state.entities.push(Entity::new_worker("w".to_string(), "origin".to_string()));
+        state.resources.set("fungus", 0.5);
+        state.map.tiles.insert(Key::new("compost"), Tile::new_compost("compost".to_string(), 0, 1));
+        state.map.get_tile_mut("compost").unwrap().add_contamination(0.02);
+
+        let warnings = state.warnings();
+        // Half a fungus at 0.1 hunger per tick lasts 150 ticks
+        assert!(matches!(&warnings[0],
+            Warning::LowFoodRunway { food, seconds_left, .. }
+                if food.as_str() == "fungus" && (*seconds_left - 150.0).abs() < 1e-6));
+        assert!(matches!(warnings[1], Warning::BlightRisk { chance, .. } if chance >= 0.02));
+        assert_eq!(warnings[2], Warning::NoUndertakers { corpses: 0 });
+        assert_eq!(engine.tick(&mut state).warnings(), state.warnings().as_slice());
+
+        // Plenty of food and an undertaker quiet those two down
+        state.resources.set("fungus", 1000.0);
+        state.entities.push(Entity::new_undertaker("u".to_string(), "origin".to_string()));
+        state.map.get_tile_mut("compost").unwrap().contamination = Some(0.0);
+        assert!(state.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_health() {
+        let mut engine = TickEngine::new(1);
+        let mut state = GameState::default();
+        state.entities.push(Entity::new_worker("w".to_string(), "origin".to_string()));
+        state.resources.set("fungus", 0.5);
+
+        // 150 seconds of food out of an hour; no brood; everything else fine
+        let health = state.health();
+        assert!((health.parts.food - 150.0 / 3600.0).abs() < 1e-9);
+        assert_eq!(health.parts.population, 0.5);
+        assert_eq!((health.parts.contamination, health.parts.sanity, health.parts.maintenance), (1.0, 1.0, 1.0));
+        assert!((health.score - 61.25).abs() < 1e-9);
+
+        // The first score is reported, then only big moves
+        let reported = |engine: &mut TickEngine, state: &mut GameState| {
+            engine.tick(state).events().iter().find_map(|event| match event.kind {
+                EventKind::HealthChanged { score, previous } => Some((score, previous)),
+                _ => None,
+            })
+        };
+        let (first, previous) = reported(&mut engine, &mut state).unwrap();
+        assert_eq!(previous, None);
+        assert_eq!(reported(&mut engine, &mut state), None);
+
+        state.resources.set("fungus", 1000.0);
+        let (score, previous) = reported(&mut engine, &mut state).unwrap();
+        assert_eq!(previous, Some(first));
+        assert!((score - 90.0).abs() < 1e-9);
+        assert_eq!(state.reported_health, Some(score));
     }
 }
@@ -0,0 +1,91 @@
+//! State summaries: the header bar of a colony without the whole save.
+//!
+//! A frontend reconnecting mid-run asks for `GameState::summary()` instead
+//! of parsing the full state just to show who's alive, what's in the stores
+//! and what's going wrong. Everything here is derived from the state; the
+//! summary is never saved.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::entity::{EntityId, VisitorType};
+use super::key::Key;
+use super::silence::SilenceStage;
+use super::warning::Warning;
+
+/// A compact digest of a colony at one tick
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    pub tick: u64,
+
+    /// Cohort ("worker", "undertaker", "hauler", "forager", "soldier", "nurse",
+    /// "visitor") -> head count
+    pub population: BTreeMap<String, u64>,
+
+    /// The largest stores, biggest first (ties by name)
+    pub top_resources: Vec<(Key, f64)>,
+
+    pub sanity: f64,
+
+    /// Trouble happening right now
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disasters: Vec<Disaster>,
+
+    /// Trouble on its way (see `GameState::warnings`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<Warning>,
+
+    /// Visitors in the colony, in arrival order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub visitors: Vec<VisitorSummary>,
+
+    pub counters: SummaryCounters,
+}
+
+/// A disaster in progress
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Disaster {
+    /// A compost tile is blighted
+    Blight { tile: Key, ticks_remaining: u64 },
+
+    /// The receiver has gone quiet (see `GameState::receiver_silence`)
+    ReceiverSilent {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        since: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stage: Option<SilenceStage>,
+    },
+}
+
+/// One visitor, as a header bar shows it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VisitorSummary {
+    pub id: EntityId,
+    pub subtype: Option<VisitorType>,
+    pub name: Option<String>,
+
+    /// Ticks until it leaves
+    pub ticks_left: u64,
+}
+
+/// Running tallies worth a glance
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SummaryCounters {
+    /// Dead waiting for an undertaker
+    pub corpses: u64,
+
+    /// Dead the undertakers have carried away, ever
+    pub processed: u64,
+
+    pub brood: u64,
+    pub expeditions_away: u64,
+    pub outposts: u64,
+    pub groups: u64,
+    pub wonders_seen: u64,
+
+    /// Summon attempts in the receiver's recent log, and how many got an
+    /// answer
+    pub recent_summons: u64,
+    pub recent_answers: u64,
+}
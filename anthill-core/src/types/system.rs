@@ -1,7 +1,10 @@
 //! Production system types.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::det;
+use super::key::Key;
 
 /// Type of production system
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -12,6 +15,8 @@ pub enum SystemType {
     Spawner,
     Crafting,
     Antenna,
+    /// Converter with diminishing returns per window
+    Shrine,
 }
 
 /// A boost from processed corpses
@@ -20,10 +25,33 @@ pub struct CorpseBoost {
     /// When this boost expires
     pub expires_at_tick: u64,
 
-    /// Bonus nutrients per tick
+    /// Bonus per tick to the system's `organic_boost` resource
     pub bonus: f64,
 }
 
+/// Volume converted by a shrine within its current window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionWindow {
+    /// Tick the window opened
+    pub started_at_tick: u64,
+
+    /// Total input consumed since the window opened
+    pub volume: f64,
+}
+
+/// Why a system's configuration can't be right
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Suspicion {
+    /// Makes nothing but some of what it eats, and less of that than it
+    /// eats: running it only ever drains `resource`
+    NetDrain { resource: String, consumes: f64, generates: f64 },
+
+    /// Consumes something that exists nowhere (no stock, no producer, no
+    /// resource tile, no price)
+    UnknownResource { resource: String },
+}
+
 /// A production system in the colony
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct System {
@@ -34,11 +62,11 @@ pub struct System {
     #[serde(rename = "type")]
     pub system_type: SystemType,
 
-    /// Resources generated per tick
+    /// Resources generated per second
     #[serde(skip_serializing_if = "Option::is_none")]
     pub generates: Option<HashMap<String, f64>>,
 
-    /// Resources consumed per tick
+    /// Resources consumed per second
     #[serde(skip_serializing_if = "Option::is_none")]
     pub consumes: Option<HashMap<String, f64>>,
 
@@ -46,7 +74,12 @@ pub struct System {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 
-    /// Active corpse boosts (for compost heap)
+    /// Resource that processed corpses amplify; systems without one never
+    /// receive corpse boosts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub organic_boost: Option<Key>,
+
+    /// Active corpse boosts (systems with `organic_boost` only)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub corpse_boosts: Vec<CorpseBoost>,
 
@@ -57,6 +90,28 @@ pub struct System {
     /// Original consumes (stored during blight)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub original_consumes: Option<HashMap<String, f64>>,
+
+    /// Conversion window (for shrines)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversion_window: Option<ConversionWindow>,
+
+    /// Tile the system sits on (uses that tile's stockpile in local logistics)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tile: Option<String>,
+
+    /// Tick since which the system has lacked its inputs (None = running)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub starved_since: Option<u64>,
+
+    /// Total output on each of the last `production_history` ticks it was
+    /// enabled, oldest first (0 while starved)
+    #[serde(default, skip_serializing_if = "VecDeque::is_empty")]
+    pub history: VecDeque<f64>,
+
+    /// Set when the engine finds the system's rates impossible; it sits
+    /// out until they're fixed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suspect: Option<Suspicion>,
 }
 
 impl System {
@@ -68,9 +123,15 @@ impl System {
             generates: Some(generates),
             consumes: None,
             description: None,
+            organic_boost: None,
             corpse_boosts: Vec::new(),
             original_generates: None,
             original_consumes: None,
+            conversion_window: None,
+            tile: None,
+            starved_since: None,
+            history: VecDeque::new(),
+            suspect: None,
         }
     }
 
@@ -86,12 +147,51 @@ impl System {
             generates: Some(generates),
             consumes: Some(consumes),
             description: None,
+            organic_boost: None,
             corpse_boosts: Vec::new(),
             original_generates: None,
             original_consumes: None,
+            conversion_window: None,
+            tile: None,
+            starved_since: None,
+            history: VecDeque::new(),
+            suspect: None,
+        }
+    }
+
+    /// Accept corpse boosts, which add to the output of `resource`
+    pub fn with_organic_boost(mut self, resource: &str) -> Self {
+        self.organic_boost = Some(Key::new(resource));
+        self
+    }
+
+    /// Create a shrine (a converter whose output diminishes with volume)
+    pub fn new_shrine(
+        name: String,
+        consumes: HashMap<String, f64>,
+        generates: HashMap<String, f64>,
+    ) -> Self {
+        Self {
+            system_type: SystemType::Shrine,
+            ..Self::new_converter(name, consumes, generates)
+        }
+    }
+
+    /// Note this tick's output, keeping the last `retention` ticks
+    pub fn record_output(&mut self, output: f64, retention: usize) {
+        self.history.push_back(output);
+        while self.history.len() > retention {
+            self.history.pop_front();
         }
     }
 
+    /// Share of the ticks in its history the system turned anything out
+    /// (None before it has any)
+    pub fn uptime(&self) -> Option<f64> {
+        let producing = self.history.iter().filter(|output| **output > 0.0).count();
+        (!self.history.is_empty()).then(|| producing as f64 / self.history.len() as f64)
+    }
+
     /// Check if system can run (has required resources)
     pub fn can_run(&self, resources: &super::resource::Resources) -> bool {
         if let Some(consumes) = &self.consumes {
@@ -101,6 +201,26 @@ impl System {
         }
     }
 
+    /// What's wrong with the system's declared rates, if anything, given
+    /// every resource that's `known` to exist. Only the first problem
+    /// found is named.
+    pub fn suspicion(&self, known: &BTreeSet<&str>) -> Option<Suspicion> {
+        let consumes = self.consumes.as_ref()?;
+        let consumed = det::entries(consumes.iter());
+        if let Some((resource, _)) = consumed.iter().find(|(resource, _)| !known.contains(resource.as_str())) {
+            return Some(Suspicion::UnknownResource { resource: resource.to_string() });
+        }
+
+        let generates = self.generates.as_ref()?;
+        if generates.is_empty() || generates.keys().any(|resource| !consumes.contains_key(resource)) {
+            return None;
+        }
+        consumed.into_iter().find_map(|(resource, consumes)| {
+            let generates = generates.get(resource).copied().unwrap_or(0.0);
+            (generates < *consumes).then(|| Suspicion::NetDrain { resource: resource.clone(), consumes: *consumes, generates })
+        })
+    }
+
     /// Get the total corpse boost bonus
     pub fn total_corpse_bonus(&self, current_tick: u64) -> f64 {
         self.corpse_boosts
@@ -137,4 +257,83 @@ impl System {
     pub fn is_disabled(&self) -> bool {
         self.original_generates.is_some()
     }
+
+    /// Volume converted in the window that is open at `current_tick`
+    pub fn window_volume(&self, current_tick: u64, window_ticks: u64) -> f64 {
+        match &self.conversion_window {
+            Some(w) if current_tick < w.started_at_tick + window_ticks => w.volume,
+            _ => 0.0,
+        }
+    }
+
+    /// Record converted volume, opening a new window if the old one expired
+    pub fn record_conversion(&mut self, current_tick: u64, window_ticks: u64, volume: f64) {
+        match &mut self.conversion_window {
+            Some(w) if current_tick < w.started_at_tick + window_ticks => w.volume += volume,
+            _ => {
+                self.conversion_window = Some(ConversionWindow {
+                    started_at_tick: current_tick,
+                    volume,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion_window_resets() {
+        let mut shrine = System::new_shrine("Shrine".to_string(), HashMap::new(), HashMap::new());
+
+        shrine.record_conversion(10, 100, 1.0);
+        shrine.record_conversion(50, 100, 1.0);
+        assert_eq!(shrine.window_volume(60, 100), 2.0);
+
+        // Window expired: volume reads as zero and a new window opens
+        assert_eq!(shrine.window_volume(110, 100), 0.0);
+        shrine.record_conversion(110, 100, 0.5);
+        assert_eq!(shrine.window_volume(110, 100), 0.5);
+    }
+
+    #[test]
+    fn test_history_keeps_the_last_ticks() {
+        let mut dig = System::new_generator("Dig".to_string(), HashMap::new());
+        assert_eq!(dig.uptime(), None);
+        for output in [1.0, 0.0, 2.0, 3.0] {
+            dig.record_output(output, 3);
+        }
+        assert_eq!(dig.history, [0.0, 2.0, 3.0]);
+        assert_eq!(dig.uptime(), Some(2.0 / 3.0));
+
+        // Shrinking the retention trims on the next tick; none keeps nothing
+        dig.record_output(4.0, 0);
+        assert!(dig.history.is_empty());
+    }
+
+    #[test]
+    fn test_suspicion_names_impossible_rates() {
+        let known = BTreeSet::from(["fungus", "ore"]);
+        let rates = |pairs: &[(&str, f64)]| pairs.iter().map(|(r, a)| (r.to_string(), *a)).collect::<HashMap<_, _>>();
+        let converter = |consumes: &[(&str, f64)], generates: &[(&str, f64)]| {
+            System::new_converter("C".to_string(), rates(consumes), rates(generates))
+        };
+
+        assert_eq!(
+            converter(&[("fungus", 2.0)], &[("fungus", 1.0)]).suspicion(&known),
+            Some(Suspicion::NetDrain { resource: "fungus".to_string(), consumes: 2.0, generates: 1.0 })
+        );
+        assert_eq!(
+            converter(&[("ectoplasm", 1.0)], &[("ore", 1.0)]).suspicion(&known),
+            Some(Suspicion::UnknownResource { resource: "ectoplasm".to_string() })
+        );
+
+        // A partial refund alongside real output, a net gain, and a plain
+        // generator are all fine
+        assert_eq!(converter(&[("fungus", 2.0)], &[("fungus", 1.0), ("ore", 1.0)]).suspicion(&known), None);
+        assert_eq!(converter(&[("fungus", 1.0)], &[("fungus", 2.0)]).suspicion(&known), None);
+        assert_eq!(System::new_generator("G".to_string(), rates(&[("ore", 1.0)])).suspicion(&known), None);
+    }
 }
@@ -0,0 +1,79 @@
+//! Threats: what comes for a colony worth raiding.
+//!
+//! Incursions arrive on their own RNG stream, more often and harder the
+//! more the colony's stores are worth (see `GameState::wealth`). Every
+//! waking ant stands against one, soldiers far better than the rest; the
+//! engine reports how it went as `DefenseResolved`. Raiders that win carry
+//! off a share of the stores, predators that win take ants.
+
+use serde::{Deserialize, Serialize};
+
+/// What came for the colony
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Threat {
+    /// Rival ants after the stores
+    Raiders,
+    /// Something hungry after the ants themselves
+    Predators,
+}
+
+impl Threat {
+    pub const ALL: [Threat; 2] = [Threat::Raiders, Threat::Predators];
+
+    /// Does a win for this threat cost the colony ants (besides fallen
+    /// defenders), rather than stores?
+    pub fn takes_ants(self) -> bool {
+        self == Threat::Predators
+    }
+}
+
+/// A defense's odds: each side's strength, and what it means for the
+/// defenders
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Odds {
+    pub strength: f64,
+    pub defense: f64,
+}
+
+impl Odds {
+    /// The colony holds if it stands at least as strong as the incursion
+    pub fn repelled(&self) -> bool {
+        self.defense >= self.strength
+    }
+
+    /// Share of the fight going the incursion's way, 0..=1 (0.5 at even odds)
+    pub fn pressure(&self) -> f64 {
+        let total = self.strength + self.defense;
+        if total > 0.0 { self.strength / total } else { 0.0 }
+    }
+
+    /// How far short the defense fell, 0..=1 (0 when repelled)
+    pub fn shortfall(&self) -> f64 {
+        if self.repelled() || self.strength <= 0.0 {
+            0.0
+        } else {
+            1.0 - self.defense / self.strength
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_odds() {
+        let even = Odds { strength: 2.0, defense: 2.0 };
+        assert!(even.repelled());
+        assert_eq!(even.pressure(), 0.5);
+        assert_eq!(even.shortfall(), 0.0);
+
+        let routed = Odds { strength: 4.0, defense: 1.0 };
+        assert!(!routed.repelled());
+        assert_eq!(routed.pressure(), 0.8);
+        assert_eq!(routed.shortfall(), 0.75);
+
+        assert_eq!(serde_json::to_string(&Threat::Predators).unwrap(), r#""predators""#);
+    }
+}
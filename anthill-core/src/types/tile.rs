@@ -1,7 +1,23 @@
 //! Map tile types.
+//!
+//! The map is tiles joined by bidirectional connections, grown outward from
+//! the origin. Edits from outside the tick go through the `GameMap` editing
+//! methods (via commands), which refuse anything that would cut a tile off
+//! from the origin.
+//!
+//! Shortest paths are cached on the map and keyed by its `version`, which
+//! every edit bumps, so a crowd walking to the same tile shares one search.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::{Arc, Mutex, PoisonError};
+use thiserror::Error;
+
+use super::hazard::Struck;
+use super::key::Key;
+
+/// ID of the tile every colony starts on
+pub const ORIGIN: &str = "origin";
 
 /// Type of map tile
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -15,6 +31,29 @@ pub enum TileType {
     Special,
     Aesthetic,
     Antenna,
+    /// What's left of a past run's colony
+    Ruin,
+}
+
+/// What has happened on a tile over the colony's life, kept by the engine
+/// so heatmaps and reflections don't need the event history
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TileActivity {
+    /// Entity-ticks spent on the tile
+    #[serde(default)]
+    pub visits: u64,
+
+    /// Ants that died there
+    #[serde(default)]
+    pub deaths: u64,
+
+    /// Meals eaten there
+    #[serde(default)]
+    pub meals: u64,
+
+    /// Of `deaths`, how many the ants there have mourned (see `morale`)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub mourned: u64,
 }
 
 /// A tile on the map
@@ -45,13 +84,37 @@ pub struct Tile {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub blight_ticks_remaining: Option<u64>,
 
+    /// Systems the blight stopped, to start again when it clears (None in
+    /// older saves: the compost heap)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blight_stopped: Option<Vec<String>>,
+
     /// Resource type for resource tiles
     #[serde(skip_serializing_if = "Option::is_none")]
     pub resource: Option<String>,
 
+    /// How much of `resource` is left to harvest. None means it never runs
+    /// out: only tiles built that way on purpose (never through
+    /// `new_resource`) have no stock, and saves from before stocks get one
+    /// when they load
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stock: Option<f64>,
+
     /// Description
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+
+    /// Height above the surface (None: at it); tiles below it can flood
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elevation: Option<i32>,
+
+    /// Tick the tile was dug, for tiles added through the engine
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dug_at: Option<u64>,
+
+    /// A flood or cave-in holding the tile (see `hazard`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hazard: Option<Struck>,
 }
 
 impl Tile {
@@ -65,8 +128,23 @@ impl Tile {
             contamination: None,
             blighted: None,
             blight_ticks_remaining: None,
+            blight_stopped: None,
             resource: None,
+            stock: None,
             description: None,
+            elevation: None,
+            dug_at: None,
+            hazard: None,
+        }
+    }
+
+    /// Create a resource tile holding `stock` of `resource`
+    pub fn new_resource(name: String, x: i32, y: i32, resource: &str, stock: f64) -> Self {
+        Self {
+            tile_type: TileType::Resource,
+            resource: Some(resource.to_string()),
+            stock: Some(stock),
+            ..Self::new_empty(name, x, y)
         }
     }
 
@@ -85,11 +163,43 @@ impl Tile {
             contamination: Some(0.0),
             blighted: Some(false),
             blight_ticks_remaining: Some(0),
+            blight_stopped: None,
             resource: None,
+            stock: None,
             description: None,
+            elevation: None,
+            dug_at: None,
+            hazard: None,
         }
     }
 
+    /// A resource tile with something left to harvest
+    pub fn is_harvestable(&self) -> bool {
+        self.tile_type == TileType::Resource && self.resource.is_some() && !self.is_depleted()
+    }
+
+    /// Has this tile's stock run out?
+    pub fn is_depleted(&self) -> bool {
+        self.stock.is_some_and(|stock| stock <= 0.0)
+    }
+
+    /// Take up to `amount` from the stock, returning what was taken
+    pub fn harvest(&mut self, amount: f64) -> f64 {
+        match self.stock {
+            None => amount,
+            Some(stock) => {
+                let taken = amount.min(stock.max(0.0));
+                self.stock = Some(stock - taken);
+                taken
+            }
+        }
+    }
+
+    /// Is the tile below the surface?
+    pub fn is_low(&self) -> bool {
+        self.elevation.is_some_and(|elevation| elevation < 0)
+    }
+
     /// Check if tile is blighted
     pub fn is_blighted(&self) -> bool {
         self.blighted.unwrap_or(false)
@@ -130,20 +240,51 @@ impl Tile {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameMap {
     /// All tiles by ID
-    pub tiles: HashMap<String, Tile>,
+    pub tiles: HashMap<Key, Tile>,
 
     /// Connections between tiles (bidirectional)
-    pub connections: Vec<(String, String)>,
+    pub connections: Vec<(Key, Key)>,
+
+    /// Bumped by every edit; cached paths from older versions are dropped
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub version: u64,
+
+    #[serde(skip)]
+    paths: PathCache,
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+/// Paths already found by `GameMap::path`. Never saved; a cloned map
+/// starts with an empty cache.
+#[derive(Debug, Default)]
+struct PathCache(Mutex<CachedPaths>);
+
+#[derive(Debug, Default)]
+struct CachedPaths {
+    /// Map version the paths were found on
+    version: u64,
+    paths: HashMap<(Key, Key), Option<Arc<[Key]>>>,
+}
+
+impl Clone for PathCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
 }
 
 impl Default for GameMap {
     fn default() -> Self {
         let mut tiles = HashMap::new();
-        tiles.insert("origin".to_string(), Tile::origin());
+        tiles.insert(Key::new(ORIGIN), Tile::origin());
 
         Self {
             tiles,
             connections: Vec::new(),
+            version: 0,
+            paths: PathCache::default(),
         }
     }
 }
@@ -162,17 +303,24 @@ impl GameMap {
     /// Check if two tiles are connected
     pub fn are_connected(&self, a: &str, b: &str) -> bool {
         self.connections.iter().any(|(x, y)| {
-            (x == a && y == b) || (x == b && y == a)
+            (*x == a && *y == b) || (*x == b && *y == a)
         })
     }
 
+    /// Manhattan distance between two tiles' coordinates
+    pub fn grid_distance(&self, a: &str, b: &str) -> Option<u64> {
+        let a = self.get_tile(a)?;
+        let b = self.get_tile(b)?;
+        Some(((a.x - b.x).unsigned_abs() + (a.y - b.y).unsigned_abs()) as u64)
+    }
+
     /// Get all tiles connected to a given tile
     pub fn neighbors(&self, tile_id: &str) -> Vec<&str> {
         self.connections.iter()
             .filter_map(|(a, b)| {
-                if a == tile_id {
+                if *a == tile_id {
                     Some(b.as_str())
-                } else if b == tile_id {
+                } else if *b == tile_id {
                     Some(a.as_str())
                 } else {
                     None
@@ -180,4 +328,253 @@ impl GameMap {
             })
             .collect()
     }
+
+    /// Note an edit to `tiles` or `connections`, so paths found before it
+    /// aren't reused. The editing methods below do this themselves.
+    pub fn changed(&mut self) {
+        self.version += 1;
+    }
+
+    /// The tiles to walk through from `from` to reach `to` (ending with
+    /// `to`; empty if already there), or None if there's no way. Cached
+    /// until the map next changes.
+    pub fn path(&self, from: &str, to: &str) -> Option<Arc<[Key]>> {
+        let mut cache = self.paths.0.lock().unwrap_or_else(PoisonError::into_inner);
+        if cache.version != self.version {
+            cache.paths.clear();
+            cache.version = self.version;
+        }
+        cache.paths
+            .entry((Key::new(from), Key::new(to)))
+            .or_insert_with(|| self.shortest_path(from, to).map(Arc::from))
+            .clone()
+    }
+
+    /// Breadth-first search from `from` to `to`, uncached. Ties go to the
+    /// connection listed first, so every host finds the same path.
+    pub fn shortest_path(&self, from: &str, to: &str) -> Option<Vec<Key>> {
+        let (from, _) = self.tiles.get_key_value(from)?;
+        if !self.tiles.contains_key(to) {
+            return None;
+        }
+        let mut adjacent: HashMap<&Key, Vec<&Key>> = HashMap::new();
+        for (a, b) in &self.connections {
+            adjacent.entry(a).or_default().push(b);
+            adjacent.entry(b).or_default().push(a);
+        }
+
+        let mut came_from: HashMap<&Key, &Key> = HashMap::from([(from, from)]);
+        let mut frontier = VecDeque::from([from]);
+        while let Some(tile) = frontier.pop_front() {
+            if *tile == to {
+                let mut path = Vec::new();
+                let mut step = tile;
+                while step != from {
+                    path.push(*step);
+                    step = came_from[step];
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for &next in adjacent.get(tile).into_iter().flatten() {
+                if self.tiles.contains_key(next) && !came_from.contains_key(next) {
+                    came_from.insert(next, tile);
+                    frontier.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Tiles next to `from` that start a shortest way to `to`, in the
+    /// order `neighbors` lists them
+    pub fn steps_toward(&self, from: &str, to: &str) -> Vec<Key> {
+        let Some(distance) = self.distance(from, to).filter(|d| *d > 0) else {
+            return Vec::new();
+        };
+        self.neighbors(from).into_iter()
+            .filter(|next| self.distance(next, to) == Some(distance - 1))
+            .map(Key::new)
+            .collect()
+    }
+
+    /// Connections to walk from `a` to `b` (0 if they're the same tile), or
+    /// None if there's no way
+    pub fn distance(&self, a: &str, b: &str) -> Option<usize> {
+        self.path(a, b).map(|path| path.len())
+    }
+
+    /// Tiles reachable from `start` over connections, `start` included
+    pub fn reachable_from(&self, start: &str) -> BTreeSet<&str> {
+        let mut reached = BTreeSet::new();
+        let Some((start, _)) = self.tiles.get_key_value(start) else {
+            return reached;
+        };
+        let mut frontier = vec![start.as_str()];
+        reached.insert(start.as_str());
+        while let Some(tile) = frontier.pop() {
+            for next in self.neighbors(tile) {
+                if self.tiles.contains_key(next) && reached.insert(next) {
+                    frontier.push(next);
+                }
+            }
+        }
+        reached
+    }
+
+    /// Add `tile` under `id`, connected to `connect_to`, which must itself
+    /// be reachable from the origin
+    pub fn add_tile(&mut self, id: &str, tile: Tile, connect_to: &str) -> Result<(), MapError> {
+        if self.tiles.contains_key(id) {
+            return Err(MapError::TileExists(id.to_string()));
+        }
+        self.check_reachable(connect_to)?;
+        self.tiles.insert(Key::new(id), tile);
+        self.connections.push((Key::new(connect_to), Key::new(id)));
+        self.changed();
+        Ok(())
+    }
+
+    /// Connect two existing tiles. New paths can't strand anything, but
+    /// one of the two must already be reachable from the origin.
+    pub fn connect(&mut self, a: &str, b: &str) -> Result<(), MapError> {
+        self.check_pair(a, b)?;
+        if self.are_connected(a, b) {
+            return Err(MapError::AlreadyConnected(a.to_string(), b.to_string()));
+        }
+        if self.check_reachable(a).is_err() {
+            self.check_reachable(b)?;
+        }
+        self.connections.push((Key::new(a), Key::new(b)));
+        self.changed();
+        Ok(())
+    }
+
+    /// Remove the connection between `a` and `b`, unless that would cut a
+    /// tile off from the origin
+    pub fn disconnect(&mut self, a: &str, b: &str) -> Result<(), MapError> {
+        self.check_pair(a, b)?;
+        if !self.are_connected(a, b) {
+            return Err(MapError::NotConnected(a.to_string(), b.to_string()));
+        }
+        let mut after = self.clone();
+        after.connections.retain(|(x, y)| !((*x == a && *y == b) || (*x == b && *y == a)));
+        self.check_still_reachable(&after, None)?;
+        *self = after;
+        self.changed();
+        Ok(())
+    }
+
+    /// Remove tile `id` and its connections, unless it is the origin or
+    /// other tiles reach the origin only through it. Whatever stands on the
+    /// tile is the caller's to check.
+    pub fn demolish(&mut self, id: &str) -> Result<Tile, MapError> {
+        if id == ORIGIN {
+            return Err(MapError::Origin);
+        }
+        if !self.tiles.contains_key(id) {
+            return Err(MapError::UnknownTile(id.to_string()));
+        }
+        let mut after = self.clone();
+        after.connections.retain(|(x, y)| *x != id && *y != id);
+        let tile = after.tiles.remove(id).expect("tile checked above");
+        self.check_still_reachable(&after, Some(id))?;
+        *self = after;
+        self.changed();
+        Ok(tile)
+    }
+
+    fn check_pair(&self, a: &str, b: &str) -> Result<(), MapError> {
+        for id in [a, b] {
+            if !self.tiles.contains_key(id) {
+                return Err(MapError::UnknownTile(id.to_string()));
+            }
+        }
+        if a == b {
+            return Err(MapError::SelfConnection(a.to_string()));
+        }
+        Ok(())
+    }
+
+    fn check_reachable(&self, id: &str) -> Result<(), MapError> {
+        if !self.tiles.contains_key(id) {
+            return Err(MapError::UnknownTile(id.to_string()));
+        }
+        if !self.reachable_from(ORIGIN).contains(id) {
+            return Err(MapError::Unreachable(id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Every tile reachable now, bar `removed`, is still reachable in `after`
+    fn check_still_reachable(&self, after: &GameMap, removed: Option<&str>) -> Result<(), MapError> {
+        let reachable = after.reachable_from(ORIGIN);
+        match self.reachable_from(ORIGIN).into_iter().find(|id| Some(*id) != removed && !reachable.contains(id)) {
+            Some(id) => Err(MapError::WouldCutOff(id.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Why a map edit was refused (the map is untouched)
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MapError {
+    #[error("a tile with ID {0} already exists")]
+    TileExists(String),
+
+    #[error("tile {0} does not exist")]
+    UnknownTile(String),
+
+    #[error("tile {0} can't be connected to itself")]
+    SelfConnection(String),
+
+    #[error("tiles {0} and {1} are already connected")]
+    AlreadyConnected(String, String),
+
+    #[error("tiles {0} and {1} are not connected")]
+    NotConnected(String, String),
+
+    #[error("tile {0} can't be reached from the origin")]
+    Unreachable(String),
+
+    #[error("that would cut tile {0} off from the origin")]
+    WouldCutOff(String),
+
+    #[error("the origin can't be demolished")]
+    Origin,
+
+    #[error("tile {tile} still has {what} on it")]
+    TileInUse { tile: String, what: &'static str },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths_are_cached_until_the_map_changes() {
+        let mut map = GameMap::default();
+        map.add_tile("hall", Tile::new_empty("Hall".to_string(), 1, 0), ORIGIN).unwrap();
+        map.add_tile("den", Tile::new_empty("Den".to_string(), 2, 0), "hall").unwrap();
+        map.add_tile("heap", Tile::new_compost("Heap".to_string(), 3, 0), "den").unwrap();
+
+        let path = map.path(ORIGIN, "heap").unwrap();
+        assert_eq!(path.iter().map(Key::as_str).collect::<Vec<_>>(), ["hall", "den", "heap"]);
+        assert!(Arc::ptr_eq(&path, &map.path(ORIGIN, "heap").unwrap()));
+        assert_eq!(map.path("heap", "heap").unwrap().len(), 0);
+        assert!(map.path(ORIGIN, "void").is_none());
+        assert_eq!(map.distance("heap", ORIGIN), Some(3));
+        assert_eq!(map.distance(ORIGIN, "void"), None);
+
+        // A raw edit isn't seen until it's reported
+        map.connections.push((Key::new(ORIGIN), Key::new("heap")));
+        assert_eq!(map.path(ORIGIN, "heap").unwrap().len(), 3);
+        map.changed();
+        assert_eq!(map.path(ORIGIN, "heap").unwrap().len(), 1);
+
+        // Editing methods report themselves
+        map.disconnect(ORIGIN, "heap").unwrap();
+        assert_eq!(map.path(ORIGIN, "heap").unwrap().len(), 3);
+        assert_eq!(map.version, 5);
+    }
 }
@@ -0,0 +1,30 @@
+//! The receiver's transmission log.
+//!
+//! Every summon attempt is recorded in state, so the receiver panel
+//! survives restarts and anything that reasons about the receiver's
+//! history (pity timers, patterns) reads the same record the engine wrote.
+
+use serde::{Deserialize, Serialize};
+
+use super::entity::{EntityId, VisitorType};
+
+/// One summon attempt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transmission {
+    pub tick: u64,
+
+    /// Influence spent
+    pub cost: f64,
+
+    pub success: bool,
+
+    /// Who answered, if anyone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visitor_type: Option<VisitorType>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visitor_id: Option<EntityId>,
+
+    /// How clearly the receiver heard the Outside at the time (0-1)
+    pub clarity: f64,
+}
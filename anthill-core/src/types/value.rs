@@ -0,0 +1,120 @@
+//! What things are worth, for every kind of exchange.
+//!
+//! There's no currency. A `Valuation` prices each resource in abstract
+//! worth, nudged by how much of it the colony holds: scarce resources are
+//! dear, hoarded ones cheap. Trades, tributes, offerings and promised gifts
+//! all price through the same table, so designers tune balance in one
+//! place. A wanderer's parting gift is a set amount instead
+//! (`Tuning::wanderer_gift`), whatever the stores hold.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::key::{AsKey, Key};
+use super::resource::Resources;
+
+/// Worth of one unit of a resource not in the table
+pub const DEFAULT_WORTH: f64 = 1.0;
+
+/// Scarcity can't move a price further than this factor either way
+pub const MAX_SCARCITY_FACTOR: f64 = 2.0;
+
+/// The valuation table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Valuation {
+    /// Worth of one unit at the reference stock
+    pub base: BTreeMap<Key, f64>,
+
+    /// Stock at which a resource is worth exactly its base
+    pub reference_stock: f64,
+
+    /// How strongly scarcity moves prices (0 = fixed prices)
+    pub elasticity: f64,
+}
+
+impl Default for Valuation {
+    fn default() -> Self {
+        let base = [
+            ("dirt", 0.1),
+            ("nutrients", 0.5),
+            ("fungus", 1.0),
+            ("ore", 2.0),
+            ("crystals", 3.0),
+            ("influence", 5.0),
+            ("insight", 8.0),
+            ("strange_matter", 10.0),
+        ];
+        Self {
+            base: base.into_iter().map(|(r, w)| (Key::new(r), w)).collect(),
+            reference_stock: 100.0,
+            elasticity: 0.5,
+        }
+    }
+}
+
+impl Valuation {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// How scarce `stock` units are: 1 at the reference stock, up to
+    /// `MAX_SCARCITY_FACTOR` when short, down to its inverse when hoarded
+    pub fn scarcity(&self, stock: f64) -> f64 {
+        (self.reference_stock / stock.max(1.0))
+            .powf(self.elasticity)
+            .clamp(1.0 / MAX_SCARCITY_FACTOR, MAX_SCARCITY_FACTOR)
+    }
+
+    /// Worth of one unit of `resource` when the colony holds `stock`
+    pub fn unit_worth(&self, resource: &str, stock: f64) -> f64 {
+        let base = self.base.get(resource).copied().unwrap_or(DEFAULT_WORTH);
+        base * self.scarcity(stock)
+    }
+
+    /// Worth of a bundle of resources against the colony's stores
+    pub fn worth<'a, K: AsKey + ?Sized + 'a>(
+        &self,
+        bundle: impl IntoIterator<Item = (&'a K, &'a f64)>,
+        stores: &Resources,
+    ) -> f64 {
+        bundle.into_iter()
+            .map(|(resource, amount)| {
+                let resource = resource.to_key();
+                self.unit_worth(&resource, stores.get(&resource)) * amount
+            })
+            .sum()
+    }
+
+    /// How much `resource` is worth `worth`
+    pub fn quote(&self, worth: f64, resource: &str, stores: &Resources) -> f64 {
+        worth / self.unit_worth(resource, stores.get(resource))
+    }
+
+    /// How much `receive` a fair swap for `amount` of `give` yields
+    pub fn exchange(&self, give: &str, amount: f64, receive: &str, stores: &Resources) -> f64 {
+        self.quote(self.unit_worth(give, stores.get(give)) * amount, receive, stores)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scarcity_moves_prices() {
+        let valuation = Valuation::default();
+        let mut stores = Resources::default();
+        stores.set("fungus", 100.0);
+        assert_eq!(valuation.unit_worth("fungus", 100.0), 1.0);
+        assert_eq!(valuation.exchange("crystals", 1.0, "fungus", &stores), 6.0);
+
+        // Scarce crystals are dear, hoarded fungus is cheap, both within bounds
+        assert_eq!(valuation.unit_worth("crystals", 0.0), 6.0);
+        assert_eq!(valuation.unit_worth("fungus", 10_000.0), 0.5);
+        assert_eq!(valuation.unit_worth("mystery", 100.0), DEFAULT_WORTH);
+
+        let bundle = [("fungus".to_string(), 2.0), ("crystals".to_string(), 1.0)];
+        assert_eq!(valuation.worth(bundle.iter().map(|(r, a)| (r, a)), &stores), 8.0);
+    }
+}
@@ -0,0 +1,54 @@
+//! Warnings: trouble the colony is heading into, judged by the engine's own
+//! thresholds so hosts don't have to guess at them.
+//!
+//! Unlike events, warnings describe the present rather than something that
+//! happened; they are recomputed after every tick and hold while the
+//! condition does.
+
+use serde::{Deserialize, Serialize};
+
+use super::key::Key;
+use super::system::Suspicion;
+
+/// A condition worth surfacing to the player
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Warning {
+    /// The ants will eat through a food store soon at the current rate
+    LowFoodRunway {
+        food: Key,
+        stock: f64,
+        seconds_left: f64,
+    },
+
+    /// Receiver maintenance falls due soon and can't be paid for
+    MaintenanceDue {
+        ticks_left: u64,
+        cost: f64,
+        strange_matter: f64,
+    },
+
+    /// Contamination makes a blight likely
+    BlightRisk {
+        tile: Key,
+        contamination: f64,
+        /// Chance of blight on each tick
+        chance: f64,
+    },
+
+    /// Nobody is left to carry the dead away
+    NoUndertakers {
+        corpses: usize,
+    },
+
+    /// A system sits out because its rates can't be right
+    SystemSetAside {
+        system_id: String,
+        suspicion: Suspicion,
+    },
+
+    /// A finished dig can't open because a tile already has its ID
+    ExcavationBlocked {
+        tile: Key,
+    },
+}
@@ -0,0 +1,180 @@
+//! The colony's weather: seasons turning over its year, and spells of rain
+//! or drought within them.
+//!
+//! With `feature::SEASONS`, a year is four seasons of `season_length`
+//! seconds each, starting in spring. Every `weather_spell` seconds the sky
+//! is rolled again from the Outside seed: spring and autumn bring rain more
+//! often, summer drought, winter neither. Season and sky together scale
+//! what systems turn out and how fast ants grow hungry. Like the Outside,
+//! the weather depends only on the seed and the time, so it can be
+//! forecast; the engine keeps the current reading in `GameState::weather`
+//! and reports each change as `SeasonChanged` or `WeatherEvent`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::digest::fnv1a64;
+use crate::engine::constants;
+use crate::rng::SeededRng;
+use super::config::Tuning;
+
+/// A quarter of the colony's year
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub const ALL: [Season; 4] = [Season::Spring, Season::Summer, Season::Autumn, Season::Winter];
+
+    /// Multiplier on system output
+    pub fn production_factor(self) -> f64 {
+        match self {
+            Season::Spring => 1.1,
+            Season::Summer => 1.2,
+            Season::Autumn => 1.0,
+            Season::Winter => 0.6,
+        }
+    }
+
+    /// Multiplier on ants' hunger
+    pub fn hunger_factor(self) -> f64 {
+        match self {
+            Season::Winter => 1.25,
+            _ => 1.0,
+        }
+    }
+
+    /// Multiplier on the chance of a spell of rain
+    fn rain_odds(self) -> f64 {
+        match self {
+            Season::Spring => 1.5,
+            Season::Summer => 0.5,
+            Season::Autumn => 1.0,
+            Season::Winter => 0.5,
+        }
+    }
+
+    /// Multiplier on the chance of a drought
+    fn drought_odds(self) -> f64 {
+        match self {
+            Season::Spring => 0.5,
+            Season::Summer => 2.0,
+            Season::Autumn => 1.0,
+            Season::Winter => 0.0,
+        }
+    }
+}
+
+/// What the sky is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sky {
+    Clear,
+    /// Things grow: more output
+    Rain,
+    /// Things wither: less output, and thirstier ants
+    Drought,
+}
+
+impl Sky {
+    pub fn production_factor(self) -> f64 {
+        match self {
+            Sky::Clear => 1.0,
+            Sky::Rain => 1.25,
+            Sky::Drought => 0.6,
+        }
+    }
+
+    pub fn hunger_factor(self) -> f64 {
+        match self {
+            Sky::Drought => 1.2,
+            _ => 1.0,
+        }
+    }
+}
+
+/// The weather at one moment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Weather {
+    pub season: Season,
+
+    /// Years gone by (0 for the colony's first)
+    pub year: u64,
+
+    pub sky: Sky,
+}
+
+impl Weather {
+    /// Weather `seconds` into a colony's life under `seed`
+    pub fn at(seed: u64, seconds: f64, tuning: &Tuning) -> Self {
+        let seconds = seconds.max(0.0);
+        let season_of = |seconds: f64| {
+            let seasons = (seconds / tuning.season_length.max(1) as f64) as u64;
+            (Season::ALL[(seasons % 4) as usize], seasons / 4)
+        };
+        let (season, year) = season_of(seconds);
+
+        // The spell's sky is set by the season it began in
+        let spell = (seconds / tuning.weather_spell.max(1) as f64) as u64;
+        let (spell_season, _) = season_of((spell * tuning.weather_spell.max(1)) as f64);
+        let roll = unit(seed ^ constants::WEATHER_STREAM, spell);
+        let sky = if roll < tuning.rain_chance * spell_season.rain_odds() {
+            Sky::Rain
+        } else if roll >= 1.0 - tuning.drought_chance * spell_season.drought_odds() {
+            Sky::Drought
+        } else {
+            Sky::Clear
+        };
+        Self { season, year, sky }
+    }
+
+    /// Multiplier on what systems turn out
+    pub fn production_factor(&self) -> f64 {
+        self.season.production_factor() * self.sky.production_factor()
+    }
+
+    /// Multiplier on how fast ants grow hungry
+    pub fn hunger_factor(&self) -> f64 {
+        self.season.hunger_factor() * self.sky.hunger_factor()
+    }
+}
+
+/// A value in [0, 1) for one spell, from a throwaway generator (hashes of
+/// consecutive spells alone are too alike)
+fn unit(seed: u64, spell: u64) -> f64 {
+    let bytes = [seed.to_le_bytes(), spell.to_le_bytes()].concat();
+    SeededRng::new(fnv1a64(&bytes)).random()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seasons_turn_and_skies_vary() {
+        let tuning = Tuning::default();
+        let length = tuning.season_length as f64;
+        let at = |seconds: f64| Weather::at(7, seconds, &tuning);
+        assert_eq!((at(0.0).season, at(0.0).year), (Season::Spring, 0));
+        assert_eq!(at(length).season, Season::Summer);
+        assert_eq!(at(3.0 * length + 1.0).season, Season::Winter);
+        assert_eq!((at(4.0 * length).season, at(4.0 * length).year), (Season::Spring, 1));
+        assert_eq!(at(1234.0), Weather::at(7, 1234.0, &tuning));
+
+        // Every spell of a year: some rain, some drought, none in winter
+        let spell = tuning.weather_spell as f64;
+        let skies: Vec<Weather> = (0..(4.0 * length / spell) as u64).map(|n| at(n as f64 * spell)).collect();
+        assert!(skies.iter().any(|w| w.sky == Sky::Rain));
+        assert!(skies.iter().any(|w| w.sky == Sky::Drought));
+        assert!(!skies.iter().any(|w| w.season == Season::Winter && w.sky == Sky::Drought));
+        assert!(skies.iter().filter(|w| w.sky == Sky::Clear).count() > skies.len() / 2);
+
+        let harsh = Weather { season: Season::Winter, year: 0, sky: Sky::Drought };
+        assert!(harsh.production_factor() < 0.5);
+        assert_eq!(harsh.hunger_factor(), 1.25 * 1.2);
+    }
+}
@@ -0,0 +1,30 @@
+//! Whispers: what influence does besides fuel the receiver.
+//!
+//! Influence held past a soft cap drains away slowly, and now and then
+//! (on its own RNG stream) some of it is spent on a whisper: a flash of
+//! insight, or a promise of something the colony will be given later.
+//! Promises are saved until they come due, so they show up in
+//! `GameState::upcoming` and survive a reload.
+
+use serde::{Deserialize, Serialize};
+
+use super::key::Key;
+
+/// What a whisper gave the colony
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Whisper {
+    /// Insight, right away
+    Insight { amount: f64 },
+
+    /// A gift to come
+    Promise(Promise),
+}
+
+/// A gift a whisper promised, and when it arrives
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Promise {
+    pub due: u64,
+    pub resource: Key,
+    pub amount: f64,
+}
@@ -0,0 +1,51 @@
+//! Wonders: once-in-a-lifetime jackpots.
+//!
+//! Every tick there is a tiny seeded chance (`Tuning::wonder_chance`, about
+//! one every two weeks of play) that one of the wonders the colony hasn't
+//! seen yet happens. Each wonder pays out a large one-time reward and is
+//! recorded in `GameState::wonders`, so no colony sees the same one twice.
+
+use serde::{Deserialize, Serialize};
+
+/// A rare, one-time happening
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Wonder {
+    /// The tunnel walls flower into crystal overnight
+    CrystallineBloom,
+    /// A message from the Outside decodes itself on the receiver
+    SelfDecodingMessage,
+    /// The fungus grows into a vaulted cathedral
+    FungalCathedral,
+    /// Something falls out of the sky and keeps glowing
+    FallenStar,
+}
+
+impl Wonder {
+    pub const ALL: [Wonder; 4] = [
+        Self::CrystallineBloom,
+        Self::SelfDecodingMessage,
+        Self::FungalCathedral,
+        Self::FallenStar,
+    ];
+
+    /// One line for encyclopedias
+    pub fn summary(self) -> &'static str {
+        match self {
+            Self::CrystallineBloom => "The tunnel walls flower into crystal overnight",
+            Self::SelfDecodingMessage => "A message from the Outside decodes itself on the receiver",
+            Self::FungalCathedral => "The fungus grows into a vaulted cathedral",
+            Self::FallenStar => "Something falls out of the sky and keeps glowing",
+        }
+    }
+
+    /// What the colony gains when it happens
+    pub fn rewards(self) -> &'static [(&'static str, f64)] {
+        match self {
+            Self::CrystallineBloom => &[("crystals", 200.0)],
+            Self::SelfDecodingMessage => &[("insight", 50.0), ("influence", 25.0)],
+            Self::FungalCathedral => &[("fungus", 1000.0), ("nutrients", 500.0)],
+            Self::FallenStar => &[("strange_matter", 40.0), ("ore", 100.0)],
+        }
+    }
+}
@@ -170,6 +170,63 @@ fn test_entity_types() {
     assert_eq!(undertaker.processing_corpse, Some(false));
 }
 
+#[test]
+fn test_legacy_compost_heap_takes_corpse_boosts() {
+    let state = GameState::from_json(SAMPLE_STATE).expect("Failed to parse sample state");
+    let heap = &state.systems["compost_heap"];
+    assert_eq!(heap.organic_boost, Some(anthill_core::Key::new("nutrients")));
+}
+
+#[test]
+fn test_legacy_processing_ticks_become_timer() {
+    let json = SAMPLE_STATE.replace(r#""processing_ticks": 0"#, r#""processing_ticks": 7"#);
+    let state = GameState::from_json(&json).expect("Failed to parse sample state");
+
+    let undertaker = state.entities.iter().find(|e| e.id == "539a5906").unwrap();
+    assert_eq!(undertaker.timer(anthill_core::types::entity::timer::PROCESSING), 7);
+
+    let saved = state.to_json().unwrap();
+    assert!(!saved.contains("processing_ticks"));
+    assert!(saved.contains(r#""timers":{"processing":7}"#));
+}
+
+/// A save from every schema version this build should still read, oldest first
+const SAVE_FIXTURES: [&str; 4] = [
+    include_str!("fixtures/saves/v0_python.json"),
+    include_str!("fixtures/saves/v1.json"),
+    include_str!("fixtures/saves/v2.json"),
+    include_str!("fixtures/saves/v3.json"),
+];
+
+#[test]
+fn test_old_schema_fixtures_load() {
+    use anthill_core::types::entity::timer;
+    use anthill_core::types::migrations::{self, SCHEMA_VERSION};
+    use anthill_core::TickEngine;
+
+    for (version, json) in SAVE_FIXTURES.iter().enumerate() {
+        let doc: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(migrations::version_of(&doc), version as u32);
+
+        let mut state = GameState::from_json(json).expect("Fixture should load");
+        assert_eq!(state.schema_version, SCHEMA_VERSION);
+        let undertaker = state.entities.iter().find(|e| e.id == "539a5906").unwrap();
+        assert_eq!(undertaker.timer(timer::PROCESSING), 12);
+        assert_eq!(state.systems["compost_heap"].organic_boost, Some(anthill_core::Key::new("nutrients")));
+
+        TickEngine::new(42).tick(&mut state);
+        assert_eq!(state.tick, 104101);
+    }
+}
+
+#[test]
+fn test_newer_schema_is_refused() {
+    let mut doc: serde_json::Value = serde_json::from_str(SAVE_FIXTURES[1]).unwrap();
+    doc["schema_version"] = 99.into();
+    let err = GameState::from_json(&doc.to_string()).unwrap_err();
+    assert!(err.to_string().contains("newer"));
+}
+
 #[test]
 fn test_map_structure() {
     let state = GameState::from_json(SAMPLE_STATE).expect("Failed to parse sample state");
@@ -182,3 +239,162 @@ fn test_map_structure() {
     assert!(!compost.is_blighted());
     assert!((compost.contamination.unwrap() - 0.01).abs() < 0.001);
 }
+
+#[test]
+fn test_envelope_roundtrip_and_tampering() {
+    use anthill_core::types::envelope::EnvelopeError;
+    use anthill_core::TickEngine;
+
+    let engine = TickEngine::new(42);
+    let state = GameState::from_json(SAMPLE_STATE).expect("Failed to parse sample state");
+    let json = state.to_json_envelope(&engine).expect("Failed to write envelope");
+
+    let restored = GameState::from_json_envelope(&json, &engine).expect("Envelope should verify");
+    assert_eq!(restored.tick, state.tick);
+
+    // Truncated
+    let truncated = &json[..json.len() / 2];
+    assert!(matches!(
+        GameState::from_json_envelope(truncated, &engine),
+        Err(EnvelopeError::Malformed(_))
+    ));
+
+    // Hand-edited
+    let edited = json.replace("\"tick\":104100", "\"tick\":999999");
+    assert_ne!(edited, json);
+    assert!(matches!(
+        GameState::from_json_envelope(&edited, &engine),
+        Err(EnvelopeError::StateDigestMismatch { .. })
+    ));
+
+    // Run under a different engine
+    assert!(matches!(
+        GameState::from_json_envelope(&json, &TickEngine::new(7)),
+        Err(EnvelopeError::ConfigDigestMismatch { .. })
+    ));
+
+    // Future format
+    let future = json.replace("\"envelope_version\":1", "\"envelope_version\":2");
+    assert!(matches!(
+        GameState::from_json_envelope(&future, &engine),
+        Err(EnvelopeError::UnsupportedVersion { found: 2, supported: 1 })
+    ));
+}
+
+#[cfg(feature = "binary")]
+#[test]
+fn test_binary_roundtrip_matches_json() {
+    use anthill_core::TickEngine;
+
+    let mut state = GameState::from_json(SAMPLE_STATE).expect("Failed to parse sample state");
+    let mut engine = TickEngine::new(42);
+    for _ in 0..500 {
+        engine.tick(&mut state);
+    }
+
+    let bytes = state.to_bytes().expect("Failed to write bytes");
+    let json = state.to_json().expect("Failed to serialize state");
+    assert!(bytes.len() < json.len());
+
+    let restored = GameState::from_bytes(&bytes).expect("Failed to read bytes");
+    assert_eq!(
+        serde_json::to_value(&restored).unwrap(),
+        serde_json::to_value(&state).unwrap()
+    );
+
+    // The restored colony carries on exactly as the original would
+    let mut original = state;
+    let mut restored = restored;
+    for _ in 0..500 {
+        engine.tick(&mut original);
+    }
+    let mut engine = TickEngine::new(42);
+    for _ in 0..500 {
+        engine.tick(&mut restored);
+    }
+    assert_eq!(restored.hash(), original.hash());
+}
+
+#[test]
+fn test_columnar_saves_load_like_row_saves() {
+    use anthill_core::types::columnar::SaveOptions;
+    use anthill_core::types::entity::DeathCause;
+    use anthill_core::types::graveyard::Corpse;
+    use anthill_core::{Entity, TickEngine};
+
+    // A big colony that has lived a while, so rows differ in most fields
+    // and only the ones they really share collapse
+    let mut state = GameState::from_json(SAMPLE_STATE).expect("Failed to parse sample state");
+    for n in 0..2000 {
+        let mut worker = Entity::new_worker(format!("w{n}"), "origin".to_string());
+        worker.hunger = (n % 97) as f64 + 0.5;
+        worker.age = n * 37 % 5000;
+        state.entities.push(worker);
+        state.graveyard.add_corpse(Corpse {
+            entity_id: format!("d{n}"),
+            entity_type: "ant".to_string(),
+            death_tick: n,
+            cause: DeathCause::Starvation,
+            tile: "origin".to_string(),
+        });
+    }
+    let mut engine = TickEngine::new(42);
+    for _ in 0..20 {
+        engine.tick(&mut state);
+    }
+
+    let rows = state.to_json().expect("Failed to serialize state");
+    let columns = state.to_json_with(SaveOptions::columnar()).expect("Failed to serialize columns");
+    assert!(columns.len() * 2 < rows.len(), "{} bytes in columns, {} in rows", columns.len(), rows.len());
+
+    // A handful of ants gains nothing worth having
+    let small = GameState::from_json(SAMPLE_STATE).expect("Failed to parse sample state");
+    let (small_rows, small_columns) = (small.to_json().unwrap(), small.to_json_with(SaveOptions::columnar()).unwrap());
+    assert!(small_columns.len() * 10 > small_rows.len() * 9, "{} bytes in columns, {} in rows", small_columns.len(), small_rows.len());
+
+    let restored = GameState::from_json(&columns).expect("Failed to parse columns");
+    assert_eq!(
+        serde_json::to_value(&restored).unwrap(),
+        serde_json::to_value(&state).unwrap()
+    );
+    assert_eq!(restored.hash(), state.hash());
+
+    #[cfg(feature = "binary")]
+    {
+        let bytes = state.to_bytes_with(SaveOptions::columnar()).expect("Failed to write bytes");
+        assert!(bytes.len() < state.to_bytes().unwrap().len());
+        assert_eq!(GameState::from_bytes(&bytes).expect("Failed to read bytes").hash(), state.hash());
+    }
+}
+
+#[cfg(feature = "binary")]
+#[test]
+fn test_binary_rejects_garbage() {
+    let state = GameState::from_json(SAMPLE_STATE).expect("Failed to parse sample state");
+    let bytes = state.to_bytes().expect("Failed to write bytes");
+
+    assert!(GameState::from_bytes(&bytes[..bytes.len() / 2]).is_err());
+    assert!(GameState::from_bytes(SAMPLE_STATE.as_bytes()).is_err());
+}
+
+#[test]
+fn test_saves_carry_a_compatibility_verdict() {
+    use anthill_core::types::compat::{Compatibility, CORE_VERSION};
+
+    // A save from this build comes back as the same
+    let state = GameState::from_json(SAMPLE_STATE).expect("Failed to parse sample state");
+    let json = state.to_json().unwrap();
+    let doc: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(doc["stamp"]["core_version"], CORE_VERSION);
+    assert_eq!(GameState::from_json_checked(&json).unwrap().1, Compatibility::Same);
+
+    // Unstamped saves are judged on their schema alone
+    assert_eq!(GameState::from_json_checked(SAVE_FIXTURES[0]).unwrap().1, Compatibility::NeedsMigration { from_schema: 0 });
+    assert_eq!(GameState::from_json_checked(SAVE_FIXTURES[3]).unwrap().1, Compatibility::MinorUpgrade { from: None });
+
+    // A save from a newer build is refused, with the reason
+    let mut doc = doc;
+    doc["stamp"]["core_version"] = "99.0.0".into();
+    let err = GameState::from_json(&doc.to_string()).unwrap_err();
+    assert!(err.to_string().contains("99.0.0"), "{err}");
+}
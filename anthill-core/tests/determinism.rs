@@ -2,7 +2,7 @@
 //!
 //! The core rule: Same seed + same inputs = same outputs, always.
 
-use anthill_core::{GameState, TickEngine, Entity, Event, EventKind};
+use anthill_core::{GameState, TickEngine, Entity, Event, EventFilter, EventKind, EventKindTag, InputLog, Tile, TickInput, Verbosity};
 use pretty_assertions::assert_eq;
 use std::collections::HashMap;
 
@@ -16,6 +16,18 @@ fn run_ticks(engine: &mut TickEngine, state: &mut GameState, n: usize) -> Vec<Ev
     all_events
 }
 
+/// Run N ticks, checksumming the state every `every` ticks
+fn run_checksummed(engine: &mut TickEngine, state: &mut GameState, n: u64, every: u64) -> Vec<(u64, u64)> {
+    let mut checksums = Vec::new();
+    for _ in 0..n {
+        engine.tick(state);
+        if state.tick.is_multiple_of(every) {
+            checksums.push((state.tick, state.hash()));
+        }
+    }
+    checksums
+}
+
 /// Compare two event sequences for equality (ignoring order within same tick)
 fn events_equal(a: &[Event], b: &[Event]) -> bool {
     if a.len() != b.len() {
@@ -91,9 +103,15 @@ fn test_determinism_with_spawning() {
         generates: None,
         consumes: None,
         description: Some("The queen produces new ants".to_string()),
+        organic_boost: None,
         corpse_boosts: Vec::new(),
         original_generates: None,
         original_consumes: None,
+        conversion_window: None,
+        tile: None,
+        starved_since: None,
+        history: Default::default(),
+        suspect: None,
     });
 
     let mut state2 = state1.clone();
@@ -118,6 +136,32 @@ fn test_determinism_with_spawning() {
 
     // Entity counts should match
     assert_eq!(state1.entities.len(), state2.entities.len());
+    assert_eq!(state1.hash(), state2.hash());
+}
+
+#[test]
+fn test_checksums_agree_every_100_ticks() {
+    let mut state1 = GameState::default();
+    state1.resources.set("nutrients", 200.0);
+    state1.resources.set("fungus", 200.0);
+    state1.resources.set("influence", 50.0);
+    state1.entities.push(Entity::new_worker("w1".to_string(), "origin".to_string()));
+    state1.entities.push(Entity::new_undertaker("u1".to_string(), "origin".to_string()));
+    let fixture = state1.clone();
+    let mut state2 = state1.clone();
+    // Host bookkeeping doesn't count
+    state2.last_save_timestamp = Some(1234.5);
+
+    let checksums1 = run_checksummed(&mut TickEngine::new(77), &mut state1, 3000, 100);
+    let checksums2 = run_checksummed(&mut TickEngine::new(77), &mut state2, 3000, 100);
+    assert_eq!(checksums1.len(), 30);
+    assert_eq!(checksums1, checksums2);
+
+    // The same colony under a different seed drifts, and the checksums
+    // catch it
+    let mut state3 = fixture;
+    let checksums3 = run_checksummed(&mut TickEngine::new(78), &mut state3, 3000, 100);
+    assert_ne!(checksums1, checksums3);
 }
 
 #[test]
@@ -134,9 +178,15 @@ fn test_determinism_with_receiver() {
         generates: None,
         consumes: None,
         description: Some("Listens to the Outside".to_string()),
+        organic_boost: None,
         corpse_boosts: Vec::new(),
         original_generates: None,
         original_consumes: None,
+        conversion_window: None,
+        tile: None,
+        starved_since: None,
+        history: Default::default(),
+        suspect: None,
     });
 
     let mut state2 = state1.clone();
@@ -180,9 +230,15 @@ fn test_different_seeds_diverge() {
         generates: None,
         consumes: None,
         description: None,
+        organic_boost: None,
         corpse_boosts: Vec::new(),
         original_generates: None,
         original_consumes: None,
+        conversion_window: None,
+        tile: None,
+        starved_since: None,
+        history: Default::default(),
+        suspect: None,
     });
 
     let mut state2 = state1.clone();
@@ -283,3 +339,336 @@ fn test_entity_lifecycle_determinism() {
     // Same resource state
     assert_eq!(state1.resources.get("fungus"), state2.resources.get("fungus"));
 }
+
+#[test]
+fn test_scaled_ticks_match_single_ticks() {
+    let seed = 777u64;
+
+    let mut state1 = GameState::default();
+    state1.resources.set("fungus", 50.0);
+    state1.resources.set("nutrients", 50.0);
+    state1.entities.push(Entity::new_worker("w1".to_string(), "origin".to_string()));
+    let mut state2 = state1.clone();
+
+    let mut engine1 = TickEngine::new(seed);
+    let mut engine2 = TickEngine::new(seed);
+
+    // 10 frames at 5x vs 50 frames at 1x
+    let events1 = engine1.tick_scaled(&mut state1, 10, 5.0).into_events();
+    let events2 = run_ticks(&mut engine2, &mut state2, 50);
+
+    assert_eq!(serde_json::to_value(&state1).unwrap(), serde_json::to_value(&state2).unwrap());
+    assert!(events_equal(&events1, &events2), "Scaled batch diverged from single ticks");
+}
+
+#[test]
+fn test_verbosity_filters_events_not_simulation() {
+    let mut state1 = GameState::default();
+    state1.resources.set("fungus", 5.0);
+    state1.entities.push(Entity::new_worker("w1".to_string(), "origin".to_string()));
+    let mut state2 = state1.clone();
+
+    let mut engine1 = TickEngine::new(9);
+    let mut engine2 = TickEngine::new(9);
+
+    let all = run_ticks(&mut engine1, &mut state1, 3000);
+    let mut notable = Vec::new();
+    for _ in 0..3000 {
+        notable.extend(engine2.tick_filtered(&mut state2, Verbosity::Notable).into_events());
+    }
+
+    assert_eq!(serde_json::to_value(&state1).unwrap(), serde_json::to_value(&state2).unwrap());
+    let expected: Vec<Event> = all.into_iter()
+        .filter(|e| e.kind.verbosity() == Verbosity::Notable)
+        .collect();
+    assert!(!expected.is_empty());
+    assert!(events_equal(&notable, &expected), "Filtering changed which notable events fired");
+}
+
+#[test]
+fn test_reflections_dont_depend_on_verbosity() {
+    use anthill_core::engine::constants;
+
+    // A blight about to clear, which only an Info-level event reports
+    let mut state1 = GameState::default();
+    let mut heap = Tile::new_compost("The Heap".to_string(), 1, 0);
+    heap.start_blight(1);
+    state1.map.add_tile("compost", heap, "origin").unwrap();
+    state1.tick = constants::REFLECTION_STILLNESS_TICKS;
+    state1.meta.reflection_triggers.last_significant_tick = state1.tick;
+    let mut state2 = state1.clone();
+
+    let mut engine1 = TickEngine::new(9);
+    let mut engine2 = TickEngine::new(9);
+    let mut due = Vec::new();
+    for _ in 0..constants::REFLECTION_AFTERMATH_DELAY + 1 {
+        engine1.tick_filtered(&mut state1, Verbosity::Debug);
+        due.extend(engine2.tick_filtered(&mut state2, Verbosity::Notable).into_events()
+            .into_iter()
+            .filter(|e| matches!(e.kind, EventKind::ReflectionDue { .. })));
+    }
+
+    assert_eq!(state1.meta.reflections.len(), 1);
+    assert_eq!(due.len(), 1);
+    assert_eq!(state1.hash(), state2.hash());
+}
+
+#[test]
+fn test_event_filter_drops_kinds_not_simulation() {
+    let mut state1 = GameState::default();
+    state1.resources.set("fungus", 5.0);
+    state1.entities.push(Entity::new_worker("w1".to_string(), "origin".to_string()));
+    let mut state2 = state1.clone();
+
+    let mut engine1 = TickEngine::new(9);
+    let mut engine2 = TickEngine::new(9);
+    let chatter = [EventKindTag::EntityAte, EventKindTag::PassiveGeneration];
+    engine2.set_event_filter(EventFilter::except(chatter));
+
+    let all = run_ticks(&mut engine1, &mut state1, 500);
+    let filtered = run_ticks(&mut engine2, &mut state2, 500);
+
+    assert_eq!(serde_json::to_value(&state1).unwrap(), serde_json::to_value(&state2).unwrap());
+    let expected: Vec<Event> = all.into_iter()
+        .filter(|e| !chatter.contains(&e.kind.tag()))
+        .collect();
+    assert!(events_equal(&filtered, &expected), "Filtering changed which other events fired");
+
+    // Only-lists and tick verbosity combine
+    engine2.set_event_filter(EventFilter::only([EventKindTag::EntityAte, EventKindTag::EntityDied]));
+    let mut kept = Vec::new();
+    for _ in 0..2500 {
+        kept.extend(engine2.tick_filtered(&mut state2, Verbosity::Info).into_events());
+    }
+    assert!(!kept.is_empty());
+    assert!(kept.iter().all(|e| e.kind.tag() == EventKindTag::EntityDied));
+
+    // Picking kinds back out of a tick's events
+    let events = engine1.tick(&mut state1);
+    let picked: Vec<&Event> = events.filter_kinds(&[EventKindTag::PassiveGeneration]).collect();
+    assert_eq!(picked.len(), events.events().iter().filter(|e| e.kind.tag() == EventKindTag::PassiveGeneration).count());
+}
+
+#[test]
+fn test_tick_n_matches_single_ticks() {
+    let mut state1 = GameState::default();
+    state1.resources.set("fungus", 5.0);
+    state1.entities.push(Entity::new_worker("w1".to_string(), "origin".to_string()));
+    state1.entities.push(Entity::new_worker("w2".to_string(), "origin".to_string()));
+    let mut state2 = state1.clone();
+
+    let mut engine1 = TickEngine::new(9);
+    let mut engine2 = TickEngine::new(9);
+
+    let events1 = run_ticks(&mut engine1, &mut state1, 3000);
+    let batch = engine2.tick_n(&mut state2, 3000);
+
+    assert_eq!(serde_json::to_value(&state1).unwrap(), serde_json::to_value(&state2).unwrap());
+    let events2: Vec<Event> = batch.events().cloned().collect();
+    assert!(events_equal(&events1, &events2), "Batch diverged from single ticks");
+    assert!(batch.ticks.iter().all(|t| t.events.iter().all(|e| e.tick == t.tick)));
+
+    let summary = &batch.summary;
+    assert_eq!(summary.ticks, 3000);
+    let died = events1.iter().filter(|e| matches!(e.kind, EventKind::EntityDied { .. })).count();
+    assert!(died > 0);
+    assert_eq!(summary.deaths as usize, died);
+    assert_eq!(summary.event_counts[&EventKindTag::EntityDied] as usize, died);
+    assert_eq!(summary.resource_deltas["fungus"], state2.resources.get("fungus") - 5.0);
+
+    // The summary tallies what happened, not what the filter kept
+    let mut state3 = GameState::default();
+    state3.resources.set("fungus", 5.0);
+    state3.entities.push(Entity::new_worker("w1".to_string(), "origin".to_string()));
+    state3.entities.push(Entity::new_worker("w2".to_string(), "origin".to_string()));
+    let mut engine3 = TickEngine::new(9);
+    engine3.set_event_filter(EventFilter::except([EventKindTag::EntityDied]).at_least(Verbosity::Notable));
+    let quiet = engine3.tick_n(&mut state3, 3000);
+    assert!(quiet.events().all(|e| e.kind.tag() != EventKindTag::EntityDied));
+    assert_eq!(quiet.summary, batch.summary);
+}
+
+#[test]
+fn test_input_log_replays_a_session() {
+    use anthill_core::types::command::Command;
+
+    let mut start = GameState::default();
+    start.resources.set("fungus", 50.0);
+    start.resources.set("nutrients", 50.0);
+    start.last_save_timestamp = Some(1000.0);
+    start.entities.push(Entity::new_worker("w1".to_string(), "origin".to_string()));
+    start.entities.push(Entity::new_worker("w2".to_string(), "origin".to_string()));
+
+    let mut engine = TickEngine::new(7);
+    let mut log = InputLog::for_engine(&engine);
+    let mut state = start.clone();
+    let mut outputs = Vec::new();
+    for n in 0..300u64 {
+        let mut input = TickInput::at(1000.0 + n as f64);
+        if n == 40 {
+            // A long absence, caught up inside the step
+            input = TickInput::at(1000.0 + n as f64 + 120.0);
+        }
+        if n == 100 {
+            input = input
+                .with_command(Command::FormGroup { group: "crew".to_string(), members: vec!["w1".to_string()] })
+                .with_command(Command::DisbandGroup { group: "nobody".to_string() });
+        }
+        outputs.push(engine.step(&mut state, &input));
+        log.push(input);
+    }
+    assert_eq!(outputs[100].rejected.len(), 1);
+    assert_eq!(outputs[100].rejected[0].index, 1);
+    assert!(outputs[40].tick > 41);
+
+    // The log survives a round trip and replays to the same place
+    let log: InputLog = serde_json::from_str(&serde_json::to_string(&log).unwrap()).unwrap();
+    let mut replayed = start.clone();
+    let replay_outputs = log.replay(&mut replayed);
+    assert_eq!(serde_json::to_value(&replayed).unwrap(), serde_json::to_value(&state).unwrap());
+    assert_eq!(serde_json::to_value(&replay_outputs).unwrap(), serde_json::to_value(&outputs).unwrap());
+}
+
+#[test]
+fn test_reload_under_a_fresh_engine_resumes_exactly() {
+    let mut state1 = GameState::default();
+    state1.resources.set("nutrients", 500.0);
+    state1.resources.set("fungus", 500.0);
+    state1.systems.insert(
+        "queen_chamber".to_string(),
+        anthill_core::System::new_generator("Queen's Chamber".to_string(), HashMap::new()),
+    );
+    let mut state2 = state1.clone();
+
+    // One session straight through
+    let mut engine1 = TickEngine::new(42);
+    let events1 = run_ticks(&mut engine1, &mut state1, 4000);
+
+    // The same, saved and reloaded partway under a new engine that's never
+    // seen the state (the queen's clock used to restart here)
+    let mut engine2 = TickEngine::new(42);
+    let mut events2 = run_ticks(&mut engine2, &mut state2, 2500);
+    let mut state2 = GameState::from_json(&state2.to_json().unwrap()).unwrap();
+    let mut engine2 = TickEngine::new(42);
+    events2.extend(run_ticks(&mut engine2, &mut state2, 1500));
+
+    assert_eq!(serde_json::to_value(&state1).unwrap(), serde_json::to_value(&state2).unwrap());
+    assert!(events_equal(&events1, &events2), "Reload changed what happened");
+    assert!(events1.iter().filter(|e| matches!(e.kind, EventKind::AntsSpawned { .. })).count() >= 2);
+}
+
+#[test]
+fn test_rng_budget_catches_a_drifting_phase() {
+    let mut start = GameState::default();
+    start.resources.set("nutrients", 500.0);
+    start.resources.set("fungus", 500.0);
+    start.systems.insert(
+        "queen_chamber".to_string(),
+        anthill_core::System::new_generator("Queen's Chamber".to_string(), HashMap::new()),
+    );
+
+    let mut engine = TickEngine::new(42);
+    engine.record_rng_budget();
+    run_ticks(&mut engine, &mut start.clone(), 2000);
+    let budget = engine.take_rng_budget().unwrap();
+    assert_eq!(budget.ticks.len(), 2000);
+    let (&spawn_tick, _) = budget.ticks.iter().find(|(_, phases)| phases.contains_key("queen")).unwrap();
+
+    // The same run stays within it
+    let mut engine = TickEngine::new(42);
+    engine.check_rng_budget(budget.clone());
+    let events = run_ticks(&mut engine, &mut start.clone(), 2000);
+    assert!(!events.iter().any(|e| matches!(e.kind, EventKind::RngBudgetExceeded { .. })));
+
+    // A queen that (per the budget) used to draw once more
+    let mut drifted = budget;
+    *drifted.ticks.get_mut(&spawn_tick).unwrap().get_mut("queen").unwrap() += 1;
+
+    let mut engine = TickEngine::new(42);
+    engine.check_rng_budget(drifted.clone());
+    let events = run_ticks(&mut engine, &mut start.clone(), 2000);
+    let overdraws: Vec<_> = events.iter().filter(|e| matches!(e.kind, EventKind::RngBudgetExceeded { .. })).collect();
+    assert_eq!(overdraws.len(), 1);
+    assert_eq!(overdraws[0].tick, spawn_tick);
+
+    // Strict engines stop at it
+    let mut engine = TickEngine::new(42);
+    engine.set_strict(true);
+    engine.check_rng_budget(drifted);
+    let mut state = start;
+    let violation = loop {
+        if let Err(violation) = engine.try_tick(&mut state) {
+            break violation;
+        }
+    };
+    assert_eq!(state.tick, spawn_tick);
+    assert!(violation.to_string().contains("queen"), "{violation}");
+}
+
+#[test]
+fn test_fractional_speed_accumulates() {
+    let mut engine = TickEngine::new(1);
+    let mut state = GameState::default();
+
+    // 0.5x: a tick every other frame, remainder carried through saves
+    engine.tick_scaled(&mut state, 3, 0.5);
+    assert_eq!(state.tick, 1);
+
+    let mut state = GameState::from_json(&state.to_json().unwrap()).unwrap();
+    engine.tick_scaled(&mut state, 1, 0.5);
+    assert_eq!(state.tick, 2);
+    assert_eq!(state.sub_tick, 0);
+
+    // 0.1x ten times is exactly one tick (no float drift)
+    engine.tick_scaled(&mut state, 10, 0.1);
+    assert_eq!(state.tick, 3);
+
+    engine.tick_scaled(&mut state, 5, -2.0);
+    assert_eq!(state.tick, 3);
+}
+
+#[test]
+fn test_large_colony_determinism() {
+    // Big enough to take the parallel entity path under `--features parallel`
+    let mut state1 = GameState::default();
+    state1.resources.set("fungus", 500.0);
+    for i in 0..1500 {
+        let mut ant = Entity::new_worker(format!("w{}", i), "origin".to_string());
+        ant.hunger = 40.0 + (i % 20) as f64;
+        state1.entities.push(ant);
+    }
+    let mut state2 = state1.clone();
+
+    let mut engine1 = TickEngine::new(31);
+    let mut engine2 = TickEngine::new(31);
+    let events1 = run_ticks(&mut engine1, &mut state1, 20);
+    let events2 = run_ticks(&mut engine2, &mut state2, 20);
+
+    assert_eq!(serde_json::to_value(&state1).unwrap(), serde_json::to_value(&state2).unwrap());
+    assert!(events_equal(&events1, &events2), "Events differ between runs");
+}
+
+#[test]
+fn test_soft_fail_leaves_sound_saves_alone() {
+    let mut state1 = GameState::new();
+    state1.resources.set("nutrients", 200.0);
+    state1.resources.set("fungus", 200.0);
+    state1.entities.push(Entity::new_worker("w1".to_string(), "origin".to_string()));
+    state1.entities.push(Entity::new_undertaker("u1".to_string(), "origin".to_string()));
+    let mut state2 = state1.clone();
+
+    let mut guarded = TickEngine::new(42);
+    guarded.set_soft_fail(true);
+    let events = run_ticks(&mut guarded, &mut state1, 2000);
+    run_ticks(&mut TickEngine::new(42), &mut state2, 2000);
+
+    assert!(!events.iter().any(|e| matches!(e.kind, EventKind::ExternalEditCorrected { .. })));
+    assert_eq!(state1.hash(), state2.hash());
+
+    // A plugin's bad edit is fixed before the tick runs, once
+    state1.get_entity_mut("w1").unwrap().hunger = f64::NAN;
+    let events = guarded.tick(&mut state1).into_events();
+    assert!(matches!(&events[0].kind, EventKind::ExternalEditCorrected { .. }));
+    assert!(state1.entities.iter().all(|e| e.hunger.is_finite()));
+    assert!(!run_ticks(&mut guarded, &mut state1, 10).iter().any(|e| matches!(e.kind, EventKind::ExternalEditCorrected { .. })));
+}
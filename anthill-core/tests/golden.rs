@@ -0,0 +1,49 @@
+//! Golden trace tests - catch unintended behavior changes.
+//!
+//! When a change is *meant* to alter the simulation, regenerate with
+//! `UPDATE_GOLDEN=1 cargo test --test golden` and commit the fixture; the
+//! fixture's diff is the review.
+
+use std::collections::HashMap;
+
+use anthill_core::trace::{Trace, TRACE_INTERVAL};
+use anthill_core::types::system::{System, SystemType};
+use anthill_core::{Entity, GameState, Tile, TickEngine};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/small_colony.trace.json");
+
+fn small_colony() -> GameState {
+    let mut state = GameState::default();
+    state.map.tiles.insert("origin".into(), Tile::new_empty("The Starting Dirt".to_string(), 0, 0));
+    state.map.tiles.insert("compost".into(), Tile::new_compost("The Heap".to_string(), 1, 0));
+    state.map.connections.push(("origin".into(), "compost".into()));
+    for (resource, amount) in [("fungus", 40.0), ("nutrients", 40.0), ("dirt", 100.0), ("influence", 20.0)] {
+        state.resources.set(resource, amount);
+    }
+    state.systems.insert("compost_heap".to_string(), System::new_generator(
+        "Compost Heap".to_string(),
+        HashMap::from([("nutrients".to_string(), 0.01)]),
+    ).with_organic_boost("nutrients"));
+    state.systems.insert("receiver".to_string(), System {
+        system_type: SystemType::Antenna,
+        ..System::new_generator("The Receiver".to_string(), HashMap::new())
+    });
+    state.entities.push(Entity::new_worker("w1".to_string(), "origin".to_string()));
+    state.entities.push(Entity::new_worker("w2".to_string(), "origin".to_string()));
+    state.entities.push(Entity::new_undertaker("u1".to_string(), "origin".to_string()));
+    state
+}
+
+#[test]
+fn test_small_colony_matches_golden_trace() {
+    let trace = Trace::record(&mut TickEngine::new(42), &mut small_colony(), 5000, TRACE_INTERVAL);
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(FIXTURE, serde_json::to_string_pretty(&trace).unwrap()).unwrap();
+        return;
+    }
+
+    let fixture: Trace = serde_json::from_str(&std::fs::read_to_string(FIXTURE).unwrap()).unwrap();
+    let diff = fixture.diff(&trace);
+    assert!(diff.is_empty(), "behavior changed; if intended, rerun with UPDATE_GOLDEN=1\n{}", diff);
+}
@@ -59,6 +59,16 @@ class CoreEngine:
 
         return new_state_json, json.loads(events_json)
 
+    def tick_report(self, state_json: str, metrics: bool = False) -> tuple[str, dict]:
+        """Run one tick, getting events, warnings and counters in one call.
+
+        Returns:
+            Tuple of (new_state_json, report_dict)
+        """
+        state = anthill_core.PyGameState.from_json(state_json)
+        report_json = self._engine.tick_report(state, metrics)
+        return state.to_json(), json.loads(report_json)
+
 class StateManager:
     """Wrapper for Rust GameState."""
 
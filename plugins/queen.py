@@ -47,7 +47,7 @@ def spawn_ant(state: dict, role: str) -> dict:
 
     if role == "undertaker":
         ant["processing_corpse"] = False
-        ant["processing_ticks"] = 0
+        ant["timers"] = {"processing": 0}
 
     state["entities"].append(ant)
     print(f"[queen] spawned {role} {ant_id} at origin")
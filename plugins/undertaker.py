@@ -48,9 +48,10 @@ def process_undertakers(state: dict) -> dict:
     for undertaker in undertakers:
         # Check if undertaker is currently processing
         if undertaker.get("processing_corpse"):
-            undertaker["processing_ticks"] = undertaker.get("processing_ticks", 0) + 1
+            timers = undertaker.setdefault("timers", {})
+            timers["processing"] = timers.get("processing", 0) + 1
 
-            if undertaker["processing_ticks"] >= CORPSE_PROCESSING_TICKS:
+            if timers["processing"] >= CORPSE_PROCESSING_TICKS:
                 # Corpse delivered to compost
                 corpse_boosts = compost_system.get("corpse_boosts", [])
                 corpse_boosts.append({
@@ -65,7 +66,7 @@ def process_undertakers(state: dict) -> dict:
                 graveyard["total_processed"] = graveyard.get("total_processed", 0) + 1
 
                 undertaker["processing_corpse"] = False
-                undertaker["processing_ticks"] = 0
+                timers["processing"] = 0
 
                 print(f"[undertaker] corpse processed, contamination now {compost_tile['contamination']:.1%}")
 
@@ -79,7 +80,7 @@ def process_undertakers(state: dict) -> dict:
             # Start processing a corpse
             corpse = corpses.pop(0)
             undertaker["processing_corpse"] = True
-            undertaker["processing_ticks"] = 0
+            undertaker.setdefault("timers", {})["processing"] = 0
             print(f"[undertaker] {undertaker['id']} collecting corpse from graveyard")
 
     return state